@@ -1,12 +1,14 @@
 "use client";
 
-import { useEffect, useMemo, type ReactNode } from "react";
+import { useEffect, useMemo, useState, type ReactNode } from "react";
 import { useMutation, useQuery } from "@tanstack/react-query";
 import {
+  AppWindow,
   ArrowRight,
   BadgeInfo,
   FolderOpen,
   HardDrive,
+  Layers,
   Loader2,
   Play,
   Sparkles,
@@ -20,12 +22,19 @@ import { Input } from "@/components/ui/input";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Skeleton } from "@/components/ui/skeleton";
 import {
+  chooseDatasetDirectories,
   chooseIndexSource,
   isTauri,
+  closeDataset,
+  describeError,
+  isRetryableError,
   listChunkItems,
-  loadChunkList,
-  loadIndex,
+  onChunkExistence,
+  onOpenRequests,
+  openChunkFiles,
+  openDataset,
   openLeaf,
+  openViewerWindow,
   peekField,
   readLastIndex,
   saveLastIndex,
@@ -56,6 +65,7 @@ export default function Page() {
     setChunkSelection,
     mode,
     triggerLoad,
+    openAt,
     selectedChunkName,
     selectChunk,
     selectedItemIndex,
@@ -73,15 +83,65 @@ export default function Page() {
     });
   }, [setIndexPath]);
 
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    void onOpenRequests((requests) => {
+      // Several requests at once (e.g. multiple files dropped on the icon): the last wins.
+      const last = requests[requests.length - 1];
+      openAt({ indexPath: last.path, chunk: last.chunk, item: last.item, field: last.field });
+    }).then((stop) => {
+      if (cancelled) stop();
+      else unlisten = stop;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [openAt]);
+
   const indexQuery = useQuery<IndexSummary>({
     queryKey: ["index-summary", mode],
     enabled: Boolean(mode),
     queryFn: () => {
       if (!mode) throw new Error("No source selected.");
-      return mode.kind === "index" ? loadIndex(mode.indexPath) : loadChunkList(mode.paths);
+      return mode.kind === "index" ? openDataset(mode.indexPath) : openChunkFiles(mode.paths);
     },
   });
 
+  useEffect(() => {
+    const handle = indexQuery.data?.handle;
+    if (handle == null) return;
+    return () => {
+      void closeDataset(handle);
+    };
+  }, [indexQuery.data?.handle]);
+
+  const [chunkExistence, setChunkExistence] = useState<Record<string, boolean>>({});
+
+  useEffect(() => {
+    setChunkExistence({});
+    const indexPathLoaded = indexQuery.data?.indexPath;
+    if (!indexPathLoaded || !isTauri()) return;
+    if (indexQuery.data?.chunks.every((chunk) => chunk.exists != null)) return;
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    void onChunkExistence(indexPathLoaded, (batch) => {
+      setChunkExistence((prev) => {
+        const next = { ...prev };
+        for (const result of batch.results) next[result.filename] = result.exists;
+        return next;
+      });
+    }).then((stop) => {
+      if (cancelled) stop();
+      else unlisten = stop;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [indexQuery.data]);
+
   useEffect(() => {
     if (indexQuery.data?.indexPath) {
       void saveLastIndex(indexQuery.data.indexPath);
@@ -117,11 +177,11 @@ export default function Page() {
   );
 
   const itemsQuery = useQuery<ItemMeta[]>({
-    queryKey: ["chunk-items", indexQuery.data?.indexPath, selectedChunk?.filename],
+    queryKey: ["chunk-items", indexQuery.data?.handle, selectedChunk?.filename],
     enabled: Boolean(indexQuery.data && selectedChunk && !indexQuery.isFetching),
     queryFn: () =>
       listChunkItems({
-        indexPath: indexQuery.data?.indexPath ?? "",
+        handle: indexQuery.data?.handle ?? 0,
         chunkFilename: selectedChunk?.filename ?? "",
       }),
     staleTime: 5 * 60 * 1000,
@@ -163,7 +223,7 @@ export default function Page() {
   const previewQuery = useQuery<FieldPreview>({
     queryKey: [
       "field-preview",
-      indexQuery.data?.indexPath,
+      indexQuery.data?.handle,
       selectedChunk?.filename,
       selectedItem?.itemIndex,
       selectedField?.fieldIndex,
@@ -171,7 +231,7 @@ export default function Page() {
     enabled: Boolean(indexQuery.data && selectedChunk && selectedItem && selectedField && !itemsQuery.isFetching),
     queryFn: () =>
       peekField({
-        indexPath: indexQuery.data?.indexPath ?? "",
+        handle: indexQuery.data?.handle ?? 0,
         chunkFilename: selectedChunk?.filename ?? "",
         itemIndex: selectedItem?.itemIndex ?? 0,
         fieldIndex: selectedField?.fieldIndex ?? 0,
@@ -185,7 +245,7 @@ export default function Page() {
         throw new Error("Select a field to open.");
       }
       return openLeaf({
-        indexPath: indexQuery.data.indexPath,
+        handle: indexQuery.data.handle,
         chunkFilename: selectedChunk.filename,
         itemIndex: selectedItem.itemIndex,
         fieldIndex: selectedField.fieldIndex,
@@ -193,7 +253,7 @@ export default function Page() {
     },
     onSuccess: (message) => setStatusMessage(message),
     onError: (err: unknown) =>
-      setStatusMessage(err instanceof Error ? err.message : "Unable to open the selected field."),
+      setStatusMessage(describeError(err)),
   });
 
   const totalBytes =
@@ -206,7 +266,13 @@ export default function Page() {
     indexQuery.isFetching || itemsQuery.isFetching || previewQuery.isFetching || openFieldMutation.isPending;
   const latestError =
     indexQuery.error || itemsQuery.error || previewQuery.error || openFieldMutation.error || undefined;
-  const errorMessage = latestError instanceof Error ? latestError.message : null;
+  const errorMessage = latestError ? describeError(latestError) : null;
+  const retryLatest = () => {
+    if (indexQuery.error) void indexQuery.refetch();
+    else if (itemsQuery.error) void itemsQuery.refetch();
+    else if (previewQuery.error) void previewQuery.refetch();
+    else openFieldMutation.reset();
+  };
   const formatList = (indexQuery.data?.dataFormat ?? []).join(" · ");
 
   const handleLoad = () => {
@@ -218,9 +284,11 @@ export default function Page() {
     }
   };
 
-  const handleChoose = async () => {
+  const handleChoose = async (folders = false) => {
     try {
-      const pick = await chooseIndexSource(indexPath, indexQuery.data?.rootDir);
+      const pick = folders
+        ? await chooseDatasetDirectories(indexPath, indexQuery.data?.rootDir)
+        : await chooseIndexSource(indexPath, indexQuery.data?.rootDir);
       if (!pick) return;
       setStatusMessage(null);
       if (pick.kind === "index") {
@@ -231,7 +299,16 @@ export default function Page() {
         triggerLoad("chunks", pick.paths);
       }
     } catch (err) {
-      setStatusMessage(err instanceof Error ? err.message : String(err));
+      setStatusMessage(describeError(err));
+    }
+  };
+
+  const handleNewWindow = async () => {
+    try {
+      const path = indexPath.trim();
+      await openViewerWindow(path ? { path } : undefined);
+    } catch (err) {
+      setStatusMessage(describeError(err));
     }
   };
 
@@ -258,12 +335,32 @@ export default function Page() {
                 <Button
                   variant="outline"
                   className="border-emerald-200 bg-white/80 text-emerald-700 hover:bg-emerald-50"
-                  onClick={handleChoose}
+                  onClick={() => handleChoose()}
                   disabled={busy || !isTauri()}
                 >
                   <FolderOpen className="mr-2 h-4 w-4" />
                   Choose
                 </Button>
+                <Button
+                  variant="outline"
+                  className="border-emerald-200 bg-white/80 text-emerald-700 hover:bg-emerald-50"
+                  onClick={() => handleChoose(true)}
+                  disabled={busy || !isTauri()}
+                  title="Open several litdata output folders as one dataset"
+                >
+                  <Layers className="mr-2 h-4 w-4" />
+                  Merge folders
+                </Button>
+                <Button
+                  variant="outline"
+                  className="border-emerald-200 bg-white/80 text-emerald-700 hover:bg-emerald-50"
+                  onClick={() => void handleNewWindow()}
+                  disabled={!isTauri()}
+                  title="Open another window sharing this one's caches and settings"
+                >
+                  <AppWindow className="mr-2 h-4 w-4" />
+                  New window
+                </Button>
                 <Button onClick={handleLoad} disabled={busy || (!indexPath.trim() && chunkSelection.length === 0) || !isTauri()}>
                   {busy ? <Loader2 className="mr-2 h-4 w-4 animate-spin" /> : <HardDrive className="mr-2 h-4 w-4" />}
                   Load
@@ -272,7 +369,7 @@ export default function Page() {
               {chunkSelection.length > 0 ? (
                 <div className="flex flex-wrap items-center gap-2 text-xs text-slate-600">
                   <Badge variant="secondary" className="bg-slate-100/80">
-                    {chunkSelection.length} selected chunk{chunkSelection.length > 1 ? "s" : ""}
+                    {chunkSelection.length} selected path{chunkSelection.length > 1 ? "s" : ""}
                   </Badge>
                   <span className="truncate">
                     {chunkSelection
@@ -309,30 +406,33 @@ export default function Page() {
           >
             <div className="flex h-full flex-col">
               <ScrollArea className="flex-1 min-h-0 rounded-[18px] border border-slate-200/70 bg-white/80">
-                {(indexQuery.data?.chunks ?? []).map((chunk) => (
-                  <div
-                    key={chunk.filename}
-                    className={cn(
-                      "grid cursor-pointer grid-cols-1 gap-2 border-b border-slate-100 px-4 py-3 transition",
-                      selectedChunk?.filename === chunk.filename
-                        ? "border-l-[3px] border-l-emerald-500 bg-emerald-50/70"
-                        : "hover:bg-slate-50",
-                    )}
-                    onClick={() => selectChunk(chunk.filename)}
-                  >
-                    <div className="flex items-center justify-between gap-3">
-                      <div className="font-semibold text-slate-900">{chunk.filename}</div>
-                      <Badge variant={chunk.exists ? "accent" : "secondary"}>
-                        {chunk.exists ? "On disk" : "Missing"}
-                      </Badge>
-                    </div>
-                    <div className="flex flex-wrap gap-2 text-xs text-slate-600">
-                      <Badge variant="secondary">{formatBytes(chunk.chunkBytes)}</Badge>
-                      <Badge variant="secondary">{chunk.chunkSize} items</Badge>
-                      {chunk.dim ? <Badge variant="secondary">{chunk.dim} dim</Badge> : null}
+                {(indexQuery.data?.chunks ?? []).map((chunk) => {
+                  const exists = chunk.exists ?? chunkExistence[chunk.filename];
+                  return (
+                    <div
+                      key={chunk.filename}
+                      className={cn(
+                        "grid cursor-pointer grid-cols-1 gap-2 border-b border-slate-100 px-4 py-3 transition",
+                        selectedChunk?.filename === chunk.filename
+                          ? "border-l-[3px] border-l-emerald-500 bg-emerald-50/70"
+                          : "hover:bg-slate-50",
+                      )}
+                      onClick={() => selectChunk(chunk.filename)}
+                    >
+                      <div className="flex items-center justify-between gap-3">
+                        <div className="font-semibold text-slate-900">{chunk.filename}</div>
+                        <Badge variant={exists ? "accent" : "secondary"}>
+                          {exists == null ? "Checking…" : exists ? "On disk" : "Missing"}
+                        </Badge>
+                      </div>
+                      <div className="flex flex-wrap gap-2 text-xs text-slate-600">
+                        <Badge variant="secondary">{formatBytes(chunk.chunkBytes)}</Badge>
+                        <Badge variant="secondary">{chunk.chunkSize} items</Badge>
+                        {chunk.dim ? <Badge variant="secondary">{chunk.dim} dim</Badge> : null}
+                      </div>
                     </div>
-                  </div>
-                ))}
+                  );
+                })}
                 {!indexQuery.data?.chunks?.length ? <EmptyState hint="Load an index.json to see chunks." /> : null}
               </ScrollArea>
             </div>
@@ -360,6 +460,11 @@ export default function Page() {
                     <div className="flex flex-wrap items-center gap-2 text-xs text-slate-600">
                       <Badge variant="secondary">{formatBytes(item.totalBytes)}</Badge>
                       <Badge variant="secondary">{item.fields.length} leaves</Badge>
+                      {item.arityMismatch ? (
+                        <Badge variant="secondary" className="border-amber-200 bg-amber-50 text-amber-800">
+                          header has {item.headerFieldCount ?? "?"} fields
+                        </Badge>
+                      ) : null}
                     </div>
                   </div>
                 ))}
@@ -431,6 +536,11 @@ export default function Page() {
                 <span className={cn(errorMessage ? "text-amber-700" : "text-slate-600")}>
                   {errorMessage ?? statusMessage ?? "Idle"}
                 </span>
+                {latestError && isRetryableError(latestError) ? (
+                  <Button variant="outline" size="sm" onClick={retryLatest} disabled={busy}>
+                    Retry
+                  </Button>
+                ) : null}
               </div>
             </div>
           </DataCard>
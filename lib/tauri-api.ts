@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { dirname } from "@tauri-apps/api/path";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { open as openDialog } from "@tauri-apps/plugin-dialog";
 import { Store } from "@tauri-apps/plugin-store";
 
@@ -9,10 +10,76 @@ export type ChunkSummary = {
   chunkSize: number;
   chunkBytes: number;
   dim?: number | null;
-  exists: boolean;
+  /** Directory or index.json this chunk came from, set when several sources are merged. */
+  source?: string | null;
+  /** `null` until the background existence check for large indexes reaches this chunk. */
+  exists?: boolean | null;
 };
 
+export type ChunkExistenceBatch = {
+  indexPath: string;
+  results: { filename: string; exists: boolean }[];
+  checked: number;
+  total: number;
+};
+
+export const CHUNK_EXISTENCE_EVENT = "chunk-existence";
+
+/** Subscribes to background existence results for `indexPath`; returns the unlisten function. */
+export async function onChunkExistence(
+  indexPath: string,
+  handler: (batch: ChunkExistenceBatch) => void,
+): Promise<() => void> {
+  await requireTauri("Checking chunks");
+  return getCurrentWebviewWindow().listen<ChunkExistenceBatch>(CHUNK_EXISTENCE_EVENT, (event) => {
+    if (event.payload.indexPath === indexPath) handler(event.payload);
+  });
+}
+
+/** A dataset to open from a `litdata://open?path=…` link or an OS file open. */
+export type OpenRequest = {
+  path: string;
+  chunk?: string | null;
+  item?: number | null;
+  field?: number | null;
+};
+
+export const OPEN_REQUEST_EVENT = "open-request";
+
+export async function takePendingOpenRequests(): Promise<OpenRequest[]> {
+  if (!isTauri()) return [];
+  return invoke<OpenRequest[]>("take_pending_open_requests");
+}
+
+/** Label of the window declared in tauri.conf.json; only it receives links and file opens. */
+export const MAIN_WINDOW = "main";
+
+/**
+ * Calls `handler` with queued open requests now and whenever new ones arrive. Extra
+ * windows instead get the one request they were created with.
+ */
+export async function onOpenRequests(handler: (requests: OpenRequest[]) => void): Promise<() => void> {
+  if (!isTauri()) return () => {};
+  const current = getCurrentWebviewWindow();
+  if (current.label !== MAIN_WINDOW) {
+    const request = await invoke<OpenRequest | null>("take_window_open_request");
+    if (request) handler([request]);
+    return () => {};
+  }
+  const drain = async () => {
+    const requests = await takePendingOpenRequests();
+    if (requests.length) handler(requests);
+  };
+  const unlisten = await current.listen(OPEN_REQUEST_EVENT, () => void drain());
+  await drain();
+  return unlisten;
+}
+
+/** Id of an open dataset; item and field commands take this instead of a path. */
+export type DatasetHandle = number;
+
 export type IndexSummary = {
+  handle: DatasetHandle;
   indexPath: string;
   rootDir: string;
   dataFormat: string[];
@@ -20,6 +87,9 @@ export type IndexSummary = {
   chunkSize?: number | null;
   chunkBytes?: number | null;
   configRaw?: Record<string, unknown> | null;
+  formatVersion?: number | null;
+  updatedAt?: string | null;
+  litdataVersion?: string | null;
   chunks: ChunkSummary[];
 };
 
@@ -32,6 +102,10 @@ export type ItemMeta = {
   itemIndex: number;
   totalBytes: number;
   fields: FieldMeta[];
+  headerFieldCount?: number | null;
+  arityMismatch: boolean;
+  /** Columns from the dataset's sidecar label file, when it has a row for this item. */
+  labels?: Record<string, string>;
 };
 
 export type FieldPreview = {
@@ -40,6 +114,8 @@ export type FieldPreview = {
   guessedExt?: string | null;
   isBinary: boolean;
   size: number;
+  /** Name of an integer label from the dataset's class map. */
+  className?: string | null;
 };
 
 const STORE_NAME = "litdata-viewer.bin";
@@ -98,12 +174,13 @@ export async function chooseIndexSource(currentPath: string, lastRoot?: string):
   await requireTauri("Choosing files");
   const defaultPath = (await resolveDefaultDialogPath(currentPath, lastRoot)) ?? undefined;
   const picked = await openDialog({
-    title: "Select litdata index.json or chunk .bin/.zst files",
+    title: "Select litdata index.json, chunk .bin/.zst files or a .zip/.tar dataset archive",
     multiple: true,
     filters: [
       { name: "LitData index", extensions: ["json"] },
       { name: "LitData chunk", extensions: ["bin", "zst"] },
-      { name: "All supported", extensions: ["json", "bin", "zst"] },
+      { name: "Dataset archive", extensions: ["zip", "tar", "tzst", "zst"] },
+      { name: "All supported", extensions: ["json", "bin", "zst", "zip", "tar", "tzst"] },
     ],
     ...(defaultPath ? { defaultPath } : {}),
   });
@@ -113,32 +190,123 @@ export async function chooseIndexSource(currentPath: string, lastRoot?: string):
   }
   const first = Array.isArray(picked) ? picked[0] : picked;
   if (typeof first !== "string") return null;
+  if (/\.(tar|tar\.zstd?|tzst)$/i.test(first)) {
+    return { kind: "index", indexPath: first };
+  }
   if (first.endsWith(".bin") || first.endsWith(".zst") || first.includes(".bin")) {
     return { kind: "chunks", paths: [first] };
   }
   return { kind: "index", indexPath: first };
 }
 
-export async function loadIndex(indexPath: string): Promise<IndexSummary> {
+/** Picks dataset directories to open together as one merged view. */
+export async function chooseDatasetDirectories(currentPath: string, lastRoot?: string): Promise<PickResult | null> {
+  await requireTauri("Choosing folders");
+  const defaultPath = (await resolveDefaultDialogPath(currentPath, lastRoot)) ?? undefined;
+  const picked = await openDialog({
+    title: "Select litdata output folders to merge",
+    directory: true,
+    multiple: true,
+    ...(defaultPath ? { defaultPath } : {}),
+  });
+  if (!picked) return null;
+  const paths = (Array.isArray(picked) ? picked : [picked]).filter((p): p is string => typeof p === "string");
+  return paths.length ? { kind: "chunks", paths } : null;
+}
+
+export async function openDataset(indexPath: string): Promise<IndexSummary> {
   await requireTauri("Loading index");
   const trimmed = indexPath.trim();
   if (!trimmed) throw new Error("Provide an index.json path to load.");
-  return invoke<IndexSummary>("load_index", { indexPath: trimmed });
+  return invoke<IndexSummary>("open_dataset", { indexPath: trimmed });
 }
 
-export async function loadChunkList(paths: string[]): Promise<IndexSummary> {
+export async function openChunkFiles(paths: string[]): Promise<IndexSummary> {
   await requireTauri("Loading chunks");
-  if (!paths.length) throw new Error("Select at least one chunk file to load.");
-  return invoke<IndexSummary>("load_chunk_list", { paths });
+  if (!paths.length) throw new Error("Select at least one chunk file or folder to load.");
+  return invoke<IndexSummary>("open_chunk_files", { paths });
+}
+
+export async function openIndexShards(dirPath: string): Promise<IndexSummary> {
+  await requireTauri("Loading index shards");
+  return invoke<IndexSummary>("open_index_shards", { dirPath });
+}
+
+export type OpenDataset = {
+  handle: DatasetHandle;
+  indexPath: string;
+  rootDir: string;
+  chunkCount: number;
+};
+
+/** Datasets this window opened; pass `all` for every window's. */
+export async function listOpenDatasets(all = false): Promise<OpenDataset[]> {
+  if (!isTauri()) return [];
+  return invoke<OpenDataset[]>("list_open_datasets", { all });
+}
+
+/**
+ * Opens another viewer window sharing this one's caches and settings, optionally
+ * showing `request`. Resolves to the new window's label.
+ */
+export async function openViewerWindow(request?: OpenRequest): Promise<string> {
+  await requireTauri("Opening windows");
+  return invoke<string>("open_viewer_window", { request: request ?? null });
+}
+
+export async function closeDataset(handle: DatasetHandle): Promise<boolean> {
+  if (!isTauri()) return false;
+  return invoke<boolean>("close_dataset", { handle });
+}
+
+export type SidecarSummary = {
+  jobId: string;
+  path: string;
+  keyColumn: string;
+  columns: string[];
+  rows: number;
+  matched: number;
+  unmatched: number;
+  unmatchedExamples: string[];
+};
+
+/**
+ * Joins a CSV/TSV/JSONL label file onto the dataset's item listings as `ItemMeta.labels`.
+ * `keyColumn` holds global item indices, or with `idField`, that field's text.
+ */
+export async function loadSidecar(params: {
+  handle: DatasetHandle;
+  path: string;
+  keyColumn: string;
+  idField?: number | null;
+  jobId?: string | null;
+}): Promise<SidecarSummary> {
+  await requireTauri("Loading labels");
+  return invoke<SidecarSummary>("load_sidecar", {
+    handle: params.handle,
+    path: params.path,
+    keyColumn: params.keyColumn,
+    idField: params.idField ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export async function clearSidecar(handle: DatasetHandle): Promise<boolean> {
+  if (!isTauri()) return false;
+  return invoke<boolean>("clear_sidecar", { handle });
 }
 
-export async function listChunkItems(params: { indexPath: string; chunkFilename: string }): Promise<ItemMeta[]> {
+export async function listChunkItems(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  jobId?: string | null;
+}): Promise<ItemMeta[]> {
   await requireTauri("Reading chunk");
-  return invoke<ItemMeta[]>("list_chunk_items", params);
+  return invoke<ItemMeta[]>("list_chunk_items", { ...params, jobId: params.jobId ?? null });
 }
 
 export async function peekField(params: {
-  indexPath: string;
+  handle: DatasetHandle;
   chunkFilename: string;
   itemIndex: number;
   fieldIndex: number;
@@ -147,8 +315,25 @@ export async function peekField(params: {
   return invoke<FieldPreview>("peek_field", params);
 }
 
+export async function readFieldRange(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex: number;
+  offset?: number | null;
+  length?: number | null;
+}): Promise<Uint8Array> {
+  await requireTauri("Reading field bytes");
+  const buffer = await invoke<ArrayBuffer>("read_field_range", {
+    ...params,
+    offset: params.offset ?? null,
+    length: params.length ?? null,
+  });
+  return new Uint8Array(buffer);
+}
+
 export async function openLeaf(params: {
-  indexPath: string;
+  handle: DatasetHandle;
   chunkFilename: string;
   itemIndex: number;
   fieldIndex: number;
@@ -156,3 +341,2073 @@ export async function openLeaf(params: {
   await requireTauri("Opening field");
   return invoke<string>("open_leaf", params);
 }
+
+/** Shows the field in a macOS Quick Look panel; rejects on other platforms. */
+export async function quickLookField(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex: number;
+}): Promise<string> {
+  await requireTauri("Quick Look");
+  return invoke<string>("quick_look_field", params);
+}
+
+export type PcmEncoding = "u8" | "s16le" | "s24le" | "s32le" | "f32le";
+
+export type AudioConversion = {
+  pcm?: { sampleRate: number; channels: number; encoding: PcmEncoding } | null;
+};
+
+export type HookOutcome = {
+  name: string;
+  /** Set when the hook could not be started. */
+  error?: string | null;
+};
+
+export type ExportedField = {
+  path: string;
+  size: number;
+  transcoded: boolean;
+  hooks: HookOutcome[];
+};
+
+export async function exportField(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex: number;
+  destPath: string;
+  audio?: AudioConversion | null;
+  jobId?: string | null;
+}): Promise<ExportedField> {
+  await requireTauri("Exporting field");
+  return invoke<ExportedField>("export_field", {
+    ...params,
+    audio: params.audio ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ValidationIssueKind =
+  | "missing_file"
+  | "unreadable_chunk"
+  | "item_count_mismatch"
+  | "byte_size_mismatch"
+  | "offsets_not_monotonic"
+  | "offset_out_of_bounds"
+  | "field_size_mismatch"
+  | "format_arity_mismatch";
+
+export type ValidationIssue = {
+  kind: ValidationIssueKind;
+  itemIndex?: number | null;
+  message: string;
+};
+
+export type ChunkReport = {
+  filename: string;
+  ok: boolean;
+  declaredItems: number;
+  actualItems?: number | null;
+  declaredBytes: number;
+  actualBytes?: number | null;
+  issues: ValidationIssue[];
+  truncatedIssues: boolean;
+};
+
+export type ValidationReport = {
+  indexPath: string;
+  ok: boolean;
+  chunkCount: number;
+  badChunks: number;
+  totalItems: number;
+  chunks: ChunkReport[];
+};
+
+export async function validateDataset(indexPath: string, jobId?: string | null): Promise<ValidationReport> {
+  await requireTauri("Validating dataset");
+  return invoke<ValidationReport>("validate_dataset", { indexPath: indexPath.trim(), jobId: jobId ?? null });
+}
+
+export type JobProgress = {
+  jobId: string;
+  phase: string;
+  done: number;
+  total: number;
+  percent: number;
+};
+
+export const JOB_PROGRESS_EVENT = "job-progress";
+
+export type ManifestSummary = {
+  jobId: string;
+  manifestPath: string;
+  chunkCount: number;
+  totalBytes: number;
+  itemHashes: boolean;
+};
+
+export type ChecksumMismatch = {
+  filename: string;
+  kind: "missing_file" | "not_in_manifest" | "size_changed" | "hash_changed" | "item_count_changed" | "item_changed";
+  itemIndex?: number | null;
+  expected?: string | null;
+  actual?: string | null;
+};
+
+export type VerifyReport = {
+  jobId: string;
+  manifestPath: string;
+  ok: boolean;
+  checkedChunks: number;
+  mismatches: ChecksumMismatch[];
+};
+
+export async function generateChecksumManifest(params: {
+  indexPath: string;
+  manifestPath?: string | null;
+  includeItems?: boolean;
+  jobId?: string | null;
+}): Promise<ManifestSummary> {
+  await requireTauri("Generating checksums");
+  return invoke<ManifestSummary>("generate_checksum_manifest", {
+    indexPath: params.indexPath,
+    manifestPath: params.manifestPath ?? null,
+    includeItems: params.includeItems ?? false,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export async function verifyChecksumManifest(params: {
+  indexPath: string;
+  manifestPath?: string | null;
+  jobId?: string | null;
+}): Promise<VerifyReport> {
+  await requireTauri("Verifying checksums");
+  return invoke<VerifyReport>("verify_checksum_manifest", {
+    indexPath: params.indexPath,
+    manifestPath: params.manifestPath ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type CorruptionInfo = {
+  chunk: string;
+  kind:
+    | "truncated_header"
+    | "truncated_offsets_table"
+    | "truncated_stream"
+    | "offsets_not_monotonic"
+    | "item_past_end"
+    | "length_mismatch";
+  firstBadItem?: number | null;
+  byteOffset?: number | null;
+  declaredLen?: number | null;
+  actualLen: number;
+  detail: string;
+};
+
+export type CorruptionScan = {
+  indexPath: string;
+  scannedChunks: number;
+  corrupt: CorruptionInfo[];
+};
+
+export async function scanChunkCorruption(indexPath: string): Promise<CorruptionScan> {
+  await requireTauri("Scanning chunks");
+  return invoke<CorruptionScan>("scan_chunk_corruption", { indexPath: indexPath.trim() });
+}
+
+export type ItemLocation = {
+  chunkFilename: string;
+  itemIndex: number;
+  globalIndex: number;
+};
+
+export type DuplicateGroup = {
+  hash: string;
+  size: number;
+  items: ItemLocation[];
+};
+
+export type DedupReport = {
+  jobId: string;
+  scannedItems: number;
+  duplicateItems: number;
+  wastedBytes: number;
+  groups: DuplicateGroup[];
+  unreadableChunks: string[];
+};
+
+export async function findDuplicateItems(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  hash?: "xxh3" | "sha256";
+  jobId?: string | null;
+}): Promise<DedupReport> {
+  await requireTauri("Scanning for duplicates");
+  return invoke<DedupReport>("find_duplicate_items", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    hash: params.hash ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type NearDuplicate = ItemLocation & {
+  hash: string;
+  /** Differing bits from the group's first item. */
+  distance: number;
+  /** JPEG, for `data:image/jpeg;base64,…`; only on the first 50 groups. */
+  thumbnailBase64?: string | null;
+};
+
+export type NearDuplicateReport = {
+  jobId: string;
+  algorithm: "dhash" | "phash";
+  maxDistance: number;
+  scannedItems: number;
+  hashedImages: number;
+  undecodableImages: number;
+  nearDuplicateItems: number;
+  groupCount: number;
+  /** Largest first, at most 500. */
+  groups: { fieldIndex: number; items: NearDuplicate[] }[];
+  unreadableChunks: string[];
+};
+
+/** Groups images whose perceptual hashes differ in at most `maxDistance` bits (default 6, at most 16). */
+export async function findNearDuplicateImages(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  algorithm?: "dhash" | "phash";
+  maxDistance?: number | null;
+  jobId?: string | null;
+}): Promise<NearDuplicateReport> {
+  await requireTauri("Scanning for near-duplicate images");
+  return invoke<NearDuplicateReport>("find_near_duplicate_images", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    algorithm: params.algorithm ?? null,
+    maxDistance: params.maxDistance ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type EmbeddingProjection = {
+  jobId: string;
+  fieldIndex: number;
+  format?: string | null;
+  dimensions: number;
+  totalItems: number;
+  sampledItems: number;
+  /** Not a float vector, a different length from the first one, or non-finite. */
+  skippedItems: number;
+  /** Share of the total variance along x and y. */
+  explainedVariance: [number, number];
+  points: (ItemLocation & { x: number; y: number })[];
+  unreadableChunks: string[];
+};
+
+/** 2D PCA of a float-vector field over a sample (default 5,000 items); `dtype` (e.g. "float32") for formats that do not record it. */
+export async function projectEmbeddings(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  dtype?: string | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<EmbeddingProjection> {
+  await requireTauri("Projecting embeddings");
+  return invoke<EmbeddingProjection>("project_embeddings", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    dtype: params.dtype ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type SizeOutlier = ItemLocation & {
+  /** Absent when the whole item is the outlier. */
+  fieldIndex?: number | null;
+  bytes: number;
+  zScore: number;
+};
+
+export type SizeOutlierReport = {
+  jobId: string;
+  stdDevs: number;
+  totalItems: number;
+  scannedItems: number;
+  arityMismatches: number;
+  /** Whole items first (`fieldIndex` null), then each field. */
+  spreads: { fieldIndex?: number | null; mean: number; stdDev: number; outliers: number }[];
+  outlierCount: number;
+  /** Largest `|zScore|` first, at most 1,000. */
+  outliers: SizeOutlier[];
+  unreadableChunks: string[];
+};
+
+/** Items whose total or per-field size is more than `stdDevs` (default 3) standard deviations from the mean. */
+export async function findSizeOutliers(params: {
+  indexPath: string;
+  stdDevs?: number | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<SizeOutlierReport> {
+  await requireTauri("Scanning for size outliers");
+  return invoke<SizeOutlierReport>("find_size_outliers", {
+    indexPath: params.indexPath,
+    stdDevs: params.stdDevs ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ChunkCompression = {
+  filename: string;
+  compressedBytes: number;
+  decompressedBytes?: number | null;
+  ratio?: number | null;
+  frames: number;
+  /** Where `decompressedBytes` came from. */
+  source: "frame_header" | "index" | "unknown";
+};
+
+export type CompressionReport = {
+  jobId: string;
+  indexPath: string;
+  chunkCount: number;
+  compressedBytes: number;
+  decompressedBytes: number;
+  overallRatio?: number | null;
+  minRatio?: number | null;
+  maxRatio?: number | null;
+  unknownChunks: number;
+  unreadableChunks: string[];
+  decompressMbPerSec?: number | null;
+  chunks: ChunkCompression[];
+};
+
+/** Per-chunk zstd compression ratios from frame headers; `measureDecode` also times decoding a few chunks. */
+export async function compressionReport(params: {
+  indexPath: string;
+  measureDecode?: boolean;
+  jobId?: string | null;
+}): Promise<CompressionReport> {
+  await requireTauri("Measuring compression");
+  return invoke<CompressionReport>("compression_report", {
+    indexPath: params.indexPath,
+    measureDecode: params.measureDecode ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type FieldEntropy = {
+  fieldIndex: number;
+  format?: string | null;
+  measuredItems: number;
+  /** Payloads under 1 KiB, left out. */
+  shortItems: number;
+  /** Bits per byte; 8 is random. */
+  meanBitsPerByte?: number | null;
+  nearRandomFraction: number;
+  byteShare: number;
+  /** Near-random data inside zstd chunks. */
+  doubleCompressed: boolean;
+};
+
+export type EntropyReport = {
+  jobId: string;
+  compression?: string | null;
+  totalItems: number;
+  sampledItems: number;
+  fields: FieldEntropy[];
+  unreadableChunks: string[];
+};
+
+/** Byte entropy per field; flags already-compressed payloads stored in zstd chunks. */
+export async function fieldEntropy(params: {
+  indexPath: string;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<EntropyReport> {
+  await requireTauri("Measuring field entropy");
+  return invoke<EntropyReport>("field_entropy", {
+    indexPath: params.indexPath,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type LanguageShare = {
+  /** ISO 639-3, e.g. `eng`. */
+  code: string;
+  name: string;
+  count: number;
+  fraction: number;
+  meanConfidence: number;
+  unreliable: number;
+};
+
+export type LanguageReport = {
+  jobId: string;
+  totalItems: number;
+  sampledItems: number;
+  fields: {
+    fieldIndex: number;
+    format?: string | null;
+    detectedItems: number;
+    undetectedItems: number;
+    /** Most common first. */
+    languages: LanguageShare[];
+  }[];
+  unreadableChunks: string[];
+};
+
+/** Language distribution of each text field, or of `fieldIndex` alone, over sampled items. */
+export async function detectLanguages(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<LanguageReport> {
+  await requireTauri("Detecting languages");
+  return invoke<LanguageReport>("detect_languages", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ContentTypeShare = {
+  /** File extension, or `empty`, `txt`, `bin`. */
+  contentType: string;
+  count: number;
+  fraction: number;
+  examples: ItemLocation[];
+};
+
+export type FieldCensus = {
+  fieldIndex: number;
+  declared?: string | null;
+  expected?: string | null;
+  dominant?: string | null;
+  mixed: boolean;
+  misdeclared: boolean;
+  /** Most common first. */
+  contentTypes: ContentTypeShare[];
+};
+
+export type ContentTypeCensus = {
+  jobId: string;
+  totalItems: number;
+  sampledItems: number;
+  fields: FieldCensus[];
+  unreadableChunks: string[];
+};
+
+/** Share of each field's payloads by magic-byte content type over sampled items. */
+export async function contentTypeCensus(params: {
+  indexPath: string;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<ContentTypeCensus> {
+  await requireTauri("Sniffing content types");
+  return invoke<ContentTypeCensus>("content_type_census", {
+    indexPath: params.indexPath,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type FieldSizeStats = {
+  fieldIndex: number;
+  format?: string | null;
+  min: number;
+  p50: number;
+  p90: number;
+  p99: number;
+  max: number;
+  mean: number;
+  totalBytes: number;
+  zeroItems: number;
+  /** Every sampled item stores this field as zero bytes. */
+  alwaysZero: boolean;
+};
+
+export type FieldSizeReport = {
+  totalItems: number;
+  sampledItems: number;
+  arityMismatches: number;
+  unreadableChunks: string[];
+  fields: FieldSizeStats[];
+};
+
+/** Per-field size percentiles over `sampleItems` items (default 10,000) spread across the dataset. */
+export async function fieldSizeDistribution(params: {
+  indexPath: string;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<FieldSizeReport> {
+  await requireTauri("Measuring field sizes");
+  return invoke<FieldSizeReport>("field_size_distribution", {
+    indexPath: params.indexPath,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type UnbalancedChunk = {
+  filename: string;
+  items: number;
+  bytes: number;
+  /** Size relative to the target; 1.0 is exactly full. */
+  fill: number;
+  status: "underfilled" | "oversized";
+  /** The last chunk a writer rank produced, which is expected to be partial. */
+  trailing: boolean;
+};
+
+export type BalanceReport = {
+  indexPath: string;
+  target: "bytes" | "items" | "median";
+  targetValue: number;
+  chunkCount: number;
+  meanFill: number;
+  minFill: number;
+  maxFill: number;
+  underfilled: number;
+  oversized: number;
+  unexpectedUnderfilled: number;
+  /** Flagged chunks, worst first. */
+  chunks: UnbalancedChunk[];
+};
+
+/** Chunk fill versus the configured `chunk_bytes`/`chunk_size`, read from the index alone. */
+export async function chunkBalance(params: {
+  indexPath: string;
+  underfilledBelow?: number | null;
+  oversizedAbove?: number | null;
+}): Promise<BalanceReport> {
+  await requireTauri("Checking chunk balance");
+  return invoke<BalanceReport>("chunk_balance", {
+    indexPath: params.indexPath,
+    underfilledBelow: params.underfilledBelow ?? null,
+    oversizedAbove: params.oversizedAbove ?? null,
+  });
+}
+
+export type DurationStats = {
+  min: number;
+  p50: number;
+  p90: number;
+  p99: number;
+  max: number;
+  mean: number;
+};
+
+export type AudioFieldStats = {
+  fieldIndex: number;
+  format?: string | null;
+  audioItems: number;
+  unknownDuration: number;
+  unreadableItems: number;
+  sampledHours: number;
+  /** `sampledHours` scaled up to every item when only a sample was read. */
+  totalHours: number;
+  /** Keys are sample rates in Hz, values item counts. */
+  sampleRates: Record<string, number>;
+  channels: Record<string, number>;
+  codecs: Record<string, number>;
+  /** Per-item duration in seconds. */
+  duration?: DurationStats | null;
+};
+
+export type AudioCorpusReport = {
+  jobId: string;
+  totalItems: number;
+  sampledItems: number;
+  extrapolated: boolean;
+  unreadableChunks: string[];
+  fields: AudioFieldStats[];
+};
+
+/** Hours, sample rates and durations of audio fields from container headers; every item unless `sampleItems` is set. */
+export async function audioCorpusStats(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<AudioCorpusReport> {
+  await requireTauri("Measuring audio");
+  return invoke<AudioCorpusReport>("audio_corpus_stats", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ResolutionCount = { width: number; height: number; count: number };
+
+export type AspectBucket = { label: string; count: number };
+
+export type DimensionStats = {
+  min: number;
+  p10: number;
+  p50: number;
+  p90: number;
+  max: number;
+};
+
+export type ImageFieldStats = {
+  fieldIndex: number;
+  format?: string | null;
+  images: number;
+  unrecognizedItems: number;
+  /** Keys are encodings such as `jpeg` or `png`, values item counts. */
+  encodings: Record<string, number>;
+  distinctResolutions: number;
+  /** Most common first, at most 50. */
+  resolutions: ResolutionCount[];
+  width: DimensionStats;
+  height: DimensionStats;
+  aspectRatios: AspectBucket[];
+};
+
+export type ImageResolutionReport = {
+  jobId: string;
+  totalItems: number;
+  sampledItems: number;
+  unreadableChunks: string[];
+  fields: ImageFieldStats[];
+};
+
+/** Resolution and aspect-ratio distribution of image fields from headers over a sample (default 2,000 items). */
+export async function imageResolutionStats(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<ImageResolutionReport> {
+  await requireTauri("Measuring images");
+  return invoke<ImageResolutionReport>("image_resolution_stats", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type LengthOutlier = { chunk: string; item: number; length: number };
+
+export type LengthDistribution = {
+  fieldIndex: number;
+  format?: string | null;
+  unit: "chars" | "tokens";
+  items: number;
+  emptyItems: number;
+  /** Text that is not UTF-8, or token payloads that are not a whole number of elements. */
+  malformedItems: number;
+  total: number;
+  lengths?: {
+    min: number;
+    p1: number;
+    p50: number;
+    p99: number;
+    max: number;
+    mean: number;
+  } | null;
+  /** Power-of-two buckets; `to` is exclusive. */
+  histogram: { from: number; to: number; count: number }[];
+  shortest: LengthOutlier[];
+  longest: LengthOutlier[];
+};
+
+export type TextLengthReport = {
+  jobId: string;
+  totalItems: number;
+  sampledItems: number;
+  tokenizerVocab?: number | null;
+  unreadableChunks: string[];
+  fields: LengthDistribution[];
+};
+
+/** Character and token length distributions of text and token fields; `tokenizerPath` adds approximate token counts for text. */
+export async function textLengthStats(params: {
+  indexPath: string;
+  fieldIndex?: number | null;
+  tokenizerPath?: string | null;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<TextLengthReport> {
+  await requireTauri("Measuring text");
+  return invoke<TextLengthReport>("text_length_stats", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex ?? null,
+    tokenizerPath: params.tokenizerPath ?? null,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ClassBalanceReport = {
+  jobId: string;
+  fieldIndex: number;
+  format?: string | null;
+  totalItems: number;
+  sampledItems: number;
+  undecodableItems: number;
+  distinctLabels: number;
+  /** Most common label's count over the least common's. */
+  imbalanceRatio: number;
+  /** 1.0 when every label is equally frequent. */
+  normalizedEntropy: number;
+  /** Most common first. */
+  labels: { label: string; name?: string | null; count: number; fraction: number }[];
+  otherLabels: number;
+  unreadableChunks: string[];
+};
+
+/** Label frequencies of one field, over every item unless `sampleItems` is set. */
+export async function classBalance(params: {
+  indexPath: string;
+  fieldIndex: number;
+  sampleItems?: number | null;
+  jobId?: string | null;
+}): Promise<ClassBalanceReport> {
+  await requireTauri("Counting labels");
+  return invoke<ClassBalanceReport>("class_balance", {
+    indexPath: params.indexPath,
+    fieldIndex: params.fieldIndex,
+    sampleItems: params.sampleItems ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type DatasetDiff = {
+  jobId: string;
+  aIndex: string;
+  bIndex: string;
+  identical: boolean;
+  aChunkCount: number;
+  bChunkCount: number;
+  aTotalItems: number;
+  bTotalItems: number;
+  configChanges: { key: string; a?: unknown; b?: unknown }[];
+  chunksAdded: string[];
+  chunksRemoved: string[];
+  chunksChanged: {
+    filename: string;
+    aItems?: number | null;
+    bItems?: number | null;
+    aBytes?: number | null;
+    bBytes?: number | null;
+  }[];
+  items?: {
+    aItems: number;
+    bItems: number;
+    addedCount: number;
+    removedCount: number;
+    changedCount: number;
+    added: ItemLocation[];
+    removed: ItemLocation[];
+    changed: { globalIndex: number; a: ItemLocation; b: ItemLocation }[];
+  } | null;
+};
+
+export async function diffDatasets(params: {
+  aPath: string;
+  bPath: string;
+  compareItems?: boolean;
+  jobId?: string | null;
+}): Promise<DatasetDiff> {
+  await requireTauri("Comparing datasets");
+  return invoke<DatasetDiff>("diff_datasets", {
+    aPath: params.aPath,
+    bPath: params.bPath,
+    compareItems: params.compareItems ?? false,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export async function setAllowExternalChunks(allow: boolean): Promise<boolean> {
+  await requireTauri("Changing chunk path policy");
+  return invoke<boolean>("set_allow_external_chunks", { allow });
+}
+
+export type SalvagedItem = {
+  salvageIndex: number;
+  start: number;
+  end: number;
+  fieldSizes: number[];
+  source: "offsets" | "scan";
+};
+
+export type SalvageReport = {
+  chunkFilename: string;
+  chunkLen: number;
+  streamTruncated: boolean;
+  offsetsItems: number;
+  scannedItems: number;
+  unrecoveredBytes: number;
+  items: SalvagedItem[];
+};
+
+export async function salvageChunk(params: { indexPath: string; chunkFilename: string }): Promise<SalvageReport> {
+  await requireTauri("Salvaging chunk");
+  return invoke<SalvageReport>("salvage_chunk", params);
+}
+
+export async function peekSalvagedField(params: {
+  indexPath: string;
+  chunkFilename: string;
+  itemStart: number;
+  fieldIndex: number;
+}): Promise<FieldPreview> {
+  await requireTauri("Previewing salvaged data");
+  return invoke<FieldPreview>("peek_salvaged_field", params);
+}
+
+export type FieldSchema = {
+  fieldIndex: number;
+  declared?: string | null;
+  contentTypes: Record<string, number>;
+  minSize: number;
+  medianSize: number;
+  maxSize: number;
+};
+
+export type SchemaReport = {
+  declaredFormat: string[];
+  sampledItems: number;
+  arityCounts: Record<string, number>;
+  fields: FieldSchema[];
+  inconsistentChunks: {
+    filename: string;
+    detectedArity?: number | null;
+    mismatchedFields: { fieldIndex: number; declared: string; observed: string }[];
+  }[];
+  unreadableChunks: string[];
+};
+
+export async function inferSchema(params: { indexPath: string; samplesPerChunk?: number }): Promise<SchemaReport> {
+  await requireTauri("Inferring schema");
+  return invoke<SchemaReport>("infer_schema", {
+    indexPath: params.indexPath,
+    samplesPerChunk: params.samplesPerChunk ?? null,
+  });
+}
+
+export type HealthReport = {
+  indexPath: string;
+  referencedChunks: number;
+  healthy: boolean;
+  missing: { filename: string; path: string; items: number; bytes: number }[];
+  missingItems: number;
+  orphans: { path: string; bytes: number; items?: number | null }[];
+  orphanItems: number;
+  orphanBytes: number;
+  duplicates: { filename: string; path: string; sameAs: string; items: number }[];
+};
+
+export async function datasetHealth(indexPath: string): Promise<HealthReport> {
+  await requireTauri("Checking dataset health");
+  return invoke<HealthReport>("dataset_health", { indexPath: indexPath.trim() });
+}
+
+export type IterationConfig = {
+  shuffle: boolean;
+  seed?: number | null;
+  epoch?: number | null;
+  numWorkers: number;
+  batchSize: number;
+  dropLast: boolean;
+  worldSize?: number | null;
+  rank?: number | null;
+};
+
+export type BatchLocation = {
+  step: number;
+  worker: number;
+  batchIndex: number;
+  totalSteps: number;
+  workerBatches: number[];
+  samples: {
+    position: number;
+    globalIndex: number;
+    chunkIndex: number;
+    chunkFilename: string;
+    itemIndex: number;
+  }[];
+};
+
+export async function locateTrainingBatch(params: {
+  indexPath: string;
+  config: IterationConfig;
+  step?: number | null;
+  worker?: number | null;
+  batchIndex?: number | null;
+}): Promise<BatchLocation> {
+  await requireTauri("Locating training batch");
+  return invoke<BatchLocation>("locate_training_batch", {
+    indexPath: params.indexPath,
+    config: params.config,
+    step: params.step ?? null,
+    worker: params.worker ?? null,
+    batchIndex: params.batchIndex ?? null,
+  });
+}
+
+export type CacheStats = {
+  budgetBytes: number;
+  usedBytes: number;
+  entries: number;
+  hits: number;
+  misses: number;
+  evictions: number;
+  evictedBytes: number;
+  diskEntries: number;
+  diskBytes: number;
+  openStreams: number;
+  streamBytes: number;
+};
+
+export async function getCacheStats(): Promise<CacheStats> {
+  await requireTauri("Reading cache stats");
+  return invoke<CacheStats>("get_cache_stats");
+}
+
+export async function clearCache(includeDisk = false): Promise<CacheStats> {
+  await requireTauri("Clearing cache");
+  return invoke<CacheStats>("clear_cache", { includeDisk });
+}
+
+export async function setChunkCacheBudget(budgetBytes: number): Promise<CacheStats> {
+  await requireTauri("Updating cache budget");
+  return invoke<CacheStats>("set_chunk_cache_budget", { budgetBytes });
+}
+
+export type MemoryStats = {
+  limitBytes: number;
+  cachedBytes: number;
+  inFlightBytes: number;
+};
+
+export async function getMemoryStats(): Promise<MemoryStats> {
+  await requireTauri("Reading memory stats");
+  return invoke<MemoryStats>("get_memory_stats");
+}
+
+export async function setMemoryLimit(limitBytes: number): Promise<MemoryStats> {
+  await requireTauri("Updating memory limit");
+  return invoke<MemoryStats>("set_memory_limit", { limitBytes });
+}
+
+export type ReadThroughput = {
+  items: number;
+  bytes: number;
+  seconds: number;
+  itemsPerSec: number;
+  mbPerSec: number;
+};
+
+export type ChunkBenchmark = {
+  filename: string;
+  items: number;
+  storedBytes: number;
+  decompressedBytes: number;
+  /** Stored-file read throughput; zstd chunks only. */
+  readMbPerSec?: number | null;
+  decompressMbPerSec?: number | null;
+  sequentialItemsPerSec: number;
+  error?: string | null;
+};
+
+export type BenchmarkReport = {
+  jobId: string;
+  indexPath: string;
+  compression?: string | null;
+  chunks: ChunkBenchmark[];
+  sequential: ReadThroughput;
+  randomCold: ReadThroughput;
+  randomWarm: ReadThroughput;
+};
+
+export async function benchmarkDataset(params: {
+  indexPath: string;
+  samples?: number | null;
+  seed?: number | null;
+  jobId?: string | null;
+}): Promise<BenchmarkReport> {
+  await requireTauri("Benchmarking dataset");
+  return invoke<BenchmarkReport>("benchmark_dataset", {
+    indexPath: params.indexPath.trim(),
+    samples: params.samples ?? null,
+    seed: params.seed ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type TrainingEstimate = {
+  jobId: string;
+  indexPath: string;
+  batchSize: number;
+  numWorkers: number;
+  /** Capped by CPU cores and by the number of chunks. */
+  effectiveWorkers: number;
+  cpuCores: number;
+  totalItems: number;
+  singleWorkerItemsPerSec: number;
+  itemsPerSec: number;
+  batchesPerSec: number;
+  stepsPerEpoch: number;
+  epochSeconds: number;
+  firstBatchSeconds: number;
+  /** Share of one worker's time per step; sums to 1. */
+  timeSplit: { storage: number; decompression: number; itemReads: number };
+  bottleneck: "storage" | "decompression" | "item_reads" | "too_few_chunks";
+  unreadableChunks: string[];
+};
+
+/** Samples/sec and epoch time for a batch size and worker count, from an uncached read of every chunk. */
+export async function estimateTrainingTime(params: {
+  indexPath: string;
+  batchSize: number;
+  numWorkers: number;
+  jobId?: string | null;
+}): Promise<TrainingEstimate> {
+  await requireTauri("Estimating training time");
+  return invoke<TrainingEstimate>("estimate_training_time", {
+    indexPath: params.indexPath.trim(),
+    batchSize: params.batchSize,
+    numWorkers: params.numWorkers,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type SpanTiming = {
+  name: string;
+  target: string;
+  fields: string;
+  startedAtMs: number;
+  durationUs: number;
+};
+
+export type SpanSummary = {
+  name: string;
+  count: number;
+  totalUs: number;
+  maxUs: number;
+};
+
+export type TraceDump = {
+  spans: SpanTiming[];
+  summary: SpanSummary[];
+};
+
+export async function getRecentSpans(params: { limit?: number | null; name?: string | null } = {}): Promise<TraceDump> {
+  await requireTauri("Reading timings");
+  return invoke<TraceDump>("get_recent_spans", { limit: params.limit ?? null, name: params.name ?? null });
+}
+
+export async function clearRecentSpans(): Promise<void> {
+  await requireTauri("Clearing timings");
+  return invoke<void>("clear_recent_spans");
+}
+
+export type RecentDataset = {
+  indexPath: string;
+  openedAt: number;
+  chunkCount: number;
+  itemCount: number;
+  valid?: boolean | null;
+};
+
+export async function getRecentDatasets(): Promise<RecentDataset[]> {
+  if (!isTauri()) return [];
+  return invoke<RecentDataset[]>("get_recent_datasets");
+}
+
+export async function removeRecent(indexPath: string): Promise<RecentDataset[]> {
+  await requireTauri("Updating recent datasets");
+  return invoke<RecentDataset[]>("remove_recent", { indexPath });
+}
+
+export type PinnedDataset = {
+  indexPath: string;
+  displayName?: string | null;
+  /** Hex color such as `#10b981`. */
+  color?: string | null;
+  pinnedAt: number;
+};
+
+export async function getPinnedDatasets(): Promise<PinnedDataset[]> {
+  if (!isTauri()) return [];
+  return invoke<PinnedDataset[]>("get_pinned_datasets");
+}
+
+export async function pinDataset(
+  indexPath: string,
+  options: { displayName?: string | null; color?: string | null } = {},
+): Promise<PinnedDataset[]> {
+  await requireTauri("Pinning datasets");
+  return invoke<PinnedDataset[]>("pin_dataset", {
+    indexPath,
+    displayName: options.displayName ?? null,
+    color: options.color ?? null,
+  });
+}
+
+export async function unpinDataset(indexPath: string): Promise<PinnedDataset[]> {
+  await requireTauri("Pinning datasets");
+  return invoke<PinnedDataset[]>("unpin_dataset", { indexPath });
+}
+
+export type DatasetUsage = {
+  indexPath: string;
+  openCount: number;
+  firstOpenedAt: number;
+  lastOpenedAt: number;
+  /** Field payload bytes shown, exported or opened externally. */
+  bytesRead: number;
+};
+
+export async function getUsageStats(): Promise<DatasetUsage[]> {
+  if (!isTauri()) return [];
+  return invoke<DatasetUsage[]>("get_usage_stats");
+}
+
+export async function clearUsageStats(): Promise<void> {
+  await requireTauri("Clearing usage stats");
+  await invoke("clear_usage_stats");
+}
+
+export type Note = {
+  text: string;
+  updatedAt: number;
+};
+
+export type DatasetNotes = {
+  /** Content hash of the index; notes follow the dataset across moves. */
+  fingerprint: string;
+  indexPath: string;
+  dataset?: Note | null;
+  chunks: Record<string, Note>;
+};
+
+export async function getDatasetNotes(indexPath: string): Promise<DatasetNotes | null> {
+  if (!isTauri()) return null;
+  return invoke<DatasetNotes>("get_dataset_notes", { indexPath });
+}
+
+/** Sets the dataset note, or a chunk's note when `chunkFilename` is given; empty text removes it. */
+export async function setDatasetNote(params: {
+  indexPath: string;
+  chunkFilename?: string | null;
+  text: string;
+}): Promise<DatasetNotes> {
+  await requireTauri("Saving notes");
+  return invoke<DatasetNotes>("set_dataset_note", { ...params, chunkFilename: params.chunkFilename ?? null });
+}
+
+export type RemoteSettings = {
+  endpoint?: string | null;
+  region?: string | null;
+  profile?: string | null;
+  anonymous: boolean;
+  /** MLflow server or file store for `runs:/` sources; falls back to MLFLOW_TRACKING_URI. */
+  mlflowTrackingUri?: string | null;
+  /** W&B API host; falls back to WANDB_BASE_URL, then api.wandb.ai. */
+  wandbBaseUrl?: string | null;
+};
+
+export type ExternalViewer = {
+  program: string;
+  /** Full argument list; `{path}` is replaced with the field file. Empty means "open with". */
+  args?: string[];
+};
+
+export type HookEvent = "open" | "export";
+
+/**
+ * Command run after a field is opened or exported. `args` and `env` values may use
+ * `{path}`, `{ext}`, `{index}`, `{chunk}`, `{item}` and `{field}`; hooks also receive
+ * `LITDATA_FILE`, `LITDATA_EXT`, `LITDATA_INDEX`, `LITDATA_CHUNK`, `LITDATA_ITEM`,
+ * `LITDATA_FIELD` and `LITDATA_EVENT` in their environment.
+ */
+export type HookRule = {
+  name?: string | null;
+  /** Empty fires on every event. */
+  events?: HookEvent[];
+  /** Extensions without the dot; empty matches all. */
+  extensions?: string[];
+  program: string;
+  args?: string[];
+  env?: Record<string, string>;
+};
+
+export type Settings = {
+  chunkCacheBudgetBytes: number;
+  maxCachedChunkBytes: number;
+  diskCacheBudgetBytes: number;
+  memoryLimitBytes: number;
+  previewBytes: number;
+  tempDir?: string | null;
+  thumbnailSize: number;
+  ioTimeoutSecs: number;
+  allowExternalChunks: boolean;
+  readOnly: boolean;
+  remote: RemoteSettings;
+  /** Extension without the dot, e.g. `wav`, to the program used by `openLeaf`. */
+  externalViewers: Record<string, ExternalViewer>;
+  hooks: HookRule[];
+};
+
+export async function getSettings(): Promise<Settings> {
+  await requireTauri("Reading settings");
+  return invoke<Settings>("get_settings");
+}
+
+export async function updateSettings(settings: Settings): Promise<Settings> {
+  await requireTauri("Saving settings");
+  return invoke<Settings>("update_settings", { settings });
+}
+
+export type LogLevel = "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+
+export type LogRecord = {
+  timestampMs: number;
+  level: LogLevel;
+  target: string;
+  message: string;
+  fields?: string;
+};
+
+export type LogDump = {
+  logDir: string;
+  records: LogRecord[];
+};
+
+export async function getRecentLogs(params: { limit?: number | null; level?: LogLevel | null } = {}): Promise<LogDump> {
+  await requireTauri("Reading logs");
+  return invoke<LogDump>("get_recent_logs", { limit: params.limit ?? null, level: params.level ?? null });
+}
+
+export type ErrorContext = {
+  dataset?: string | null;
+  chunk?: string | null;
+  itemIndex?: number | null;
+  fieldIndex?: number | null;
+  byteOffset?: number | null;
+};
+
+/** Shape of a rejected command: `{ code, message }`, with `Read` carrying structured context. */
+export type AppError =
+  | { code: "Read"; message: { context: ErrorContext; cause: AppError; retryable: boolean } }
+  | { code: "NewerFormat"; message: { found: number; supported: number } }
+  | { code: string; message?: unknown };
+
+function isAppError(err: unknown): err is AppError {
+  return typeof err === "object" && err !== null && typeof (err as { code?: unknown }).code === "string";
+}
+
+function describeContext(context: ErrorContext): string {
+  const parts: string[] = [];
+  if (context.itemIndex != null) parts.push(`item ${context.itemIndex}`);
+  if (context.fieldIndex != null) parts.push(`field ${context.fieldIndex}`);
+  if (context.chunk) parts.push(`in ${context.chunk}`);
+  if (context.byteOffset != null) parts.push(`at offset 0x${context.byteOffset.toString(16).toUpperCase()}`);
+  if (!parts.length && context.dataset) parts.push(context.dataset);
+  return parts.join(" ");
+}
+
+/** Human-readable text for anything a command or the UI may throw. */
+export function describeError(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  if (!isAppError(err)) return String(err);
+  if (err.code === "Read") {
+    const { context, cause } = (err as Extract<AppError, { code: "Read" }>).message;
+    return `Failed reading ${describeContext(context)}: ${describeError(cause)}`;
+  }
+  if (err.code === "NewerFormat") {
+    const { found, supported } = (err as Extract<AppError, { code: "NewerFormat" }>).message;
+    return `Index format version ${found} is newer than this viewer understands (up to ${supported}); update the viewer to open it.`;
+  }
+  if (typeof err.message === "string") return `${err.code}: ${err.message}`;
+  if (err.message && typeof err.message === "object" && "detail" in err.message) {
+    return `${err.code}: ${String((err.message as { detail: unknown }).detail)}`;
+  }
+  return err.code;
+}
+
+export function isRetryableError(err: unknown): boolean {
+  if (!isAppError(err)) return false;
+  if (err.code === "Read") return (err as Extract<AppError, { code: "Read" }>).message.retryable;
+  return ["Io", "Timeout", "Task", "MemoryLimit"].includes(err.code);
+}
+
+export async function revealInFileManager(path: string): Promise<void> {
+  await requireTauri("Revealing files");
+  return invoke<void>("reveal_in_file_manager", { path });
+}
+
+export type Bookmark = {
+  indexPath: string;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex?: number | null;
+  label?: string | null;
+  tags: string[];
+  createdAt: number;
+};
+
+export async function getBookmarks(indexPath?: string | null): Promise<Bookmark[]> {
+  if (!isTauri()) return [];
+  return invoke<Bookmark[]>("get_bookmarks", { indexPath: indexPath ?? null });
+}
+
+export async function addBookmark(params: {
+  indexPath: string;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex?: number | null;
+  label?: string | null;
+  tags?: string[] | null;
+}): Promise<Bookmark[]> {
+  await requireTauri("Saving bookmarks");
+  return invoke<Bookmark[]>("add_bookmark", {
+    ...params,
+    fieldIndex: params.fieldIndex ?? null,
+    label: params.label ?? null,
+    tags: params.tags ?? null,
+  });
+}
+
+export async function removeBookmark(params: {
+  indexPath: string;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex?: number | null;
+}): Promise<Bookmark[]> {
+  await requireTauri("Updating bookmarks");
+  return invoke<Bookmark[]>("remove_bookmark", { ...params, fieldIndex: params.fieldIndex ?? null });
+}
+
+export type PaletteMatchKind = "chunk" | "bookmark" | "tag" | "recent_dataset";
+
+export type PaletteMatch = {
+  kind: PaletteMatchKind;
+  label: string;
+  detail?: string | null;
+  score: number;
+  /** Char indices of `label` that matched the query, for highlighting. */
+  positions: number[];
+  handle?: DatasetHandle | null;
+  indexPath?: string | null;
+  chunkFilename?: string | null;
+  itemIndex?: number | null;
+  fieldIndex?: number | null;
+};
+
+export async function fuzzyFind(query: string, limit?: number | null): Promise<PaletteMatch[]> {
+  if (!isTauri()) return [];
+  return invoke<PaletteMatch[]>("fuzzy_find", { query, limit: limit ?? null });
+}
+
+export type RelativeItem = {
+  chunkFilename: string;
+  itemIndex: number;
+  globalIndex: number;
+  totalItems: number;
+  clamped: boolean;
+};
+
+export async function resolveRelativeItem(params: {
+  handle: DatasetHandle;
+  currentChunk: string;
+  currentItem: number;
+  delta: number;
+}): Promise<RelativeItem> {
+  await requireTauri("Navigating items");
+  return invoke<RelativeItem>("resolve_relative_item", params);
+}
+
+export type ApiServerStatus = {
+  running: boolean;
+  address?: string | null;
+  /** Send as `Authorization: Bearer <token>`; regenerated on every start. */
+  token?: string | null;
+};
+
+export async function startApiServer(port?: number | null): Promise<ApiServerStatus> {
+  await requireTauri("Starting the API server");
+  return invoke<ApiServerStatus>("start_api_server", { port: port ?? null });
+}
+
+export async function stopApiServer(): Promise<ApiServerStatus> {
+  await requireTauri("Stopping the API server");
+  return invoke<ApiServerStatus>("stop_api_server");
+}
+
+export async function getApiServerStatus(): Promise<ApiServerStatus> {
+  if (!isTauri()) return { running: false };
+  return invoke<ApiServerStatus>("get_api_server_status");
+}
+
+export type DiscoveredDataset = {
+  indexPath: string;
+  /** Directory of the index relative to the scanned root; empty for the root itself. */
+  relativeDir: string;
+  chunkCount: number;
+  itemCount: number;
+  totalBytes: number;
+  dataFormat: string[];
+  compression?: string | null;
+  error?: string | null;
+};
+
+export type DatasetScan = {
+  root: string;
+  directoriesScanned: number;
+  unreadable: string[];
+  datasets: DiscoveredDataset[];
+};
+
+export async function scanForDatasets(
+  root: string,
+  options: { maxDepth?: number | null; jobId?: string | null } = {},
+): Promise<DatasetScan> {
+  await requireTauri("Scanning for datasets");
+  return invoke<DatasetScan>("scan_for_datasets", {
+    root: root.trim(),
+    maxDepth: options.maxDepth ?? null,
+    jobId: options.jobId ?? null,
+  });
+}
+
+export type DecoderPlugin = {
+  name: string;
+  version?: string | null;
+  /** `data_format` entries the plugin decodes. */
+  formats: string[];
+  module: string;
+};
+
+export type DecodedField = {
+  plugin: string;
+  kind: "text" | "json" | "image";
+  text?: string | null;
+  json?: unknown;
+  mime?: string | null;
+  imageBase64?: string | null;
+};
+
+export async function listDecoderPlugins(): Promise<DecoderPlugin[]> {
+  if (!isTauri()) return [];
+  return invoke<DecoderPlugin[]>("list_decoder_plugins");
+}
+
+/** Installs the plugin described by a `plugin.json` manifest next to its `.wasm` module. */
+export async function installDecoderPlugin(manifestPath: string): Promise<DecoderPlugin[]> {
+  await requireTauri("Installing decoder plugins");
+  return invoke<DecoderPlugin[]>("install_decoder_plugin", { manifestPath });
+}
+
+export async function removeDecoderPlugin(name: string): Promise<DecoderPlugin[]> {
+  await requireTauri("Removing decoder plugins");
+  return invoke<DecoderPlugin[]>("remove_decoder_plugin", { name });
+}
+
+export async function decodeField(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  itemIndex: number;
+  fieldIndex: number;
+}): Promise<DecodedField> {
+  await requireTauri("Decoding fields");
+  return invoke<DecodedField>("decode_field", params);
+}
+
+export type RecompressReport = {
+  jobId: string;
+  indexPath: string;
+  chunkCount: number;
+  inputBytes: number;
+  outputBytes: number;
+  frameSize: number;
+};
+
+export async function recompressDataset(params: {
+  indexPath: string;
+  destDir: string;
+  frameSize?: number | null;
+  level?: number | null;
+  jobId?: string | null;
+}): Promise<RecompressReport> {
+  await requireTauri("Recompressing dataset");
+  return invoke<RecompressReport>("recompress_dataset", {
+    indexPath: params.indexPath,
+    destDir: params.destDir,
+    frameSize: params.frameSize ?? null,
+    level: params.level ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ChunkEdit = {
+  filename: string;
+  itemsBefore: number;
+  itemsAfter: number;
+  /** Every item was removed, so the chunk left the index. */
+  dropped: boolean;
+};
+
+export type DeleteReport = {
+  jobId: string;
+  indexPath: string;
+  /** `false` for a preview; nothing was written. */
+  applied: boolean;
+  removedItems: number;
+  remainingItems: number;
+  chunks: ChunkEdit[];
+  backupDir?: string | null;
+};
+
+/** Removes items by global index and rewrites the affected chunks; previews unless `confirm` is set. The handle then reads the rewritten index. */
+export async function deleteItems(params: {
+  handle: DatasetHandle;
+  globalIndices: number[];
+  confirm: boolean;
+  jobId?: string | null;
+}): Promise<DeleteReport> {
+  await requireTauri("Deleting items");
+  return invoke<DeleteReport>("delete_items", {
+    handle: params.handle,
+    globalIndices: params.globalIndices,
+    confirm: params.confirm,
+    jobId: params.jobId ?? null,
+  });
+}
+
+/** One field's payload, already in the field's serialized form (e.g. encoded JPEG bytes for `jpeg`). */
+export type FieldSource = { file: string } | { text: string } | { base64: string };
+
+export type AppendReport = {
+  jobId: string;
+  indexPath: string;
+  appendedItems: number;
+  totalItems: number;
+  chunks: { filename: string; items: number; chunkBytes: number }[];
+  backupDir: string;
+};
+
+/** Packs items (one source per `data_format` field) into new chunks and appends them to index.json. The handle then reads the new index. */
+export async function appendItems(params: {
+  handle: DatasetHandle;
+  items: FieldSource[][];
+  jobId?: string | null;
+}): Promise<AppendReport> {
+  await requireTauri("Appending items");
+  return invoke<AppendReport>("append_items", {
+    handle: params.handle,
+    items: params.items,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ReplaceReport = {
+  jobId: string;
+  indexPath: string;
+  globalIndex: number;
+  fieldIndex: number;
+  chunkFilename: string;
+  itemIndex: number;
+  oldBytes: number;
+  newBytes: number;
+  chunkBytes: number;
+  backupDir: string;
+};
+
+/** Swaps one field of one item for new bytes, rewriting its chunk and index.json. The handle then reads the rewritten index. */
+export async function replaceField(params: {
+  handle: DatasetHandle;
+  globalIndex: number;
+  fieldIndex: number;
+  source: FieldSource;
+  jobId?: string | null;
+}): Promise<ReplaceReport> {
+  await requireTauri("Replacing a field");
+  return invoke<ReplaceReport>("replace_field", {
+    handle: params.handle,
+    globalIndex: params.globalIndex,
+    fieldIndex: params.fieldIndex,
+    source: params.source,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type FolderField = "contents" | "fileName" | "relativePath";
+
+export type FolderLayout = {
+  fields: FolderField[];
+  /** data_format for `contents` fields; `bytes` when omitted. */
+  contentFormat?: string | null;
+  /** Only files with these extensions; all files when omitted. */
+  extensions?: string[] | null;
+  chunkSize?: number | null;
+  chunkBytes?: number | null;
+  compression?: "zstd" | null;
+  /** Write `index.json.zstd` instead of `index.json`. */
+  compressIndex?: boolean;
+};
+
+export type FolderDatasetReport = {
+  jobId: string;
+  indexPath: string;
+  items: number;
+  chunkCount: number;
+  dataFormat: string[];
+  inputBytes: number;
+  outputBytes: number;
+};
+
+/** Packs every file under a folder, one item per file, into a new dataset in `destDir`. */
+export async function createDatasetFromFolder(params: {
+  sourceDir: string;
+  destDir: string;
+  layout: FolderLayout;
+  jobId?: string | null;
+}): Promise<FolderDatasetReport> {
+  await requireTauri("Creating a dataset");
+  return invoke<FolderDatasetReport>("create_dataset_from_folder", {
+    sourceDir: params.sourceDir,
+    destDir: params.destDir,
+    layout: params.layout,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type MergeReport = {
+  jobId: string;
+  indexPath: string;
+  sources: { indexPath: string; chunks: number; items: number; firstChunk: string | null }[];
+  chunkCount: number;
+  totalItems: number;
+  linkedChunks: number;
+  copiedBytes: number;
+};
+
+/** Combines datasets with the same data_format into one index in `destDir`, renumbering chunks. */
+export async function mergeDatasets(params: {
+  indexPaths: string[];
+  destDir: string;
+  /** Hard-link chunks where the filesystem allows it instead of copying. */
+  link?: boolean;
+  /** Write `index.json.zstd` instead of `index.json`. */
+  compressIndex?: boolean;
+  jobId?: string | null;
+}): Promise<MergeReport> {
+  await requireTauri("Merging datasets");
+  return invoke<MergeReport>("merge_datasets", {
+    indexPaths: params.indexPaths,
+    destDir: params.destDir,
+    link: params.link ?? false,
+    compressIndex: params.compressIndex ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type RenumberReport = {
+  jobId: string;
+  indexPath: string;
+  applied: boolean;
+  renames: { from: string; to: string }[];
+  unchanged: number;
+  backupDir: string | null;
+  validation: ValidationReport | null;
+};
+
+/** Renames chunks to `chunk-0-<i>` in index order and validates the result; a preview unless `confirm`. */
+export async function renumberChunks(params: {
+  indexPath: string;
+  confirm: boolean;
+  jobId?: string | null;
+}): Promise<RenumberReport> {
+  await requireTauri("Renumbering chunks");
+  return invoke<RenumberReport>("renumber_chunks", {
+    indexPath: params.indexPath,
+    confirm: params.confirm,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type IndexConversion = {
+  indexPath: string;
+  previousPath: string;
+  compressed: boolean;
+  converted: boolean;
+  bytesBefore: number;
+  bytesAfter: number;
+  backupDir: string | null;
+};
+
+/** Rewrites a dataset's index as `index.json.zstd` (or back to plain JSON); open `indexPath` afterwards. */
+export async function convertIndexFormat(indexPath: string, compressed: boolean): Promise<IndexConversion> {
+  await requireTauri("Converting the index");
+  return invoke<IndexConversion>("convert_index_format", { indexPath, compressed });
+}
+
+export type ChunkRepair = {
+  filename: string;
+  action: "corrected" | "dropped" | "unrepairable";
+  declaredItems: number;
+  actualItems: number | null;
+  declaredBytes: number;
+  actualBytes: number | null;
+  reason: string | null;
+};
+
+export type RepairReport = {
+  jobId: string;
+  indexPath: string;
+  applied: boolean;
+  checkedChunks: number;
+  repairs: ChunkRepair[];
+  backupDir: string | null;
+};
+
+/** Recomputes chunk_size/chunk_bytes from the chunk files and fixes index.json; a preview unless `confirm`. */
+export async function repairIndex(params: {
+  indexPath: string;
+  /** Remove entries whose chunk file is missing. */
+  dropMissing?: boolean;
+  confirm: boolean;
+  jobId?: string | null;
+}): Promise<RepairReport> {
+  await requireTauri("Repairing the index");
+  return invoke<RepairReport>("repair_index", {
+    indexPath: params.indexPath,
+    dropMissing: params.dropMissing ?? false,
+    confirm: params.confirm,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type RedactionRule = {
+  /** Regular expression in Rust `regex` syntax. */
+  pattern: string;
+  /** May refer to capture groups as `$1` or `${name}`. */
+  replacement: string;
+};
+
+export type RedactReport = {
+  jobId: string;
+  indexPath: string;
+  fields: number[];
+  chunkCount: number;
+  itemsChanged: number;
+  rules: { pattern: string; matches: number }[];
+};
+
+/** Writes a copy of the dataset to `destDir` with `rules` applied to its text fields. */
+export async function redactDataset(params: {
+  indexPath: string;
+  destDir: string;
+  rules: RedactionRule[];
+  /** Defaults to every str/text field. */
+  fields?: number[];
+  jobId?: string | null;
+}): Promise<RedactReport> {
+  await requireTauri("Redacting a dataset");
+  return invoke<RedactReport>("redact_dataset", {
+    indexPath: params.indexPath,
+    destDir: params.destDir,
+    rules: params.rules,
+    fields: params.fields ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ArtifactSource = {
+  uri: string;
+  provider: "mlflow" | "wandb";
+  /** Where the dataset's files live, e.g. `s3://bucket/prefix` or a local directory. */
+  resolved: string;
+  /** Directory to pass to `openDataset`; null when the storage is not readable from here. */
+  localPath: string | null;
+  downloadedFiles: number;
+  downloadedBytes: number;
+};
+
+/**
+ * Resolves `runs:/<run_id>/<path>`, `mlflow-artifacts:/<path>` or
+ * `wandb://<entity>/<project>/<name>:<alias>` to the dataset behind it, downloading
+ * artifacts that are only served through the MLflow tracking server.
+ */
+export async function resolveArtifact(params: { uri: string; jobId?: string | null }): Promise<ArtifactSource> {
+  await requireTauri("Resolving artifacts");
+  return invoke<ArtifactSource>("resolve_artifact", { uri: params.uri, jobId: params.jobId ?? null });
+}
+
+export type DvcSource = {
+  /** The `.dvc` file describing the dataset. */
+  pointer: string;
+  repoRoot: string;
+  md5: string;
+  remote: string | null;
+  remoteUrl: string | null;
+  /** Directory to pass to `openDataset`. */
+  localPath: string;
+  /** True when `localPath` is the repository's own checkout. */
+  checkedOut: boolean;
+  files: number;
+  fromCache: number;
+  fetched: number;
+  fetchedBytes: number;
+};
+
+/**
+ * Opens a DVC-tracked dataset by its `.dvc` file or tracked path, assembling it from the
+ * repository cache and the configured remote when it is not checked out.
+ */
+export async function resolveDvc(params: {
+  path: string;
+  remote?: string | null;
+  jobId?: string | null;
+}): Promise<DvcSource> {
+  await requireTauri("Opening DVC datasets");
+  return invoke<DvcSource>("resolve_dvc", {
+    path: params.path,
+    remote: params.remote ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type SharedMedia = {
+  token: string;
+  /** Loopback URL; anyone holding it can read until it expires. */
+  url: string;
+  fileName: string;
+  contentType: string;
+  bytes: number;
+  /** Unix seconds. */
+  expiresAt: number;
+};
+
+/**
+ * Serves a field (pass both `itemIndex` and `fieldIndex`) or the whole chunk file at a
+ * temporary localhost URL, e.g. for a Jupyter notebook.
+ */
+export async function shareMedia(params: {
+  handle: DatasetHandle;
+  chunkFilename: string;
+  itemIndex?: number | null;
+  fieldIndex?: number | null;
+  ttlSecs?: number | null;
+}): Promise<SharedMedia> {
+  await requireTauri("Sharing media");
+  return invoke<SharedMedia>("share_media", {
+    handle: params.handle,
+    chunkFilename: params.chunkFilename,
+    itemIndex: params.itemIndex ?? null,
+    fieldIndex: params.fieldIndex ?? null,
+    ttlSecs: params.ttlSecs ?? null,
+  });
+}
+
+/** Revokes one shared URL, or every one when `token` is omitted. */
+export async function unshareMedia(token?: string | null): Promise<number> {
+  await requireTauri("Revoking shared media");
+  return invoke<number>("unshare_media", { token: token ?? null });
+}
+
+export async function listSharedMedia(): Promise<SharedMedia[]> {
+  await requireTauri("Listing shared media");
+  return invoke<SharedMedia[]>("list_shared_media");
+}
+
+export type AnnotationFormat = "coco" | "yolo";
+export type BoxFormat = "xywh" | "xyxy" | "cxcywh";
+
+export type AnnotationExportReport = {
+  jobId: string;
+  format: AnnotationFormat;
+  destDir: string;
+  /** `annotations.json` for COCO, `data.yaml` for YOLO. */
+  annotationPath: string;
+  images: number;
+  annotations: number;
+  categories: { id: number; name: string; annotations: number }[];
+  skippedItems: number;
+  skipped: { chunkFilename: string; itemIndex: number; reason: string }[];
+};
+
+/**
+ * Exports an image field and a JSON box/label field as a COCO or YOLO annotation set in
+ * an empty `destDir`.
+ */
+export async function exportAnnotations(params: {
+  indexPath: string;
+  imageField: number;
+  annotationField: number;
+  destDir: string;
+  format: AnnotationFormat;
+  boxFormat?: BoxFormat | null;
+  normalizedBoxes?: boolean | null;
+  jobId?: string | null;
+}): Promise<AnnotationExportReport> {
+  await requireTauri("Exporting annotations");
+  return invoke<AnnotationExportReport>("export_annotations", {
+    indexPath: params.indexPath,
+    imageField: params.imageField,
+    annotationField: params.annotationField,
+    destDir: params.destDir,
+    format: params.format,
+    boxFormat: params.boxFormat ?? null,
+    normalizedBoxes: params.normalizedBoxes ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ClassNames = {
+  fingerprint: string;
+  indexPath: string;
+  sourcePath: string;
+  /** Fields whose integer values are shown by name. */
+  fields: number[];
+  /** Class names by id; JSON object keys are the ids as strings. */
+  names: Record<string, string>;
+};
+
+/**
+ * Imports a JSON or YAML class map (`{0: "cat"}`, a list of names, or a YOLO `data.yaml`)
+ * for a dataset. It applies to `fields`, or to every `int` field when omitted.
+ */
+export async function importClassNames(params: {
+  indexPath: string;
+  path: string;
+  fields?: number[] | null;
+}): Promise<ClassNames> {
+  await requireTauri("Importing class names");
+  return invoke<ClassNames>("import_class_names", {
+    indexPath: params.indexPath,
+    path: params.path,
+    fields: params.fields ?? null,
+  });
+}
+
+export async function getClassNames(indexPath: string): Promise<ClassNames | null> {
+  await requireTauri("Reading class names");
+  return invoke<ClassNames | null>("get_class_names", { indexPath });
+}
+
+export async function clearClassNames(indexPath: string): Promise<boolean> {
+  await requireTauri("Clearing class names");
+  return invoke<boolean>("clear_class_names", { indexPath });
+}
+
+export type PlaylistReport = {
+  jobId: string;
+  playlistPath: string;
+  entries: number;
+  totalSeconds: number;
+  skipped: { globalIndex: number; reason: string }[];
+  /** Media tokens of `http` entries; pass to `unshareMedia` when done listening. */
+  tokens: string[];
+  opened: boolean;
+};
+
+/**
+ * Writes an M3U playlist of an audio field for the selected items, as temp files or
+ * localhost URLs, and optionally opens it in the default player.
+ */
+export async function exportAudioPlaylist(params: {
+  handle: DatasetHandle;
+  globalIndices: number[];
+  fieldIndex: number;
+  source?: "files" | "http" | null;
+  open: boolean;
+  jobId?: string | null;
+}): Promise<PlaylistReport> {
+  await requireTauri("Exporting playlists");
+  return invoke<PlaylistReport>("export_audio_playlist", {
+    handle: params.handle,
+    globalIndices: params.globalIndices,
+    fieldIndex: params.fieldIndex,
+    source: params.source ?? null,
+    open: params.open,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export type ShareBundleReport = {
+  jobId: string;
+  bundlePath: string;
+  fingerprint: string;
+  items: number;
+  fieldBytes: number;
+};
+
+/**
+ * Zips the selected items with a manifest (dataset fingerprint, global indices, field
+ * formats and SHA-256 digests) for sharing without the whole dataset.
+ */
+export async function exportShareBundle(params: {
+  indexPath: string;
+  globalIndices: number[];
+  destPath: string;
+  fields?: number[] | null;
+  note?: string | null;
+  jobId?: string | null;
+}): Promise<ShareBundleReport> {
+  await requireTauri("Exporting share bundles");
+  return invoke<ShareBundleReport>("export_share_bundle", {
+    indexPath: params.indexPath,
+    globalIndices: params.globalIndices,
+    destPath: params.destPath,
+    fields: params.fields ?? null,
+    note: params.note ?? null,
+    jobId: params.jobId ?? null,
+  });
+}
+
+export const CHUNK_ITEMS_PAGE_EVENT = "chunk-items-page";
+
+export type ItemPage = {
+  jobId: string;
+  chunkFilename: string;
+  startItem: number;
+  totalItems: number;
+  items: ItemMeta[];
+};
+
+export type ItemStreamSummary = {
+  jobId: string;
+  totalItems: number;
+  pages: number;
+};
+
+export async function streamChunkItems(
+  params: { handle: DatasetHandle; chunkFilename: string; pageSize?: number | null },
+  onPage: (page: ItemPage) => void,
+): Promise<ItemStreamSummary> {
+  await requireTauri("Reading chunk");
+  const jobId = `items-${Date.now()}-${Math.random().toString(36).slice(2)}`;
+  const unlisten = await getCurrentWebviewWindow().listen<ItemPage>(CHUNK_ITEMS_PAGE_EVENT, (event) => {
+    if (event.payload.jobId === jobId) onPage(event.payload);
+  });
+  try {
+    return await invoke<ItemStreamSummary>("stream_chunk_items", {
+      handle: params.handle,
+      chunkFilename: params.chunkFilename,
+      pageSize: params.pageSize ?? null,
+      jobId,
+    });
+  } finally {
+    unlisten();
+  }
+}
@@ -0,0 +1,182 @@
+//! Headless access to LitData datasets for machines without a display, using the same
+//! reader as the desktop app.
+
+use litdata_core::reader::{AppError, AppResult, Dataset};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+usage: litdata-viewer-cli <command> <index> [options]
+
+commands:
+  inspect   chunk list; with --chunk, its items; with --chunk and --item, field previews
+  validate  check every chunk against the index (exit code 1 if problems are found)
+  export    write one field to a file: --chunk NAME --item N --field N [--out PATH]
+  stats     item and byte totals, missing chunks and orphan files; with --sample N,
+            per-field size percentiles over N items spread across the dataset
+  balance   chunks far below or above the configured chunk_bytes/chunk_size
+            [--under RATIO (default 0.5)] [--over RATIO (default 1.5)]
+
+<index> is an index.json, a directory containing one, or a single chunk file.
+Results are printed to stdout as JSON.";
+
+const COMMANDS: [&str; 5] = ["inspect", "validate", "export", "stats", "balance"];
+const PREVIEW_BYTES: usize = 2048;
+const DEFAULT_UNDER: f64 = 0.5;
+const DEFAULT_OVER: f64 = 1.5;
+
+struct Args {
+    command: String,
+    index: PathBuf,
+    chunk: Option<String>,
+    item: Option<u32>,
+    field: Option<usize>,
+    out: Option<PathBuf>,
+    sample: Option<u32>,
+    under: Option<f64>,
+    over: Option<f64>,
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("{flag} expects a number, got {value:?}"))
+}
+
+fn parse_args(mut raw: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = raw.next().ok_or("missing command")?;
+    if !COMMANDS.contains(&command.as_str()) {
+        return Err(format!("unknown command {command:?}"));
+    }
+    let index = raw.next().ok_or("missing index path")?.into();
+    let mut args = Args {
+        command,
+        index,
+        chunk: None,
+        item: None,
+        field: None,
+        out: None,
+        sample: None,
+        under: None,
+        over: None,
+    };
+    while let Some(flag) = raw.next() {
+        match flag.as_str() {
+            "--chunk" => args.chunk = Some(raw.next().ok_or("--chunk needs a value")?),
+            "--item" => args.item = Some(parse_number("--item", raw.next())?),
+            "--field" => args.field = Some(parse_number("--field", raw.next())?),
+            "--out" => args.out = Some(raw.next().ok_or("--out needs a value")?.into()),
+            "--sample" => args.sample = Some(parse_number("--sample", raw.next())?),
+            "--under" => args.under = Some(parse_number("--under", raw.next())?),
+            "--over" => args.over = Some(parse_number("--over", raw.next())?),
+            other => return Err(format!("unknown option {other}")),
+        }
+    }
+    Ok(args)
+}
+
+fn print_json(value: &impl Serialize) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::Invalid(format!("serializing output: {e}")))?;
+    match writeln!(io::stdout().lock(), "{json}") {
+        // A closed pipe (`| head`) just means nobody wants the rest.
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldReport<T> {
+    field_index: usize,
+    preview: T,
+}
+
+fn inspect(dataset: &Dataset, args: &Args) -> AppResult<()> {
+    let Some(chunk) = args.chunk.as_deref() else {
+        return print_json(&serde_json::json!({
+            "indexPath": dataset.index_path(),
+            "dataFormat": dataset.data_format(),
+            "chunks": dataset.chunks(),
+        }));
+    };
+    let Some(item) = args.item else {
+        return print_json(&dataset.items(chunk)?);
+    };
+    let fields = (0..dataset.data_format().len())
+        .map(|field| {
+            Ok(FieldReport {
+                field_index: field,
+                preview: dataset.preview_field(chunk, item, field, PREVIEW_BYTES)?,
+            })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    print_json(&fields)
+}
+
+fn export(dataset: &Dataset, args: &Args) -> AppResult<()> {
+    let (Some(chunk), Some(item), Some(field)) = (args.chunk.as_deref(), args.item, args.field)
+    else {
+        return Err(AppError::Invalid(
+            "export needs --chunk, --item and --field".into(),
+        ));
+    };
+    let data = dataset.read_field(chunk, item, field)?;
+    let name = dataset.field_file_name(chunk, item, field, &data);
+    let out = match &args.out {
+        Some(out) if out.is_dir() => out.join(name),
+        Some(out) => out.clone(),
+        None => Path::new(".").join(name),
+    };
+    fs::write(&out, &data)?;
+    print_json(&serde_json::json!({ "path": out, "size": data.len() }))
+}
+
+fn run(args: &Args) -> AppResult<ExitCode> {
+    let dataset = Dataset::open(&args.index)?;
+    match args.command.as_str() {
+        "inspect" => inspect(&dataset, args)?,
+        "validate" => {
+            let report = dataset.validate()?;
+            print_json(&report)?;
+            if !report.is_ok() {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        "export" => export(&dataset, args)?,
+        "balance" => print_json(&dataset.balance(
+            args.under.unwrap_or(DEFAULT_UNDER),
+            args.over.unwrap_or(DEFAULT_OVER),
+        )?)?,
+        _ => print_json(&dataset.stats(args.sample)?)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    if raw.is_empty() || raw.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let args = match parse_args(raw.into_iter()) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
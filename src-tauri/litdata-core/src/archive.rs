@@ -0,0 +1,435 @@
+//! Datasets shipped inside a `.zip`, `.tar` or `.tar.zst`, browsed in place. A path such
+//! as `/data/set.zip/train/index.json` names a member of the archive. Opening an archive
+//! builds a map from member names to their offsets; stored members are then read as byte
+//! ranges of the archive file and compressed ones are inflated on demand, so nothing is
+//! extracted to disk.
+
+use crate::chunk::ChunkAccess;
+use crate::error::{AppError, AppResult};
+use crate::index::{index_from_text, ParsedIndex};
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, Read},
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
+};
+use zip::{CompressionMethod, ZipArchive};
+
+/// Archives kept open between reads; the whole registry is dropped past this.
+const MAX_OPEN_ARCHIVES: usize = 8;
+
+/// Index names looked for inside an archive; per-worker shards are not merged here.
+const ARCHIVE_INDEX_NAMES: [&str; 3] = ["index.json", "index.json.zstd", "index.json.zst"];
+
+/// Tar headers and member data are laid out in blocks of this size.
+const TAR_BLOCK: u64 = 512;
+/// Largest GNU long-name or pax header read into memory; real ones are a few hundred bytes.
+const MAX_TAR_EXTENSION: u64 = 1024 * 1024;
+
+/// Memory claimed for one inflated member; released when dropped.
+pub type Reservation = Box<dyn Any + Send>;
+
+static RESERVE: OnceLock<fn(u64) -> AppResult<Reservation>> = OnceLock::new();
+
+/// Has every compressed member claim its size through `reserve` before it is inflated,
+/// so a host with a memory budget can make the read wait or fail. The first hook set
+/// stays in place.
+pub fn set_reserve_hook(reserve: fn(u64) -> AppResult<Reservation>) {
+    let _ = RESERVE.set(reserve);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Zip,
+    Tar,
+    /// A zstd-compressed tar; member offsets are positions in the decompressed stream.
+    TarZst,
+}
+
+struct Member {
+    /// Position in the zip's central directory; unused for tars.
+    index: usize,
+    /// Offset of the member's data in the archive file, or in the decompressed stream of
+    /// a `.tar.zst`.
+    offset: u64,
+    size: u64,
+    stored: bool,
+}
+
+struct Archive {
+    modified: Option<SystemTime>,
+    len: u64,
+    format: Format,
+    members: HashMap<String, Member>,
+    /// Reader for inflating compressed zip members.
+    zip: Option<Mutex<ZipArchive<File>>>,
+}
+
+fn registry() -> &'static Mutex<HashMap<PathBuf, Arc<Archive>>> {
+    static ARCHIVES: OnceLock<Mutex<HashMap<PathBuf, Arc<Archive>>>> = OnceLock::new();
+    ARCHIVES.get_or_init(Default::default)
+}
+
+fn format_of(path: &Path) -> Option<Format> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(Format::Zip)
+    } else if name.ends_with(".tar") {
+        Some(Format::Tar)
+    } else if [".tar.zst", ".tar.zstd", ".tzst"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    {
+        Some(Format::TarZst)
+    } else {
+        None
+    }
+}
+
+/// Whether `path` has an archive extension the viewer can browse.
+pub fn is_archive_path(path: &Path) -> bool {
+    format_of(path).is_some()
+}
+
+/// Splits a path running through an archive into the archive file and the member name
+/// within it, `""` for the archive itself. `None` for ordinary paths.
+pub fn split_archive_path(path: &Path) -> Option<(PathBuf, String)> {
+    let archive = path
+        .ancestors()
+        .find(|p| is_archive_path(p) && p.is_file())?;
+    let inner = path.strip_prefix(archive).ok()?;
+    Some((archive.to_path_buf(), member_name(inner)))
+}
+
+/// Member name in the archive's `/`-separated spelling, without `./` segments.
+fn member_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn zip_error(archive: &Path, err: zip::result::ZipError) -> AppError {
+    AppError::Invalid(format!("{}: {err}", archive.display()))
+}
+
+fn tar_error(archive: &Path, problem: &str) -> AppError {
+    AppError::Invalid(format!("{}: {problem}", archive.display()))
+}
+
+/// The open archive at `path`, reading its member list again if the file changed.
+fn open_archive(path: &Path) -> AppResult<Arc<Archive>> {
+    let format = format_of(path)
+        .ok_or_else(|| AppError::Invalid(format!("{} is not an archive", path.display())))?;
+    let meta = fs::metadata(path)?;
+    let modified = meta.modified().ok();
+    let mut guard = registry()
+        .lock()
+        .map_err(|_| AppError::Task("archive registry lock poisoned".into()))?;
+    if let Some(archive) = guard.get(path) {
+        if archive.modified == modified && archive.len == meta.len() {
+            return Ok(archive.clone());
+        }
+    }
+    let (members, zip) = match format {
+        Format::Zip => {
+            let (members, zip) = zip_members(path)?;
+            (members, Some(Mutex::new(zip)))
+        }
+        Format::Tar => {
+            let mut file = BufReader::new(File::open(path)?);
+            let skip = |r: &mut BufReader<File>, n: u64| {
+                r.seek_relative(n as i64)?;
+                Ok(())
+            };
+            (tar_members(path, &mut file, skip, true)?, None)
+        }
+        Format::TarZst => {
+            let mut stream = zstd::stream::Decoder::new(File::open(path)?)?;
+            let skip = |r: &mut zstd::stream::Decoder<'_, BufReader<File>>, n: u64| {
+                io::copy(&mut r.take(n), &mut io::sink()).map(drop)
+            };
+            (tar_members(path, &mut stream, skip, false)?, None)
+        }
+    };
+    let archive = Arc::new(Archive {
+        modified,
+        len: meta.len(),
+        format,
+        members,
+        zip,
+    });
+    if guard.len() >= MAX_OPEN_ARCHIVES {
+        guard.clear();
+    }
+    guard.insert(path.to_path_buf(), archive.clone());
+    Ok(archive)
+}
+
+fn zip_members(path: &Path) -> AppResult<(HashMap<String, Member>, ZipArchive<File>)> {
+    let mut zip = ZipArchive::new(File::open(path)?).map_err(|e| zip_error(path, e))?;
+    let mut members = HashMap::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index_raw(index).map_err(|e| zip_error(path, e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        members.insert(
+            member_name(Path::new(entry.name())),
+            Member {
+                index,
+                offset: entry.data_start(),
+                size: entry.size(),
+                stored: entry.compression() == CompressionMethod::Stored && !entry.encrypted(),
+            },
+        );
+    }
+    Ok((members, zip))
+}
+
+/// Walks the headers of a tar read from `reader`, passing over member data with `skip`,
+/// and maps each regular file to where its data starts. Understands ustar prefixes, GNU
+/// long names and pax `path`/`size` records.
+fn tar_members<R: Read>(
+    archive: &Path,
+    reader: &mut R,
+    skip: impl Fn(&mut R, u64) -> io::Result<()>,
+    stored: bool,
+) -> AppResult<HashMap<String, Member>> {
+    let mut members = HashMap::new();
+    let mut pos = 0u64;
+    let mut long_name: Option<String> = None;
+    let mut pax: HashMap<String, String> = HashMap::new();
+    let mut header = [0u8; TAR_BLOCK as usize];
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            // Some writers stop without the closing zero blocks.
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        pos += TAR_BLOCK;
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let mut size = tar_number(&header[124..136])
+            .ok_or_else(|| tar_error(archive, "tar header has a malformed size"))?;
+        let padded = size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+        match header[156] {
+            b'L' | b'x' => {
+                if size > MAX_TAR_EXTENSION {
+                    return Err(tar_error(archive, "tar extended header is too large"));
+                }
+                let mut data = vec![0u8; size as usize];
+                reader.read_exact(&mut data)?;
+                skip(reader, padded - size)?;
+                pos += padded;
+                if header[156] == b'L' {
+                    long_name = Some(tar_text(&data));
+                } else {
+                    pax = pax_records(&data);
+                }
+                continue;
+            }
+            b'0' | 0 | b'7' => {
+                let name = long_name
+                    .take()
+                    .or_else(|| pax.remove("path"))
+                    .unwrap_or_else(|| ustar_name(&header));
+                if let Some(pax_size) = pax.remove("size").and_then(|s| s.parse().ok()) {
+                    size = pax_size;
+                }
+                members.insert(
+                    member_name(Path::new(&name)),
+                    Member {
+                        index: 0,
+                        offset: pos,
+                        size,
+                        stored,
+                    },
+                );
+            }
+            _ => {}
+        }
+        long_name = None;
+        pax.clear();
+        let padded = size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+        skip(reader, padded)?;
+        pos += padded;
+    }
+    Ok(members)
+}
+
+/// A numeric header field: NUL- or space-terminated octal, or base-256 when the top bit
+/// of the first byte is set, as GNU tar writes sizes past 8 GiB.
+fn tar_number(field: &[u8]) -> Option<u64> {
+    if field.first().is_some_and(|b| b & 0x80 != 0) {
+        return field[1..]
+            .iter()
+            .try_fold(u64::from(field[0] & 0x7f), |acc, b| {
+                acc.checked_mul(256)?.checked_add(u64::from(*b))
+            });
+    }
+    let text = tar_text(field);
+    let digits = text.trim();
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+fn tar_text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn ustar_name(header: &[u8]) -> String {
+    let name = tar_text(&header[..100]);
+    let prefix = if &header[257..262] == b"ustar" {
+        tar_text(&header[345..500])
+    } else {
+        String::new()
+    };
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{prefix}/{name}")
+    }
+}
+
+/// `key=value` pairs of pax extended header records, each written `<len> key=value\n`.
+fn pax_records(data: &[u8]) -> HashMap<String, String> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .filter_map(|record| {
+            let (_, pair) = record.split_once(' ')?;
+            let (key, value) = pair.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Whether `path` names a file inside an archive.
+pub fn contains(path: &Path) -> bool {
+    let Some((archive, inner)) = split_archive_path(path) else {
+        return false;
+    };
+    open_archive(&archive).is_ok_and(|a| a.members.contains_key(&inner))
+}
+
+/// Access to the archived file at `path`: stored members are a slice of the archive,
+/// compressed ones are inflated into memory. `None` when `path` is not in an archive.
+pub fn member_access(path: &Path) -> AppResult<Option<ChunkAccess>> {
+    let Some((archive_path, inner)) = split_archive_path(path) else {
+        return Ok(None);
+    };
+    let archive = open_archive(&archive_path)?;
+    let member = archive
+        .members
+        .get(&inner)
+        .ok_or_else(|| AppError::Missing(path.display().to_string()))?;
+    if member.stored {
+        return Ok(Some(ChunkAccess::Slice {
+            path: archive_path,
+            offset: member.offset,
+            len: member.size,
+        }));
+    }
+    Ok(Some(ChunkAccess::Memory(
+        inflate(&archive_path, &archive, member)?.into(),
+    )))
+}
+
+/// Bytes of the archived file at `path`, as stored on disk would hold them.
+pub fn read_member(path: &Path) -> AppResult<Vec<u8>> {
+    match member_access(path)? {
+        Some(ChunkAccess::Memory(buf)) => Ok(buf.to_vec()),
+        Some(access) => access.read_exact_at(0, crate::chunk::span_len(0, access.len()?)?),
+        None => Err(AppError::Missing(path.display().to_string())),
+    }
+}
+
+fn inflate(archive_path: &Path, archive: &Archive, member: &Member) -> AppResult<Vec<u8>> {
+    let len = crate::chunk::span_len(0, member.size)?;
+    let _reservation = RESERVE
+        .get()
+        .map(|reserve| reserve(member.size))
+        .transpose()?;
+    // The size comes from the archive, so the buffer grows with the data actually read.
+    let mut buf = Vec::new();
+    match (archive.format, &archive.zip) {
+        (Format::Zip, Some(zip)) => {
+            let mut zip = zip
+                .lock()
+                .map_err(|_| AppError::Task("archive lock poisoned".into()))?;
+            let entry = zip
+                .by_index(member.index)
+                .map_err(|e| zip_error(archive_path, e))?;
+            entry.take(member.size).read_to_end(&mut buf)?;
+        }
+        _ => {
+            // A zstd stream has no random access: decode up to the member and keep its bytes.
+            let mut stream = zstd::stream::Decoder::new(File::open(archive_path)?)?;
+            io::copy(&mut (&mut stream).take(member.offset), &mut io::sink())?;
+            stream.take(member.size).read_to_end(&mut buf)?;
+        }
+    }
+    if buf.len() != len {
+        return Err(tar_error(archive_path, "archive ends inside a member"));
+    }
+    Ok(buf)
+}
+
+/// Parses the dataset in `archive` at or around `inner`: the index itself, the
+/// directory holding it, a chunk beside it, or the whole archive. Chunk paths of the
+/// result run through the archive.
+pub fn parse_archived_index(archive_path: &Path, inner: &str) -> AppResult<ParsedIndex> {
+    let archive = open_archive(archive_path)?;
+    let within =
+        |dir: &str, of: &str| dir.is_empty() || of == dir || of.starts_with(&format!("{dir}/"));
+    let indexes: Vec<(&str, &str)> = archive
+        .members
+        .keys()
+        .filter_map(|name| {
+            let (dir, file) = name.rsplit_once('/').unwrap_or(("", name));
+            ARCHIVE_INDEX_NAMES
+                .contains(&file)
+                .then_some((dir, name.as_str()))
+        })
+        .collect();
+    // The nearest index enclosing `inner`, else the shallowest one beneath it.
+    let chosen = indexes
+        .iter()
+        .filter(|(dir, _)| within(dir, inner))
+        .max_by_key(|(dir, name)| (dir.len(), std::cmp::Reverse(*name)))
+        .or_else(|| {
+            indexes
+                .iter()
+                .filter(|(dir, _)| within(inner, dir))
+                .min_by_key(|(dir, name)| (dir.split('/').count(), *dir, *name))
+        })
+        .map(|(_, name)| name.to_string())
+        .ok_or_else(|| {
+            AppError::Missing(format!(
+                "index.json in {}",
+                archive_path.join(inner).display()
+            ))
+        })?;
+    let source = archive_path.join(&chosen);
+    let raw = read_member(&source)?;
+    let content = crate::index::decode_index_bytes(&source, raw)?;
+    index_from_text(source, &content)
+}
+
+/// The file on disk holding `path`: the archive it runs through, or `path` itself.
+pub fn backing_file(path: &Path) -> PathBuf {
+    split_archive_path(path)
+        .map(|(archive, _)| archive)
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -0,0 +1,172 @@
+use crate::error::{AppError, AppResult};
+use crate::index::ParsedIndex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Chunks below this share of the target stall streaming on per-chunk overhead.
+pub const DEFAULT_UNDERFILLED_BELOW: f64 = 0.5;
+/// litdata overshoots `chunk_bytes` by at most one item; well past that, a single
+/// chunk download holds up a worker.
+pub const DEFAULT_OVERSIZED_ABOVE: f64 = 1.5;
+
+/// What each chunk's fill is measured against.
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceTarget {
+    /// `config.chunk_bytes`.
+    Bytes,
+    /// `config.chunk_size` items.
+    Items,
+    /// Neither is configured; the median chunk's byte size.
+    Median,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkFill {
+    Underfilled,
+    Oversized,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbalancedChunk {
+    filename: String,
+    items: u32,
+    bytes: u64,
+    /// Size relative to the target; 1.0 is exactly full.
+    fill: f64,
+    status: ChunkFill,
+    /// The last chunk a writer rank produced, which is expected to be partial.
+    trailing: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceReport {
+    index_path: String,
+    target: BalanceTarget,
+    target_value: u64,
+    chunk_count: usize,
+    mean_fill: f64,
+    min_fill: f64,
+    max_fill: f64,
+    underfilled: usize,
+    oversized: usize,
+    /// Underfilled chunks that are not a rank's trailing chunk; the ones worth fixing.
+    unexpected_underfilled: usize,
+    /// Flagged chunks, worst first.
+    chunks: Vec<UnbalancedChunk>,
+}
+
+/// `(rank, index)` from litdata's `chunk-{rank}-{index}.bin` naming.
+fn chunk_rank_index(filename: &str) -> Option<(u32, u32)> {
+    let name = filename.rsplit('/').next()?;
+    let mut parts = name.strip_prefix("chunk-")?.split(['-', '.']);
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+pub fn balance_of(
+    parsed: &ParsedIndex,
+    underfilled_below: f64,
+    oversized_above: f64,
+) -> AppResult<BalanceReport> {
+    if !(underfilled_below >= 0.0 && oversized_above > underfilled_below) {
+        return Err(AppError::Invalid(format!(
+            "thresholds must satisfy 0 <= underfilled ({underfilled_below}) < oversized ({oversized_above})"
+        )));
+    }
+    let config = &parsed.config;
+    let (target, target_value) = match (config.chunk_bytes, config.chunk_size) {
+        (Some(bytes), _) if bytes > 0 => (BalanceTarget::Bytes, bytes),
+        (_, Some(items)) if items > 0 => (BalanceTarget::Items, items as u64),
+        _ => {
+            let mut sizes: Vec<u64> = parsed.chunks.iter().map(|c| c.chunk_bytes).collect();
+            sizes.sort_unstable();
+            (
+                BalanceTarget::Median,
+                sizes.get(sizes.len() / 2).copied().unwrap_or(0),
+            )
+        }
+    };
+
+    let mut last_of_rank: HashMap<u32, u32> = HashMap::new();
+    for chunk in &parsed.chunks {
+        if let Some((rank, index)) = chunk_rank_index(&chunk.filename) {
+            let last = last_of_rank.entry(rank).or_insert(index);
+            *last = (*last).max(index);
+        }
+    }
+    // Without litdata's naming, only the final chunk is known to be partial.
+    let trailing = |i: usize, filename: &str| match chunk_rank_index(filename) {
+        Some((rank, index)) => last_of_rank.get(&rank) == Some(&index),
+        None => i + 1 == parsed.chunks.len(),
+    };
+
+    let fills: Vec<f64> = parsed
+        .chunks
+        .iter()
+        .map(|c| {
+            let measured = match target {
+                BalanceTarget::Items => c.chunk_size as u64,
+                BalanceTarget::Bytes | BalanceTarget::Median => c.chunk_bytes,
+            };
+            if target_value == 0 {
+                1.0
+            } else {
+                measured as f64 / target_value as f64
+            }
+        })
+        .collect();
+    let mut chunks: Vec<UnbalancedChunk> = parsed
+        .chunks
+        .iter()
+        .zip(&fills)
+        .enumerate()
+        .filter_map(|(i, (chunk, &fill))| {
+            let status = if fill < underfilled_below {
+                ChunkFill::Underfilled
+            } else if fill > oversized_above {
+                ChunkFill::Oversized
+            } else {
+                return None;
+            };
+            Some(UnbalancedChunk {
+                filename: chunk.filename.clone(),
+                items: chunk.chunk_size,
+                bytes: chunk.chunk_bytes,
+                fill,
+                status,
+                trailing: trailing(i, &chunk.filename),
+            })
+        })
+        .collect();
+    // Worst first: furthest from a full chunk, with expected trailing chunks last.
+    chunks.sort_by(|a, b| {
+        let badness = |c: &UnbalancedChunk| (c.fill.max(f64::MIN_POSITIVE)).ln().abs();
+        a.trailing
+            .cmp(&b.trailing)
+            .then(badness(b).total_cmp(&badness(a)))
+    });
+    let count = |status| chunks.iter().filter(|c| c.status == status).count();
+    Ok(BalanceReport {
+        index_path: parsed.source.display().to_string(),
+        target,
+        target_value,
+        chunk_count: parsed.chunks.len(),
+        mean_fill: if fills.is_empty() {
+            0.0
+        } else {
+            fills.iter().sum::<f64>() / fills.len() as f64
+        },
+        min_fill: fills.iter().copied().reduce(f64::min).unwrap_or(0.0),
+        max_fill: fills.iter().copied().reduce(f64::max).unwrap_or(0.0),
+        underfilled: count(ChunkFill::Underfilled),
+        oversized: count(ChunkFill::Oversized),
+        unexpected_underfilled: chunks
+            .iter()
+            .filter(|c| c.status == ChunkFill::Underfilled && !c.trailing)
+            .count(),
+        chunks,
+    })
+}
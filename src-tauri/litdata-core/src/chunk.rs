@@ -0,0 +1,346 @@
+use crate::archive;
+use crate::corruption::truncated_stream;
+use crate::error::{AppError, AppResult, ErrorContext, ResultExt};
+use crate::file_pool;
+use crate::index::ParsedIndex;
+use crate::prefix::StreamPrefix;
+use std::{
+    fs::{self, File},
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Read-ahead used when scanning item headers sequentially.
+const REGION_READ_BUFFER: usize = 256 * 1024;
+
+pub fn read_le_u32(bytes: &[u8]) -> AppResult<u32> {
+    let buf: [u8; 4] = bytes.try_into().map_err(|_| AppError::MalformedChunk)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+pub fn read_le_u64(bytes: &[u8]) -> AppResult<u64> {
+    let buf: [u8; 8] = bytes.try_into().map_err(|_| AppError::MalformedChunk)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Byte length of `start..end` as an in-memory size, rejecting inverted or oversized spans.
+pub fn span_len(start: u64, end: u64) -> AppResult<usize> {
+    let len = end.checked_sub(start).ok_or(AppError::MalformedChunk)?;
+    usize::try_from(len)
+        .map_err(|_| AppError::Overflow(format!("span of {len} bytes exceeds addressable memory")))
+}
+
+/// Entry width of a chunk's offsets table. litdata writes u32 offsets; chunks past 4 GB
+/// need u64 entries, which are recognised by where the first offset points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetWidth {
+    U32,
+    U64,
+}
+
+impl OffsetWidth {
+    pub fn entry_size(self) -> u64 {
+        match self {
+            OffsetWidth::U32 => 4,
+            OffsetWidth::U64 => 8,
+        }
+    }
+
+    /// First byte after the item count and `num_items + 1` offsets.
+    pub fn table_end(self, num_items: u32) -> u64 {
+        4 + (num_items as u64 + 1) * self.entry_size()
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> AppResult<u64> {
+        match self {
+            OffsetWidth::U32 => read_le_u32(bytes).map(u64::from),
+            OffsetWidth::U64 => read_le_u64(bytes),
+        }
+    }
+
+    /// Picks the layout whose first offset points right past its own table; `head` holds
+    /// the bytes following the item count.
+    pub fn detect(num_items: u32, head: &[u8], chunk_len: u64) -> OffsetWidth {
+        let narrow = head.get(0..4).and_then(|b| read_le_u32(b).ok());
+        if narrow.map(u64::from) == Some(OffsetWidth::U32.table_end(num_items)) {
+            return OffsetWidth::U32;
+        }
+        let wide_end = OffsetWidth::U64.table_end(num_items);
+        let wide = head.get(0..8).and_then(|b| read_le_u64(b).ok());
+        if wide == Some(wide_end) && wide_end <= chunk_len {
+            return OffsetWidth::U64;
+        }
+        OffsetWidth::U32
+    }
+}
+
+pub enum ChunkAccess {
+    File(PathBuf),
+    Memory(Arc<[u8]>),
+    Stream(StreamPrefix),
+    /// `len` bytes at `offset` in `path`, as for a chunk stored uncompressed in an archive.
+    Slice {
+        path: PathBuf,
+        offset: u64,
+        len: u64,
+    },
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl ChunkAccess {
+    pub fn len(&self) -> AppResult<u64> {
+        match self {
+            ChunkAccess::File(path) => Ok(fs::metadata(path)?.len()),
+            ChunkAccess::Memory(buf) => Ok(buf.len() as u64),
+            ChunkAccess::Stream(stream) => Ok(stream.len()),
+            ChunkAccess::Slice { len, .. } => Ok(*len),
+        }
+    }
+
+    pub fn read_exact_at(&self, offset: u64, len: usize) -> AppResult<Vec<u8>> {
+        match self {
+            ChunkAccess::File(path) => {
+                let (handle, file_len) = file_pool::open(path)?;
+                // Refuse to allocate for reads a hostile header points past the end of the file.
+                let end = offset
+                    .checked_add(len as u64)
+                    .ok_or(AppError::MalformedChunk)?;
+                if end > file_len {
+                    return Err(AppError::MalformedChunk);
+                }
+                let mut fp = handle
+                    .lock()
+                    .map_err(|_| AppError::Task("chunk file lock poisoned".into()))?;
+                fp.seek(SeekFrom::Start(offset))?;
+                let mut buf = vec![0u8; len];
+                fp.read_exact(&mut buf).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::UnexpectedEof => AppError::MalformedChunk,
+                    _ => AppError::from(e),
+                })?;
+                Ok(buf)
+            }
+            ChunkAccess::Memory(buf) => {
+                let end = offset
+                    .checked_add(len as u64)
+                    .ok_or(AppError::MalformedChunk)?;
+                if end > buf.len() as u64 {
+                    return Err(AppError::MalformedChunk);
+                }
+                Ok(buf[offset as usize..end as usize].to_vec())
+            }
+            ChunkAccess::Stream(stream) => stream.read_exact_at(offset, len),
+            ChunkAccess::Slice {
+                path,
+                offset: start,
+                len: slice_len,
+            } => {
+                let end = offset
+                    .checked_add(len as u64)
+                    .ok_or(AppError::MalformedChunk)?;
+                if end > *slice_len {
+                    return Err(AppError::MalformedChunk);
+                }
+                ChunkAccess::File(path.clone()).read_exact_at(start + offset, len)
+            }
+        }
+    }
+
+    /// Reads many small `(offset, len)` regions; for files this is one buffered forward
+    /// pass instead of a seek and read per region.
+    pub fn read_regions(&self, regions: &[(u64, usize)]) -> AppResult<Vec<Vec<u8>>> {
+        let ChunkAccess::File(path) = self else {
+            return regions
+                .iter()
+                .map(|(offset, len)| self.read_exact_at(*offset, *len))
+                .collect();
+        };
+        let (handle, file_len) = file_pool::open(path)?;
+        let mut fp = handle
+            .lock()
+            .map_err(|_| AppError::Task("chunk file lock poisoned".into()))?;
+        let mut reader = BufReader::with_capacity(REGION_READ_BUFFER, &mut *fp);
+        let mut pos = reader.seek(SeekFrom::Start(0))?;
+        let mut out = Vec::with_capacity(regions.len());
+        for (offset, len) in regions {
+            let end = offset
+                .checked_add(*len as u64)
+                .ok_or(AppError::MalformedChunk)?;
+            if end > file_len {
+                return Err(AppError::MalformedChunk);
+            }
+            if *offset >= pos {
+                reader.seek_relative((*offset - pos) as i64)?;
+            } else {
+                reader.seek(SeekFrom::Start(*offset))?;
+            }
+            let mut buf = vec![0u8; *len];
+            reader.read_exact(&mut buf).map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => AppError::MalformedChunk,
+                _ => AppError::from(e),
+            })?;
+            pos = end;
+            out.push(buf);
+        }
+        Ok(out)
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn parse_offsets(access: &ChunkAccess) -> AppResult<(u32, Vec<u64>)> {
+    let chunk_len = access.len()?;
+    let num_buf = access.read_exact_at(0, 4)?;
+    let num_items = read_le_u32(&num_buf)?;
+    let narrow_end = OffsetWidth::U32.table_end(num_items);
+    if narrow_end > chunk_len {
+        return Err(AppError::ImplausibleHeader {
+            num_items,
+            required: narrow_end,
+            chunk_len,
+        });
+    }
+    let head = access.read_exact_at(4, (chunk_len - 4).min(8) as usize)?;
+    let width = OffsetWidth::detect(num_items, &head, chunk_len);
+    let offsets_len = width.table_end(num_items) - 4;
+    let offsets_buf = access.read_exact_at(4, span_len(0, offsets_len)?)?;
+    let mut offsets = Vec::with_capacity(num_items as usize + 1);
+    for entry in offsets_buf.chunks_exact(width.entry_size() as usize) {
+        offsets.push(width.decode(entry)?);
+    }
+    Ok((num_items, offsets))
+}
+
+#[tracing::instrument(skip(access, table, format_len))]
+pub fn read_field_bytes(
+    access: &ChunkAccess,
+    table: &(u32, Vec<u64>),
+    item_index: u32,
+    field_index: usize,
+    format_len: usize,
+    limit: Option<usize>,
+) -> AppResult<(Vec<u8>, u32)> {
+    let (cursor, size) = field_span(access, table, item_index, field_index, format_len)?;
+    let desired = limit.map(|l| l.min(size as usize)).unwrap_or(size as usize);
+    let data = access
+        .read_exact_at(cursor, desired)
+        .within(|| ErrorContext::default().offset(cursor))?;
+    Ok((data, size))
+}
+
+/// Chunk offset and size of one field's payload, given the chunk's offsets table.
+pub fn field_span(
+    access: &ChunkAccess,
+    (num_items, offsets): &(u32, Vec<u64>),
+    item_index: u32,
+    field_index: usize,
+    format_len: usize,
+) -> AppResult<(u64, u32)> {
+    let header_len = format_len * 4;
+    if item_index >= *num_items {
+        return Err(AppError::Invalid("item index out of range".into()));
+    }
+    let start = offsets[item_index as usize];
+    let end = offsets[item_index as usize + 1];
+    if end < start {
+        return Err(AppError::MalformedChunk);
+    }
+    let header = if header_len > 0 {
+        Some(access.read_exact_at(start, header_len)?)
+    } else {
+        None
+    };
+    let mut sizes = Vec::new();
+    if let Some(head) = header {
+        for j in 0..format_len {
+            let pos = j * 4;
+            sizes.push(read_le_u32(&head[pos..pos + 4])?);
+        }
+    }
+    if field_index >= sizes.len() {
+        return Err(AppError::Invalid("field index out of range".into()));
+    }
+    let mut cursor = start + header_len as u64;
+    for (idx, sz) in sizes.iter().enumerate() {
+        if idx == field_index {
+            if cursor + *sz as u64 > end {
+                return Err(AppError::MalformedChunk);
+            }
+            return Ok((cursor, *sz));
+        }
+        cursor = cursor.checked_add(*sz as u64).ok_or_else(|| {
+            AppError::Overflow(format!(
+                "field {idx} of item {item_index} overflows the chunk"
+            ))
+        })?;
+    }
+    Err(AppError::MalformedChunk)
+}
+
+/// Reads the full serialized bytes (size header included) of one item.
+pub fn read_item_bytes(
+    access: &ChunkAccess,
+    offsets: &[u64],
+    item_index: u32,
+) -> AppResult<Vec<u8>> {
+    let idx = item_index as usize;
+    if idx + 1 >= offsets.len() {
+        return Err(AppError::Invalid("item index out of range".into()));
+    }
+    let (start, end) = (offsets[idx], offsets[idx + 1]);
+    access.read_exact_at(start, span_len(start, end)?)
+}
+
+/// Splits an item's bytes into its fields using the leading per-field size header.
+pub fn split_fields(item: &[u8], format_len: usize) -> AppResult<Vec<&[u8]>> {
+    let header_len = format_len * 4;
+    if item.len() < header_len {
+        return Err(AppError::MalformedChunk);
+    }
+    let mut fields = Vec::with_capacity(format_len);
+    let mut cursor = header_len;
+    for j in 0..format_len {
+        let size = read_le_u32(&item[j * 4..j * 4 + 4])? as usize;
+        let end = cursor.checked_add(size).ok_or(AppError::MalformedChunk)?;
+        if end > item.len() {
+            return Err(AppError::MalformedChunk);
+        }
+        fields.push(&item[cursor..end]);
+        cursor = end;
+    }
+    Ok(fields)
+}
+
+/// Inflates a whole zstd chunk. A stream that stops early is reported as corruption,
+/// located as far as the decoded prefix allows.
+pub fn decompress_chunk(chunk_filename: &str, compressed: impl Read) -> AppResult<Vec<u8>> {
+    let mut decoder = zstd::stream::Decoder::new(compressed)?;
+    let mut buf = Vec::new();
+    decoder
+        .read_to_end(&mut buf)
+        .map_err(|e| AppError::Corrupt(Box::new(truncated_stream(chunk_filename, &buf, &e))))?;
+    Ok(buf)
+}
+
+/// Opens a chunk without caching: zstd chunks are decompressed into memory, plain ones
+/// are read from the file as needed.
+pub fn open_chunk(parsed: &ParsedIndex, chunk_filename: &str) -> AppResult<ChunkAccess> {
+    let chunk_path = parsed.chunk_path(chunk_filename)?;
+    let archived = archive::member_access(&chunk_path)?;
+    if archived.is_none() && !chunk_path.exists() {
+        return Err(AppError::Missing(chunk_path.display().to_string()));
+    }
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(ref c) if c == "zstd" => {
+            let buf = match archived {
+                Some(access) => {
+                    let raw = access.read_exact_at(0, span_len(0, access.len()?)?)?;
+                    decompress_chunk(chunk_filename, &raw[..])?
+                }
+                None => decompress_chunk(chunk_filename, BufReader::new(File::open(&chunk_path)?))?,
+            };
+            Ok(ChunkAccess::Memory(buf.into()))
+        }
+        Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None => Ok(archived.unwrap_or(ChunkAccess::File(chunk_path))),
+    }
+}
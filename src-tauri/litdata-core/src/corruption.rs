@@ -0,0 +1,169 @@
+use crate::chunk::{read_le_u32, ChunkAccess, OffsetWidth};
+use crate::error::AppError;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CorruptionKind {
+    /// The chunk is too short to hold its item count.
+    TruncatedHeader,
+    /// The offsets table runs past the end of the chunk.
+    TruncatedOffsetsTable,
+    /// The zstd stream ended early or failed mid-way.
+    TruncatedStream,
+    /// An item ends before it starts.
+    OffsetsNotMonotonic,
+    /// An item extends past the available bytes.
+    ItemPastEnd,
+    /// The offsets table and the chunk length disagree about where the data ends.
+    LengthMismatch,
+}
+
+/// Where and why a chunk stopped making sense.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptionInfo {
+    pub chunk: String,
+    pub kind: CorruptionKind,
+    pub first_bad_item: Option<u32>,
+    pub byte_offset: Option<u64>,
+    pub declared_len: Option<u64>,
+    pub actual_len: u64,
+    pub detail: String,
+}
+
+impl CorruptionInfo {
+    pub fn new(chunk: &str, kind: CorruptionKind, actual_len: u64, detail: String) -> Self {
+        CorruptionInfo {
+            chunk: chunk.to_string(),
+            kind,
+            first_bad_item: None,
+            byte_offset: None,
+            declared_len: None,
+            actual_len,
+            detail,
+        }
+    }
+
+    pub fn at(mut self, item: Option<u32>, offset: u64) -> Self {
+        self.first_bad_item = item;
+        self.byte_offset = Some(offset);
+        self
+    }
+}
+
+/// Locates the first inconsistency between a chunk's offsets table and its actual length.
+pub fn diagnose_access(access: &ChunkAccess, chunk: &str) -> Option<CorruptionInfo> {
+    let total_len = access.len().ok()?;
+    if total_len < 4 {
+        return Some(
+            CorruptionInfo::new(
+                chunk,
+                CorruptionKind::TruncatedHeader,
+                total_len,
+                format!("chunk is only {total_len} bytes"),
+            )
+            .at(None, total_len),
+        );
+    }
+    let num_items = read_le_u32(&access.read_exact_at(0, 4).ok()?).ok()?;
+    let head = access
+        .read_exact_at(4, (total_len - 4).min(8) as usize)
+        .ok()?;
+    let width = OffsetWidth::detect(num_items, &head, total_len);
+    let table_end = width.table_end(num_items);
+    if table_end > total_len {
+        return Some(
+            CorruptionInfo::new(
+                chunk,
+                CorruptionKind::TruncatedOffsetsTable,
+                total_len,
+                format!(
+                    "{num_items} items need an offsets table ending at {table_end}, chunk has {total_len} bytes"
+                ),
+            )
+            .at(None, total_len),
+        );
+    }
+    let table = access
+        .read_exact_at(4, usize::try_from(table_end - 4).ok()?)
+        .ok()?;
+    let step = width.entry_size() as usize;
+    let mut prev_end = table_end;
+    for item in 0..num_items {
+        let pos = item as usize * step;
+        let start = width.decode(&table[pos..pos + step]).ok()?;
+        let end = width.decode(&table[pos + step..pos + 2 * step]).ok()?;
+        if end < start || (item == 0 && start < table_end) {
+            return Some(
+                CorruptionInfo::new(
+                    chunk,
+                    CorruptionKind::OffsetsNotMonotonic,
+                    total_len,
+                    format!("item {item} spans {start}..{end}"),
+                )
+                .at(Some(item), start),
+            );
+        }
+        if end > total_len {
+            return Some(
+                CorruptionInfo::new(
+                    chunk,
+                    CorruptionKind::ItemPastEnd,
+                    total_len,
+                    format!("item {item} ends at {end}, chunk has {total_len} bytes"),
+                )
+                .at(Some(item), total_len),
+            );
+        }
+        prev_end = end;
+    }
+    if prev_end != total_len && num_items > 0 {
+        return Some(
+            CorruptionInfo::new(
+                chunk,
+                CorruptionKind::LengthMismatch,
+                total_len,
+                format!("offsets end at {prev_end}, chunk has {total_len} bytes"),
+            )
+            .at(None, prev_end),
+        );
+    }
+    None
+}
+
+/// Replaces a bare `MalformedChunk` with a located corruption report when one can be found.
+pub fn explain_corruption(err: AppError, access: &ChunkAccess, chunk: &str) -> AppError {
+    match err {
+        AppError::MalformedChunk => match diagnose_access(access, chunk) {
+            Some(info) => {
+                tracing::warn!(chunk, kind = ?info.kind, "corrupt chunk: {}", info.detail);
+                AppError::Corrupt(Box::new(info))
+            }
+            None => AppError::MalformedChunk,
+        },
+        AppError::Read {
+            context,
+            cause,
+            retryable,
+        } => AppError::Read {
+            context,
+            cause: Box::new(explain_corruption(*cause, access, chunk)),
+            retryable,
+        },
+        other => other,
+    }
+}
+
+/// Report for a zstd stream that stopped after producing `decoded` bytes.
+pub fn truncated_stream(chunk: &str, decoded: &[u8], err: &std::io::Error) -> CorruptionInfo {
+    let prefix = ChunkAccess::Memory(decoded.into());
+    let first_bad_item = diagnose_access(&prefix, chunk).and_then(|info| info.first_bad_item);
+    CorruptionInfo::new(
+        chunk,
+        CorruptionKind::TruncatedStream,
+        decoded.len() as u64,
+        format!("decompression failed after {} bytes: {err}", decoded.len()),
+    )
+    .at(first_bad_item, decoded.len() as u64)
+}
@@ -0,0 +1,367 @@
+//! Decoder plugins: sandboxed WebAssembly modules that turn fields of a custom
+//! `data_format` into a previewable text, JSON or image.
+//!
+//! A plugin is a directory holding a `plugin.json` manifest
+//! (`{"name", "version", "formats": [...], "module": "decoder.wasm"}`) and a module
+//! exporting:
+//!
+//! - `memory`
+//! - `alloc(len: i32) -> i32`: space for the host to write inputs into
+//! - `decode(format_ptr: i32, format_len: i32, data_ptr: i32, data_len: i32) -> i64`:
+//!   returns `(ptr << 32) | len` of its output
+//!
+//! The output is one line of JSON header, `{"kind": "text" | "json" | "image"}` plus
+//! `"mime"` for images, then a newline and the body: UTF-8 text, a JSON document or
+//! the encoded image bytes. Modules get no imports, so they can only compute.
+
+use crate::error::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+const MANIFEST_FILE: &str = "plugin.json";
+/// Fields larger than this are not handed to plugins.
+pub const MAX_DECODE_BYTES: usize = 64 * 1024 * 1024;
+/// Linear memory a plugin may grow to, inputs included.
+const MAX_PLUGIN_MEMORY: usize = 256 * 1024 * 1024;
+/// Instruction budget per call, so a looping plugin fails instead of hanging a worker.
+const DECODE_FUEL: u64 = 5_000_000_000;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoderPlugin {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    /// `data_format` entries handled, matched exactly or up to a `:` parameter suffix.
+    formats: Vec<String>,
+    /// Module file name, relative to the manifest.
+    module: String,
+}
+
+impl DecoderPlugin {
+    fn handles(&self, format: &str) -> bool {
+        let base = format.split(':').next().unwrap_or(format);
+        self.formats.iter().any(|f| f == format || f == base)
+    }
+}
+
+#[derive(Deserialize)]
+struct OutputHeader {
+    kind: DecodedKind,
+    mime: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodedKind {
+    Text,
+    Json,
+    Image,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedField {
+    plugin: String,
+    kind: DecodedKind,
+    text: Option<String>,
+    json: Option<serde_json::Value>,
+    mime: Option<String>,
+    /// Image bytes, base64-encoded for a data URL.
+    image_base64: Option<String>,
+}
+
+struct LoadedPlugin {
+    manifest: DecoderPlugin,
+    module: Module,
+}
+
+struct Registry {
+    /// Directory the plugins were loaded from.
+    dir: PathBuf,
+    engine: Engine,
+    plugins: Vec<Arc<LoadedPlugin>>,
+}
+
+/// A plugin picked for one `data_format` entry, ready to run.
+pub struct Decoder {
+    registry: Arc<Registry>,
+    plugin: Arc<LoadedPlugin>,
+    format: String,
+}
+
+/// Compiled plugins, loaded on first use and dropped whenever the set changes.
+static REGISTRY: Mutex<Option<Arc<Registry>>> = Mutex::new(None);
+
+fn engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+fn read_manifest(path: &Path) -> AppResult<DecoderPlugin> {
+    let raw = fs::read(path)?;
+    let manifest: DecoderPlugin = serde_json::from_slice(&raw)
+        .map_err(|e| AppError::Invalid(format!("{}: {e}", path.display())))?;
+    let valid_name = !manifest.name.is_empty()
+        && manifest
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(AppError::Invalid(format!(
+            "plugin name {:?} may only use letters, digits, '-' and '_'",
+            manifest.name
+        )));
+    }
+    if manifest.formats.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "plugin {} declares no formats",
+            manifest.name
+        )));
+    }
+    Ok(manifest)
+}
+
+fn compile(engine: &Engine, manifest: &DecoderPlugin, wasm: &[u8]) -> AppResult<Module> {
+    let module = Module::new(engine, wasm)
+        .map_err(|e| AppError::Invalid(format!("plugin {}: {e}", manifest.name)))?;
+    if module.imports().len() > 0 {
+        return Err(AppError::Invalid(format!(
+            "plugin {} imports host functions; decoders must be self-contained",
+            manifest.name
+        )));
+    }
+    for export in ["memory", "alloc", "decode"] {
+        if module.get_export(export).is_none() {
+            return Err(AppError::Invalid(format!(
+                "plugin {} does not export `{export}`",
+                manifest.name
+            )));
+        }
+    }
+    Ok(module)
+}
+
+fn load_registry(dir: &Path) -> AppResult<Registry> {
+    let engine = engine();
+    let mut plugins = Vec::new();
+    let dir = dir.to_path_buf();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Registry {
+            dir,
+            engine,
+            plugins,
+        });
+    };
+    let mut manifests: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path().join(MANIFEST_FILE))
+        .filter(|p| p.is_file())
+        .collect();
+    manifests.sort();
+    for path in manifests {
+        // One broken plugin should not disable the others.
+        let loaded = read_manifest(&path).and_then(|manifest| {
+            let wasm = fs::read(path.with_file_name(&manifest.module))?;
+            let module = compile(&engine, &manifest, &wasm)?;
+            Ok(LoadedPlugin { manifest, module })
+        });
+        match loaded {
+            Ok(plugin) => plugins.push(Arc::new(plugin)),
+            Err(e) => tracing::warn!(manifest = %path.display(), "skipping decoder plugin: {e}"),
+        }
+    }
+    Ok(Registry {
+        dir,
+        engine,
+        plugins,
+    })
+}
+
+fn lock() -> AppResult<std::sync::MutexGuard<'static, Option<Arc<Registry>>>> {
+    REGISTRY
+        .lock()
+        .map_err(|_| AppError::Task("decoder registry lock poisoned".into()))
+}
+
+fn registry(dir: &Path) -> AppResult<Arc<Registry>> {
+    let mut guard = lock()?;
+    if let Some(registry) = guard.as_ref().filter(|r| r.dir == dir) {
+        return Ok(registry.clone());
+    }
+    let registry = Arc::new(load_registry(dir)?);
+    *guard = Some(registry.clone());
+    Ok(registry)
+}
+
+fn invalidate() {
+    if let Ok(mut guard) = REGISTRY.lock() {
+        *guard = None;
+    }
+}
+
+fn run_plugin(
+    engine: &Engine,
+    plugin: &LoadedPlugin,
+    format: &str,
+    data: &[u8],
+) -> AppResult<Vec<u8>> {
+    let name = &plugin.manifest.name;
+    let fail = |e: &dyn std::fmt::Display| AppError::Invalid(format!("plugin {name}: {e}"));
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_PLUGIN_MEMORY)
+        .build();
+    let mut store: Store<StoreLimits> = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(DECODE_FUEL).map_err(|e| fail(&e))?;
+    let instance = Linker::<StoreLimits>::new(engine)
+        .instantiate(&mut store, &plugin.module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| fail(&e))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| fail(&"no exported memory"))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|e| fail(&e))?;
+    let decode = instance
+        .get_typed_func::<(i32, i32, i32, i32), i64>(&store, "decode")
+        .map_err(|e| fail(&e))?;
+    let mut write = |bytes: &[u8]| -> AppResult<(i32, i32)> {
+        let len = i32::try_from(bytes.len()).map_err(|e| fail(&e))?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| fail(&e))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, bytes)
+            .map_err(|e| fail(&e))?;
+        Ok((ptr, len))
+    };
+    let (format_ptr, format_len) = write(format.as_bytes())?;
+    let (data_ptr, data_len) = write(data)?;
+    let packed = decode
+        .call(&mut store, (format_ptr, format_len, data_ptr, data_len))
+        .map_err(|e| fail(&e))?;
+    let (ptr, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+    let mut out = vec![0u8; len];
+    memory.read(&store, ptr, &mut out).map_err(|e| fail(&e))?;
+    Ok(out)
+}
+
+fn interpret(plugin: &str, output: &[u8]) -> AppResult<DecodedField> {
+    let fail = |detail: String| AppError::Invalid(format!("plugin {plugin}: {detail}"));
+    let split = output
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(output.len());
+    let header: OutputHeader = serde_json::from_slice(&output[..split])
+        .map_err(|e| fail(format!("bad output header: {e}")))?;
+    let body = output.get(split + 1..).unwrap_or_default();
+    let mut decoded = DecodedField {
+        plugin: plugin.to_string(),
+        kind: header.kind,
+        text: None,
+        json: None,
+        mime: header.mime,
+        image_base64: None,
+    };
+    match header.kind {
+        DecodedKind::Text => decoded.text = Some(String::from_utf8_lossy(body).into_owned()),
+        DecodedKind::Json => {
+            decoded.json = Some(
+                serde_json::from_slice(body).map_err(|e| fail(format!("bad JSON body: {e}")))?,
+            )
+        }
+        DecodedKind::Image => {
+            if decoded.mime.is_none() {
+                return Err(fail("image output without a mime type".into()));
+            }
+            decoded.image_base64 = Some(STANDARD.encode(body));
+        }
+    }
+    Ok(decoded)
+}
+
+fn manifests(dir: &Path) -> AppResult<Vec<DecoderPlugin>> {
+    Ok(registry(dir)?
+        .plugins
+        .iter()
+        .map(|p| p.manifest.clone())
+        .collect())
+}
+
+/// Plugins installed under `dir`; ones that fail to load are skipped.
+pub fn list(dir: &Path) -> AppResult<Vec<DecoderPlugin>> {
+    manifests(dir)
+}
+
+/// Validates the plugin whose `plugin.json` is at `manifest_path` and copies it into
+/// `dir`, replacing an installed plugin of the same name.
+pub fn install(dir: &Path, manifest_path: &Path) -> AppResult<Vec<DecoderPlugin>> {
+    let manifest = read_manifest(manifest_path)?;
+    let wasm = fs::read(manifest_path.with_file_name(&manifest.module))?;
+    compile(&engine(), &manifest, &wasm)?;
+    let target = dir.join(&manifest.name);
+    fs::create_dir_all(&target)?;
+    let module_name = Path::new(&manifest.module)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or("decoder.wasm")
+        .to_string();
+    fs::write(target.join(&module_name), wasm)?;
+    let installed = DecoderPlugin {
+        module: module_name,
+        ..manifest
+    };
+    let json = serde_json::to_vec_pretty(&installed)
+        .map_err(|e| AppError::Invalid(format!("serializing plugin manifest: {e}")))?;
+    fs::write(target.join(MANIFEST_FILE), json)?;
+    tracing::info!(plugin = %installed.name, "installed decoder plugin");
+    invalidate();
+    manifests(dir)
+}
+
+pub fn remove(dir: &Path, name: &str) -> AppResult<Vec<DecoderPlugin>> {
+    let target = dir.join(name);
+    if target.parent() != Some(dir) || !target.join(MANIFEST_FILE).is_file() {
+        return Err(AppError::Missing(format!("decoder plugin {name}")));
+    }
+    fs::remove_dir_all(&target)?;
+    invalidate();
+    manifests(dir)
+}
+
+/// The plugin under `dir` registered for the `data_format` entry `format`.
+pub fn decoder_for(dir: &Path, format: &str) -> AppResult<Decoder> {
+    let registry = registry(dir)?;
+    let plugin = registry
+        .plugins
+        .iter()
+        .find(|p| p.manifest.handles(format))
+        .cloned()
+        .ok_or_else(|| AppError::Missing(format!("decoder plugin for format {format}")))?;
+    Ok(Decoder {
+        registry,
+        plugin,
+        format: format.to_string(),
+    })
+}
+
+impl Decoder {
+    /// Runs the plugin over a field's full bytes.
+    pub fn decode(&self, data: &[u8]) -> AppResult<DecodedField> {
+        if data.len() > MAX_DECODE_BYTES {
+            return Err(AppError::Invalid(format!(
+                "field is {} bytes; decoder plugins take at most {MAX_DECODE_BYTES}",
+                data.len()
+            )));
+        }
+        let output = run_plugin(&self.registry.engine, &self.plugin, &self.format, data)?;
+        interpret(&self.plugin.manifest.name, &output)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::corruption::CorruptionInfo;
+use crate::index::ParsedIndex;
+use serde::Serialize;
+use thiserror::Error;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Where a failed read was looking. Each part is serialized as its own field so the
+/// frontend can point at the item and offer a retry without parsing messages.
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorContext {
+    pub dataset: Option<String>,
+    pub chunk: Option<String>,
+    pub item_index: Option<u32>,
+    pub field_index: Option<usize>,
+    pub byte_offset: Option<u64>,
+}
+
+impl ErrorContext {
+    pub fn chunk(parsed: &ParsedIndex, chunk_filename: &str) -> Self {
+        ErrorContext {
+            dataset: Some(parsed.source.display().to_string()),
+            chunk: Some(chunk_filename.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn item(mut self, item_index: u32) -> Self {
+        self.item_index = Some(item_index);
+        self
+    }
+
+    pub fn field(mut self, field_index: usize) -> Self {
+        self.field_index = Some(field_index);
+        self
+    }
+
+    pub fn offset(mut self, byte_offset: u64) -> Self {
+        self.byte_offset = Some(byte_offset);
+        self
+    }
+
+    /// Fills parts this context lacks from an outer one.
+    fn fill_from(&mut self, outer: ErrorContext) {
+        self.dataset = self.dataset.take().or(outer.dataset);
+        self.chunk = self.chunk.take().or(outer.chunk);
+        self.item_index = self.item_index.or(outer.item_index);
+        self.field_index = self.field_index.or(outer.field_index);
+        self.byte_offset = self.byte_offset.or(outer.byte_offset);
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(item) = self.item_index {
+            parts.push(format!("item {item}"));
+        }
+        if let Some(field) = self.field_index {
+            parts.push(format!("field {field}"));
+        }
+        if let Some(chunk) = &self.chunk {
+            parts.push(format!("in {chunk}"));
+        }
+        if let Some(offset) = self.byte_offset {
+            parts.push(format!("at offset {offset:#X}"));
+        }
+        if parts.is_empty() {
+            if let Some(dataset) = &self.dataset {
+                parts.push(dataset.clone());
+            }
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "code", content = "message")]
+pub enum AppError {
+    #[error("invalid request: {0}")]
+    Invalid(String),
+    #[error("not found: {0}")]
+    Missing(String),
+    #[error("unsupported compression: {0}")]
+    UnsupportedCompression(String),
+    #[error(
+        "index format version {found} is newer than this viewer understands (up to {supported}); update the viewer to open it"
+    )]
+    NewerFormat { found: u32, supported: u32 },
+    #[error("malformed chunk")]
+    MalformedChunk,
+    #[error("offset overflow: {0}")]
+    Overflow(String),
+    #[error(
+        "chunk header claims {num_items} items ({required} bytes of offsets) but the chunk is only {chunk_len} bytes"
+    )]
+    ImplausibleHeader {
+        num_items: u32,
+        required: u64,
+        chunk_len: u64,
+    },
+    #[error("corrupt chunk {}: {}", .0.chunk, .0.detail)]
+    Corrupt(Box<CorruptionInfo>),
+    #[error("failed reading {context}: {cause}")]
+    Read {
+        context: Box<ErrorContext>,
+        cause: Box<AppError>,
+        retryable: bool,
+    },
+    #[error("io error: {0}")]
+    Io(String),
+    #[error("memory limit of {limit} bytes reached; {requested} more bytes were needed")]
+    MemoryLimit { requested: u64, limit: u64 },
+    #[error("timed out reading {0}")]
+    Timeout(String),
+    #[error("task error: {0}")]
+    Task(String),
+    #[error("open error: {0}")]
+    Open(String),
+    #[error("security violation: {0}")]
+    Security(String),
+    #[error("read-only mode: {0}")]
+    ReadOnly(String),
+    #[error("audio conversion error: {0}")]
+    Audio(String),
+}
+
+impl AppError {
+    /// Whether the same request may succeed if simply tried again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Io(_)
+            | AppError::Timeout(_)
+            | AppError::Task(_)
+            | AppError::MemoryLimit { .. } => true,
+            AppError::Read { retryable, .. } => *retryable,
+            _ => false,
+        }
+    }
+
+    /// Attaches `context`, merging into an existing one rather than nesting.
+    pub fn within(self, context: ErrorContext) -> AppError {
+        match self {
+            AppError::Read {
+                context: mut inner,
+                cause,
+                retryable,
+            } => {
+                inner.fill_from(context);
+                AppError::Read {
+                    context: inner,
+                    cause,
+                    retryable,
+                }
+            }
+            cause => {
+                let mut context = context;
+                if let AppError::Corrupt(info) = &cause {
+                    context.byte_offset = context.byte_offset.or(info.byte_offset);
+                }
+                AppError::Read {
+                    retryable: cause.is_retryable(),
+                    context: Box::new(context),
+                    cause: Box::new(cause),
+                }
+            }
+        }
+    }
+}
+
+pub trait ResultExt<T> {
+    fn within(self, context: impl FnOnce() -> ErrorContext) -> AppResult<T>;
+}
+
+impl<T> ResultExt<T> for AppResult<T> {
+    fn within(self, context: impl FnOnce() -> ErrorContext) -> AppResult<T> {
+        self.map_err(|e| e.within(context()))
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(value: std::io::Error) -> Self {
+        AppError::Io(value.to_string())
+    }
+}
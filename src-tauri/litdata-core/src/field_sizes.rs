@@ -0,0 +1,171 @@
+use crate::chunk::{parse_offsets, read_le_u32, ChunkAccess};
+use crate::error::{AppError, AppResult};
+use crate::index::ParsedIndex;
+use crate::progress::ReportProgress;
+use crate::scan::par_map;
+use serde::Serialize;
+
+/// Items read when the caller does not say; enough for stable percentiles.
+pub const DEFAULT_SAMPLE_ITEMS: u32 = 10_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSizeStats {
+    field_index: usize,
+    /// The `data_format` entry for this field.
+    format: Option<String>,
+    min: u32,
+    p50: u32,
+    p90: u32,
+    p99: u32,
+    max: u32,
+    mean: f64,
+    total_bytes: u64,
+    zero_items: u64,
+    /// Every sampled item stores this field as zero bytes.
+    always_zero: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSizeReport {
+    total_items: u64,
+    sampled_items: u64,
+    /// Sampled items whose size header does not add up to the item's length; their
+    /// sizes are left out of the distribution.
+    arity_mismatches: u64,
+    unreadable_chunks: Vec<String>,
+    fields: Vec<FieldSizeStats>,
+}
+
+/// Item indexes to read from each chunk: every `total / sample`-th item of the dataset.
+pub fn sample_plan(parsed: &ParsedIndex, sample_items: u32) -> Vec<(usize, Vec<u32>)> {
+    let total: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+    let wanted = (sample_items as u64).min(total);
+    let mut plan: Vec<(usize, Vec<u32>)> = Vec::new();
+    let mut chunk = 0usize;
+    let mut chunk_start = 0u64;
+    for k in 0..wanted {
+        let global = (k as u128 * total as u128 / wanted as u128) as u64;
+        while global >= chunk_start + parsed.chunks[chunk].chunk_size as u64 {
+            chunk_start += parsed.chunks[chunk].chunk_size as u64;
+            chunk += 1;
+        }
+        let item = (global - chunk_start) as u32;
+        match plan.last_mut() {
+            Some((last, items)) if *last == chunk => items.push(item),
+            _ => plan.push((chunk, vec![item])),
+        }
+    }
+    plan
+}
+
+/// Per-field sizes of `item`, or `None` when its header does not match its length.
+pub fn field_sizes(
+    access: &ChunkAccess,
+    offsets: &[u64],
+    item: u32,
+    format_len: usize,
+) -> AppResult<Option<Vec<u32>>> {
+    let idx = item as usize;
+    if idx + 1 >= offsets.len() {
+        return Err(AppError::Invalid("item index out of range".into()));
+    }
+    let (start, end) = (offsets[idx], offsets[idx + 1]);
+    let header_len = (format_len * 4) as u64;
+    if end < start || end - start < header_len {
+        return Ok(None);
+    }
+    let head = access.read_exact_at(start, header_len as usize)?;
+    let sizes = head
+        .chunks_exact(4)
+        .map(read_le_u32)
+        .collect::<AppResult<Vec<u32>>>()?;
+    let payload: u64 = sizes.iter().map(|s| *s as u64).sum();
+    Ok((payload + header_len == end - start).then_some(sizes))
+}
+
+/// Nearest-rank percentile of an ascending slice.
+pub fn percentile<T: Copy>(sorted: &[T], pct: u64) -> T {
+    let rank = (pct * sorted.len() as u64).div_ceil(100).max(1);
+    sorted[rank as usize - 1]
+}
+
+/// Distribution of each field's payload size over up to `sample_items` items, spread
+/// evenly across the dataset. Chunks are opened through `load`.
+pub fn field_sizes_of<F>(
+    parsed: &ParsedIndex,
+    sample_items: u32,
+    load: F,
+    progress: &dyn ReportProgress,
+) -> AppResult<FieldSizeReport>
+where
+    F: Fn(&str) -> AppResult<ChunkAccess> + Sync,
+{
+    let format_len = parsed.format_len();
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("sampling", 0, plan.len() as u64);
+    let sampled = par_map(&plan, Some((progress, "sampling")), |(chunk, items)| {
+        let access = load(&parsed.chunks[*chunk].filename)?;
+        let (_, offsets) = parse_offsets(&access)?;
+        items
+            .iter()
+            .map(|item| field_sizes(&access, &offsets, *item, format_len))
+            .collect::<AppResult<Vec<_>>>()
+    });
+
+    let mut per_field: Vec<Vec<u32>> = vec![Vec::new(); format_len];
+    let mut sampled_items = 0u64;
+    let mut arity_mismatches = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, _), result) in plan.iter().zip(sampled) {
+        let Ok(items) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        for sizes in items {
+            sampled_items += 1;
+            match sizes {
+                Some(sizes) => {
+                    for (field, size) in sizes.into_iter().enumerate() {
+                        per_field[field].push(size);
+                    }
+                }
+                None => arity_mismatches += 1,
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let fields = per_field
+        .into_iter()
+        .enumerate()
+        .filter(|(_, sizes)| !sizes.is_empty())
+        .map(|(field_index, mut sizes)| {
+            sizes.sort_unstable();
+            let total_bytes: u64 = sizes.iter().map(|s| *s as u64).sum();
+            let zero_items = sizes.iter().filter(|s| **s == 0).count() as u64;
+            FieldSizeStats {
+                field_index,
+                format: formats.get(field_index).cloned(),
+                min: sizes[0],
+                p50: percentile(&sizes, 50),
+                p90: percentile(&sizes, 90),
+                p99: percentile(&sizes, 99),
+                max: sizes[sizes.len() - 1],
+                mean: total_bytes as f64 / sizes.len() as f64,
+                total_bytes,
+                zero_items,
+                always_zero: zero_items == sizes.len() as u64,
+            }
+        })
+        .collect();
+    Ok(FieldSizeReport {
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        arity_mismatches,
+        unreadable_chunks,
+        fields,
+    })
+}
@@ -0,0 +1,69 @@
+use crate::error::{AppError, AppResult};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
+};
+
+/// Open chunk files kept around between reads; the least recently used is closed first.
+const MAX_OPEN_FILES: usize = 16;
+
+struct PooledFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+    file: Arc<Mutex<File>>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct FilePool {
+    files: Vec<PooledFile>,
+    clock: u64,
+}
+
+fn pool() -> &'static Mutex<FilePool> {
+    static POOL: OnceLock<Mutex<FilePool>> = OnceLock::new();
+    POOL.get_or_init(Default::default)
+}
+
+/// Returns a shared handle to `path` and its current length, reopening it if the file
+/// changed on disk since it was pooled.
+pub(crate) fn open(path: &Path) -> AppResult<(Arc<Mutex<File>>, u64)> {
+    let meta = std::fs::metadata(path)?;
+    let modified = meta.modified().ok();
+    let mut guard = pool()
+        .lock()
+        .map_err(|_| AppError::Task("file pool lock poisoned".into()))?;
+    guard.clock += 1;
+    let now = guard.clock;
+    if let Some(pos) = guard.files.iter().position(|f| f.path == path) {
+        let entry = &mut guard.files[pos];
+        if entry.modified == modified && entry.len == meta.len() {
+            entry.last_used = now;
+            return Ok((entry.file.clone(), entry.len));
+        }
+        guard.files.swap_remove(pos);
+    }
+    let file = Arc::new(Mutex::new(File::open(path)?));
+    if guard.files.len() >= MAX_OPEN_FILES {
+        if let Some(oldest) = guard
+            .files
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, f)| f.last_used)
+            .map(|(idx, _)| idx)
+        {
+            guard.files.swap_remove(oldest);
+        }
+    }
+    guard.files.push(PooledFile {
+        path: path.to_path_buf(),
+        modified,
+        len: meta.len(),
+        file: file.clone(),
+        last_used: now,
+    });
+    Ok((file, meta.len()))
+}
@@ -0,0 +1,154 @@
+use crate::error::AppResult;
+use crate::index::{is_chunk_path, probe_chunk_header, ParsedIndex};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Identifies the file behind `path`: equal for symlinks to one file and, on Unix, for
+/// hard links too. `None` when the file cannot be read.
+pub fn file_identity(path: &Path) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let meta = fs::metadata(path).ok()?;
+        Some(format!("{}:{}", meta.dev(), meta.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        fs::canonicalize(path).ok().map(|p| p.display().to_string())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingChunk {
+    filename: String,
+    path: String,
+    items: u32,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanFile {
+    path: String,
+    bytes: u64,
+    /// Item count from the chunk header, when the file is an uncompressed readable chunk.
+    items: Option<u32>,
+}
+
+/// A chunk whose file is the same as an earlier chunk's, through a symlink or hard
+/// link, so its items are counted twice.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateChunk {
+    filename: String,
+    path: String,
+    same_as: String,
+    items: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    index_path: String,
+    referenced_chunks: usize,
+    healthy: bool,
+    pub(crate) missing: Vec<MissingChunk>,
+    missing_items: u64,
+    pub(crate) orphans: Vec<OrphanFile>,
+    orphan_items: u64,
+    orphan_bytes: u64,
+    duplicates: Vec<DuplicateChunk>,
+}
+
+pub fn health_of(parsed: &ParsedIndex) -> AppResult<HealthReport> {
+    let mut referenced: HashSet<PathBuf> = HashSet::new();
+    // Orphans are looked for wherever the index keeps its chunks.
+    let mut scan_dirs: BTreeSet<PathBuf> = BTreeSet::from([parsed.root_dir.clone()]);
+    let mut identities: HashMap<String, &str> = HashMap::new();
+    let mut missing = Vec::new();
+    let mut duplicates = Vec::new();
+    for chunk in &parsed.chunks {
+        let path = parsed.chunk_path(&chunk.filename)?;
+        if let Some(parent) = path.parent() {
+            scan_dirs.insert(parent.to_path_buf());
+        }
+        match file_identity(&path) {
+            None => missing.push(MissingChunk {
+                filename: chunk.filename.clone(),
+                path: path.display().to_string(),
+                items: chunk.chunk_size,
+                bytes: chunk.chunk_bytes,
+            }),
+            Some(identity) => match identities.get(&identity) {
+                Some(first) => duplicates.push(DuplicateChunk {
+                    filename: chunk.filename.clone(),
+                    path: path.display().to_string(),
+                    same_as: first.to_string(),
+                    items: chunk.chunk_size,
+                }),
+                None => {
+                    identities.insert(identity, &chunk.filename);
+                }
+            },
+        }
+        referenced.insert(path);
+    }
+
+    let orphans = find_orphans(parsed, &scan_dirs, &referenced, &identities);
+    let missing_items = missing.iter().map(|m| m.items as u64).sum();
+    let orphan_items = orphans.iter().filter_map(|o| o.items).map(u64::from).sum();
+    let orphan_bytes = orphans.iter().map(|o| o.bytes).sum();
+    Ok(HealthReport {
+        index_path: parsed.source.display().to_string(),
+        referenced_chunks: parsed.chunks.len(),
+        healthy: missing.is_empty() && orphans.is_empty() && duplicates.is_empty(),
+        missing,
+        missing_items,
+        orphans,
+        orphan_items,
+        orphan_bytes,
+        duplicates,
+    })
+}
+
+fn find_orphans(
+    parsed: &ParsedIndex,
+    scan_dirs: &BTreeSet<PathBuf>,
+    referenced: &HashSet<PathBuf>,
+    identities: &HashMap<String, &str>,
+) -> Vec<OrphanFile> {
+    let compressed = parsed.config.compression.is_some();
+    let mut orphans = Vec::new();
+    for dir in scan_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && is_chunk_path(p) && *p != parsed.source)
+            .filter(|p| !referenced.contains(p))
+            // Another name for a referenced chunk is an alias, not an orphan.
+            .filter(|p| file_identity(p).is_none_or(|id| !identities.contains_key(&id)))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let items = if compressed {
+                None
+            } else {
+                probe_chunk_header(&path).ok().map(|(n, _)| n)
+            };
+            orphans.push(OrphanFile {
+                path: path.display().to_string(),
+                bytes,
+                items,
+            });
+        }
+    }
+    orphans
+}
@@ -0,0 +1,454 @@
+use crate::archive;
+use crate::chunk::{parse_offsets, ChunkAccess};
+use crate::error::{AppError, AppResult};
+use crate::long_path::long_path;
+use crate::security::confine_to_root;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+/// Newest index format version this reader understands. Indexes that do not record one
+/// predate the field and are read as version 1.
+pub const SUPPORTED_FORMAT_VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+struct IndexFile {
+    chunks: Vec<RawChunk>,
+    config: IndexConfig,
+    #[serde(flatten)]
+    metadata: IndexMetadata,
+}
+
+/// Provenance LitData records beside `chunks` and `config`; each field is absent in
+/// indexes written by versions that did not record it.
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(from = "MetadataFields")]
+pub struct IndexMetadata {
+    pub format_version: Option<u32>,
+    /// When the index was last written, as LitData recorded it (usually epoch seconds).
+    pub updated_at: Option<String>,
+    pub litdata_version: Option<String>,
+}
+
+/// The metadata keys as written. Values of an unexpected type are ignored rather than
+/// failing the whole index, and `format_version` wins over the older `version`.
+#[derive(Deserialize)]
+struct MetadataFields {
+    #[serde(default, deserialize_with = "whole_number")]
+    format_version: Option<u32>,
+    #[serde(default, deserialize_with = "whole_number")]
+    version: Option<u32>,
+    #[serde(default, deserialize_with = "text_or_number")]
+    updated_at: Option<String>,
+    #[serde(default, deserialize_with = "text_or_number")]
+    litdata_version: Option<String>,
+}
+
+impl From<MetadataFields> for IndexMetadata {
+    fn from(fields: MetadataFields) -> Self {
+        IndexMetadata {
+            format_version: fields.format_version.or(fields.version),
+            updated_at: fields.updated_at,
+            litdata_version: fields.litdata_version,
+        }
+    }
+}
+
+/// Strings and bare numbers, as `updated_at` has been written both ways.
+fn text_or_number<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    Ok(match Option::<serde_json::Value>::deserialize(de)? {
+        Some(serde_json::Value::String(text)) => Some(text),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// A non-negative integer, also when written as a string; anything else is `None`.
+fn whole_number<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<u32>, D::Error> {
+    Ok(match Option::<serde_json::Value>::deserialize(de)? {
+        Some(serde_json::Value::Number(n)) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        Some(serde_json::Value::String(text)) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+#[derive(Deserialize, Clone, Serialize)]
+pub struct IndexConfig {
+    pub compression: Option<String>,
+    pub chunk_size: Option<u32>,
+    pub chunk_bytes: Option<u64>,
+    pub data_format: Option<Vec<String>>,
+    pub data_spec: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RawChunk {
+    pub filename: String,
+    pub chunk_bytes: u64,
+    pub chunk_size: u32,
+    pub dim: Option<u32>,
+}
+
+/// A dataset's index, or a standalone chunk file standing in for one.
+pub struct ParsedIndex {
+    pub root_dir: PathBuf,
+    pub source: PathBuf,
+    pub config: IndexConfig,
+    pub config_raw: serde_json::Value,
+    pub chunks: Vec<RawChunk>,
+    pub metadata: IndexMetadata,
+    /// Files for chunks that do not live under `root_dir`, as in a merged view over
+    /// several directories. Empty for a plain index.json.
+    pub chunk_locations: HashMap<String, ChunkLocation>,
+}
+
+/// Where a chunk of a merged dataset came from.
+pub struct ChunkLocation {
+    pub path: PathBuf,
+    /// The directory or index.json the chunk was gathered from.
+    pub source: PathBuf,
+}
+
+impl ParsedIndex {
+    pub fn chunk_path(&self, chunk_filename: &str) -> AppResult<PathBuf> {
+        match self.chunk_locations.get(chunk_filename) {
+            Some(location) => Ok(long_path(&location.path)),
+            None => confine_to_root(&self.root_dir, chunk_filename),
+        }
+    }
+
+    /// Decompressed size the index records for `chunk_filename`, or 0 when unknown.
+    pub fn declared_chunk_bytes(&self, chunk_filename: &str) -> u64 {
+        self.chunks
+            .iter()
+            .find(|c| c.filename == chunk_filename)
+            .map(|c| c.chunk_bytes)
+            .unwrap_or(0)
+    }
+
+    /// Directories holding this dataset's index and chunks, for read-only checks.
+    pub fn dataset_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.root_dir.clone()];
+        for location in self.chunk_locations.values() {
+            if let Some(dir) = location.path.parent() {
+                if !dirs.iter().any(|d| d == dir) {
+                    dirs.push(dir.to_path_buf());
+                }
+            }
+        }
+        dirs
+    }
+
+    pub fn format_len(&self) -> usize {
+        self.config
+            .data_format
+            .as_ref()
+            .map(|v| v.len())
+            .unwrap_or(0)
+    }
+}
+
+#[tracing::instrument(skip_all, fields(path = %index_path.display()))]
+pub fn parse_index(index_path: &Path) -> AppResult<ParsedIndex> {
+    let index_path = &long_path(index_path);
+    if let Some((archive_path, inner)) = archive::split_archive_path(index_path) {
+        return archive::parse_archived_index(&archive_path, &inner);
+    }
+    if is_chunk_path(index_path) {
+        if let Some((found, _)) = find_chunk_index(index_path) {
+            return parse_index(&found);
+        }
+        return parse_chunk_only(index_path);
+    }
+
+    let resolved = resolve_index_path(index_path)?;
+    let content = read_index_file(&resolved)?;
+    index_from_text(resolved, &content)
+}
+
+pub(crate) fn index_from_text(resolved: PathBuf, content: &str) -> AppResult<ParsedIndex> {
+    let parsed: IndexFile = serde_json::from_str(content)
+        .map_err(|e| AppError::Invalid(format!("index.json parse error: {e}")))?;
+    if let Some(found) = parsed.metadata.format_version {
+        if found > SUPPORTED_FORMAT_VERSION {
+            return Err(AppError::NewerFormat {
+                found,
+                supported: SUPPORTED_FORMAT_VERSION,
+            });
+        }
+    }
+    let config = parsed.config;
+    let config_raw = serde_json::to_value(&config).unwrap_or(serde_json::Value::Null);
+    let root_dir = resolved
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."));
+    Ok(ParsedIndex {
+        root_dir,
+        source: resolved,
+        config,
+        config_raw,
+        chunks: parsed.chunks,
+        metadata: parsed.metadata,
+        chunk_locations: HashMap::new(),
+    })
+}
+
+fn parse_chunk_only(index_path: &Path) -> AppResult<ParsedIndex> {
+    let root_dir = index_path
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."));
+    let (num_items, size) = probe_chunk_header(index_path)?;
+    let name = index_path
+        .file_name()
+        .map(|f| f.to_string_lossy())
+        .unwrap_or_else(|| "chunk.bin".into());
+    // A name that is not UTF-8 is shown lossily; the location keeps the real path.
+    let mut chunk_locations = HashMap::new();
+    if index_path.file_name().and_then(|f| f.to_str()).is_none() {
+        chunk_locations.insert(
+            name.to_string(),
+            ChunkLocation {
+                path: index_path.to_path_buf(),
+                source: index_path.to_path_buf(),
+            },
+        );
+    }
+
+    let chunk = RawChunk {
+        filename: name.into_owned(),
+        chunk_bytes: size,
+        chunk_size: num_items.max(1),
+        dim: None,
+    };
+    let fallback_config = IndexConfig {
+        compression: None,
+        chunk_size: Some(num_items.max(1)),
+        chunk_bytes: Some(size),
+        data_format: Some(vec!["bytes".into()]),
+        data_spec: None,
+    };
+    Ok(ParsedIndex {
+        root_dir,
+        source: index_path.to_path_buf(),
+        config: fallback_config.clone(),
+        config_raw: serde_json::to_value(fallback_config).unwrap_or(serde_json::Value::Null),
+        chunks: vec![chunk],
+        metadata: IndexMetadata::default(),
+        chunk_locations,
+    })
+}
+
+pub fn is_chunk_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("bin") || ext.eq_ignore_ascii_case("zst"))
+        .unwrap_or(false)
+        || path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().contains(".bin"))
+}
+
+/// The index file in the same directory as `chunk_path`, if there is one.
+pub fn find_neighbor_index(chunk_path: &Path) -> Option<PathBuf> {
+    index_in_dir(chunk_path.parent()?)
+}
+
+/// The index a directory holds: a merged `index.json` first, then the first worker's,
+/// then any other index file by name.
+fn index_in_dir(dir: &Path) -> Option<PathBuf> {
+    let candidates = [
+        "index.json",
+        "index.json.zstd",
+        "index.json.zst",
+        "0.index.json",
+        "0.index.json.zstd",
+        "0.index.json.zst",
+    ];
+    for name in candidates {
+        let candidate = dir.join(name);
+        if candidate.exists() {
+            return Some(candidate);
+        }
+    }
+    index_files_in(dir).into_iter().next()
+}
+
+/// How many directories above a chunk file `find_chunk_index` looks for its index.
+const MAX_CHUNK_NESTING: usize = 4;
+
+/// Whether two chunk filenames from an index name the same relative path, so
+/// `./shard_00//chunk-0-0.bin` matches `shard_00/chunk-0-0.bin`.
+pub fn same_chunk_name(a: impl AsRef<Path>, b: impl AsRef<Path>) -> bool {
+    fn parts(name: &Path) -> impl Iterator<Item = Component<'_>> {
+        name.components()
+            .filter(|c| !matches!(c, Component::CurDir))
+    }
+    parts(a.as_ref()).eq(parts(b.as_ref()))
+}
+
+/// The index a chunk file belongs to, with the chunk's filename as that index spells
+/// it. An index next to the chunk is taken as is; one in a parent directory only when
+/// it lists the chunk by its nested path, like `shard_00/chunk-0-0.bin`.
+pub fn find_chunk_index(chunk_path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let mut relative = PathBuf::from(chunk_path.file_name()?);
+    if let Some(found) = find_neighbor_index(chunk_path) {
+        return Some((found, relative));
+    }
+    let mut dir = chunk_path.parent()?;
+    for _ in 0..MAX_CHUNK_NESTING {
+        relative = Path::new(dir.file_name()?).join(relative);
+        dir = dir.parent()?;
+        let Some(index) = index_in_dir(dir) else {
+            continue;
+        };
+        let Ok(parsed) = parse_index_file(&index) else {
+            continue;
+        };
+        if let Some(chunk) = parsed
+            .chunks
+            .iter()
+            .find(|c| same_chunk_name(&c.filename, &relative))
+        {
+            return Some((index, PathBuf::from(&chunk.filename)));
+        }
+    }
+    None
+}
+
+/// Whether `name` is an index file litdata writes: the merged `index.json` or a
+/// per-worker `N.index.json`, optionally zstd-compressed.
+pub fn is_index_file_name(name: &str) -> bool {
+    matches!(name, "index.json" | "index.json.zstd" | "index.json.zst")
+        || name.ends_with(".index.json")
+        || name.contains(".index.json.")
+}
+
+fn index_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e2| e2.path()))
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|f| is_index_file_name(&f.to_string_lossy()))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// The per-worker `N.index.json` files in `dir`, in worker order, leaving out a merged
+/// `index.json` since it repeats their chunks.
+pub fn index_shards(dir: &Path) -> Vec<PathBuf> {
+    let mut shards: Vec<PathBuf> = index_files_in(dir)
+        .into_iter()
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with("index.json"))
+        })
+        .collect();
+    let worker = |p: &PathBuf| -> Option<u64> {
+        let name = p.file_name()?.to_str()?;
+        name.split_once(".index.json")?.0.parse().ok()
+    };
+    // Numeric first so `10.index.json` follows `9.index.json`.
+    shards.sort_by_key(|p| (worker(p).unwrap_or(u64::MAX), p.clone()));
+    shards
+}
+
+pub fn resolve_index_path(path: &Path) -> AppResult<PathBuf> {
+    let path = &long_path(path);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    if path.is_dir() {
+        if let Some(found) = index_in_dir(path) {
+            return Ok(found);
+        }
+    } else if let Some(parent) = path.parent() {
+        let base = path.file_stem().unwrap_or("index".as_ref());
+        let sibling = |suffix: &str| {
+            let mut name = base.to_os_string();
+            name.push(suffix);
+            parent.join(name)
+        };
+        let candidates = [
+            path.to_path_buf(),
+            path.with_extension("json"),
+            path.with_extension("json.zstd"),
+            path.with_extension("json.zst"),
+            sibling(".json"),
+            sibling(".json.zstd"),
+            sibling(".json.zst"),
+        ];
+        for candidate in candidates {
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+        }
+    }
+    Err(AppError::Missing(path.display().to_string()))
+}
+
+/// Parses `raw`, the bytes of the index file at `resolved`, as read by the caller.
+pub fn parse_index_bytes(resolved: PathBuf, raw: Vec<u8>) -> AppResult<ParsedIndex> {
+    let content = decode_index_bytes(&resolved, raw)?;
+    index_from_text(resolved, &content)
+}
+
+pub fn read_index_file(path: &Path) -> AppResult<String> {
+    decode_index_bytes(path, fs::read(path)?)
+}
+
+/// Index text from the raw bytes of `path`, inflating `.zst`/`.zstd` indexes.
+pub(crate) fn decode_index_bytes(path: &Path, raw: Vec<u8>) -> AppResult<String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if ext.contains("zst") {
+        let mut decoder = zstd::stream::Decoder::new(&raw[..])?;
+        let mut s = String::new();
+        decoder.read_to_string(&mut s)?;
+        Ok(s)
+    } else {
+        String::from_utf8(raw)
+            .map_err(|e| AppError::Invalid(format!("index.json is not UTF-8: {e}")))
+    }
+}
+
+/// Parses the index file at `path` itself, without resolving neighbours.
+pub fn parse_index_file(path: &Path) -> AppResult<ParsedIndex> {
+    let path = long_path(path);
+    index_from_text(path.clone(), &read_index_file(&path)?)
+}
+
+/// Reads a standalone chunk's item count, checking the offsets table fits in the file.
+pub fn probe_chunk_header(path: &Path) -> AppResult<(u32, u64)> {
+    let access = ChunkAccess::File(path.to_path_buf());
+    let (num_items, _) = parse_offsets(&access)?;
+    Ok((num_items, access.len()?))
+}
+
+/// Compression a chunk's file name implies: LitData names compressed chunks like
+/// `chunk-0-0.zstd.bin`, and a bare `.zst`/`.zstd` suffix means the same.
+pub fn chunk_compression(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    let stem = name.strip_suffix(".bin").unwrap_or(&name);
+    (stem.ends_with(".zstd") || stem.ends_with(".zst")).then_some("zstd")
+}
+
+/// Reads a zstd chunk's item count from the head of its decompressed stream.
+pub fn probe_compressed_chunk_items(path: &Path) -> AppResult<u32> {
+    let mut head = [0u8; 4];
+    zstd::stream::Decoder::new(fs::File::open(path)?)?.read_exact(&mut head)?;
+    Ok(u32::from_le_bytes(head))
+}
@@ -0,0 +1,141 @@
+use crate::chunk::{parse_offsets, read_le_u32, ChunkAccess};
+use crate::error::{AppError, AppResult};
+use crate::sniff::guess_ext;
+use crate::validate::{detect_field_count, MAX_ARITY_PROBE};
+use hex::encode as hex_encode;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMeta {
+    field_index: usize,
+    size: u32,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemMeta {
+    pub item_index: u32,
+    total_bytes: u64,
+    fields: Vec<FieldMeta>,
+    /// Field count the item's size header actually encodes, when it can be determined.
+    header_field_count: Option<usize>,
+    /// The header disagrees with `data_format`, so the listed fields are unreliable.
+    arity_mismatch: bool,
+    /// Columns from the dataset's sidecar label file, when it has a row for this item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldPreview {
+    pub preview_text: Option<String>,
+    hex_snippet: String,
+    guessed_ext: Option<String>,
+    is_binary: bool,
+    size: u32,
+    /// Name of an integer label from the dataset's class map.
+    pub class_name: Option<String>,
+}
+
+pub fn collect_item_meta(access: &ChunkAccess, format_len: usize) -> AppResult<Vec<ItemMeta>> {
+    let (num_items, offsets) = parse_offsets(access)?;
+    item_meta_range(access, &offsets, format_len, 0..num_items)
+}
+
+/// Metadata for items in `range`, given the chunk's parsed offsets table.
+pub fn item_meta_range(
+    access: &ChunkAccess,
+    offsets: &[u64],
+    format_len: usize,
+    range: std::ops::Range<u32>,
+) -> AppResult<Vec<ItemMeta>> {
+    let header_len = format_len * 4;
+    let mut regions = Vec::with_capacity(range.len());
+    for item_idx in range.start as usize..range.end as usize {
+        let (start, end) = (offsets[item_idx], offsets[item_idx + 1]);
+        if end < start {
+            return Err(AppError::MalformedChunk);
+        }
+        // Enough bytes for the declared header, or for guessing the real arity.
+        let probe = (end - start).min(header_len.max(MAX_ARITY_PROBE * 4) as u64);
+        regions.push((start, if header_len > 0 { probe as usize } else { 0 }));
+    }
+    let heads = access.read_regions(&regions)?;
+    let mut items = Vec::with_capacity(range.len());
+    for (item_idx, head) in range.zip(heads) {
+        let item_len = offsets[item_idx as usize + 1] - offsets[item_idx as usize];
+        let mut sizes = Vec::new();
+        let mut header_field_count = None;
+        if header_len as u64 > item_len {
+            header_field_count = detect_field_count(&head, item_len);
+        } else if header_len > 0 {
+            for j in 0..format_len {
+                let pos = j * 4;
+                sizes.push(read_le_u32(&head[pos..pos + 4])?);
+            }
+            let field_total: u64 = sizes.iter().map(|s| *s as u64).sum();
+            header_field_count = if field_total + header_len as u64 == item_len {
+                Some(format_len)
+            } else {
+                detect_field_count(&head, item_len)
+            };
+        }
+        let arity_mismatch = header_len > 0 && header_field_count != Some(format_len);
+        items.push(ItemMeta {
+            item_index: item_idx,
+            total_bytes: item_len,
+            fields: sizes
+                .into_iter()
+                .enumerate()
+                .map(|(idx, size)| FieldMeta {
+                    field_index: idx,
+                    size,
+                })
+                .collect(),
+            header_field_count,
+            arity_mismatch,
+            labels: None,
+        });
+    }
+    Ok(items)
+}
+
+/// Builds the text/hex preview for the first bytes of a field.
+pub fn build_preview(data: Vec<u8>, size: u32, data_format: Option<&String>) -> FieldPreview {
+    let text = String::from_utf8(data.clone()).ok();
+    let guessed_ext = guess_ext(data_format, &data);
+    let hex_snippet = hex_encode(data.iter().take(48).copied().collect::<Vec<u8>>());
+    FieldPreview {
+        preview_text: text.as_ref().map(|s| s.chars().take(400).collect()),
+        hex_snippet,
+        guessed_ext,
+        is_binary: text.is_none(),
+        size,
+        class_name: None,
+    }
+}
+
+pub fn leaf_file_name(
+    chunk_filename: &str,
+    item_index: u32,
+    field_index: usize,
+    ext: &str,
+) -> String {
+    format!(
+        "{}-i{}-f{}.{}",
+        sanitize(chunk_filename),
+        item_index,
+        field_index,
+        ext
+    )
+}
+
+fn sanitize(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
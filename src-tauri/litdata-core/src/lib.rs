@@ -0,0 +1,36 @@
+//! Reading LitData datasets: index parsing, chunk access, field extraction, content
+//! sniffing, validation and decoder plugins, with no dependency on the desktop app. The
+//! viewer's Tauri commands, its CLI, its Python module and the API server are built on
+//! top of this crate.
+
+mod file_pool;
+
+pub mod archive;
+pub mod balance;
+pub mod chunk;
+pub mod corruption;
+pub mod decoders;
+pub mod error;
+pub mod field_sizes;
+pub mod health;
+pub mod index;
+pub mod item;
+pub mod long_path;
+pub mod prefix;
+pub mod progress;
+pub mod reader;
+pub mod scan;
+pub mod security;
+pub mod sniff;
+pub mod validate;
+
+pub use chunk::{
+    decompress_chunk, open_chunk, parse_offsets, read_field_bytes, read_item_bytes, split_fields,
+    ChunkAccess,
+};
+pub use error::{AppError, AppResult, ErrorContext, ResultExt};
+pub use index::{
+    parse_index, resolve_index_path, ChunkLocation, IndexConfig, IndexMetadata, ParsedIndex,
+    RawChunk, SUPPORTED_FORMAT_VERSION,
+};
+pub use sniff::{guess_ext, sniff_content_type};
@@ -0,0 +1,78 @@
+//! Extended-length paths for Windows. Dataset roots on network shares easily pass
+//! `MAX_PATH`, past which the plain Win32 file APIs fail; the `\\?\` form lifts the
+//! limit. Elsewhere paths pass through unchanged.
+
+use std::path::{Path, PathBuf};
+
+/// Paths at least this long get the `\\?\` prefix. `CreateDirectoryW` gives up at 248
+/// characters, a little before `MAX_PATH`, so shorter paths keep their familiar form.
+#[cfg(target_os = "windows")]
+const LONG_PATH_THRESHOLD: usize = 248;
+
+/// `path` in a form the file APIs accept whatever its length: on Windows a long
+/// absolute path becomes `\\?\C:\...` or `\\?\UNC\server\share\...`.
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let long = path.as_os_str().len() >= LONG_PATH_THRESHOLD;
+        let absolute = if long && !path.is_absolute() {
+            std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+        } else {
+            path.to_path_buf()
+        };
+        if let Some(extended) = absolute.to_str().and_then(extended_form) {
+            return PathBuf::from(extended);
+        }
+    }
+    path.to_path_buf()
+}
+
+/// `path` without the `\\?\` prefix, for showing to people and for tools such as
+/// Explorer that reject it.
+pub fn plain_path(path: &Path) -> PathBuf {
+    let Some(shown) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if let Some(unc) = shown.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{unc}"));
+    }
+    match shown.strip_prefix(r"\\?\") {
+        Some(plain) => PathBuf::from(plain),
+        None => path.to_path_buf(),
+    }
+}
+
+/// The `\\?\` spelling of a long drive or UNC path. Such paths skip Win32 normalization,
+/// so separators are made backslashes and `.` and `..` are resolved here.
+#[cfg(target_os = "windows")]
+fn extended_form(path: &str) -> Option<String> {
+    if path.len() < LONG_PATH_THRESHOLD || path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let path = path.replace('/', r"\");
+    let (prefix, rest, kept) = if let Some(unc) = path.strip_prefix(r"\\") {
+        // `server\share` is the root and cannot be climbed out of.
+        (r"\\?\UNC\", unc, 2)
+    } else if path.as_bytes().get(1) == Some(&b':') && path.as_bytes().get(2) == Some(&b'\\') {
+        (r"\\?\", path.as_str(), 1)
+    } else {
+        // Relative or drive-relative: nothing to anchor a verbatim path to.
+        return None;
+    };
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.len() > kept {
+                    parts.pop();
+                }
+            }
+            part => parts.push(part),
+        }
+    }
+    if parts.len() < kept {
+        return None;
+    }
+    Some(format!("{prefix}{}", parts.join(r"\")))
+}
@@ -0,0 +1,82 @@
+use crate::error::{AppError, AppResult};
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// Decode at least this much more whenever a read runs past the decoded prefix.
+const MIN_DECODE_STEP: u64 = 1024 * 1024;
+
+type ChunkDecoder = zstd::stream::Decoder<'static, BufReader<File>>;
+
+struct PrefixState {
+    /// `None` once the stream has ended or failed.
+    decoder: Option<ChunkDecoder>,
+    decoded: Vec<u8>,
+}
+
+/// A zstd chunk decompressed lazily, only as far as reads have needed so far.
+///
+/// Clones share the decoder and the decoded prefix, so later reads of the same chunk
+/// resume where earlier ones stopped.
+#[derive(Clone)]
+pub struct StreamPrefix {
+    state: Arc<Mutex<PrefixState>>,
+    declared_len: u64,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl StreamPrefix {
+    /// `declared_len` is the index's decompressed `chunk_bytes`.
+    pub fn open(path: &Path, declared_len: u64) -> AppResult<Self> {
+        let decoder = zstd::stream::Decoder::new(File::open(path)?)?;
+        Ok(StreamPrefix {
+            state: Arc::new(Mutex::new(PrefixState {
+                decoder: Some(decoder),
+                decoded: Vec::new(),
+            })),
+            declared_len,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.declared_len
+    }
+
+    pub fn decoded_len(&self) -> usize {
+        self.state.lock().map(|s| s.decoded.len()).unwrap_or(0)
+    }
+
+    pub fn read_exact_at(&self, offset: u64, len: usize) -> AppResult<Vec<u8>> {
+        let end = offset
+            .checked_add(len as u64)
+            .ok_or(AppError::MalformedChunk)?;
+        if end > self.declared_len {
+            return Err(AppError::MalformedChunk);
+        }
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| AppError::Task("chunk stream lock poisoned".into()))?;
+        let have = state.decoded.len() as u64;
+        if end > have {
+            let target = end.max(have + MIN_DECODE_STEP).min(self.declared_len);
+            let PrefixState { decoder, decoded } = &mut *state;
+            if let Some(active) = decoder.as_mut() {
+                let wanted = target - decoded.len() as u64;
+                match active.by_ref().take(wanted).read_to_end(decoded) {
+                    Ok(n) if (n as u64) < wanted => *decoder = None,
+                    Ok(_) => {}
+                    Err(_) => *decoder = None,
+                }
+            }
+        }
+        let decoded = &state.decoded;
+        if end > decoded.len() as u64 {
+            return Err(AppError::MalformedChunk);
+        }
+        Ok(decoded[offset as usize..end as usize].to_vec())
+    }
+}
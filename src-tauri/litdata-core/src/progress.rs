@@ -0,0 +1,35 @@
+//! Progress of long scans, reported the same way whether it ends up in the app's
+//! webview or on a terminal.
+
+use std::io::{self, Write};
+
+/// Receives `(phase, done, total)` as a scan advances. Reports are best-effort: a sink
+/// that cannot show them just drops them.
+pub trait ReportProgress: Sync {
+    fn report(&self, phase: &str, done: u64, total: u64);
+}
+
+/// Share of `total` that `done` represents, as a percentage capped at 100.
+pub fn percent(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        (done as f64 / total as f64 * 100.0).min(100.0)
+    }
+}
+
+/// Progress drawn as a single updating line on stderr, for command-line tools.
+pub struct Terminal;
+
+impl ReportProgress for Terminal {
+    fn report(&self, phase: &str, done: u64, total: u64) {
+        let percent = percent(done, total);
+        let end = if done >= total { "\n" } else { "" };
+        let mut stderr = io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r\x1b[K{phase}: {done}/{total} ({percent:.0}%){end}"
+        );
+        let _ = stderr.flush();
+    }
+}
@@ -0,0 +1,224 @@
+//! Blocking entry points over the same reading code the app's commands use, so the
+//! CLI, the Python module and the GUI interpret datasets alike.
+
+use crate::balance::balance_of;
+use crate::chunk::{open_chunk, parse_offsets, read_field_bytes, ChunkAccess};
+use crate::field_sizes::field_sizes_of;
+use crate::health::health_of;
+use crate::index::{parse_index, ParsedIndex};
+use crate::item::{build_preview, collect_item_meta, leaf_file_name};
+use crate::progress::Terminal;
+use crate::sniff::guess_ext;
+use crate::validate::validate_parsed;
+use serde::Serialize;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+pub use crate::balance::BalanceReport;
+pub use crate::error::{AppError, AppResult};
+pub use crate::field_sizes::FieldSizeReport;
+pub use crate::health::HealthReport;
+pub use crate::item::{FieldPreview, ItemMeta};
+pub use crate::validate::ValidationReport;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkInfo {
+    pub filename: String,
+    pub items: u32,
+    pub bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetStats {
+    index_path: String,
+    root_dir: String,
+    compression: Option<String>,
+    data_format: Vec<String>,
+    chunk_count: usize,
+    total_items: u64,
+    total_bytes: u64,
+    largest_chunk_bytes: u64,
+    missing_chunks: usize,
+    orphan_files: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_sizes: Option<FieldSizeReport>,
+}
+
+/// A parsed index plus the most recently opened chunk, so reading several fields of one
+/// compressed chunk decompresses it once.
+pub struct Dataset {
+    parsed: ParsedIndex,
+    last_chunk: Mutex<Option<(String, Arc<ChunkAccess>)>>,
+}
+
+impl Dataset {
+    /// Opens an index.json, a directory containing one, or a single chunk file.
+    pub fn open(path: &Path) -> AppResult<Self> {
+        Ok(Dataset {
+            parsed: parse_index(path)?,
+            last_chunk: Mutex::new(None),
+        })
+    }
+
+    fn chunk(&self, chunk_filename: &str) -> AppResult<Arc<ChunkAccess>> {
+        if let Ok(last) = self.last_chunk.lock() {
+            if let Some((_, access)) = last.as_ref().filter(|(name, _)| name == chunk_filename) {
+                return Ok(access.clone());
+            }
+        }
+        let access = Arc::new(open_chunk(&self.parsed, chunk_filename)?);
+        if let Ok(mut last) = self.last_chunk.lock() {
+            *last = Some((chunk_filename.to_string(), access.clone()));
+        }
+        Ok(access)
+    }
+
+    pub fn index_path(&self) -> &Path {
+        &self.parsed.source
+    }
+
+    pub fn data_format(&self) -> &[String] {
+        self.parsed
+            .config
+            .data_format
+            .as_deref()
+            .unwrap_or_default()
+    }
+
+    pub fn chunks(&self) -> Vec<ChunkInfo> {
+        self.parsed
+            .chunks
+            .iter()
+            .map(|c| ChunkInfo {
+                filename: c.filename.clone(),
+                items: c.chunk_size,
+                bytes: c.chunk_bytes,
+            })
+            .collect()
+    }
+
+    pub fn items(&self, chunk_filename: &str) -> AppResult<Vec<ItemMeta>> {
+        let access = self.chunk(chunk_filename)?;
+        collect_item_meta(&access, self.parsed.format_len())
+    }
+
+    /// Complete payload of one field.
+    pub fn read_field(
+        &self,
+        chunk_filename: &str,
+        item_index: u32,
+        field_index: usize,
+    ) -> AppResult<Vec<u8>> {
+        let access = self.chunk(chunk_filename)?;
+        let table = parse_offsets(&access)?;
+        let (data, _) = read_field_bytes(
+            &access,
+            &table,
+            item_index,
+            field_index,
+            self.parsed.format_len(),
+            None,
+        )?;
+        Ok(data)
+    }
+
+    /// The same text/hex preview the app shows, limited to `limit` bytes.
+    pub fn preview_field(
+        &self,
+        chunk_filename: &str,
+        item_index: u32,
+        field_index: usize,
+        limit: usize,
+    ) -> AppResult<FieldPreview> {
+        let access = self.chunk(chunk_filename)?;
+        let table = parse_offsets(&access)?;
+        let (data, size) = read_field_bytes(
+            &access,
+            &table,
+            item_index,
+            field_index,
+            self.parsed.format_len(),
+            Some(limit),
+        )?;
+        Ok(build_preview(
+            data,
+            size,
+            self.data_format().get(field_index),
+        ))
+    }
+
+    /// File name the app would give this field when opening or exporting it.
+    pub fn field_file_name(
+        &self,
+        chunk_filename: &str,
+        item_index: u32,
+        field_index: usize,
+        data: &[u8],
+    ) -> String {
+        let ext =
+            guess_ext(self.data_format().get(field_index), data).unwrap_or_else(|| "bin".into());
+        leaf_file_name(chunk_filename, item_index, field_index, &ext)
+    }
+
+    /// Full structural check of every chunk, reporting progress on stderr.
+    pub fn validate(&self) -> AppResult<ValidationReport> {
+        validate_parsed(
+            &self.parsed,
+            |chunk| open_chunk(&self.parsed, chunk),
+            &Terminal,
+        )
+    }
+
+    pub fn health(&self) -> AppResult<HealthReport> {
+        health_of(&self.parsed)
+    }
+
+    /// Chunk fill against the configured target, flagging chunks outside
+    /// `[underfilled_below, oversized_above]` of it.
+    pub fn balance(
+        &self,
+        underfilled_below: f64,
+        oversized_above: f64,
+    ) -> AppResult<BalanceReport> {
+        balance_of(&self.parsed, underfilled_below, oversized_above)
+    }
+
+    /// Index totals and health; with `field_sample`, also the per-field size
+    /// distribution over that many items.
+    pub fn stats(&self, field_sample: Option<u32>) -> AppResult<DatasetStats> {
+        let health = self.health()?;
+        let field_sizes = field_sample
+            .map(|sample| {
+                field_sizes_of(
+                    &self.parsed,
+                    sample,
+                    |chunk| open_chunk(&self.parsed, chunk),
+                    &Terminal,
+                )
+            })
+            .transpose()?;
+        Ok(DatasetStats {
+            index_path: self.parsed.source.display().to_string(),
+            root_dir: self.parsed.root_dir.display().to_string(),
+            compression: self.parsed.config.compression.clone(),
+            data_format: self.data_format().to_vec(),
+            chunk_count: self.parsed.chunks.len(),
+            total_items: self.parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+            total_bytes: self.parsed.chunks.iter().map(|c| c.chunk_bytes).sum(),
+            largest_chunk_bytes: self
+                .parsed
+                .chunks
+                .iter()
+                .map(|c| c.chunk_bytes)
+                .max()
+                .unwrap_or(0),
+            missing_chunks: health.missing.len(),
+            orphan_files: health.orphans.len(),
+            field_sizes,
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::progress::ReportProgress;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    OnceLock,
+};
+
+/// Chunks processed at once by full-dataset scans; each may hold a decompressed chunk.
+const MAX_CONCURRENT_CHUNKS: usize = 4;
+
+fn pool() -> Option<&'static ThreadPool> {
+    static POOL: OnceLock<Option<ThreadPool>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_CONCURRENT_CHUNKS);
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("litdata-scan-{i}"))
+            .build()
+            .ok()
+    })
+    .as_ref()
+}
+
+/// Maps `f` over `items` on the scan pool, keeping input order and reporting each
+/// completion under `phase`.
+pub fn par_map<I, T, F>(items: &[I], progress: Option<(&dyn ReportProgress, &str)>, f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let total = items.len() as u64;
+    let done = AtomicU64::new(0);
+    let run = || {
+        items
+            .par_iter()
+            .map(|item| {
+                let out = f(item);
+                let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Some((progress, phase)) = progress {
+                    progress.report(phase, finished, total);
+                }
+                out
+            })
+            .collect()
+    };
+    match pool() {
+        Some(pool) => pool.install(run),
+        None => run(),
+    }
+}
@@ -0,0 +1,150 @@
+use crate::error::{AppError, AppResult};
+use crate::long_path::{long_path, plain_path};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Escape hatch for datasets that deliberately reference chunks outside their root.
+static ALLOW_OUTSIDE_ROOT: AtomicBool = AtomicBool::new(false);
+
+pub fn allow_outside_root() -> bool {
+    ALLOW_OUTSIDE_ROOT.load(Ordering::Relaxed)
+}
+
+pub fn set_allow_outside_root(allow: bool) {
+    ALLOW_OUTSIDE_ROOT.store(allow, Ordering::Relaxed);
+}
+
+/// Joins an index-provided chunk filename onto `root`, rejecting anything that would
+/// resolve outside of it (drive prefixes or `..` escapes). Absolute filenames, which
+/// some tooling writes, are used as they are when they point inside `root`.
+///
+/// The check is lexical; symlinks inside the dataset are followed as-is. Long results
+/// come back in Windows' extended-length form.
+pub fn confine_to_root(root: &Path, chunk_filename: &str) -> AppResult<PathBuf> {
+    confine(root, chunk_filename).map(|path| long_path(&path))
+}
+
+fn confine(root: &Path, chunk_filename: &str) -> AppResult<PathBuf> {
+    let candidate = Path::new(chunk_filename);
+    if looks_absolute(chunk_filename) {
+        return absolute_chunk_path(root, chunk_filename);
+    }
+    if allow_outside_root() {
+        return Ok(root.join(candidate));
+    }
+    let mut resolved: PathBuf = root.components().collect();
+    let base_depth = resolved.components().count();
+    for component in candidate.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if resolved.components().count() <= base_depth {
+                    return Err(escape_error(chunk_filename));
+                }
+                resolved.pop();
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(escape_error(chunk_filename));
+            }
+        }
+    }
+    if resolved.components().count() <= base_depth {
+        return Err(AppError::Invalid(format!(
+            "chunk filename {chunk_filename:?} does not name a file"
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Absolute on this platform, or on the one that wrote the index: `/data/...` and
+/// `C:\data\...` both count, so an index from another OS is not joined onto `root`.
+fn looks_absolute(chunk_filename: &str) -> bool {
+    let bytes = chunk_filename.as_bytes();
+    Path::new(chunk_filename).is_absolute()
+        || chunk_filename.starts_with(['/', '\\'])
+        || (bytes.len() > 2
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'/' | b'\\'))
+}
+
+/// Last component of a filename written with either separator, or `None` when it does
+/// not name a file: empty, or `.`/`..` as in `C:\data\..` read on a Unix host.
+fn bare_name(chunk_filename: &str) -> Option<&str> {
+    chunk_filename
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !matches!(*name, "" | "." | ".."))
+}
+
+/// `path` with `.` and `..` resolved lexically, and without the `\\?\` prefix so a long
+/// root still contains the plain absolute paths an index lists.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in plain_path(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// An absolute chunk path inside `root` is used directly. Outside it, a file of the
+/// same name next to the index wins, since the dataset has usually been moved since the
+/// index was written; failing that the path is used only when chunks outside the root
+/// are allowed.
+fn absolute_chunk_path(root: &Path, chunk_filename: &str) -> AppResult<PathBuf> {
+    let path = Path::new(chunk_filename);
+    let native = path.is_absolute();
+    if native {
+        let resolved = normalize(path);
+        let root = normalize(root);
+        if resolved.starts_with(&root) && resolved != root {
+            return Ok(resolved);
+        }
+    }
+    if let Some(name) = bare_name(chunk_filename) {
+        let moved = root.join(name);
+        if !native || (!path.exists() && moved.is_file()) {
+            return Ok(moved);
+        }
+    }
+    if native && allow_outside_root() {
+        return Ok(normalize(path));
+    }
+    Err(escape_error(chunk_filename))
+}
+
+/// Name for the chunk in a copy of its dataset: relative filenames are kept, absolute
+/// ones become their path under `root`, or just their file name when outside it.
+pub fn relative_chunk_name(root: &Path, chunk_filename: &str) -> String {
+    if !looks_absolute(chunk_filename) {
+        return chunk_filename.to_string();
+    }
+    let path = Path::new(chunk_filename);
+    if path.is_absolute() {
+        if let Ok(inner) = normalize(path).strip_prefix(normalize(root)) {
+            let parts: Vec<String> = inner
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            if !parts.is_empty() {
+                return parts.join("/");
+            }
+        }
+    }
+    bare_name(chunk_filename).unwrap_or_default().to_string()
+}
+
+fn escape_error(chunk_filename: &str) -> AppError {
+    AppError::Security(format!(
+        "chunk filename {chunk_filename:?} resolves outside the dataset root"
+    ))
+}
@@ -0,0 +1,93 @@
+pub fn guess_ext(data_format: Option<&String>, data: &[u8]) -> Option<String> {
+    if let Some(fmt) = data_format {
+        let fmt_lower = fmt.to_lowercase();
+        if fmt_lower == "bytes" || fmt_lower == "bin" {
+            if let Some(magic) = detect_magic_ext(data) {
+                return Some(magic);
+            }
+            return Some("bin".into());
+        }
+    }
+    if let Some(fmt) = data_format {
+        if let Some((_, subtype)) = fmt.split_once(':') {
+            if !subtype.is_empty() {
+                return Some(subtype.trim().trim_start_matches('.').to_string());
+            }
+        }
+        if let Some((_, ext)) = fmt.rsplit_once('.') {
+            if !ext.is_empty() {
+                return Some(ext.to_string());
+            }
+        }
+        let fmt_lower = fmt.to_lowercase();
+        let map = [
+            ("jpeg", "jpg"),
+            ("jpg", "jpg"),
+            ("pil", "png"),
+            ("png", "png"),
+            ("tiff", "tiff"),
+            ("str", "txt"),
+            ("string", "txt"),
+            ("int", "txt"),
+            ("float", "txt"),
+            ("bool", "txt"),
+            ("bytes", "bin"),
+            ("audio", "wav"),
+        ];
+        if let Some((_, ext)) = map.iter().find(|(k, _)| *k == fmt_lower) {
+            return Some((*ext).into());
+        }
+        if fmt_lower.contains("wav") {
+            return Some("wav".into());
+        }
+        if fmt_lower.contains("mp3") {
+            return Some("mp3".into());
+        }
+        if fmt_lower.contains("flac") {
+            return Some("flac".into());
+        }
+    }
+    if let Some(magic_ext) = detect_magic_ext(data) {
+        return Some(magic_ext);
+    }
+    if std::str::from_utf8(data)
+        .map(|s| !s.trim().is_empty())
+        .unwrap_or(false)
+    {
+        return Some("txt".into());
+    }
+    infer::get(data).map(|t| t.extension().to_string())
+}
+
+/// Content type of a field judged from its bytes alone, as a file extension.
+pub fn sniff_content_type(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "empty".into();
+    }
+    if let Some(ext) = detect_magic_ext(data) {
+        return ext;
+    }
+    if let Some(kind) = infer::get(data) {
+        return kind.extension().to_string();
+    }
+    if std::str::from_utf8(data).is_ok() {
+        return "txt".into();
+    }
+    "bin".into()
+}
+
+fn detect_magic_ext(data: &[u8]) -> Option<String> {
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+        return Some("wav".into());
+    }
+    if data.len() >= 3 && &data[0..3] == b"ID3" {
+        return Some("mp3".into());
+    }
+    if data.len() >= 2 && data[0] == 0xFF && (data[1] & 0xE0) == 0xE0 {
+        return Some("mp3".into());
+    }
+    if data.len() >= 4 && &data[0..4] == b"fLaC" {
+        return Some("flac".into());
+    }
+    None
+}
@@ -0,0 +1,291 @@
+use crate::chunk::{parse_offsets, read_le_u32, ChunkAccess, OffsetWidth};
+use crate::error::{AppError, AppResult};
+use crate::index::{ParsedIndex, RawChunk};
+use crate::progress::ReportProgress;
+use crate::scan::par_map;
+use serde::Serialize;
+
+/// Stop collecting issues for a chunk after this many; the chunk is already known to be bad.
+const MAX_ISSUES_PER_CHUNK: usize = 64;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    MissingFile,
+    UnreadableChunk,
+    ItemCountMismatch,
+    ByteSizeMismatch,
+    OffsetsNotMonotonic,
+    OffsetOutOfBounds,
+    FieldSizeMismatch,
+    FormatArityMismatch,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    kind: IssueKind,
+    item_index: Option<u32>,
+    message: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkReport {
+    filename: String,
+    ok: bool,
+    declared_items: u32,
+    actual_items: Option<u32>,
+    declared_bytes: u64,
+    actual_bytes: Option<u64>,
+    issues: Vec<ValidationIssue>,
+    truncated_issues: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    index_path: String,
+    ok: bool,
+    chunk_count: usize,
+    bad_chunks: usize,
+    total_items: u64,
+    chunks: Vec<ChunkReport>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.ok
+    }
+}
+
+impl ChunkReport {
+    fn new(chunk: &RawChunk) -> Self {
+        ChunkReport {
+            filename: chunk.filename.clone(),
+            ok: true,
+            declared_items: chunk.chunk_size,
+            actual_items: None,
+            declared_bytes: chunk.chunk_bytes,
+            actual_bytes: None,
+            issues: Vec::new(),
+            truncated_issues: false,
+        }
+    }
+
+    fn push(&mut self, kind: IssueKind, item_index: Option<u32>, message: String) {
+        self.ok = false;
+        if self.issues.len() >= MAX_ISSUES_PER_CHUNK {
+            self.truncated_issues = true;
+            return;
+        }
+        self.issues.push(ValidationIssue {
+            kind,
+            item_index,
+            message,
+        });
+    }
+}
+
+/// Checks every chunk of `parsed` against the index, opening each through `load`.
+pub fn validate_parsed<F>(
+    parsed: &ParsedIndex,
+    load: F,
+    progress: &dyn ReportProgress,
+) -> AppResult<ValidationReport>
+where
+    F: Fn(&str) -> AppResult<ChunkAccess> + Sync,
+{
+    let total = parsed.chunks.len() as u64;
+    progress.report("validating", 0, total);
+    let chunks = par_map(&parsed.chunks, Some((progress, "validating")), |chunk| {
+        validate_chunk(parsed, chunk, &load)
+    });
+    progress.report("done", total, total);
+    let total_items = chunks
+        .iter()
+        .map(|c| c.actual_items.unwrap_or(0) as u64)
+        .sum();
+    let bad_chunks = chunks.iter().filter(|c| !c.ok).count();
+    Ok(ValidationReport {
+        index_path: parsed.source.display().to_string(),
+        ok: bad_chunks == 0,
+        chunk_count: chunks.len(),
+        bad_chunks,
+        total_items,
+        chunks,
+    })
+}
+
+fn validate_chunk<F>(parsed: &ParsedIndex, chunk: &RawChunk, load: &F) -> ChunkReport
+where
+    F: Fn(&str) -> AppResult<ChunkAccess>,
+{
+    let mut report = ChunkReport::new(chunk);
+    let access = match load(&chunk.filename) {
+        Ok(access) => access,
+        Err(AppError::Missing(path)) => {
+            report.push(
+                IssueKind::MissingFile,
+                None,
+                format!("{path} does not exist"),
+            );
+            return report;
+        }
+        Err(e) => {
+            report.push(IssueKind::UnreadableChunk, None, e.to_string());
+            return report;
+        }
+    };
+    if let Err(e) = check_chunk(parsed, chunk, &access, &mut report) {
+        report.push(IssueKind::UnreadableChunk, None, e.to_string());
+    }
+    report
+}
+
+fn check_chunk(
+    parsed: &ParsedIndex,
+    chunk: &RawChunk,
+    access: &ChunkAccess,
+    report: &mut ChunkReport,
+) -> AppResult<()> {
+    let total_len = access.len()?;
+    report.actual_bytes = Some(total_len);
+    if total_len != chunk.chunk_bytes {
+        report.push(
+            IssueKind::ByteSizeMismatch,
+            None,
+            format!(
+                "index declares {} bytes, chunk holds {}",
+                chunk.chunk_bytes, total_len
+            ),
+        );
+    }
+
+    let (num_items, offsets) = parse_offsets(access)?;
+    report.actual_items = Some(num_items);
+    if num_items != chunk.chunk_size {
+        report.push(
+            IssueKind::ItemCountMismatch,
+            None,
+            format!(
+                "index declares {} items, chunk header has {}",
+                chunk.chunk_size, num_items
+            ),
+        );
+    }
+
+    let table_end = if offsets.first() == Some(&OffsetWidth::U64.table_end(num_items)) {
+        OffsetWidth::U64.table_end(num_items)
+    } else {
+        OffsetWidth::U32.table_end(num_items)
+    };
+    if let Some(first) = offsets.first() {
+        if *first < table_end {
+            report.push(
+                IssueKind::OffsetOutOfBounds,
+                Some(0),
+                format!(
+                    "first item starts at {first}, inside the offsets table (ends at {table_end})"
+                ),
+            );
+        }
+    }
+    if let Some(last) = offsets.last() {
+        if *last > total_len {
+            report.push(
+                IssueKind::OffsetOutOfBounds,
+                num_items.checked_sub(1),
+                format!("last offset {last} is past the end of the chunk ({total_len} bytes)"),
+            );
+        }
+    }
+
+    let format_len = parsed
+        .config
+        .data_format
+        .as_ref()
+        .map(|v| v.len())
+        .unwrap_or(0);
+    let header_len = (format_len * 4) as u64;
+    for item in 0..num_items {
+        let start = offsets[item as usize];
+        let end = offsets[item as usize + 1];
+        if end < start {
+            report.push(
+                IssueKind::OffsetsNotMonotonic,
+                Some(item),
+                format!("item ends at {end} before it starts at {start}"),
+            );
+            continue;
+        }
+        if end > total_len {
+            report.push(
+                IssueKind::OffsetOutOfBounds,
+                Some(item),
+                format!("item spans {start}..{end} past the end of the chunk ({total_len} bytes)"),
+            );
+            continue;
+        }
+        if header_len == 0 {
+            continue;
+        }
+        let item_len = end - start;
+        if item_len < header_len {
+            report.push(
+                IssueKind::FormatArityMismatch,
+                Some(item),
+                format!("item is {item_len} bytes, too short for a {format_len}-field header"),
+            );
+            continue;
+        }
+        let head = access.read_exact_at(start, header_len as usize)?;
+        let mut field_total = 0u64;
+        for pos in (0..head.len()).step_by(4) {
+            field_total += read_le_u32(&head[pos..pos + 4])? as u64;
+        }
+        if field_total + header_len != item_len {
+            let detected = access
+                .read_exact_at(start, item_len.min(MAX_ARITY_PROBE as u64 * 4) as usize)
+                .ok()
+                .and_then(|probe| detect_field_count(&probe, item_len));
+            match detected {
+                Some(count) if count != format_len => report.push(
+                    IssueKind::FormatArityMismatch,
+                    Some(item),
+                    format!(
+                        "data_format has {format_len} fields but the item header encodes {count}"
+                    ),
+                ),
+                _ => report.push(
+                    IssueKind::FieldSizeMismatch,
+                    Some(item),
+                    format!(
+                        "field sizes sum to {} bytes (+{} header) but the item is {} bytes",
+                        field_total, header_len, item_len
+                    ),
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Largest field count considered when guessing the real header arity.
+pub const MAX_ARITY_PROBE: usize = 64;
+
+/// Finds the smallest field count whose size header exactly accounts for `item_len` bytes.
+pub fn detect_field_count(head: &[u8], item_len: u64) -> Option<usize> {
+    let mut sum = 0u64;
+    for (idx, chunk) in head.chunks_exact(4).take(MAX_ARITY_PROBE).enumerate() {
+        sum += read_le_u32(chunk).ok()? as u64;
+        let count = idx + 1;
+        if sum + (count as u64) * 4 == item_len {
+            return Some(count);
+        }
+        if sum + (count as u64) * 4 > item_len {
+            return None;
+        }
+    }
+    None
+}
@@ -0,0 +1,108 @@
+//! `litdata_viewer` Python module over [`litdata_core::reader`], so integrity checks and
+//! raw reads from Python go through the same parsing code as the app.
+
+use litdata_core::reader::{AppError, Dataset as CoreDataset};
+use pyo3::{create_exception, exceptions::PyException, prelude::*, types::PyBytes};
+use serde::Serialize;
+use std::path::PathBuf;
+
+create_exception!(litdata_viewer, LitDataError, PyException);
+
+/// Raises a reader error as `LitDataError`.
+fn py_err(err: AppError) -> PyErr {
+    LitDataError::new_err(err.to_string())
+}
+
+/// Hands a report to Python as plain dicts/lists, with the same camelCase keys the app uses.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value)
+        .map_err(|e| LitDataError::new_err(format!("serializing result: {e}")))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+/// An opened dataset: an index.json, a directory containing one, or a single chunk file.
+#[pyclass(name = "Dataset", module = "litdata_viewer", frozen)]
+struct PyDataset {
+    inner: CoreDataset,
+}
+
+#[pymethods]
+impl PyDataset {
+    #[new]
+    fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py
+            .allow_threads(|| CoreDataset::open(&path))
+            .map_err(py_err)?;
+        Ok(PyDataset { inner })
+    }
+
+    #[getter]
+    fn index_path(&self) -> PathBuf {
+        self.inner.index_path().to_path_buf()
+    }
+
+    #[getter]
+    fn data_format(&self) -> Vec<String> {
+        self.inner.data_format().to_vec()
+    }
+
+    fn chunks(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.chunks())
+    }
+
+    fn items(&self, py: Python<'_>, chunk_filename: &str) -> PyResult<PyObject> {
+        let items = py
+            .allow_threads(|| self.inner.items(chunk_filename))
+            .map_err(py_err)?;
+        to_py(py, &items)
+    }
+
+    /// Raw bytes of one field, exactly as stored in the chunk.
+    fn read_field<'py>(
+        &self,
+        py: Python<'py>,
+        chunk_filename: &str,
+        item_index: u32,
+        field_index: usize,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let data = py
+            .allow_threads(|| {
+                self.inner
+                    .read_field(chunk_filename, item_index, field_index)
+            })
+            .map_err(py_err)?;
+        Ok(PyBytes::new(py, &data))
+    }
+
+    /// Full structural check of every chunk; progress goes to stderr.
+    fn validate(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let report = py.allow_threads(|| self.inner.validate()).map_err(py_err)?;
+        to_py(py, &report)
+    }
+
+    fn health(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let report = py.allow_threads(|| self.inner.health()).map_err(py_err)?;
+        to_py(py, &report)
+    }
+
+    /// With `sample`, also per-field size percentiles over that many items.
+    #[pyo3(signature = (sample=None))]
+    fn stats(&self, py: Python<'_>, sample: Option<u32>) -> PyResult<PyObject> {
+        let stats = py
+            .allow_threads(|| self.inner.stats(sample))
+            .map_err(py_err)?;
+        to_py(py, &stats)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Dataset({:?})", self.inner.index_path())
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "litdata_viewer")]
+fn litdata_viewer(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDataset>()?;
+    m.add("LitDataError", m.py().get_type::<LitDataError>())?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::litdata::{AppError, AppResult};
+use crate::settings;
+use litdata_core::archive;
+use std::{future::Future, io, path::Path, time::Duration, time::SystemTime};
+
+/// Reads that stall longer than this (a dropped network mount, a sleeping disk) fail
+/// with `AppError::Timeout` instead of pinning a command forever. Overridable in settings.
+pub(crate) const DEFAULT_IO_TIMEOUT_SECS: u64 = 30;
+
+async fn bounded<T>(path: &Path, op: impl Future<Output = io::Result<T>>) -> AppResult<T> {
+    let timeout = Duration::from_secs(settings::current().io_timeout_secs);
+    match tokio::time::timeout(timeout, op).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(AppError::Timeout(path.display().to_string())),
+    }
+}
+
+pub(crate) async fn read(path: &Path) -> AppResult<Vec<u8>> {
+    bounded(path, tokio::fs::read(path)).await
+}
+
+pub(crate) async fn exists(path: &Path) -> bool {
+    bounded(path, tokio::fs::try_exists(path))
+        .await
+        .unwrap_or(false)
+}
+
+pub(crate) async fn canonicalize(path: &Path) -> AppResult<std::path::PathBuf> {
+    bounded(path, tokio::fs::canonicalize(path)).await
+}
+
+/// (mtime, len) of `path`, used to notice that a cached parse went stale.
+pub(crate) async fn stamp(path: &Path) -> (Option<SystemTime>, u64) {
+    let path = &archive::backing_file(path);
+    bounded(path, tokio::fs::metadata(path))
+        .await
+        .map(|m| (m.modified().ok(), m.len()))
+        .unwrap_or((None, 0))
+}
@@ -0,0 +1,520 @@
+use crate::class_names::{self, ClassNames};
+use crate::litdata::{
+    guess_ext, load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError,
+    AppResult, ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Skipped items listed in the report; the rest are only counted.
+const MAX_LISTED_SKIPS: usize = 100;
+/// Keys holding a list of objects, tried on an annotation dict before anything else.
+const LIST_KEYS: [&str; 3] = ["annotations", "objects", "instances"];
+const BOX_KEYS: [&str; 4] = ["bbox", "box", "boxes", "bboxes"];
+const LABEL_KEYS: [&str; 7] = [
+    "category_id",
+    "label",
+    "labels",
+    "class",
+    "classes",
+    "class_id",
+    "category",
+];
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationFormat {
+    /// One `annotations.json` with images, annotations and categories.
+    Coco,
+    /// One `labels/<image>.txt` per image plus an Ultralytics `data.yaml`.
+    Yolo,
+}
+
+/// How the boxes in the annotation field are written.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoxFormat {
+    /// Left, top, width, height, as COCO does.
+    #[default]
+    Xywh,
+    /// Left, top, right, bottom, as torchvision does.
+    Xyxy,
+    /// Center x, center y, width, height, as YOLO does.
+    Cxcywh,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedCategory {
+    id: u32,
+    name: String,
+    annotations: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedItem {
+    chunk_filename: String,
+    item_index: u32,
+    reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationExportReport {
+    job_id: String,
+    format: AnnotationFormat,
+    dest_dir: String,
+    /// `annotations.json` for COCO, `data.yaml` for YOLO.
+    annotation_path: String,
+    images: u64,
+    annotations: u64,
+    categories: Vec<ExportedCategory>,
+    skipped_items: u64,
+    /// The first skipped items, with why.
+    skipped: Vec<SkippedItem>,
+}
+
+/// One object: its label as written in the field, and its box as absolute `xywh`.
+struct Object {
+    label: String,
+    bbox: [f64; 4],
+}
+
+struct ExportedImage {
+    id: u64,
+    file_name: String,
+    width: u32,
+    height: u32,
+    objects: Vec<Object>,
+}
+
+/// Writes the images in `image_field` to `dest_dir/images` and the boxes and labels in
+/// the JSON `annotation_field` as a COCO or YOLO annotation set beside them. Boxes are
+/// read as `box_format` (COCO's `xywh` by default), in pixels unless `normalized_boxes`.
+/// Integer labels take their category names from the dataset's class map.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_annotations(
+    app: AppHandle,
+    index_path: String,
+    image_field: usize,
+    annotation_field: usize,
+    dest_dir: String,
+    format: AnnotationFormat,
+    box_format: Option<BoxFormat>,
+    normalized_boxes: Option<bool>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<AnnotationExportReport> {
+    let progress = Progress::new(app.clone(), job_id, "annotations");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(index_path.trim()))?;
+        let dest = Path::new(dest_dir.trim());
+        ensure_writable(dest, &workspace.dataset_dirs(&parsed))?;
+        let names = class_names::for_dataset(&app, &parsed);
+        export_sync(
+            &parsed,
+            image_field,
+            annotation_field,
+            dest,
+            format,
+            box_format.unwrap_or_default(),
+            normalized_boxes.unwrap_or(false),
+            names.as_deref(),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn first_key<'a>(object: &'a serde_json::Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| object.get(*key))
+}
+
+fn label_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_box(value: &Value) -> Option<[f64; 4]> {
+    let values = value.as_array()?;
+    if values.len() != 4 {
+        return None;
+    }
+    let mut bbox = [0.0; 4];
+    for (slot, value) in bbox.iter_mut().zip(values) {
+        *slot = value.as_f64()?;
+    }
+    Some(bbox)
+}
+
+/// Objects in an annotation field: a list of `{bbox, label}` dicts, a dict of parallel
+/// `boxes` and `labels` lists, or either wrapped under `annotations`.
+fn parse_objects(value: &Value) -> Option<Vec<(String, [f64; 4])>> {
+    match value {
+        Value::Array(entries) => entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_object()?;
+                let bbox = parse_box(first_key(entry, &BOX_KEYS)?)?;
+                Some((label_text(first_key(entry, &LABEL_KEYS)?)?, bbox))
+            })
+            .collect(),
+        Value::Object(object) => {
+            if let Some(list) = first_key(object, &LIST_KEYS) {
+                return parse_objects(list);
+            }
+            let boxes = first_key(object, &BOX_KEYS)?.as_array()?;
+            let labels = first_key(object, &LABEL_KEYS)?.as_array()?;
+            if boxes.len() != labels.len() {
+                return None;
+            }
+            boxes
+                .iter()
+                .zip(labels)
+                .map(|(bbox, label)| Some((label_text(label)?, parse_box(bbox)?)))
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+/// Converts a box to absolute `xywh` in an image of `width` x `height`.
+fn to_xywh(
+    bbox: [f64; 4],
+    format: BoxFormat,
+    normalized: bool,
+    width: u32,
+    height: u32,
+) -> [f64; 4] {
+    let [a, b, c, d] = if normalized {
+        let (w, h) = (width as f64, height as f64);
+        [bbox[0] * w, bbox[1] * h, bbox[2] * w, bbox[3] * h]
+    } else {
+        bbox
+    };
+    match format {
+        BoxFormat::Xywh => [a, b, c, d],
+        BoxFormat::Xyxy => [a, b, c - a, d - b],
+        BoxFormat::Cxcywh => [a - c / 2.0, b - d / 2.0, c, d],
+    }
+}
+
+/// Category ids by label. Labels that are all non-negative integers keep their value;
+/// otherwise ids follow the labels' sorted order from 0.
+fn category_ids(labels: impl Iterator<Item = String>) -> BTreeMap<String, u32> {
+    let labels: Vec<String> = labels.collect();
+    if labels.iter().all(|label| label.parse::<u32>().is_ok()) {
+        return labels
+            .into_iter()
+            .map(|label| {
+                let id = label.parse().unwrap_or_default();
+                (label, id)
+            })
+            .collect();
+    }
+    let mut sorted = labels;
+    sorted.sort();
+    sorted.dedup();
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(id, label)| (label, id as u32))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn export_sync(
+    parsed: &ParsedIndex,
+    image_field: usize,
+    annotation_field: usize,
+    dest: &Path,
+    format: AnnotationFormat,
+    box_format: BoxFormat,
+    normalized: bool,
+    names: Option<&ClassNames>,
+    progress: &Progress,
+) -> AppResult<AnnotationExportReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    if image_field >= formats.len() || annotation_field >= formats.len() {
+        return Err(AppError::Invalid("field index out of range".into()));
+    }
+    if image_field == annotation_field {
+        return Err(AppError::Invalid(
+            "image and annotation fields must differ".into(),
+        ));
+    }
+    if dest.as_os_str().is_empty() {
+        return Err(AppError::Invalid("destination directory is empty".into()));
+    }
+    if fs::read_dir(dest).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(AppError::Invalid(format!(
+            "{} is not empty; choose a new folder for the export",
+            dest.display()
+        )));
+    }
+    let images_dir = dest.join("images");
+    fs::create_dir_all(&images_dir)?;
+    let result = export_into(
+        parsed,
+        &formats,
+        image_field,
+        annotation_field,
+        dest,
+        &images_dir,
+        format,
+        box_format,
+        normalized,
+        names,
+        progress,
+    );
+    if result.is_err() {
+        // The folder was empty or new, so nothing else is lost.
+        let _ = fs::remove_dir_all(dest);
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn export_into(
+    parsed: &ParsedIndex,
+    formats: &[String],
+    image_field: usize,
+    annotation_field: usize,
+    dest: &Path,
+    images_dir: &Path,
+    format: AnnotationFormat,
+    box_format: BoxFormat,
+    normalized: bool,
+    names: Option<&ClassNames>,
+    progress: &Progress,
+) -> AppResult<AnnotationExportReport> {
+    let mut first_item = 0u64;
+    let chunks: Vec<(usize, u64)> = parsed
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(position, chunk)| {
+            let start = first_item;
+            first_item += chunk.chunk_size as u64;
+            (position, start)
+        })
+        .collect();
+    let total = chunks.len() as u64;
+    progress.report("exporting", 0, total);
+    let exported = par_map(
+        &chunks,
+        Some((progress, "exporting")),
+        |(position, start)| {
+            let filename = &parsed.chunks[*position].filename;
+            let scratch = ChunkCache::default();
+            let access = load_chunk_access(parsed, filename, &scratch)?;
+            let table = parse_offsets(&access)?;
+            let mut images = Vec::new();
+            let mut skipped = Vec::new();
+            for item in 0..table.0 {
+                let skip = |reason: String| SkippedItem {
+                    chunk_filename: filename.clone(),
+                    item_index: item,
+                    reason,
+                };
+                let (annotation, _) =
+                    read_field_bytes(&access, &table, item, annotation_field, formats.len(), None)?;
+                let Some(objects) = serde_json::from_slice::<Value>(&annotation)
+                    .ok()
+                    .as_ref()
+                    .and_then(parse_objects)
+                else {
+                    skipped.push(skip("annotation field holds no boxes with labels".into()));
+                    continue;
+                };
+                let (image, _) =
+                    read_field_bytes(&access, &table, item, image_field, formats.len(), None)?;
+                let dimensions = image::ImageReader::new(Cursor::new(&image))
+                    .with_guessed_format()
+                    .ok()
+                    .and_then(|reader| reader.into_dimensions().ok());
+                let Some((width, height)) = dimensions else {
+                    skipped.push(skip("image field is not a readable image".into()));
+                    continue;
+                };
+                let id = start + item as u64;
+                let ext =
+                    guess_ext(formats.get(image_field), &image).unwrap_or_else(|| "bin".into());
+                let file_name = format!("{id:08}.{ext}");
+                fs::write(images_dir.join(&file_name), &image)?;
+                images.push(ExportedImage {
+                    id,
+                    file_name,
+                    width,
+                    height,
+                    objects: objects
+                        .into_iter()
+                        .map(|(label, bbox)| Object {
+                            label,
+                            bbox: to_xywh(bbox, box_format, normalized, width, height),
+                        })
+                        .collect(),
+                });
+            }
+            AppResult::Ok((images, skipped))
+        },
+    );
+    let mut images = Vec::new();
+    let mut skipped = Vec::new();
+    for result in exported {
+        let (chunk_images, chunk_skipped) = result?;
+        images.extend(chunk_images);
+        skipped.extend(chunk_skipped);
+    }
+
+    let ids = category_ids(
+        images
+            .iter()
+            .flat_map(|image| image.objects.iter().map(|o| o.label.clone())),
+    );
+    let mut counts: BTreeMap<u32, u64> = BTreeMap::new();
+    for object in images.iter().flat_map(|image| &image.objects) {
+        *counts.entry(ids[&object.label]).or_default() += 1;
+    }
+    let mut categories: Vec<ExportedCategory> = ids
+        .iter()
+        .map(|(label, id)| ExportedCategory {
+            id: *id,
+            name: label
+                .parse()
+                .ok()
+                .and_then(|class| names?.class_name(class))
+                .unwrap_or(label)
+                .to_string(),
+            annotations: counts.get(id).copied().unwrap_or_default(),
+        })
+        .collect();
+    categories.sort_by_key(|category| category.id);
+
+    let annotation_path = match format {
+        AnnotationFormat::Coco => write_coco(dest, &images, &ids, &categories)?,
+        AnnotationFormat::Yolo => write_yolo(dest, &images, &ids, &categories)?,
+    };
+    progress.report("done", total, total);
+    let skipped_items = skipped.len() as u64;
+    skipped.truncate(MAX_LISTED_SKIPS);
+    Ok(AnnotationExportReport {
+        job_id: progress.job_id().to_string(),
+        format,
+        dest_dir: dest.display().to_string(),
+        annotation_path: annotation_path.display().to_string(),
+        images: images.len() as u64,
+        annotations: counts.values().sum(),
+        categories,
+        skipped_items,
+        skipped,
+    })
+}
+
+fn write_coco(
+    dest: &Path,
+    images: &[ExportedImage],
+    ids: &BTreeMap<String, u32>,
+    categories: &[ExportedCategory],
+) -> AppResult<PathBuf> {
+    let mut annotations = Vec::new();
+    for image in images {
+        for object in &image.objects {
+            let [x, y, w, h] = object.bbox;
+            annotations.push(json!({
+                "id": annotations.len() + 1,
+                "image_id": image.id,
+                "category_id": ids[&object.label],
+                "bbox": [x, y, w, h],
+                "area": w * h,
+                "iscrowd": 0,
+            }));
+        }
+    }
+    let document = json!({
+        "images": images
+            .iter()
+            .map(|image| json!({
+                "id": image.id,
+                "file_name": image.file_name,
+                "width": image.width,
+                "height": image.height,
+            }))
+            .collect::<Vec<_>>(),
+        "annotations": annotations,
+        "categories": categories
+            .iter()
+            .map(|category| json!({ "id": category.id, "name": category.name }))
+            .collect::<Vec<_>>(),
+    });
+    let path = dest.join("annotations.json");
+    let bytes = serde_json::to_vec(&document)
+        .map_err(|e| AppError::Invalid(format!("serializing annotations: {e}")))?;
+    fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+fn write_yolo(
+    dest: &Path,
+    images: &[ExportedImage],
+    ids: &BTreeMap<String, u32>,
+    categories: &[ExportedCategory],
+) -> AppResult<PathBuf> {
+    let labels_dir = dest.join("labels");
+    fs::create_dir_all(&labels_dir)?;
+    for image in images {
+        let (width, height) = (image.width as f64, image.height as f64);
+        let mut lines = String::new();
+        for object in &image.objects {
+            let [x, y, w, h] = object.bbox;
+            let norm = |v: f64, size: f64| (v / size).clamp(0.0, 1.0);
+            lines.push_str(&format!(
+                "{} {:.6} {:.6} {:.6} {:.6}\n",
+                ids[&object.label],
+                norm(x + w / 2.0, width),
+                norm(y + h / 2.0, height),
+                norm(w, width),
+                norm(h, height),
+            ));
+        }
+        let stem = Path::new(&image.file_name).with_extension("txt");
+        fs::write(labels_dir.join(stem), lines)?;
+    }
+    // YOLO class ids index into `names`, so gaps get placeholder names.
+    let mut yaml = format!(
+        "path: {}\ntrain: images\nval: images\nnames:\n",
+        json!(dest.display().to_string())
+    );
+    let last = categories.last().map_or(0, |category| category.id + 1);
+    let by_id: BTreeMap<u32, &str> = categories
+        .iter()
+        .map(|category| (category.id, category.name.as_str()))
+        .collect();
+    for id in 0..last {
+        let name = by_id
+            .get(&id)
+            .map_or_else(|| format!("class{id}"), |name| name.to_string());
+        yaml.push_str(&format!("  {id}: {}\n", json!(name)));
+    }
+    let path = dest.join("data.yaml");
+    fs::write(&path, yaml)?;
+    Ok(path)
+}
@@ -0,0 +1,228 @@
+use crate::index_cache::IndexCache;
+use crate::litdata::{AppError, AppResult, ChunkCache, ParsedIndex};
+use crate::offsets_cache::OffsetsCache;
+use crate::progress::Progress;
+use crate::rewrite::{
+    check_declared_type, encode_chunk, ensure_rewritable, group_into_chunks, index_document,
+    pack_chunk, pack_item, partial_path, write_index_partial, Backup, RewriteCaches,
+};
+use crate::security::ensure_writable;
+use crate::workspace::{DatasetHandle, Workspace};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Where one field's payload comes from. Payloads are stored as given, so they must
+/// already be in the field's serialized form: encoded JPEG bytes for `jpeg`, UTF-8 for
+/// `str`, and so on.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldSource {
+    /// Contents of a local file.
+    File(String),
+    /// UTF-8 text.
+    Text(String),
+    /// Raw bytes, base64-encoded.
+    Base64(String),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendedChunk {
+    filename: String,
+    items: u32,
+    /// Uncompressed size, as recorded in index.json.
+    chunk_bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendReport {
+    job_id: String,
+    index_path: String,
+    appended_items: u64,
+    total_items: u64,
+    chunks: Vec<AppendedChunk>,
+    /// Where the replaced index.json was moved.
+    backup_dir: String,
+}
+
+/// Packs `items`, one source per field in `data_format` order, into new chunks with the
+/// dataset's compression and appends them to index.json. Existing chunks are left
+/// untouched; the previous index.json is moved to a backup directory, and the dataset's
+/// open handles move to the new one.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn append_items(
+    app: AppHandle,
+    handle: DatasetHandle,
+    items: Vec<Vec<FieldSource>>,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    offsets: tauri::State<'_, OffsetsCache>,
+    indexes: tauri::State<'_, IndexCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<AppendReport> {
+    let progress = Progress::new(app, job_id, "append-items");
+    let caches = RewriteCaches {
+        chunks: (*cache).clone(),
+        offsets: (*offsets).clone(),
+        indexes: (*indexes).clone(),
+    };
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = workspace.get(handle)?;
+        append_sync(&parsed, &items, &progress, &caches, &workspace)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+pub(crate) fn load_source(source: &FieldSource) -> AppResult<Vec<u8>> {
+    match source {
+        FieldSource::File(path) => Ok(fs::read(path.trim())?),
+        FieldSource::Text(text) => Ok(text.clone().into_bytes()),
+        FieldSource::Base64(encoded) => STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| AppError::Invalid(format!("invalid base64 payload: {e}"))),
+    }
+}
+
+/// `count` unused chunk filenames continuing litdata's `chunk-{rank}-{index}` numbering
+/// for rank 0.
+fn new_chunk_names(parsed: &ParsedIndex, count: usize) -> AppResult<Vec<String>> {
+    let taken: HashSet<&str> = parsed.chunks.iter().map(|c| c.filename.as_str()).collect();
+    let mut next = parsed
+        .chunks
+        .iter()
+        .filter_map(|c| {
+            let rest = c.filename.strip_prefix("chunk-0-")?;
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u64>().ok()
+        })
+        .max()
+        .map_or(0, |n| n + 1);
+    let suffix = match parsed.config.compression.as_deref() {
+        Some(c) => format!(".{}.bin", c.to_lowercase()),
+        None => ".bin".into(),
+    };
+    let mut names = Vec::with_capacity(count);
+    while names.len() < count {
+        let name = format!("chunk-0-{next}{suffix}");
+        next += 1;
+        if !taken.contains(name.as_str()) && !parsed.chunk_path(&name)?.exists() {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+pub(crate) fn append_sync(
+    parsed: &ParsedIndex,
+    items: &[Vec<FieldSource>],
+    progress: &Progress,
+    caches: &RewriteCaches,
+    workspace: &Workspace,
+) -> AppResult<AppendReport> {
+    ensure_rewritable(parsed)?;
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    if formats.is_empty() {
+        return Err(AppError::Invalid("index declares no data_format".into()));
+    }
+    if items.is_empty() {
+        return Err(AppError::Invalid("no items to append".into()));
+    }
+    ensure_writable(&parsed.root_dir, &workspace.dataset_dirs(parsed))?;
+
+    let total = items.len() as u64;
+    progress.report("reading", 0, total);
+    let mut packed = Vec::with_capacity(items.len());
+    for (i, sources) in items.iter().enumerate() {
+        if sources.len() != formats.len() {
+            return Err(AppError::Invalid(format!(
+                "item {i} has {} fields; data_format declares {}",
+                sources.len(),
+                formats.len()
+            )));
+        }
+        let fields = sources
+            .iter()
+            .map(load_source)
+            .collect::<AppResult<Vec<_>>>()?;
+        for (field, (data, format)) in fields.iter().zip(&formats).enumerate() {
+            check_declared_type(format, data, &format!("item {i} field {field}"))?;
+        }
+        packed.push(pack_item(i as u64, &fields)?);
+        progress.report("reading", i as u64 + 1, total);
+    }
+
+    let lens: Vec<u64> = packed.iter().map(|item| item.len() as u64).collect();
+    let groups = group_into_chunks(parsed.config.chunk_size, parsed.config.chunk_bytes, &lens);
+    let names = new_chunk_names(parsed, groups.len())?;
+    let mut appended = Vec::with_capacity(groups.len());
+    let mut partials = Vec::with_capacity(groups.len());
+    let written = (|| {
+        for (range, name) in groups.iter().zip(&names) {
+            progress.report("writing", range.start as u64, total);
+            let raw = pack_chunk(packed[range.clone()].iter().map(Vec::as_slice));
+            let target = parsed.chunk_path(name)?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let partial = partial_path(&target);
+            fs::write(&partial, encode_chunk(&raw, parsed)?)?;
+            partials.push((partial, target));
+            appended.push(AppendedChunk {
+                filename: name.clone(),
+                items: range.len() as u32,
+                chunk_bytes: raw.len() as u64,
+            });
+        }
+        let mut index = index_document(parsed)?;
+        let entries = index
+            .get_mut("chunks")
+            .and_then(|c| c.as_array_mut())
+            .ok_or_else(|| AppError::Invalid("index.json has no chunks list".into()))?;
+        for chunk in &appended {
+            entries.push(serde_json::json!({
+                "chunk_bytes": chunk.chunk_bytes,
+                "chunk_size": chunk.items,
+                "dim": null,
+                "filename": chunk.filename,
+            }));
+        }
+        write_index_partial(&parsed.source, &index)
+    })();
+    let index_partial = match written {
+        Ok(partial) => partial,
+        Err(e) => {
+            for (partial, _) in &partials {
+                let _ = fs::remove_file(partial);
+            }
+            return Err(e);
+        }
+    };
+
+    for (partial, target) in &partials {
+        fs::rename(partial, target)?;
+        caches.forget_chunk(target);
+    }
+    let backup = Backup::create(&parsed.root_dir)?;
+    backup.replace(&parsed.source, &index_partial)?;
+    caches.refresh_index(parsed, workspace);
+    progress.report("done", total, total);
+    Ok(AppendReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        appended_items: total,
+        total_items: parsed
+            .chunks
+            .iter()
+            .map(|c| c.chunk_size as u64)
+            .sum::<u64>()
+            + total,
+        chunks: appended,
+        backup_dir: backup.dir().display().to_string(),
+    })
+}
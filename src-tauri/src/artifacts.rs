@@ -0,0 +1,498 @@
+use crate::http::{parse_url, Http};
+use crate::litdata::{is_index_file_name, resolve_index_path, AppError, AppResult};
+use crate::progress::Progress;
+use crate::settings;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Url};
+use xxhash_rust::xxh3::xxh3_128;
+
+const DEFAULT_WANDB_BASE_URL: &str = "https://api.wandb.ai";
+
+const WANDB_MANIFEST_QUERY: &str = "\
+query ArtifactManifest($entity: String!, $project: String!, $name: String!) {
+  project(name: $project, entityName: $entity) {
+    artifact(name: $name) { currentManifest { file { directUrl } } }
+  }
+}";
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactProvider {
+    Mlflow,
+    Wandb,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactSource {
+    uri: String,
+    provider: ArtifactProvider,
+    /// Where the dataset's files live, such as `s3://bucket/prefix` or a local directory.
+    resolved: String,
+    /// Directory to open; `None` when the storage cannot be read from here, as for
+    /// object stores.
+    local_path: Option<String>,
+    /// Files fetched through the MLflow tracking server into the local cache.
+    downloaded_files: usize,
+    downloaded_bytes: u64,
+}
+
+/// Resolves an artifact reference to the storage behind it: `runs:/<run_id>/<path>` and
+/// `mlflow-artifacts:/<path>` through the MLflow tracking server, and
+/// `wandb://<entity>/<project>/<name>:<alias>` through the W&B API. Artifacts the
+/// tracking server only serves through its proxy are downloaded to the cache directory.
+#[tauri::command]
+pub async fn resolve_artifact(
+    app: AppHandle,
+    uri: String,
+    job_id: Option<String>,
+) -> AppResult<ArtifactSource> {
+    let progress = Progress::new(app, job_id, "artifact");
+    spawn_blocking(move || resolve_sync(uri.trim(), &progress))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Setting, else environment variable, ignoring blanks.
+fn configured(setting: Option<String>, env: &str) -> Option<String> {
+    setting
+        .or_else(|| std::env::var(env).ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub(crate) fn resolve_sync(uri: &str, progress: &Progress) -> AppResult<ArtifactSource> {
+    progress.report("resolving", 0, 1);
+    let source = if let Some(rest) = uri.strip_prefix("runs:/") {
+        resolve_mlflow_run(uri, rest, progress)
+    } else if uri.starts_with("mlflow-artifacts:") {
+        let (server, path) = split_proxied(uri)?;
+        let server = match server {
+            Some(server) => MlflowServer::at(server)?,
+            None => MlflowServer::configured()?,
+        };
+        fetch_proxied(uri, &server, &path, progress)
+    } else if let Some(reference) = uri
+        .strip_prefix("wandb-artifact://")
+        .or_else(|| uri.strip_prefix("wandb://"))
+    {
+        resolve_wandb(uri, reference.trim_start_matches('/'))
+    } else {
+        Err(AppError::Invalid(format!(
+            "{uri} is not an MLflow (runs:/, mlflow-artifacts:) or W&B (wandb://) reference"
+        )))
+    }?;
+    progress.report("done", 1, 1);
+    Ok(source)
+}
+
+/// A local directory the artifact resolved to, when it holds a dataset.
+fn local_dataset(resolved: &str) -> Option<String> {
+    let path = match resolved.strip_prefix("file://") {
+        Some(rest) => Url::parse(resolved)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .unwrap_or_else(|| PathBuf::from(rest)),
+        None if Path::new(resolved).is_absolute() => PathBuf::from(resolved),
+        None => return None,
+    };
+    resolve_index_path(&path)
+        .is_ok()
+        .then(|| path.display().to_string())
+}
+
+/// `<parent>/<path>` for a `/`-separated artifact path, without doubled separators.
+fn join_artifact_path(parent: &str, path: &str) -> String {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        return parent.to_string();
+    }
+    if parent.is_empty() {
+        return path.to_string();
+    }
+    format!("{}/{path}", parent.trim_end_matches('/'))
+}
+
+/// An artifact path naming an index file stands for the dataset directory holding it.
+fn dataset_dir(path: &str) -> &str {
+    match path.rsplit_once('/') {
+        Some((dir, name)) if is_index_file_name(name) => dir,
+        None if is_index_file_name(path) => "",
+        _ => path,
+    }
+}
+
+enum MlflowServer {
+    Http {
+        base: Url,
+        /// `Authorization` header value.
+        auth: Option<String>,
+    },
+    /// A file-based tracking store, as `mlflow` uses without a server.
+    Local(PathBuf),
+}
+
+impl MlflowServer {
+    fn configured() -> AppResult<Self> {
+        let uri = configured(
+            settings::current().remote.mlflow_tracking_uri.clone(),
+            "MLFLOW_TRACKING_URI",
+        )
+        .ok_or_else(|| {
+            AppError::Invalid(
+                "no MLflow tracking server; set one in settings or MLFLOW_TRACKING_URI".into(),
+            )
+        })?;
+        if uri.starts_with("http://") || uri.starts_with("https://") {
+            return MlflowServer::at(&uri);
+        }
+        let path = match Url::parse(&uri) {
+            Ok(url) if url.scheme() == "file" => url
+                .to_file_path()
+                .map_err(|_| AppError::Invalid(format!("{uri} is not a local path")))?,
+            _ => PathBuf::from(&uri),
+        };
+        Ok(MlflowServer::Local(path))
+    }
+
+    /// The server at `base`, authenticated from `MLFLOW_TRACKING_TOKEN` or
+    /// `MLFLOW_TRACKING_USERNAME`/`MLFLOW_TRACKING_PASSWORD` like the MLflow client.
+    fn at(base: &str) -> AppResult<Self> {
+        let base = if base.contains("://") {
+            base.to_string()
+        } else {
+            format!("http://{base}")
+        };
+        let base = parse_url(&format!("{}/", base.trim_end_matches('/')))?;
+        let auth = match (
+            std::env::var("MLFLOW_TRACKING_TOKEN"),
+            std::env::var("MLFLOW_TRACKING_USERNAME"),
+        ) {
+            (Ok(token), _) => Some(format!("Bearer {token}")),
+            (_, Ok(user)) => {
+                let password = std::env::var("MLFLOW_TRACKING_PASSWORD").unwrap_or_default();
+                Some(format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{user}:{password}"))
+                ))
+            }
+            _ => None,
+        };
+        Ok(MlflowServer::Http { base, auth })
+    }
+}
+
+/// Server named in a `mlflow-artifacts://host:port/path` URI, if any, and the path.
+fn split_proxied(uri: &str) -> AppResult<(Option<&str>, String)> {
+    let rest = uri
+        .strip_prefix("mlflow-artifacts:")
+        .ok_or_else(|| AppError::Invalid(format!("{uri} is not an mlflow-artifacts URI")))?;
+    match rest.strip_prefix("//") {
+        Some(authority_path) => {
+            let (host, path) = authority_path
+                .split_once('/')
+                .unwrap_or((authority_path, ""));
+            let host = (!host.is_empty()).then_some(host);
+            Ok((host, path.trim_matches('/').to_string()))
+        }
+        None => Ok((None, rest.trim_matches('/').to_string())),
+    }
+}
+
+/// `artifact_uri` from a file store's `<experiment>/<run_id>/meta.yaml`.
+fn local_run_artifact_uri(store: &Path, run_id: &str) -> AppResult<String> {
+    let experiments =
+        fs::read_dir(store).map_err(|e| AppError::Io(format!("{}: {e}", store.display())))?;
+    for experiment in experiments.flatten() {
+        let meta = experiment.path().join(run_id).join("meta.yaml");
+        let Ok(text) = fs::read_to_string(&meta) else {
+            continue;
+        };
+        let uri = text.lines().find_map(|line| {
+            line.strip_prefix("artifact_uri:")
+                .map(|v| v.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        });
+        return uri
+            .ok_or_else(|| AppError::Invalid(format!("{} has no artifact_uri", meta.display())));
+    }
+    Err(AppError::Missing(format!(
+        "run {run_id} in {}",
+        store.display()
+    )))
+}
+
+fn resolve_mlflow_run(uri: &str, rest: &str, progress: &Progress) -> AppResult<ArtifactSource> {
+    let rest = rest.trim_start_matches('/');
+    let (run_id, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if run_id.is_empty() || !run_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(AppError::Invalid(format!("{uri} does not name a run id")));
+    }
+    let server = MlflowServer::configured()?;
+    let artifact_root = match &server {
+        MlflowServer::Local(store) => local_run_artifact_uri(store, run_id)?,
+        MlflowServer::Http { base, auth } => {
+            let mut url = base
+                .join("api/2.0/mlflow/runs/get")
+                .map_err(|e| AppError::Invalid(e.to_string()))?;
+            url.query_pairs_mut().append_pair("run_id", run_id);
+            let run = Http::new().get_json(&url, auth.as_deref())?;
+            run.pointer("/run/info/artifact_uri")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| AppError::Invalid(format!("{url} returned no artifact_uri")))?
+        }
+    };
+    let resolved = join_artifact_path(&artifact_root, dataset_dir(path));
+    if resolved.starts_with("mlflow-artifacts:") {
+        let (host, proxied) = split_proxied(&resolved)?;
+        let server = match host {
+            Some(host) => MlflowServer::at(host)?,
+            None => server,
+        };
+        return fetch_proxied(uri, &server, &proxied, progress);
+    }
+    Ok(ArtifactSource {
+        uri: uri.to_string(),
+        provider: ArtifactProvider::Mlflow,
+        local_path: local_dataset(&resolved),
+        resolved,
+        downloaded_files: 0,
+        downloaded_bytes: 0,
+    })
+}
+
+/// Every file below `dir` on the server's artifact proxy, as `(path, size)`.
+fn list_proxied(
+    http: &Http,
+    base: &Url,
+    auth: Option<&str>,
+    dir: &str,
+) -> AppResult<Vec<(String, u64)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_string()];
+    while let Some(dir) = pending.pop() {
+        let mut url = base
+            .join("api/2.0/mlflow-artifacts/artifacts")
+            .map_err(|e| AppError::Invalid(e.to_string()))?;
+        url.query_pairs_mut().append_pair("path", &dir);
+        let listing = http.get_json(&url, auth)?;
+        let entries = listing.get("files").and_then(Value::as_array);
+        for entry in entries.into_iter().flatten() {
+            // Servers report either the full path or the name within `dir`.
+            let Some(name) = entry
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(|p| p.rsplit('/').next())
+            else {
+                continue;
+            };
+            let path = join_artifact_path(&dir, name);
+            if entry.get("is_dir").and_then(Value::as_bool) == Some(true) {
+                pending.push(path);
+            } else {
+                let size = entry.get("file_size").and_then(Value::as_u64).unwrap_or(0);
+                files.push((path, size));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Downloads a dataset served through the tracking server's artifact proxy into the
+/// cache directory. Files already there with the listed size are kept.
+fn fetch_proxied(
+    uri: &str,
+    server: &MlflowServer,
+    path: &str,
+    progress: &Progress,
+) -> AppResult<ArtifactSource> {
+    let MlflowServer::Http { base, auth } = server else {
+        return Err(AppError::Invalid(format!(
+            "{uri} is served by an MLflow tracking server; set its http(s) URL"
+        )));
+    };
+    let dir = dataset_dir(path);
+    let http = Http::new();
+    let files = list_proxied(&http, base, auth.as_deref(), dir)?;
+    if files.is_empty() {
+        return Err(AppError::Missing(format!("{uri} holds no files")));
+    }
+    let resolved = format!("{base}api/2.0/mlflow-artifacts/artifacts/{dir}");
+    let cache_dir = settings::temp_root()
+        .join("artifacts")
+        .join(format!("{:032x}", xxh3_128(resolved.as_bytes())));
+
+    let total = files.len() as u64;
+    let mut downloaded_files = 0;
+    let mut downloaded_bytes = 0;
+    for (done, (path, size)) in files.iter().enumerate() {
+        progress.report("downloading", done as u64, total);
+        let relative = path
+            .strip_prefix(dir)
+            .unwrap_or(path)
+            .trim_start_matches('/');
+        if relative
+            .split('/')
+            .any(|part| part == ".." || part.is_empty())
+        {
+            return Err(AppError::Security(format!(
+                "artifact path {path:?} escapes the download directory"
+            )));
+        }
+        let dest = cache_dir.join(relative);
+        if fs::metadata(&dest).is_ok_and(|m| m.len() == *size) {
+            continue;
+        }
+        let mut url = base
+            .join("api/2.0/mlflow-artifacts/artifacts/")
+            .map_err(|e| AppError::Invalid(e.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Invalid(format!("{base} cannot carry a path")))?
+            .pop_if_empty()
+            .extend(path.split('/'));
+        downloaded_bytes += http.download(&url, auth.as_deref(), &dest)?;
+        downloaded_files += 1;
+    }
+    progress.report("downloading", total, total);
+
+    if resolve_index_path(&cache_dir).is_err() {
+        return Err(AppError::Invalid(format!(
+            "{uri} holds no index.json; point at the dataset directory"
+        )));
+    }
+    Ok(ArtifactSource {
+        uri: uri.to_string(),
+        provider: ArtifactProvider::Mlflow,
+        resolved,
+        local_path: Some(cache_dir.display().to_string()),
+        downloaded_files,
+        downloaded_bytes,
+    })
+}
+
+/// W&B API key from `WANDB_API_KEY`, else the `.netrc` entry `wandb login` writes.
+fn wandb_api_key(host: &str) -> Option<String> {
+    if let Some(key) = configured(None, "WANDB_API_KEY") {
+        return Some(key);
+    }
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    let text = [".netrc", "_netrc"]
+        .iter()
+        .find_map(|name| fs::read_to_string(Path::new(&home).join(name)).ok())?;
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut in_host = false;
+    for pair in tokens.windows(2) {
+        match pair[0] {
+            "machine" => in_host = pair[1] == host,
+            "password" if in_host => return Some(pair[1].to_string()),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolves `<entity>/<project>/<name>[:<alias>][/<path>]` through the artifact's
+/// manifest. Only reference artifacts, whose entries point at external storage, can be
+/// resolved; files uploaded to W&B itself have no location outside of it.
+fn resolve_wandb(uri: &str, reference: &str) -> AppResult<ArtifactSource> {
+    let mut parts = reference.splitn(4, '/');
+    let (Some(entity), Some(project), Some(name)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(AppError::Invalid(format!(
+            "{uri} should look like wandb://<entity>/<project>/<name>:<alias>"
+        )));
+    };
+    let subpath = parts.next().unwrap_or("").trim_matches('/');
+    let name = if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{name}:latest")
+    };
+
+    let base = configured(
+        settings::current().remote.wandb_base_url.clone(),
+        "WANDB_BASE_URL",
+    )
+    .unwrap_or_else(|| DEFAULT_WANDB_BASE_URL.into());
+    let base = parse_url(&format!("{}/", base.trim_end_matches('/')))?;
+    let host = base.host_str().unwrap_or_default().to_string();
+    let key = wandb_api_key(&host).ok_or_else(|| {
+        AppError::Invalid(format!(
+            "no W&B API key for {host}; set WANDB_API_KEY or run `wandb login`"
+        ))
+    })?;
+    let auth = format!("Basic {}", STANDARD.encode(format!("api:{key}")));
+    let http = Http::new();
+    let graphql = base
+        .join("graphql")
+        .map_err(|e| AppError::Invalid(e.to_string()))?;
+    let body = json!({
+        "query": WANDB_MANIFEST_QUERY,
+        "variables": {"entity": entity, "project": project, "name": name},
+    });
+    let answer = http.post_json(&graphql, Some(&auth), &body)?;
+    if let Some(message) = answer.pointer("/errors/0/message").and_then(Value::as_str) {
+        return Err(AppError::Invalid(format!("W&B: {message}")));
+    }
+    let manifest_url = answer
+        .pointer("/data/project/artifact/currentManifest/file/directUrl")
+        .and_then(Value::as_str)
+        .ok_or_else(|| AppError::Missing(format!("artifact {entity}/{project}/{name}")))?;
+    let manifest_url = parse_url(manifest_url)?;
+    // Signed storage URLs must not carry the API key; URLs on the API host need it.
+    let manifest_auth = (manifest_url.host_str() == Some(host.as_str())).then_some(auth.as_str());
+    let manifest = http.get_json(&manifest_url, manifest_auth)?;
+
+    let contents = manifest
+        .get("contents")
+        .and_then(Value::as_object)
+        .ok_or_else(|| AppError::Invalid(format!("{manifest_url} is not an artifact manifest")))?;
+    let under = |path: &str| {
+        subpath.is_empty() || path == subpath || path.starts_with(&format!("{subpath}/"))
+    };
+    let index = contents
+        .keys()
+        .filter(|path| under(path))
+        .filter(|path| is_index_file_name(path.rsplit('/').next().unwrap_or(path)))
+        .min_by_key(|path| (path.matches('/').count(), path.as_str()))
+        .ok_or_else(|| {
+            AppError::Invalid(format!(
+                "artifact {name} has no index.json under {subpath:?}"
+            ))
+        })?;
+    let root = index.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let uploaded = contents
+        .iter()
+        .filter(|(path, _)| root.is_empty() || path.starts_with(&format!("{root}/")))
+        .filter(|(_, entry)| entry.get("ref").and_then(Value::as_str).is_none())
+        .count();
+    if uploaded > 0 {
+        return Err(AppError::Invalid(format!(
+            "{uploaded} files of {name} are stored in W&B itself; download them with \
+             `wandb artifact get {entity}/{project}/{name}` and open that folder"
+        )));
+    }
+    let index_ref = contents[index]
+        .get("ref")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let resolved = index_ref
+        .rsplit_once('/')
+        .map_or(index_ref, |(dir, _)| dir)
+        .to_string();
+    Ok(ArtifactSource {
+        uri: uri.to_string(),
+        provider: ArtifactProvider::Wandb,
+        local_path: local_dataset(&resolved),
+        resolved,
+        downloaded_files: 0,
+        downloaded_bytes: 0,
+    })
+}
@@ -0,0 +1,244 @@
+use crate::litdata::{AppError, AppResult};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use serde::Deserialize;
+use std::{io::Cursor, path::Path};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader, Track},
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+/// Requested conversion when exporting an audio field. The output is always WAV.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioConversion {
+    /// Layout of headerless PCM samples; leave empty for encoded sources (flac, mp3, ogg, wav).
+    pcm: Option<PcmLayout>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PcmLayout {
+    sample_rate: u32,
+    channels: u16,
+    encoding: PcmEncoding,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PcmEncoding {
+    U8,
+    S16le,
+    S24le,
+    S32le,
+    F32le,
+}
+
+impl PcmEncoding {
+    fn sample_width(self) -> usize {
+        match self {
+            PcmEncoding::U8 => 1,
+            PcmEncoding::S16le => 2,
+            PcmEncoding::S24le => 3,
+            PcmEncoding::S32le | PcmEncoding::F32le => 4,
+        }
+    }
+}
+
+fn audio_err(context: &str, err: impl std::fmt::Display) -> AppError {
+    AppError::Audio(format!("{context}: {err}"))
+}
+
+/// Writes `data` to `out` as a WAV file, decoding or wrapping it according to `conversion`.
+pub fn transcode_to_wav(
+    data: &[u8],
+    source_ext: Option<&str>,
+    conversion: &AudioConversion,
+    out: &Path,
+) -> AppResult<()> {
+    if let Some(layout) = &conversion.pcm {
+        return write_raw_pcm(data, layout, out);
+    }
+    if source_ext
+        .map(|ext| ext.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false)
+    {
+        std::fs::write(out, data)?;
+        return Ok(());
+    }
+    decode_to_wav(data, source_ext, out)
+}
+
+fn write_raw_pcm(data: &[u8], layout: &PcmLayout, out: &Path) -> AppResult<()> {
+    if layout.sample_rate == 0 || layout.channels == 0 {
+        return Err(AppError::Invalid(
+            "sample rate and channel count must be non-zero".into(),
+        ));
+    }
+    let width = layout.encoding.sample_width();
+    let frame = width * layout.channels as usize;
+    if !data.len().is_multiple_of(frame) {
+        return Err(AppError::Invalid(format!(
+            "{} bytes is not a whole number of {}-channel {:?} frames",
+            data.len(),
+            layout.channels,
+            layout.encoding
+        )));
+    }
+    let spec = WavSpec {
+        channels: layout.channels,
+        sample_rate: layout.sample_rate,
+        bits_per_sample: (width * 8) as u16,
+        sample_format: match layout.encoding {
+            PcmEncoding::F32le => SampleFormat::Float,
+            _ => SampleFormat::Int,
+        },
+    };
+    let mut writer = WavWriter::create(out, spec).map_err(|e| audio_err("creating wav", e))?;
+    for sample in data.chunks_exact(width) {
+        let written = match layout.encoding {
+            PcmEncoding::U8 => writer.write_sample((sample[0] as i16 - 128) as i8),
+            PcmEncoding::S16le => writer.write_sample(i16::from_le_bytes([sample[0], sample[1]])),
+            PcmEncoding::S24le => {
+                writer.write_sample(i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) >> 8)
+            }
+            PcmEncoding::S32le => writer.write_sample(i32::from_le_bytes([
+                sample[0], sample[1], sample[2], sample[3],
+            ])),
+            PcmEncoding::F32le => writer.write_sample(f32::from_le_bytes([
+                sample[0], sample[1], sample[2], sample[3],
+            ])),
+        };
+        written.map_err(|e| audio_err("writing wav", e))?;
+    }
+    writer.finalize().map_err(|e| audio_err("writing wav", e))
+}
+
+/// Opens the container; only headers are read until packets are requested.
+fn open_format(data: &[u8], source_ext: Option<&str>) -> AppResult<Box<dyn FormatReader>> {
+    let mut hint = Hint::new();
+    if let Some(ext) = source_ext {
+        hint.with_extension(ext);
+    }
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| audio_err("unrecognized audio", e))?;
+    Ok(probed.format)
+}
+
+fn audio_track(format: &dyn FormatReader) -> AppResult<&Track> {
+    format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AppError::Audio("no decodable audio track".into()))
+}
+
+/// What the container headers say about an encoded audio payload.
+pub(crate) struct AudioHeader {
+    pub(crate) codec: String,
+    pub(crate) sample_rate: Option<u32>,
+    pub(crate) channels: Option<u16>,
+    /// Playback length; `None` when the headers do not give a frame count.
+    pub(crate) seconds: Option<f64>,
+}
+
+/// Reads format, rate, channels and length from the headers without decoding samples.
+/// MP3 without a Xing/VBRI header gets a length estimated from its frame size.
+pub(crate) fn probe_header(data: &[u8], source_ext: Option<&str>) -> AppResult<AudioHeader> {
+    let format = open_format(data, source_ext)?;
+    let params = &audio_track(format.as_ref())?.codec_params;
+    let codec = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map(|d| d.short_name.to_string())
+        .unwrap_or_else(|| "unknown".into());
+    let seconds = match (params.n_frames, params.sample_rate) {
+        (Some(frames), Some(rate)) if rate > 0 => Some(frames as f64 / rate as f64),
+        _ => None,
+    };
+    Ok(AudioHeader {
+        codec,
+        sample_rate: params.sample_rate,
+        channels: params.channels.map(|c| c.count() as u16),
+        seconds,
+    })
+}
+
+fn decode_to_wav(data: &[u8], source_ext: Option<&str>, out: &Path) -> AppResult<()> {
+    let mut format = open_format(data, source_ext)?;
+    let track = audio_track(format.as_ref())?;
+    let track_id = track.id;
+    // Keep 24-bit sources lossless; everything else is written as 16-bit PCM.
+    let high_depth = track
+        .codec_params
+        .bits_per_sample
+        .map(|b| b > 16)
+        .unwrap_or(false);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| audio_err("unsupported codec", e))?;
+
+    let mut writer: Option<WavWriter<_>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(audio_err("reading packet", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(audio_err("decoding", e)),
+        };
+        let spec = *decoded.spec();
+        let w = match writer.as_mut() {
+            Some(w) => w,
+            None => {
+                let wav_spec = WavSpec {
+                    channels: spec.channels.count() as u16,
+                    sample_rate: spec.rate,
+                    bits_per_sample: if high_depth { 24 } else { 16 },
+                    sample_format: SampleFormat::Int,
+                };
+                let created =
+                    WavWriter::create(out, wav_spec).map_err(|e| audio_err("creating wav", e))?;
+                writer.insert(created)
+            }
+        };
+        if high_depth {
+            let mut buf = SampleBuffer::<i32>::new(decoded.capacity() as u64, spec);
+            buf.copy_interleaved_ref(decoded);
+            for s in buf.samples() {
+                w.write_sample(*s >> 8)
+                    .map_err(|e| audio_err("writing wav", e))?;
+            }
+        } else {
+            let mut buf = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+            buf.copy_interleaved_ref(decoded);
+            for s in buf.samples() {
+                w.write_sample(*s)
+                    .map_err(|e| audio_err("writing wav", e))?;
+            }
+        }
+    }
+    match writer {
+        Some(w) => w.finalize().map_err(|e| audio_err("writing wav", e)),
+        None => Err(AppError::Audio("audio stream contained no samples".into())),
+    }
+}
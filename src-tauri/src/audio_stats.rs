@@ -0,0 +1,229 @@
+use crate::audio::{probe_header, AudioHeader};
+use crate::field_sizes::{percentile, sample_plan};
+use crate::litdata::{
+    guess_ext, load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError,
+    AppResult, ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+pub(crate) const AUDIO_EXTS: [&str; 6] = ["wav", "mp3", "flac", "ogg", "oga", "opus"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationStats {
+    min: f64,
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    max: f64,
+    mean: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFieldStats {
+    field_index: usize,
+    format: Option<String>,
+    /// Items whose payload parsed as audio.
+    audio_items: u64,
+    /// Parsed items whose headers carry no length.
+    unknown_duration: u64,
+    /// Items that sniffed as audio but whose headers could not be read.
+    unreadable_items: u64,
+    /// Sum over the items read.
+    sampled_hours: f64,
+    /// `sampled_hours` scaled up to every item when only a sample was read.
+    total_hours: f64,
+    sample_rates: BTreeMap<u32, u64>,
+    channels: BTreeMap<u16, u64>,
+    codecs: BTreeMap<String, u64>,
+    /// Per-item duration in seconds.
+    duration: Option<DurationStats>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioCorpusReport {
+    job_id: String,
+    total_items: u64,
+    sampled_items: u64,
+    /// Totals are extrapolated from a sample rather than summed over every item.
+    extrapolated: bool,
+    unreadable_chunks: Vec<String>,
+    /// Fields where at least one item parsed as audio.
+    fields: Vec<AudioFieldStats>,
+}
+
+/// Hours, sample rates and per-item durations of audio fields, from container headers
+/// only. Reads every item unless `sample_items` is given; `field_index` restricts the
+/// scan to one field, otherwise audio fields are recognized by format and magic bytes.
+#[tauri::command]
+pub async fn audio_corpus_stats(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<AudioCorpusReport> {
+    let progress = Progress::new(app, job_id, "audio-stats");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        audio_stats_of(&parsed, field_index, sample_items, &progress)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Fields worth probing: declared audio formats, plus raw byte fields that may hold
+/// audio files.
+fn candidate_fields(formats: &[String], field_index: Option<usize>) -> AppResult<Vec<usize>> {
+    if let Some(field) = field_index {
+        if field >= formats.len() {
+            return Err(AppError::Invalid("field index out of range".into()));
+        }
+        return Ok(vec![field]);
+    }
+    Ok(formats
+        .iter()
+        .enumerate()
+        .filter(|(_, fmt)| {
+            let lower = fmt.to_lowercase();
+            lower == "bytes"
+                || lower == "bin"
+                || lower.starts_with("audio")
+                || guess_ext(Some(fmt), &[]).is_some_and(|ext| AUDIO_EXTS.contains(&ext.as_str()))
+        })
+        .map(|(field, _)| field)
+        .collect())
+}
+
+/// `Some(Err)` when the payload looks like audio but its headers are unreadable; `None`
+/// when it is not audio at all.
+fn probe_item(data: &[u8], format: &String) -> Option<AppResult<AudioHeader>> {
+    let ext = guess_ext(Some(format), data);
+    let declared = ext.as_deref().is_some_and(|ext| AUDIO_EXTS.contains(&ext))
+        || format.to_lowercase().starts_with("audio");
+    let sniffed = data.starts_with(b"OggS");
+    if !declared && !sniffed {
+        return None;
+    }
+    Some(probe_header(data, ext.as_deref()))
+}
+
+fn duration_stats(mut seconds: Vec<f64>) -> Option<DurationStats> {
+    if seconds.is_empty() {
+        return None;
+    }
+    seconds.sort_by(f64::total_cmp);
+    Some(DurationStats {
+        min: seconds[0],
+        p50: percentile(&seconds, 50),
+        p90: percentile(&seconds, 90),
+        p99: percentile(&seconds, 99),
+        max: seconds[seconds.len() - 1],
+        mean: seconds.iter().sum::<f64>() / seconds.len() as f64,
+    })
+}
+
+pub(crate) fn audio_stats_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    sample_items: Option<u32>,
+    progress: &Progress,
+) -> AppResult<AudioCorpusReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = candidate_fields(&formats, field_index)?;
+    let total_items: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+    let plan = sample_plan(parsed, sample_items.unwrap_or(u32::MAX));
+    progress.report("probing", 0, plan.len() as u64);
+    let probed = par_map(&plan, Some((progress, "probing")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut headers = Vec::with_capacity(items.len() * fields.len());
+        for item in items {
+            for &field in &fields {
+                let (data, _) =
+                    read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                headers.push((field, probe_item(&data, &formats[field])));
+            }
+        }
+        AppResult::Ok(headers)
+    });
+
+    let mut per_field: BTreeMap<usize, (AudioFieldStats, Vec<f64>)> = BTreeMap::new();
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(probed) {
+        let Ok(headers) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (field, header) in headers {
+            let Some(header) = header else { continue };
+            let (stats, seconds) = per_field.entry(field).or_insert_with(|| {
+                let stats = AudioFieldStats {
+                    field_index: field,
+                    format: formats.get(field).cloned(),
+                    audio_items: 0,
+                    unknown_duration: 0,
+                    unreadable_items: 0,
+                    sampled_hours: 0.0,
+                    total_hours: 0.0,
+                    sample_rates: BTreeMap::new(),
+                    channels: BTreeMap::new(),
+                    codecs: BTreeMap::new(),
+                    duration: None,
+                };
+                (stats, Vec::new())
+            });
+            let Ok(header) = header else {
+                stats.unreadable_items += 1;
+                continue;
+            };
+            stats.audio_items += 1;
+            *stats.codecs.entry(header.codec).or_default() += 1;
+            if let Some(rate) = header.sample_rate {
+                *stats.sample_rates.entry(rate).or_default() += 1;
+            }
+            if let Some(channels) = header.channels {
+                *stats.channels.entry(channels).or_default() += 1;
+            }
+            match header.seconds {
+                Some(s) => seconds.push(s),
+                None => stats.unknown_duration += 1,
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let extrapolated = sampled_items < total_items;
+    let scale = if sampled_items == 0 {
+        0.0
+    } else {
+        total_items as f64 / sampled_items as f64
+    };
+    let fields = per_field
+        .into_values()
+        .filter(|(stats, _)| stats.audio_items > 0)
+        .map(|(mut stats, seconds)| {
+            stats.sampled_hours = seconds.iter().sum::<f64>() / 3600.0;
+            stats.total_hours = stats.sampled_hours * scale;
+            stats.duration = duration_stats(seconds);
+            stats
+        })
+        .collect();
+    Ok(AudioCorpusReport {
+        job_id: progress.job_id().to_string(),
+        total_items,
+        sampled_items,
+        extrapolated,
+        unreadable_chunks,
+        fields,
+    })
+}
@@ -0,0 +1,25 @@
+use crate::litdata::{parse_index, AppError, AppResult};
+use litdata_core::balance::{balance_of, DEFAULT_OVERSIZED_ABOVE, DEFAULT_UNDERFILLED_BELOW};
+use std::path::Path;
+use tauri::async_runtime::spawn_blocking;
+
+pub use litdata_core::balance::BalanceReport;
+
+/// Compares every chunk's size to the configured `chunk_bytes` or `chunk_size`, from the
+/// index alone.
+#[tauri::command]
+pub async fn chunk_balance(
+    index_path: String,
+    underfilled_below: Option<f64>,
+    oversized_above: Option<f64>,
+) -> AppResult<BalanceReport> {
+    spawn_blocking(move || {
+        balance_of(
+            &parse_index(Path::new(&index_path))?,
+            underfilled_below.unwrap_or(DEFAULT_UNDERFILLED_BELOW),
+            oversized_above.unwrap_or(DEFAULT_OVERSIZED_ABOVE),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
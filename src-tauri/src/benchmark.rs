@@ -0,0 +1,286 @@
+use crate::inflate;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_item_bytes, AppError, AppResult,
+    ChunkAccess, ChunkCache, ParsedIndex,
+};
+use crate::order::Mt19937;
+use crate::progress::Progress;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_RANDOM_SAMPLES: u32 = 1000;
+/// Cold random reads decompress a whole chunk each; keep that pass short.
+const MAX_COLD_SAMPLES: u32 = 64;
+const DEFAULT_SEED: u32 = 42;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadThroughput {
+    items: u64,
+    bytes: u64,
+    seconds: f64,
+    items_per_sec: f64,
+    mb_per_sec: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkBenchmark {
+    pub(crate) filename: String,
+    pub(crate) items: u32,
+    pub(crate) stored_bytes: u64,
+    pub(crate) decompressed_bytes: u64,
+    /// Reading the stored file into memory; `None` for uncompressed chunks, whose item
+    /// reads go to disk directly.
+    pub(crate) read_mb_per_sec: Option<f64>,
+    /// Zstd decode throughput over decompressed bytes; `None` for uncompressed chunks.
+    pub(crate) decompress_mb_per_sec: Option<f64>,
+    pub(crate) sequential_items_per_sec: f64,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    job_id: String,
+    index_path: String,
+    compression: Option<String>,
+    chunks: Vec<ChunkBenchmark>,
+    sequential: ReadThroughput,
+    /// Random reads that reopen (and decompress) the chunk every time.
+    random_cold: ReadThroughput,
+    /// Random reads served from a warmed chunk cache.
+    random_warm: ReadThroughput,
+}
+
+fn throughput(items: u64, bytes: u64, elapsed: Duration) -> ReadThroughput {
+    let seconds = elapsed.as_secs_f64();
+    let rate = |n: f64| if seconds > 0.0 { n / seconds } else { 0.0 };
+    ReadThroughput {
+        items,
+        bytes,
+        seconds,
+        items_per_sec: rate(items as f64),
+        mb_per_sec: rate(bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// Measures how fast a dataloader could read this dataset from the current disk:
+/// per-chunk decompression, a sequential pass over every item, and seeded random item
+/// reads with and without the chunk cache.
+#[tauri::command]
+pub async fn benchmark_dataset(
+    app: AppHandle,
+    index_path: String,
+    samples: Option<u32>,
+    seed: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<BenchmarkReport> {
+    let progress = Progress::new(app, job_id, "benchmark");
+    spawn_blocking(move || {
+        benchmark_sync(
+            Path::new(&index_path),
+            samples.unwrap_or(DEFAULT_RANDOM_SAMPLES),
+            seed.unwrap_or(DEFAULT_SEED),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Time a zstd chunk spent being read from disk and being decompressed.
+#[derive(Clone, Copy)]
+struct ZstdTiming {
+    read: Duration,
+    decompress: Duration,
+}
+
+/// Loads a chunk bypassing both cache tiers, returning its stored size and, for zstd
+/// chunks, how long reading the file and decompression alone took.
+fn open_uncached(
+    parsed: &ParsedIndex,
+    chunk_filename: &str,
+) -> AppResult<(ChunkAccess, u64, Option<ZstdTiming>)> {
+    let chunk_path = parsed.chunk_path(chunk_filename)?;
+    if !chunk_path.exists() {
+        return Err(AppError::Missing(chunk_path.display().to_string()));
+    }
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(ref c) if c == "zstd" => {
+            let read_started = Instant::now();
+            let compressed = fs::read(&chunk_path)?;
+            let read = read_started.elapsed();
+            let started = Instant::now();
+            let buf = match inflate::decompress_frames(&compressed, None) {
+                Some(Ok(buf)) => buf,
+                _ => {
+                    let mut buf = Vec::new();
+                    zstd::stream::Decoder::new(&compressed[..])?.read_to_end(&mut buf)?;
+                    buf
+                }
+            };
+            let elapsed = started.elapsed();
+            Ok((
+                ChunkAccess::Memory(buf.into()),
+                compressed.len() as u64,
+                Some(ZstdTiming {
+                    read,
+                    decompress: elapsed,
+                }),
+            ))
+        }
+        Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None => {
+            let len = fs::metadata(&chunk_path)?.len();
+            Ok((ChunkAccess::File(chunk_path), len, None))
+        }
+    }
+}
+
+fn read_all_items(access: &ChunkAccess) -> AppResult<(u32, u64)> {
+    let (num_items, offsets) = parse_offsets(access)?;
+    let mut bytes = 0u64;
+    for item in 0..num_items {
+        bytes += read_item_bytes(access, &offsets, item)?.len() as u64;
+    }
+    Ok((num_items, bytes))
+}
+
+/// Opens every chunk uncached and reads all its items in order, timing each step.
+pub(crate) fn sequential_pass(
+    parsed: &ParsedIndex,
+    progress: &Progress,
+) -> (Vec<ChunkBenchmark>, ReadThroughput) {
+    let total_chunks = parsed.chunks.len() as u64;
+    let mut chunks = Vec::with_capacity(parsed.chunks.len());
+    let (mut seq_items, mut seq_bytes, mut seq_time) = (0u64, 0u64, Duration::ZERO);
+    for (done, chunk) in parsed.chunks.iter().enumerate() {
+        progress.report("sequential", done as u64, total_chunks);
+        let mut report = ChunkBenchmark {
+            filename: chunk.filename.clone(),
+            items: 0,
+            stored_bytes: 0,
+            decompressed_bytes: 0,
+            read_mb_per_sec: None,
+            decompress_mb_per_sec: None,
+            sequential_items_per_sec: 0.0,
+            error: None,
+        };
+        let measured =
+            open_uncached(parsed, &chunk.filename).and_then(|(access, stored, timing)| {
+                let decompressed = access.len()?;
+                let started = Instant::now();
+                let (items, bytes) = read_all_items(&access)?;
+                Ok((
+                    stored,
+                    decompressed,
+                    timing,
+                    items,
+                    bytes,
+                    started.elapsed(),
+                ))
+            });
+        match measured {
+            Ok((stored, decompressed, timing, items, bytes, elapsed)) => {
+                report.items = items;
+                report.stored_bytes = stored;
+                report.decompressed_bytes = decompressed;
+                report.read_mb_per_sec = timing.map(|t| throughput(1, stored, t.read).mb_per_sec);
+                report.decompress_mb_per_sec =
+                    timing.map(|t| throughput(1, decompressed, t.decompress).mb_per_sec);
+                report.sequential_items_per_sec =
+                    throughput(items as u64, bytes, elapsed).items_per_sec;
+                seq_items += items as u64;
+                seq_bytes += bytes;
+                seq_time += elapsed;
+            }
+            Err(e) => report.error = Some(e.to_string()),
+        }
+        chunks.push(report);
+    }
+    (chunks, throughput(seq_items, seq_bytes, seq_time))
+}
+
+fn benchmark_sync(
+    index_path: &Path,
+    samples: u32,
+    seed: u32,
+    progress: &Progress,
+) -> AppResult<BenchmarkReport> {
+    let parsed = parse_index(index_path)?;
+    let total_chunks = parsed.chunks.len() as u64;
+    let (chunks, sequential) = sequential_pass(&parsed, progress);
+
+    // Seeded (chunk, item) picks, uniform over items of the chunks that could be read.
+    let readable: Vec<(usize, u32)> = chunks
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.error.is_none() && c.items > 0)
+        .map(|(i, c)| (i, c.items))
+        .collect();
+    let total_items: u64 = readable.iter().map(|(_, n)| *n as u64).sum();
+    let mut picks = Vec::new();
+    if total_items > 0 {
+        let mut rng = Mt19937::from_seed(seed);
+        let max = u32::try_from(total_items - 1).unwrap_or(u32::MAX);
+        for _ in 0..samples {
+            let mut global = rng.interval(max) as u64;
+            for (chunk, items) in &readable {
+                if global < *items as u64 {
+                    picks.push((*chunk, global as u32));
+                    break;
+                }
+                global -= *items as u64;
+            }
+        }
+    }
+
+    let cold_picks = &picks[..picks.len().min(MAX_COLD_SAMPLES as usize)];
+    let (mut cold_bytes, cold_started) = (0u64, Instant::now());
+    for (done, (chunk, item)) in cold_picks.iter().enumerate() {
+        progress.report("random_cold", done as u64, cold_picks.len() as u64);
+        let (access, _, _) = open_uncached(&parsed, &parsed.chunks[*chunk].filename)?;
+        let (_, offsets) = parse_offsets(&access)?;
+        cold_bytes += read_item_bytes(&access, &offsets, *item)?.len() as u64;
+    }
+    let random_cold = throughput(cold_picks.len() as u64, cold_bytes, cold_started.elapsed());
+
+    // Warm the cache with every sampled chunk first, so the timed pass measures hits.
+    let cache = ChunkCache::default();
+    let mut warmed = HashMap::new();
+    for (chunk, _) in &picks {
+        if !warmed.contains_key(chunk) {
+            let access = load_chunk_access(&parsed, &parsed.chunks[*chunk].filename, &cache)?;
+            let (_, offsets) = parse_offsets(&access)?;
+            warmed.insert(*chunk, offsets);
+        }
+    }
+    let (mut warm_bytes, warm_started) = (0u64, Instant::now());
+    for (done, (chunk, item)) in picks.iter().enumerate() {
+        if done % 100 == 0 {
+            progress.report("random_warm", done as u64, picks.len() as u64);
+        }
+        let access = load_chunk_access(&parsed, &parsed.chunks[*chunk].filename, &cache)?;
+        warm_bytes += read_item_bytes(&access, &warmed[chunk], *item)?.len() as u64;
+    }
+    let random_warm = throughput(picks.len() as u64, warm_bytes, warm_started.elapsed());
+    progress.report("done", total_chunks, total_chunks);
+
+    Ok(BenchmarkReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        compression: parsed.config.compression.clone(),
+        chunks,
+        sequential,
+        random_cold,
+        random_warm,
+    })
+}
@@ -0,0 +1,160 @@
+use crate::litdata::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Serializes read-modify-write cycles on the bookmark list.
+static BOOKMARKS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A saved item (or one field of it), identified by dataset, chunk, item and field.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub(crate) index_path: String,
+    pub(crate) chunk_filename: String,
+    pub(crate) item_index: u32,
+    pub(crate) field_index: Option<usize>,
+    pub(crate) label: Option<String>,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    created_at: u64,
+}
+
+impl Bookmark {
+    fn same_target(&self, other: &Bookmark) -> bool {
+        self.index_path == other.index_path
+            && self.chunk_filename == other.chunk_filename
+            && self.item_index == other.item_index
+            && self.field_index == other.field_index
+    }
+}
+
+fn bookmarks_path(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir.join(BOOKMARKS_FILE))
+}
+
+/// A missing or unreadable file is treated as no bookmarks rather than failing the caller.
+fn read_list(path: &PathBuf) -> Vec<Bookmark> {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_list(path: &PathBuf, list: &[Bookmark]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(list)
+        .map_err(|e| AppError::Invalid(format!("serializing bookmarks: {e}")))?;
+    let partial = path.with_extension("json.part");
+    fs::write(&partial, json)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Every bookmark, newest first.
+pub(crate) fn list(app: &AppHandle) -> AppResult<Vec<Bookmark>> {
+    Ok(read_list(&bookmarks_path(app)?))
+}
+
+fn update(app: &AppHandle, change: impl FnOnce(&mut Vec<Bookmark>)) -> AppResult<Vec<Bookmark>> {
+    let path = bookmarks_path(app)?;
+    let _guard = BOOKMARKS_LOCK
+        .lock()
+        .map_err(|_| AppError::Task("bookmarks lock poisoned".into()))?;
+    let mut list = read_list(&path);
+    change(&mut list);
+    write_list(&path, &list)?;
+    Ok(list)
+}
+
+/// Bookmarks, optionally only those in one dataset.
+#[tauri::command]
+pub async fn get_bookmarks(app: AppHandle, index_path: Option<String>) -> AppResult<Vec<Bookmark>> {
+    spawn_blocking(move || {
+        let mut bookmarks = list(&app)?;
+        if let Some(index_path) = index_path {
+            bookmarks.retain(|b| b.index_path == index_path);
+        }
+        Ok(bookmarks)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Saves a bookmark; bookmarking the same target again replaces its label and tags.
+#[tauri::command]
+pub async fn add_bookmark(
+    app: AppHandle,
+    index_path: String,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: Option<usize>,
+    label: Option<String>,
+    tags: Option<Vec<String>>,
+) -> AppResult<Vec<Bookmark>> {
+    let mut tags: Vec<String> = tags
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    let entry = Bookmark {
+        index_path,
+        chunk_filename,
+        item_index,
+        field_index,
+        label: label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty()),
+        tags,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+    };
+    spawn_blocking(move || {
+        update(&app, |list| {
+            list.retain(|b| !b.same_target(&entry));
+            list.insert(0, entry);
+        })
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn remove_bookmark(
+    app: AppHandle,
+    index_path: String,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: Option<usize>,
+) -> AppResult<Vec<Bookmark>> {
+    spawn_blocking(move || {
+        update(&app, |list| {
+            list.retain(|b| {
+                !(b.index_path == index_path
+                    && b.chunk_filename == chunk_filename
+                    && b.item_index == item_index
+                    && b.field_index == field_index)
+            })
+        })
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
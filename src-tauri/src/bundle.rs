@@ -0,0 +1,285 @@
+use crate::litdata::{
+    guess_ext, load_chunk_access, parse_index, parse_offsets, read_item_bytes, split_fields,
+    AppError, AppResult, ChunkCache, ParsedIndex,
+};
+use crate::notes::fingerprint;
+use crate::progress::Progress;
+use crate::rewrite::locate;
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    version: u32,
+    created_at: u64,
+    note: Option<String>,
+    dataset: BundleDataset,
+    items: Vec<BundleItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleDataset {
+    /// Same fingerprint as dataset notes, so the bundle matches the dataset wherever it
+    /// is mounted.
+    fingerprint: String,
+    index_path: String,
+    data_format: Vec<String>,
+    compression: Option<String>,
+    total_items: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleItem {
+    global_index: u64,
+    chunk_filename: String,
+    item_index: u32,
+    /// Over the decompressed item bytes, as in checksum manifests with item hashes.
+    sha256: String,
+    fields: Vec<BundleField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleField {
+    field_index: usize,
+    format: Option<String>,
+    /// Path inside the archive.
+    file: String,
+    bytes: u64,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareBundleReport {
+    job_id: String,
+    bundle_path: String,
+    fingerprint: String,
+    items: usize,
+    /// Field bytes before compression.
+    field_bytes: u64,
+}
+
+/// Zips the items at `global_indices` with a manifest naming the dataset fingerprint,
+/// each item's global index and location, and SHA-256 digests of every item and field,
+/// so a colleague can see exactly which samples were flagged without the dataset.
+/// `fields` limits the fields included; all by default. A directory `dest_path` gets a
+/// timestamped file name.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_share_bundle(
+    app: AppHandle,
+    index_path: String,
+    global_indices: Vec<u64>,
+    dest_path: String,
+    fields: Option<Vec<usize>>,
+    note: Option<String>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<ShareBundleReport> {
+    let progress = Progress::new(app, job_id, "share-bundle");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(index_path.trim()))?;
+        let dest = bundle_path(Path::new(dest_path.trim()))?;
+        ensure_writable(&dest, &workspace.dataset_dirs(&parsed))?;
+        bundle_sync(
+            &parsed,
+            &global_indices,
+            fields.as_deref(),
+            note.filter(|n| !n.trim().is_empty()),
+            &dest,
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn bundle_path(dest: &Path) -> AppResult<PathBuf> {
+    if dest.as_os_str().is_empty() {
+        return Err(AppError::Invalid("bundle destination is empty".into()));
+    }
+    if dest.is_dir() {
+        return Ok(dest.join(format!("litdata-share-{}.zip", unix_secs())));
+    }
+    Ok(dest.to_path_buf())
+}
+
+/// One item's manifest entry with the field payloads to store.
+type ReadItem = (BundleItem, Vec<Vec<u8>>);
+
+fn read_items(
+    parsed: &ParsedIndex,
+    filename: &str,
+    items: &[(u64, u32)],
+    fields: &[usize],
+) -> AppResult<Vec<ReadItem>> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let scratch = ChunkCache::default();
+    let access = load_chunk_access(parsed, filename, &scratch)?;
+    let (_, offsets) = parse_offsets(&access)?;
+    items
+        .iter()
+        .map(|(global, item)| {
+            let bytes = read_item_bytes(&access, &offsets, *item)?;
+            let parts = split_fields(&bytes, formats.len())?;
+            let mut entries = Vec::with_capacity(fields.len());
+            let mut payloads = Vec::with_capacity(fields.len());
+            for field in fields {
+                let data = parts[*field];
+                let ext = guess_ext(formats.get(*field), data).unwrap_or_else(|| "bin".into());
+                entries.push(BundleField {
+                    field_index: *field,
+                    format: formats.get(*field).cloned(),
+                    file: format!("items/{global:08}/f{field}.{ext}"),
+                    bytes: data.len() as u64,
+                    sha256: hex::encode(Sha256::digest(data)),
+                });
+                payloads.push(data.to_vec());
+            }
+            let entry = BundleItem {
+                global_index: *global,
+                chunk_filename: filename.to_string(),
+                item_index: *item,
+                sha256: hex::encode(Sha256::digest(&bytes)),
+                fields: entries,
+            };
+            Ok((entry, payloads))
+        })
+        .collect()
+}
+
+fn zip_err(e: zip::result::ZipError) -> AppError {
+    AppError::Io(format!("writing bundle: {e}"))
+}
+
+pub(crate) fn bundle_sync(
+    parsed: &ParsedIndex,
+    global_indices: &[u64],
+    fields: Option<&[usize]>,
+    note: Option<String>,
+    dest: &Path,
+    progress: &Progress,
+) -> AppResult<ShareBundleReport> {
+    if global_indices.is_empty() {
+        return Err(AppError::Invalid("select at least one item".into()));
+    }
+    let format_len = parsed.format_len();
+    let fields: Vec<usize> = match fields {
+        Some(fields) if !fields.is_empty() => fields.to_vec(),
+        _ => (0..format_len).collect(),
+    };
+    if let Some(bad) = fields.iter().find(|f| **f >= format_len) {
+        return Err(AppError::Invalid(format!("field index {bad} out of range")));
+    }
+    let mut by_chunk: BTreeMap<usize, Vec<(u64, u32)>> = BTreeMap::new();
+    for global in global_indices {
+        let (chunk, item) = locate(parsed, *global)?;
+        let items = by_chunk.entry(chunk).or_default();
+        if !items.iter().any(|(g, _)| g == global) {
+            items.push((*global, item));
+        }
+    }
+    let groups: Vec<(usize, Vec<(u64, u32)>)> = by_chunk.into_iter().collect();
+    let total = groups.len() as u64;
+    progress.report("reading", 0, total);
+    let read = par_map(&groups, Some((progress, "reading")), |(chunk, items)| {
+        read_items(parsed, &parsed.chunks[*chunk].filename, items, &fields)
+    });
+    let mut items = Vec::new();
+    for chunk_items in read {
+        items.extend(chunk_items?);
+    }
+    items.sort_by_key(|(entry, _)| entry.global_index);
+
+    let field_bytes = items
+        .iter()
+        .flat_map(|(entry, _)| &entry.fields)
+        .map(|f| f.bytes)
+        .sum();
+    let fingerprint = fingerprint(parsed);
+    let partial = dest.with_extension("zip.part");
+    let written = write_bundle(&partial, parsed, &fingerprint, note, items);
+    let count = match written {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, dest)?;
+    progress.report("done", total, total);
+    Ok(ShareBundleReport {
+        job_id: progress.job_id().to_string(),
+        bundle_path: dest.display().to_string(),
+        fingerprint,
+        items: count,
+        field_bytes,
+    })
+}
+
+fn write_bundle(
+    path: &Path,
+    parsed: &ParsedIndex,
+    fingerprint: &str,
+    note: Option<String>,
+    items: Vec<ReadItem>,
+) -> AppResult<usize> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let mut entries = Vec::with_capacity(items.len());
+    for (entry, payloads) in items {
+        for (field, data) in entry.fields.iter().zip(&payloads) {
+            zip.start_file(field.file.as_str(), options)
+                .map_err(zip_err)?;
+            zip.write_all(data)?;
+        }
+        entries.push(entry);
+    }
+    let count = entries.len();
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        created_at: unix_secs(),
+        note,
+        dataset: BundleDataset {
+            fingerprint: fingerprint.to_string(),
+            index_path: parsed.source.display().to_string(),
+            data_format: parsed.config.data_format.clone().unwrap_or_default(),
+            compression: parsed.config.compression.clone(),
+            total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        },
+        items: entries,
+    };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppError::Invalid(format!("serializing manifest: {e}")))?;
+    zip.start_file(MANIFEST_NAME, options).map_err(zip_err)?;
+    zip.write_all(&json)?;
+    zip.finish().map_err(zip_err)?;
+    Ok(count)
+}
@@ -0,0 +1,328 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_item_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const MANIFEST_VERSION: u32 = 1;
+const DEFAULT_MANIFEST_NAME: &str = "litdata-manifest.json";
+const HASH_BUF_BYTES: usize = 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u32,
+    algorithm: String,
+    created_at: u64,
+    chunks: Vec<ManifestChunk>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestChunk {
+    filename: String,
+    bytes: u64,
+    sha256: String,
+    /// Per-item digests over the decompressed item bytes, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    items: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSummary {
+    job_id: String,
+    manifest_path: String,
+    chunk_count: usize,
+    total_bytes: u64,
+    item_hashes: bool,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchKind {
+    MissingFile,
+    NotInManifest,
+    SizeChanged,
+    HashChanged,
+    ItemCountChanged,
+    ItemChanged,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumMismatch {
+    filename: String,
+    kind: MismatchKind,
+    item_index: Option<u32>,
+    expected: Option<String>,
+    actual: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    job_id: String,
+    manifest_path: String,
+    ok: bool,
+    checked_chunks: usize,
+    mismatches: Vec<ChecksumMismatch>,
+}
+
+fn default_manifest_path(parsed: &ParsedIndex, manifest_path: Option<String>) -> PathBuf {
+    manifest_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| parsed.root_dir.join(DEFAULT_MANIFEST_NAME))
+}
+
+fn hash_file(path: &Path) -> AppResult<(u64, String)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; HASH_BUF_BYTES];
+    let mut total = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+    }
+    Ok((total, hex::encode(hasher.finalize())))
+}
+
+fn hash_items(parsed: &ParsedIndex, chunk_filename: &str) -> AppResult<Vec<String>> {
+    let scratch = ChunkCache::default();
+    let access = load_chunk_access(parsed, chunk_filename, &scratch)?;
+    let (num_items, offsets) = parse_offsets(&access)?;
+    let mut hashes = Vec::with_capacity(num_items as usize);
+    for item in 0..num_items {
+        let bytes = read_item_bytes(&access, &offsets, item)?;
+        hashes.push(hex::encode(Sha256::digest(&bytes)));
+    }
+    Ok(hashes)
+}
+
+#[tauri::command]
+pub async fn generate_checksum_manifest(
+    app: AppHandle,
+    index_path: String,
+    manifest_path: Option<String>,
+    include_items: bool,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<ManifestSummary> {
+    let progress = Progress::new(app, job_id, "checksum");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        generate_manifest_sync(
+            Path::new(&index_path),
+            manifest_path,
+            include_items,
+            &progress,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn generate_manifest_sync(
+    index_path: &Path,
+    manifest_path: Option<String>,
+    include_items: bool,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<ManifestSummary> {
+    let parsed = parse_index(index_path)?;
+    let out = default_manifest_path(&parsed, manifest_path);
+    ensure_writable(&out, &workspace.dataset_dirs(&parsed))?;
+    let total = parsed.chunks.len() as u64;
+    progress.report("hashing", 0, total);
+    let chunks = par_map(&parsed.chunks, Some((progress, "hashing")), |chunk| {
+        let path = parsed.chunk_path(&chunk.filename)?;
+        if !path.exists() {
+            return Err(AppError::Missing(path.display().to_string()));
+        }
+        let (bytes, sha256) = hash_file(&path)?;
+        let items = if include_items {
+            Some(hash_items(&parsed, &chunk.filename)?)
+        } else {
+            None
+        };
+        Ok(ManifestChunk {
+            filename: chunk.filename.clone(),
+            bytes,
+            sha256,
+            items,
+        })
+    })
+    .into_iter()
+    .collect::<AppResult<Vec<_>>>()?;
+    let total_bytes = chunks.iter().map(|c| c.bytes).sum();
+    let manifest = Manifest {
+        version: MANIFEST_VERSION,
+        algorithm: "sha256".into(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        chunks,
+    };
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppError::Invalid(format!("serializing manifest: {e}")))?;
+    fs::write(&out, json)?;
+    progress.report("done", total, total);
+    Ok(ManifestSummary {
+        job_id: progress.job_id().to_string(),
+        manifest_path: out.display().to_string(),
+        chunk_count: manifest.chunks.len(),
+        total_bytes,
+        item_hashes: include_items,
+    })
+}
+
+#[tauri::command]
+pub async fn verify_checksum_manifest(
+    app: AppHandle,
+    index_path: String,
+    manifest_path: Option<String>,
+    job_id: Option<String>,
+) -> AppResult<VerifyReport> {
+    let progress = Progress::new(app, job_id, "verify");
+    spawn_blocking(move || verify_manifest_sync(Path::new(&index_path), manifest_path, &progress))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn verify_manifest_sync(
+    index_path: &Path,
+    manifest_path: Option<String>,
+    progress: &Progress,
+) -> AppResult<VerifyReport> {
+    let parsed = parse_index(index_path)?;
+    let source = default_manifest_path(&parsed, manifest_path);
+    if !source.exists() {
+        return Err(AppError::Missing(source.display().to_string()));
+    }
+    let manifest: Manifest = serde_json::from_slice(&fs::read(&source)?)
+        .map_err(|e| AppError::Invalid(format!("manifest parse error: {e}")))?;
+    if manifest.algorithm != "sha256" {
+        return Err(AppError::Invalid(format!(
+            "unsupported manifest algorithm: {}",
+            manifest.algorithm
+        )));
+    }
+
+    let mut mismatches = Vec::new();
+    let expected: HashMap<&str, &ManifestChunk> = manifest
+        .chunks
+        .iter()
+        .map(|c| (c.filename.as_str(), c))
+        .collect();
+    for chunk in &parsed.chunks {
+        if !expected.contains_key(chunk.filename.as_str()) {
+            mismatches.push(ChecksumMismatch {
+                filename: chunk.filename.clone(),
+                kind: MismatchKind::NotInManifest,
+                item_index: None,
+                expected: None,
+                actual: None,
+            });
+        }
+    }
+
+    let total = manifest.chunks.len() as u64;
+    progress.report("verifying", 0, total);
+    for found in par_map(&manifest.chunks, Some((progress, "verifying")), |entry| {
+        verify_entry(&parsed, entry)
+    }) {
+        mismatches.extend(found?);
+    }
+    progress.report("done", total, total);
+    Ok(VerifyReport {
+        job_id: progress.job_id().to_string(),
+        manifest_path: source.display().to_string(),
+        ok: mismatches.is_empty(),
+        checked_chunks: manifest.chunks.len(),
+        mismatches,
+    })
+}
+
+fn verify_entry(parsed: &ParsedIndex, entry: &ManifestChunk) -> AppResult<Vec<ChecksumMismatch>> {
+    let mut found = Vec::new();
+    let path = parsed.chunk_path(&entry.filename)?;
+    if !path.exists() {
+        found.push(ChecksumMismatch {
+            filename: entry.filename.clone(),
+            kind: MismatchKind::MissingFile,
+            item_index: None,
+            expected: Some(entry.sha256.clone()),
+            actual: None,
+        });
+        return Ok(found);
+    }
+    let (bytes, sha256) = hash_file(&path)?;
+    if bytes != entry.bytes {
+        found.push(ChecksumMismatch {
+            filename: entry.filename.clone(),
+            kind: MismatchKind::SizeChanged,
+            item_index: None,
+            expected: Some(entry.bytes.to_string()),
+            actual: Some(bytes.to_string()),
+        });
+    } else if sha256 != entry.sha256 {
+        found.push(ChecksumMismatch {
+            filename: entry.filename.clone(),
+            kind: MismatchKind::HashChanged,
+            item_index: None,
+            expected: Some(entry.sha256.clone()),
+            actual: Some(sha256),
+        });
+    } else {
+        return Ok(found);
+    }
+    // Item digests pinpoint which samples changed inside a modified chunk.
+    if let Some(expected_items) = &entry.items {
+        let actual_items = match hash_items(parsed, &entry.filename) {
+            Ok(items) => items,
+            Err(_) => return Ok(found),
+        };
+        if actual_items.len() != expected_items.len() {
+            found.push(ChecksumMismatch {
+                filename: entry.filename.clone(),
+                kind: MismatchKind::ItemCountChanged,
+                item_index: None,
+                expected: Some(expected_items.len().to_string()),
+                actual: Some(actual_items.len().to_string()),
+            });
+        }
+        for (item, (want, got)) in expected_items.iter().zip(&actual_items).enumerate() {
+            if want != got {
+                found.push(ChecksumMismatch {
+                    filename: entry.filename.clone(),
+                    kind: MismatchKind::ItemChanged,
+                    item_index: Some(item as u32),
+                    expected: Some(want.clone()),
+                    actual: Some(got.clone()),
+                });
+            }
+        }
+    }
+    Ok(found)
+}
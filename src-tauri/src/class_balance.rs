@@ -0,0 +1,190 @@
+use crate::class_names::{self, ClassNames};
+use crate::field_sizes::sample_plan;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Labels longer than this are not class labels; the field is probably the wrong one.
+const MAX_LABEL_BYTES: usize = 256;
+/// Labels listed in the report; rarer ones are only counted in `other_labels`.
+const MAX_LISTED_LABELS: usize = 10_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelCount {
+    label: String,
+    /// From the dataset's class map.
+    name: Option<String>,
+    count: u64,
+    /// Share of the decoded labels.
+    fraction: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassBalanceReport {
+    job_id: String,
+    field_index: usize,
+    format: Option<String>,
+    total_items: u64,
+    sampled_items: u64,
+    /// Items whose payload is empty, overlong, or neither text nor a packed integer.
+    undecodable_items: u64,
+    distinct_labels: usize,
+    /// Most common label's count over the least common's; 1.0 is perfectly balanced.
+    imbalance_ratio: f64,
+    /// Shannon entropy over its maximum for this many labels; 1.0 is perfectly balanced.
+    normalized_entropy: f64,
+    /// Most common first.
+    labels: Vec<LabelCount>,
+    /// Distinct labels beyond the listed ones.
+    other_labels: usize,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Frequency of each value of the label field `field_index`, over every item unless
+/// `sample_items` is given.
+#[tauri::command]
+pub async fn class_balance(
+    app: AppHandle,
+    index_path: String,
+    field_index: usize,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<ClassBalanceReport> {
+    let progress = Progress::new(app.clone(), job_id, "class-balance");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        let names = class_names::for_dataset(&app, &parsed);
+        class_balance_of(
+            &parsed,
+            field_index,
+            sample_items.unwrap_or(u32::MAX),
+            names.as_deref(),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// litdata writes `int` and `str` fields as UTF-8 text; packed little-endian integers
+/// from custom serializers are read by width.
+fn decode_label(data: &[u8]) -> Option<String> {
+    if data.is_empty() || data.len() > MAX_LABEL_BYTES {
+        return None;
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        if !text.contains('\0') {
+            return Some(text.trim().to_string());
+        }
+    }
+    match data.len() {
+        1 => Some(data[0].to_string()),
+        2 => Some(i16::from_le_bytes(data.try_into().ok()?).to_string()),
+        4 => Some(i32::from_le_bytes(data.try_into().ok()?).to_string()),
+        8 => Some(i64::from_le_bytes(data.try_into().ok()?).to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn class_balance_of(
+    parsed: &ParsedIndex,
+    field_index: usize,
+    sample_items: u32,
+    names: Option<&ClassNames>,
+    progress: &Progress,
+) -> AppResult<ClassBalanceReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    if field_index >= formats.len() {
+        return Err(AppError::Invalid("field index out of range".into()));
+    }
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("counting", 0, plan.len() as u64);
+    let counted = par_map(&plan, Some((progress, "counting")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        let mut undecodable = 0u64;
+        for item in items {
+            let (data, _) =
+                read_field_bytes(&access, &table, *item, field_index, formats.len(), None)?;
+            match decode_label(&data) {
+                Some(label) => *counts.entry(label).or_default() += 1,
+                None => undecodable += 1,
+            }
+        }
+        AppResult::Ok((counts, undecodable))
+    });
+
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    let mut sampled_items = 0u64;
+    let mut undecodable_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(counted) {
+        let Ok((chunk_counts, undecodable)) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        undecodable_items += undecodable;
+        for (label, count) in chunk_counts {
+            *counts.entry(label).or_default() += count;
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let decoded: u64 = counts.values().sum();
+    let distinct_labels = counts.len();
+    let mut labels: Vec<(String, u64)> = counts.into_iter().collect();
+    labels.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let imbalance_ratio = match (labels.first(), labels.last()) {
+        (Some((_, most)), Some((_, least))) => *most as f64 / *least as f64,
+        _ => 1.0,
+    };
+    let entropy: f64 = labels
+        .iter()
+        .map(|(_, count)| {
+            let p = *count as f64 / decoded as f64;
+            -p * p.ln()
+        })
+        .sum();
+    let normalized_entropy = if distinct_labels > 1 {
+        entropy / (distinct_labels as f64).ln()
+    } else {
+        1.0
+    };
+    let other_labels = distinct_labels.saturating_sub(MAX_LISTED_LABELS);
+    labels.truncate(MAX_LISTED_LABELS);
+    Ok(ClassBalanceReport {
+        job_id: progress.job_id().to_string(),
+        field_index,
+        format: formats.get(field_index).cloned(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        undecodable_items,
+        distinct_labels,
+        imbalance_ratio,
+        normalized_entropy,
+        labels: labels
+            .into_iter()
+            .map(|(label, count)| LabelCount {
+                name: names
+                    .and_then(|names| names.name(field_index, &label))
+                    .map(str::to_string),
+                label,
+                count,
+                fraction: count as f64 / decoded as f64,
+            })
+            .collect(),
+        other_labels,
+        unreadable_chunks,
+    })
+}
@@ -0,0 +1,282 @@
+use crate::index_cache::IndexCache;
+use crate::litdata::{AppError, AppResult, ParsedIndex};
+use crate::notes::fingerprint;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const CLASS_NAMES_DIR: &str = "class-names";
+/// litdata's data_format for integer fields, the ones a mapping applies to by default.
+const INT_FORMAT: &str = "int";
+
+/// Mappings already read, by dataset fingerprint; `None` records that there is none.
+static LOADED: Mutex<Option<HashMap<String, Option<Arc<ClassNames>>>>> = Mutex::new(None);
+
+/// Names for the integer labels of one dataset, stored in the app data folder by
+/// dataset fingerprint so they follow the dataset like its notes do.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassNames {
+    fingerprint: String,
+    index_path: String,
+    /// The file the mapping was imported from.
+    source_path: String,
+    /// Fields whose values are looked up.
+    fields: Vec<usize>,
+    names: BTreeMap<i64, String>,
+}
+
+impl ClassNames {
+    /// Name of `label` when it is an integer class of a mapped field.
+    pub(crate) fn name(&self, field_index: usize, label: &str) -> Option<&str> {
+        if !self.fields.contains(&field_index) {
+            return None;
+        }
+        self.class_name(label.trim().parse().ok()?)
+    }
+
+    /// Name of class `id`, whatever field it came from.
+    pub(crate) fn class_name(&self, id: i64) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+}
+
+fn names_path(app: &AppHandle, fingerprint: &str) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir
+        .join(CLASS_NAMES_DIR)
+        .join(format!("{fingerprint}.json")))
+}
+
+fn remember(fingerprint: &str, names: Option<Arc<ClassNames>>) {
+    if let Ok(mut guard) = LOADED.lock() {
+        guard
+            .get_or_insert_with(HashMap::new)
+            .insert(fingerprint.to_string(), names);
+    }
+}
+
+/// The dataset's mapping, if one was imported. A missing or unreadable file counts as
+/// none rather than failing the caller.
+pub(crate) fn for_dataset(app: &AppHandle, parsed: &ParsedIndex) -> Option<Arc<ClassNames>> {
+    let fingerprint = fingerprint(parsed);
+    if let Some(known) = LOADED
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref()?.get(&fingerprint).cloned())
+    {
+        return known;
+    }
+    let names = names_path(app, &fingerprint)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|raw| serde_json::from_slice::<ClassNames>(&raw).ok())
+        .map(Arc::new);
+    remember(&fingerprint, names.clone());
+    names
+}
+
+fn class_id(key: &str) -> AppResult<i64> {
+    key.trim()
+        .parse()
+        .map_err(|_| AppError::Invalid(format!("class id {key:?} is not an integer")))
+}
+
+fn name_text(value: &Value) -> AppResult<String> {
+    match value {
+        Value::String(name) => Ok(name.clone()),
+        Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+        other => Err(AppError::Invalid(format!("class name {other} is not text"))),
+    }
+}
+
+/// `{"0": "cat"}`, `["cat", "dog"]`, or either under a `names` key as in YOLO's
+/// `data.yaml`.
+fn names_from_json(value: &Value) -> AppResult<BTreeMap<i64, String>> {
+    match value {
+        Value::Object(object) => match object.get("names") {
+            Some(inner) => names_from_json(inner),
+            None => object
+                .iter()
+                .map(|(key, name)| Ok((class_id(key)?, name_text(name)?)))
+                .collect(),
+        },
+        Value::Array(names) => names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| Ok((id as i64, name_text(name)?)))
+            .collect(),
+        _ => Err(AppError::Invalid(
+            "expected an object of id to name, or a list of names".into(),
+        )),
+    }
+}
+
+fn unquote(text: &str) -> String {
+    let text = text.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    text.to_string()
+}
+
+/// The YAML shapes class maps come in: `0: cat` lines, `- cat` lists, or a flow list
+/// like `names: [cat, dog]`. Keys that are not integers, such as `path:` or `train:` in
+/// a `data.yaml`, are skipped.
+fn names_from_yaml(text: &str) -> AppResult<BTreeMap<i64, String>> {
+    let mut names = BTreeMap::new();
+    let mut listed = 0i64;
+    for line in text.lines() {
+        let line = match line.find(" #") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(item) = line.strip_prefix("- ") {
+            names.insert(listed, unquote(item));
+            listed += 1;
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if let Some(flow) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            if key.trim() == "names" {
+                for (id, name) in flow.split(',').enumerate() {
+                    names.insert(id as i64, unquote(name));
+                }
+            }
+            continue;
+        }
+        if let Ok(id) = key.trim().parse::<i64>() {
+            names.insert(id, unquote(value));
+        }
+    }
+    Ok(names)
+}
+
+fn parse_mapping(path: &Path) -> AppResult<BTreeMap<i64, String>> {
+    let text = fs::read_to_string(path)?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        || text.trim_start().starts_with(['{', '[']);
+    let names = if is_json {
+        let value: Value = serde_json::from_str(&text)
+            .map_err(|e| AppError::Invalid(format!("{}: {e}", path.display())))?;
+        names_from_json(&value)?
+    } else {
+        names_from_yaml(&text)?
+    };
+    if names.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "{} maps no class ids to names",
+            path.display()
+        )));
+    }
+    Ok(names)
+}
+
+/// Imports a JSON or YAML class map (`{0: "cat", 1: "dog"}`, a list of names, or a YOLO
+/// `data.yaml`) for a dataset, replacing any earlier one. It applies to `fields`, or to
+/// every `int` field when not given.
+#[tauri::command]
+pub async fn import_class_names(
+    app: AppHandle,
+    index_path: String,
+    path: String,
+    fields: Option<Vec<usize>>,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<ClassNames> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    spawn_blocking(move || {
+        let formats = parsed.config.data_format.clone().unwrap_or_default();
+        let fields = match fields {
+            Some(fields) => {
+                if let Some(bad) = fields.iter().find(|f| **f >= formats.len()) {
+                    return Err(AppError::Invalid(format!("field index {bad} out of range")));
+                }
+                fields
+            }
+            None => formats
+                .iter()
+                .enumerate()
+                .filter(|(_, format)| format.eq_ignore_ascii_case(INT_FORMAT))
+                .map(|(field, _)| field)
+                .collect(),
+        };
+        let source = Path::new(path.trim());
+        let names = ClassNames {
+            fingerprint: fingerprint(&parsed),
+            index_path: parsed.source.display().to_string(),
+            source_path: source.display().to_string(),
+            fields,
+            names: parse_mapping(source)?,
+        };
+        let out = names_path(&app, &names.fingerprint)?;
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_vec_pretty(&names)
+            .map_err(|e| AppError::Invalid(format!("serializing class names: {e}")))?;
+        let partial = out.with_extension("json.part");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, &out)?;
+        remember(&names.fingerprint, Some(Arc::new(names.clone())));
+        Ok(names)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn get_class_names(
+    app: AppHandle,
+    index_path: String,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<Option<ClassNames>> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    spawn_blocking(move || Ok(for_dataset(&app, &parsed).map(|names| (*names).clone())))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Forgets the dataset's class map. Returns whether there was one.
+#[tauri::command]
+pub async fn clear_class_names(
+    app: AppHandle,
+    index_path: String,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<bool> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    spawn_blocking(move || {
+        let fingerprint = fingerprint(&parsed);
+        let path = names_path(&app, &fingerprint)?;
+        let existed = path.exists();
+        if existed {
+            fs::remove_file(&path)?;
+        }
+        remember(&fingerprint, None);
+        Ok(existed)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
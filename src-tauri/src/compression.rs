@@ -0,0 +1,188 @@
+use crate::inflate::data_frames;
+use crate::litdata::{parse_index, AppError, AppResult, ParsedIndex};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{fs, path::Path, time::Instant};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Chunks fully decoded to time decompression, spread across the dataset.
+const DECODE_SAMPLE_CHUNKS: usize = 3;
+
+/// Where a chunk's decompressed size came from.
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeSource {
+    /// Every zstd frame header records its content size.
+    FrameHeader,
+    /// Some frame omits it; the index's `chunk_bytes` stands in.
+    Index,
+    /// Neither is available.
+    Unknown,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkCompression {
+    filename: String,
+    compressed_bytes: u64,
+    decompressed_bytes: Option<u64>,
+    /// Decompressed over compressed; higher saves more.
+    ratio: Option<f64>,
+    frames: usize,
+    source: SizeSource,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionReport {
+    job_id: String,
+    index_path: String,
+    chunk_count: usize,
+    /// Over chunks whose decompressed size is known.
+    compressed_bytes: u64,
+    decompressed_bytes: u64,
+    overall_ratio: Option<f64>,
+    min_ratio: Option<f64>,
+    max_ratio: Option<f64>,
+    unknown_chunks: usize,
+    /// Missing, unreadable, or not a walkable zstd stream.
+    unreadable_chunks: Vec<String>,
+    /// Single-threaded zstd decode throughput over decompressed bytes, from a few
+    /// sampled chunks; only when measured.
+    decompress_mb_per_sec: Option<f64>,
+    chunks: Vec<ChunkCompression>,
+}
+
+/// Compressed versus decompressed size of every chunk of a zstd dataset, from frame
+/// headers without decompressing. With `measure_decode`, a few chunks are also fully
+/// decoded to time decompression on this machine.
+#[tauri::command]
+pub async fn compression_report(
+    app: AppHandle,
+    index_path: String,
+    measure_decode: Option<bool>,
+    job_id: Option<String>,
+) -> AppResult<CompressionReport> {
+    let progress = Progress::new(app, job_id, "compression");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        compression_of(&parsed, measure_decode.unwrap_or(false), &progress)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn chunk_compression(parsed: &ParsedIndex, filename: &str) -> AppResult<ChunkCompression> {
+    let compressed = fs::read(parsed.chunk_path(filename)?)?;
+    let frames = data_frames(&compressed)
+        .ok_or_else(|| AppError::Invalid(format!("{filename} is not a complete zstd stream")))?;
+    let from_headers = frames
+        .iter()
+        .map(|(_, size)| *size)
+        .try_fold(0u64, |total, size| total.checked_add(size?));
+    let declared = parsed.declared_chunk_bytes(filename);
+    let (decompressed_bytes, source) = match from_headers {
+        Some(total) => (Some(total), SizeSource::FrameHeader),
+        None if declared > 0 => (Some(declared), SizeSource::Index),
+        None => (None, SizeSource::Unknown),
+    };
+    let compressed_bytes = compressed.len() as u64;
+    Ok(ChunkCompression {
+        filename: filename.to_string(),
+        compressed_bytes,
+        decompressed_bytes,
+        ratio: decompressed_bytes
+            .filter(|_| compressed_bytes > 0)
+            .map(|d| d as f64 / compressed_bytes as f64),
+        frames: frames.len(),
+        source,
+    })
+}
+
+/// Decompressed MiB per second over `filenames`, decoded one after another.
+fn decode_throughput(parsed: &ParsedIndex, filenames: &[&str]) -> Option<f64> {
+    let mut bytes = 0u64;
+    let mut seconds = 0f64;
+    for filename in filenames {
+        let Ok(compressed) = parsed
+            .chunk_path(filename)
+            .and_then(|p| fs::read(p).map_err(AppError::from))
+        else {
+            continue;
+        };
+        let started = Instant::now();
+        let Ok(decoded) = zstd::stream::decode_all(&compressed[..]) else {
+            continue;
+        };
+        seconds += started.elapsed().as_secs_f64();
+        bytes += decoded.len() as u64;
+    }
+    (seconds > 0.0).then(|| bytes as f64 / (1024.0 * 1024.0) / seconds)
+}
+
+pub(crate) fn compression_of(
+    parsed: &ParsedIndex,
+    measure_decode: bool,
+    progress: &Progress,
+) -> AppResult<CompressionReport> {
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(c) if c == "zstd" => {}
+        Some(other) => return Err(AppError::UnsupportedCompression(other)),
+        None => {
+            return Err(AppError::Invalid(
+                "dataset is not compressed; chunks are stored as written".into(),
+            ))
+        }
+    }
+    let total = parsed.chunks.len() as u64;
+    progress.report("reading headers", 0, total);
+    let measured = par_map(&parsed.chunks, Some((progress, "reading headers")), |c| {
+        chunk_compression(parsed, &c.filename)
+    });
+    let mut chunks = Vec::new();
+    let mut unreadable_chunks = Vec::new();
+    for (chunk, result) in parsed.chunks.iter().zip(measured) {
+        match result {
+            Ok(measured) => chunks.push(measured),
+            Err(_) => unreadable_chunks.push(chunk.filename.clone()),
+        }
+    }
+
+    let decompress_mb_per_sec = if measure_decode && !chunks.is_empty() {
+        progress.report("timing decode", total, total);
+        let step = chunks.len().div_ceil(DECODE_SAMPLE_CHUNKS);
+        let sample: Vec<&str> = chunks
+            .iter()
+            .step_by(step)
+            .map(|c| c.filename.as_str())
+            .collect();
+        decode_throughput(parsed, &sample)
+    } else {
+        None
+    };
+    progress.report("done", total, total);
+
+    let known: Vec<&ChunkCompression> = chunks
+        .iter()
+        .filter(|c| c.decompressed_bytes.is_some())
+        .collect();
+    let compressed_bytes: u64 = known.iter().map(|c| c.compressed_bytes).sum();
+    let decompressed_bytes: u64 = known.iter().filter_map(|c| c.decompressed_bytes).sum();
+    let ratios = || chunks.iter().filter_map(|c| c.ratio);
+    Ok(CompressionReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        chunk_count: parsed.chunks.len(),
+        compressed_bytes,
+        decompressed_bytes,
+        overall_ratio: (compressed_bytes > 0)
+            .then(|| decompressed_bytes as f64 / compressed_bytes as f64),
+        min_ratio: ratios().reduce(f64::min),
+        max_ratio: ratios().reduce(f64::max),
+        unknown_chunks: chunks.len() - known.len(),
+        unreadable_chunks,
+        decompress_mb_per_sec,
+        chunks,
+    })
+}
@@ -0,0 +1,194 @@
+use crate::dedup::ItemLocation;
+use crate::field_sizes::sample_plan;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, sniff_content_type, AppError,
+    AppResult, ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::schema::expected_content_type;
+use serde::Serialize;
+use std::{cmp::Reverse, collections::BTreeMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_SAMPLE_ITEMS: u32 = 2_000;
+/// Items listed per content type, so a stray type can be opened and inspected.
+const EXAMPLES_PER_TYPE: usize = 3;
+/// A field whose most common type covers less than this share is reported as mixed.
+const MIXED_BELOW: f64 = 0.99;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTypeShare {
+    /// As a file extension, or `empty`, `txt`, `bin`.
+    content_type: String,
+    count: u64,
+    /// Share of the field's sampled payloads.
+    fraction: f64,
+    examples: Vec<ItemLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldCensus {
+    field_index: usize,
+    declared: Option<String>,
+    /// What the declared format promises, when it promises a content type.
+    expected: Option<&'static str>,
+    /// Most common non-empty type.
+    dominant: Option<String>,
+    /// The dominant type covers under 99% of the non-empty payloads.
+    mixed: bool,
+    /// The dominant type is not the one `data_format` promises.
+    misdeclared: bool,
+    /// Most common first.
+    content_types: Vec<ContentTypeShare>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTypeCensus {
+    job_id: String,
+    total_items: u64,
+    sampled_items: u64,
+    fields: Vec<FieldCensus>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Share of each field's payloads by content type, judged from magic bytes over up to
+/// `sample_items` items spread across the dataset.
+#[tauri::command]
+pub async fn content_type_census(
+    app: AppHandle,
+    index_path: String,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<ContentTypeCensus> {
+    let progress = Progress::new(app, job_id, "content-types");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        census_of(
+            &parsed,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[derive(Default)]
+struct Tally {
+    count: u64,
+    examples: Vec<ItemLocation>,
+}
+
+pub(crate) fn census_of(
+    parsed: &ParsedIndex,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<ContentTypeCensus> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    if formats.is_empty() {
+        return Err(AppError::Invalid("index declares no data_format".into()));
+    }
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("sniffing", 0, plan.len() as u64);
+    // Per item, one content type per field.
+    let sniffed = par_map(&plan, Some((progress, "sniffing")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        items
+            .iter()
+            .map(|item| {
+                let kinds = (0..formats.len())
+                    .map(|field| {
+                        let (data, _) =
+                            read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                        Ok(sniff_content_type(&data))
+                    })
+                    .collect::<AppResult<Vec<_>>>()?;
+                Ok((*item, kinds))
+            })
+            .collect::<AppResult<Vec<_>>>()
+    });
+
+    let chunk_starts: Vec<u64> = parsed
+        .chunks
+        .iter()
+        .scan(0u64, |start, c| {
+            let this = *start;
+            *start += c.chunk_size as u64;
+            Some(this)
+        })
+        .collect();
+    let mut tallies: Vec<BTreeMap<String, Tally>> =
+        formats.iter().map(|_| BTreeMap::new()).collect();
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, _), result) in plan.iter().zip(sniffed) {
+        let filename = &parsed.chunks[*chunk].filename;
+        let Ok(items) = result else {
+            unreadable_chunks.push(filename.clone());
+            continue;
+        };
+        for (item, kinds) in items {
+            sampled_items += 1;
+            for (field, kind) in kinds.into_iter().enumerate() {
+                let tally = tallies[field].entry(kind).or_default();
+                tally.count += 1;
+                if tally.examples.len() < EXAMPLES_PER_TYPE {
+                    tally.examples.push(ItemLocation {
+                        chunk_filename: filename.clone(),
+                        item_index: item,
+                        global_index: chunk_starts[*chunk] + item as u64,
+                    });
+                }
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let fields = tallies
+        .into_iter()
+        .enumerate()
+        .map(|(field, tally)| {
+            let declared = formats.get(field).cloned();
+            let expected = declared.as_deref().and_then(expected_content_type);
+            let mut content_types: Vec<ContentTypeShare> = tally
+                .into_iter()
+                .map(|(content_type, t)| ContentTypeShare {
+                    content_type,
+                    count: t.count,
+                    fraction: t.count as f64 / sampled_items as f64,
+                    examples: t.examples,
+                })
+                .collect();
+            content_types.sort_by_key(|c| Reverse(c.count));
+            let non_empty = || content_types.iter().filter(|c| c.content_type != "empty");
+            let filled: u64 = non_empty().map(|c| c.count).sum();
+            let dominant = non_empty().next();
+            let mixed = dominant.is_some_and(|d| (d.count as f64) < filled as f64 * MIXED_BELOW);
+            let dominant = dominant.map(|d| d.content_type.clone());
+            FieldCensus {
+                field_index: field,
+                declared,
+                expected,
+                misdeclared: expected
+                    .zip(dominant.as_deref())
+                    .is_some_and(|(e, d)| e != d),
+                dominant,
+                mixed,
+                content_types,
+            }
+        })
+        .collect();
+    Ok(ContentTypeCensus {
+        job_id: progress.job_id().to_string(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        fields,
+        unreadable_chunks,
+    })
+}
@@ -0,0 +1,61 @@
+use crate::litdata::{load_chunk_access, parse_index, AppError, AppResult, ChunkCache};
+use crate::scan::par_map;
+use litdata_core::corruption::{diagnose_access, CorruptionInfo, CorruptionKind};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::async_runtime::spawn_blocking;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptionScan {
+    index_path: String,
+    scanned_chunks: usize,
+    corrupt: Vec<CorruptionInfo>,
+}
+
+#[tauri::command]
+pub async fn scan_chunk_corruption(index_path: String) -> AppResult<CorruptionScan> {
+    let path = PathBuf::from(index_path);
+    spawn_blocking(move || scan_chunk_corruption_sync(path))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn scan_chunk_corruption_sync(index_path: PathBuf) -> AppResult<CorruptionScan> {
+    let parsed = parse_index(&index_path)?;
+    let corrupt = par_map(&parsed.chunks, None, |chunk| {
+        let scratch = ChunkCache::default();
+        let found = match load_chunk_access(&parsed, &chunk.filename, &scratch) {
+            Ok(access) => diagnose_access(&access, &chunk.filename).or_else(|| {
+                let len = access.len().ok()?;
+                (len != chunk.chunk_bytes).then(|| {
+                    CorruptionInfo::new(
+                        &chunk.filename,
+                        CorruptionKind::LengthMismatch,
+                        len,
+                        format!(
+                            "index declares {} bytes, chunk holds {len}",
+                            chunk.chunk_bytes
+                        ),
+                    )
+                    .at(None, len.min(chunk.chunk_bytes))
+                })
+            }),
+            Err(AppError::Corrupt(info)) => Some(*info),
+            // Missing or unreadable files are covered by validate_dataset.
+            Err(_) => None,
+        };
+        found.map(|mut info| {
+            info.declared_len = Some(chunk.chunk_bytes);
+            info
+        })
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(CorruptionScan {
+        index_path: parsed.source.display().to_string(),
+        scanned_chunks: parsed.chunks.len(),
+        corrupt,
+    })
+}
@@ -0,0 +1,95 @@
+use crate::litdata::{
+    load_chunk_access_async, read_field_bytes, AppError, AppResult, ChunkCache, ErrorContext,
+    ResultExt,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::usage;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::decoders::{self, DecodedField, DecoderPlugin, MAX_DECODE_BYTES};
+use std::path::{Path, PathBuf};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const PLUGINS_DIR: &str = "decoders";
+
+fn plugins_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir.join(PLUGINS_DIR))
+}
+
+#[tauri::command]
+pub async fn list_decoder_plugins(app: AppHandle) -> AppResult<Vec<DecoderPlugin>> {
+    spawn_blocking(move || decoders::list(&plugins_dir(&app)?))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Validates the plugin whose `plugin.json` is at `manifest_path` and copies it into the
+/// app data dir, replacing an installed plugin of the same name.
+#[tauri::command]
+pub async fn install_decoder_plugin(
+    app: AppHandle,
+    manifest_path: String,
+) -> AppResult<Vec<DecoderPlugin>> {
+    spawn_blocking(move || decoders::install(&plugins_dir(&app)?, Path::new(&manifest_path)))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn remove_decoder_plugin(app: AppHandle, name: String) -> AppResult<Vec<DecoderPlugin>> {
+    spawn_blocking(move || decoders::remove(&plugins_dir(&app)?, &name))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Runs the plugin registered for the field's `data_format` entry over its full bytes.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn decode_field(
+    app: AppHandle,
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: usize,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
+) -> AppResult<DecodedField> {
+    let parsed = workspace.get(handle)?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let format = formats.get(field_index).cloned().ok_or_else(|| {
+        AppError::Invalid(format!("field {field_index} has no data_format entry"))
+    })?;
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, None)
+        .await
+        .within(|| context.clone())?;
+    let offsets = (*offsets).clone();
+    spawn_blocking(move || {
+        let decoder = decoders::decoder_for(&plugins_dir(&app)?, &format)?;
+        let table = offsets.get(&parsed.chunk_path(&chunk_filename)?, &access)?;
+        let (data, size) = read_field_bytes(
+            &access,
+            &table,
+            item_index,
+            field_index,
+            formats.len(),
+            Some(MAX_DECODE_BYTES + 1),
+        )?;
+        if size as usize > MAX_DECODE_BYTES {
+            return Err(AppError::Invalid(format!(
+                "field is {size} bytes; decoder plugins take at most {MAX_DECODE_BYTES}"
+            )));
+        }
+        usage::record_bytes(&parsed.source, data.len());
+        decoder.decode(&data)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
+}
@@ -0,0 +1,156 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_item_bytes, split_fields, AppError,
+    AppResult, ChunkCache,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+use xxhash_rust::xxh3::xxh3_128;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupHash {
+    #[default]
+    Xxh3,
+    Sha256,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemLocation {
+    pub(crate) chunk_filename: String,
+    pub(crate) item_index: u32,
+    pub(crate) global_index: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    hash: String,
+    size: u64,
+    items: Vec<ItemLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupReport {
+    job_id: String,
+    scanned_items: u64,
+    duplicate_items: u64,
+    wasted_bytes: u64,
+    groups: Vec<DuplicateGroup>,
+    unreadable_chunks: Vec<String>,
+}
+
+fn digest(kind: DedupHash, bytes: &[u8]) -> String {
+    match kind {
+        DedupHash::Xxh3 => format!("{:032x}", xxh3_128(bytes)),
+        DedupHash::Sha256 => hex::encode(Sha256::digest(bytes)),
+    }
+}
+
+/// Hashes every item (or one field of every item) and groups identical payloads.
+#[tauri::command]
+pub async fn find_duplicate_items(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    hash: Option<DedupHash>,
+    job_id: Option<String>,
+) -> AppResult<DedupReport> {
+    let progress = Progress::new(app, job_id, "dedup");
+    spawn_blocking(move || {
+        find_duplicates_sync(
+            Path::new(&index_path),
+            field_index,
+            hash.unwrap_or_default(),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn find_duplicates_sync(
+    index_path: &Path,
+    field_index: Option<usize>,
+    hash: DedupHash,
+    progress: &Progress,
+) -> AppResult<DedupReport> {
+    let parsed = parse_index(index_path)?;
+    let format_len = parsed.format_len();
+    if let Some(field) = field_index {
+        if field >= format_len {
+            return Err(AppError::Invalid("field index out of range".into()));
+        }
+    }
+    let mut seen: HashMap<String, (u64, Vec<ItemLocation>)> = HashMap::new();
+    let mut unreadable_chunks = Vec::new();
+    let mut scanned_items = 0u64;
+    let mut global_base = 0u64;
+    let total = parsed.chunks.len() as u64;
+    progress.report("hashing", 0, total);
+    let digests = par_map(&parsed.chunks, Some((progress, "hashing")), |chunk| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(&parsed, &chunk.filename, &scratch)?;
+        let (num_items, offsets) = parse_offsets(&access)?;
+        let mut digests = Vec::with_capacity(num_items as usize);
+        for item in 0..num_items {
+            let bytes = read_item_bytes(&access, &offsets, item)?;
+            let payload = match field_index {
+                Some(field) => split_fields(&bytes, format_len)?[field],
+                None => &bytes[..],
+            };
+            digests.push((digest(hash, payload), payload.len() as u64));
+        }
+        AppResult::Ok(digests)
+    });
+    for (chunk, scanned) in parsed.chunks.iter().zip(digests) {
+        match scanned {
+            Ok(digests) => {
+                let num_items = digests.len() as u64;
+                for (item, (key, size)) in digests.into_iter().enumerate() {
+                    let entry = seen.entry(key).or_insert_with(|| (size, Vec::new()));
+                    entry.1.push(ItemLocation {
+                        chunk_filename: chunk.filename.clone(),
+                        item_index: item as u32,
+                        global_index: global_base + item as u64,
+                    });
+                }
+                scanned_items += num_items;
+                global_base += num_items;
+            }
+            Err(_) => {
+                unreadable_chunks.push(chunk.filename.clone());
+                global_base += chunk.chunk_size as u64;
+            }
+        }
+    }
+    progress.report("done", total, total);
+
+    let mut groups: Vec<DuplicateGroup> = seen
+        .into_iter()
+        .filter(|(_, (_, items))| items.len() > 1)
+        .map(|(hash, (size, items))| DuplicateGroup { hash, size, items })
+        .collect();
+    groups.sort_by(|a, b| {
+        let waste = |g: &DuplicateGroup| g.size * (g.items.len() as u64 - 1);
+        waste(b).cmp(&waste(a))
+    });
+    let duplicate_items = groups.iter().map(|g| g.items.len() as u64 - 1).sum();
+    let wasted_bytes = groups
+        .iter()
+        .map(|g| g.size * (g.items.len() as u64 - 1))
+        .sum();
+    Ok(DedupReport {
+        job_id: progress.job_id().to_string(),
+        scanned_items,
+        duplicate_items,
+        wasted_bytes,
+        groups,
+        unreadable_chunks,
+    })
+}
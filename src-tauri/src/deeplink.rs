@@ -0,0 +1,167 @@
+//! Opening the viewer from outside: `litdata://open?path=…&chunk=…&item=…&field=…` links,
+//! index/chunk files handed over by the OS, and the same arguments passed to a second
+//! launch. Requests queue here until the webview takes them, since a cold start
+//! delivers them before the page is listening.
+
+use crate::litdata::{is_chunk_path, AppError, AppResult};
+use crate::windows::MAIN_WINDOW;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Mutex};
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const URL_SCHEME: &str = "litdata";
+/// Payload-less signal that `take_pending_open_requests` has something to return.
+pub const OPEN_REQUEST_EVENT: &str = "open-request";
+
+static PENDING: Mutex<Vec<OpenRequest>> = Mutex::new(Vec::new());
+
+/// A dataset to open and, optionally, the sample to select in it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+    /// index.json, a directory holding one, or a chunk file.
+    path: String,
+    chunk: Option<String>,
+    item: Option<u32>,
+    field: Option<usize>,
+}
+
+impl OpenRequest {
+    fn for_path(path: PathBuf) -> Self {
+        // A chunk file opens its whole dataset with that chunk selected.
+        let chunk = is_chunk_path(&path)
+            .then(|| path.file_name().map(|f| f.to_string_lossy().into_owned()))
+            .flatten();
+        OpenRequest {
+            path: path.display().to_string(),
+            chunk,
+            item: None,
+            field: None,
+        }
+    }
+}
+
+fn parse_link(url: &Url) -> AppResult<OpenRequest> {
+    if url.host_str() != Some("open") {
+        return Err(AppError::Invalid(format!(
+            "unsupported link {url}; expected {URL_SCHEME}://open?path=…"
+        )));
+    }
+    let mut path = None;
+    let mut chunk = None;
+    let mut item = None;
+    let mut field = None;
+    for (key, value) in url.query_pairs() {
+        let number = || {
+            value
+                .parse()
+                .map_err(|_| AppError::Invalid(format!("{key}={value} is not a number in {url}")))
+        };
+        match key.as_ref() {
+            "path" => path = Some(PathBuf::from(value.as_ref())),
+            "chunk" => chunk = Some(value.to_string()),
+            "item" => item = Some(number()?),
+            "field" => field = Some(number()? as usize),
+            _ => {}
+        }
+    }
+    let path = path.ok_or_else(|| AppError::Invalid(format!("{url} has no path")))?;
+    let mut request = OpenRequest::for_path(path);
+    if chunk.is_some() {
+        request.chunk = chunk;
+    }
+    request.item = item;
+    request.field = field;
+    Ok(request)
+}
+
+/// Interprets one launch argument or OS-delivered URL; anything that is neither a link
+/// nor an existing dataset path (such as a flag) is ignored.
+fn parse_target(raw: &str) -> Option<OpenRequest> {
+    if let Ok(url) = Url::parse(raw) {
+        match url.scheme() {
+            URL_SCHEME => {
+                return parse_link(&url)
+                    .map_err(|e| tracing::warn!("ignoring link: {e}"))
+                    .ok()
+            }
+            "file" => return url.to_file_path().ok().map(OpenRequest::for_path),
+            // Single-letter schemes are Windows drive letters, not URLs.
+            scheme if scheme.len() > 1 => return None,
+            _ => {}
+        }
+    }
+    let path = PathBuf::from(raw);
+    path.exists().then(|| OpenRequest::for_path(path))
+}
+
+fn enqueue(app: &AppHandle, requests: impl IntoIterator<Item = OpenRequest>) {
+    let Ok(mut pending) = PENDING.lock() else {
+        return;
+    };
+    let before = pending.len();
+    pending.extend(requests);
+    if pending.len() == before {
+        return;
+    }
+    tracing::info!(count = pending.len() - before, "queued open requests");
+    drop(pending);
+    // Best-effort: a page that is not listening yet takes the queue on mount. Only the
+    // main window drains the queue; extra windows get their dataset when created.
+    let _ = app.emit_to(MAIN_WINDOW, OPEN_REQUEST_EVENT, ());
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Command-line arguments of this or a second launch. Links in a second launch's
+/// arguments are skipped because the deep-link plugin already forwards them.
+pub(crate) fn handle_args(app: &AppHandle, args: &[String], include_links: bool) {
+    let requests = args
+        .iter()
+        .filter(|arg| include_links || !arg.starts_with(&format!("{URL_SCHEME}:")))
+        .filter_map(|arg| parse_target(arg));
+    enqueue(app, requests);
+}
+
+/// Files and links macOS delivers through `RunEvent::Opened`; links arrive there too
+/// but the deep-link plugin handles those.
+#[cfg(target_os = "macos")]
+pub(crate) fn handle_opened(app: &AppHandle, urls: &[Url]) {
+    let requests = urls
+        .iter()
+        .filter(|url| url.scheme() == "file")
+        .filter_map(|url| parse_target(url.as_str()));
+    enqueue(app, requests);
+}
+
+pub(crate) fn init(app: &AppHandle) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    handle_args(app, &args, true);
+    // Installed builds register the scheme at install time; dev and AppImage runs
+    // need it registered at runtime.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("could not register {URL_SCHEME}:// links: {e}");
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        let requests = event
+            .urls()
+            .iter()
+            .filter_map(|url| parse_target(url.as_str()))
+            .collect::<Vec<_>>();
+        enqueue(&handle, requests);
+    });
+}
+
+/// Drains requests that arrived since the last call.
+#[tauri::command]
+pub async fn take_pending_open_requests() -> AppResult<Vec<OpenRequest>> {
+    let mut pending = PENDING
+        .lock()
+        .map_err(|_| AppError::Task("open request lock poisoned".into()))?;
+    Ok(std::mem::take(&mut *pending))
+}
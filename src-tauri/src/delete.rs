@@ -0,0 +1,277 @@
+use crate::index_cache::IndexCache;
+use crate::litdata::{
+    load_chunk_access, parse_offsets, AppError, AppResult, ChunkAccess, ChunkCache, ParsedIndex,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::progress::Progress;
+use crate::rewrite::{
+    encode_chunk, ensure_rewritable, index_document, pack_chunk, partial_path, set_chunk_entry,
+    write_index_partial, Backup, RewriteCaches,
+};
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkEdit {
+    filename: String,
+    items_before: u32,
+    items_after: u32,
+    /// Every item was removed, so the chunk leaves the index.
+    dropped: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteReport {
+    job_id: String,
+    index_path: String,
+    /// `false` for the preview returned without `confirm`; nothing was written.
+    applied: bool,
+    removed_items: u64,
+    remaining_items: u64,
+    chunks: Vec<ChunkEdit>,
+    /// Where the replaced chunks and index.json were moved.
+    backup_dir: Option<String>,
+}
+
+/// Removes the items at `global_indices` and rewrites the chunks holding them, along with
+/// index.json. Without `confirm` it only reports what would change. Replaced files are
+/// moved to a timestamped backup directory under the dataset root, not deleted, and the
+/// dataset's open handles move to the rewritten index.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_items(
+    app: AppHandle,
+    handle: DatasetHandle,
+    global_indices: Vec<u64>,
+    confirm: bool,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    offsets: tauri::State<'_, OffsetsCache>,
+    indexes: tauri::State<'_, IndexCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<DeleteReport> {
+    let progress = Progress::new(app, job_id, "delete-items");
+    let caches = RewriteCaches {
+        chunks: (*cache).clone(),
+        offsets: (*offsets).clone(),
+        indexes: (*indexes).clone(),
+    };
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = workspace.get(handle)?;
+        delete_sync(
+            &parsed,
+            &global_indices,
+            confirm,
+            &progress,
+            &caches,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Items to remove, by chunk position, from dataset-wide indices.
+fn removals_by_chunk(
+    parsed: &ParsedIndex,
+    global_indices: &[u64],
+) -> AppResult<BTreeMap<usize, BTreeSet<u32>>> {
+    let total: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+    let wanted: BTreeSet<u64> = global_indices.iter().copied().collect();
+    if let Some(last) = wanted.last().filter(|last| **last >= total) {
+        return Err(AppError::Invalid(format!(
+            "item {last} is out of range; the dataset has {total} items"
+        )));
+    }
+    let mut removals: BTreeMap<usize, BTreeSet<u32>> = BTreeMap::new();
+    let mut chunk = 0usize;
+    let mut chunk_start = 0u64;
+    for global in wanted {
+        while global >= chunk_start + parsed.chunks[chunk].chunk_size as u64 {
+            chunk_start += parsed.chunks[chunk].chunk_size as u64;
+            chunk += 1;
+        }
+        removals
+            .entry(chunk)
+            .or_default()
+            .insert((global - chunk_start) as u32);
+    }
+    Ok(removals)
+}
+
+/// Writes `filename` without `removed` next to the original; returns the items kept
+/// and the decompressed size.
+fn rewrite_chunk(
+    parsed: &ParsedIndex,
+    filename: &str,
+    removed: &BTreeSet<u32>,
+) -> AppResult<(u32, u64)> {
+    let scratch = ChunkCache::default();
+    let access = load_chunk_access(parsed, filename, &scratch)?;
+    let (num_items, offsets) = parse_offsets(&access)?;
+    // Items the index places past the chunk's real end would otherwise be skipped silently.
+    if let Some(item) = removed.range(num_items..).next() {
+        return Err(AppError::Invalid(format!(
+            "item {item} is past the end of {filename}, which holds {num_items} items"
+        )));
+    }
+    let data = match access {
+        ChunkAccess::Memory(buf) => buf,
+        other => other.read_exact_at(0, other.len()? as usize)?.into(),
+    };
+    let kept: Vec<&[u8]> = (0..num_items)
+        .filter(|item| !removed.contains(item))
+        .map(|item| {
+            let (start, end) = (offsets[item as usize], offsets[item as usize + 1]);
+            data.get(start as usize..end as usize)
+                .ok_or(AppError::MalformedChunk)
+        })
+        .collect::<AppResult<_>>()?;
+    if kept.is_empty() {
+        return Ok((0, 0));
+    }
+    let raw = pack_chunk(kept.iter().copied());
+    fs::write(
+        partial_path(&parsed.chunk_path(filename)?),
+        encode_chunk(&raw, parsed)?,
+    )?;
+    Ok((kept.len() as u32, raw.len() as u64))
+}
+
+/// index.json with rewritten chunks' sizes updated and emptied chunks removed.
+fn updated_index(
+    parsed: &ParsedIndex,
+    rewritten: &[(usize, (u32, u64))],
+) -> AppResult<serde_json::Value> {
+    let dropped: BTreeSet<&str> = rewritten
+        .iter()
+        .filter(|(_, (items, _))| *items == 0)
+        .map(|(chunk, _)| parsed.chunks[*chunk].filename.as_str())
+        .collect();
+    let mut index = index_document(parsed)?;
+    if let Some(entries) = index.get_mut("chunks").and_then(|c| c.as_array_mut()) {
+        entries.retain(|e| {
+            e.get("filename")
+                .and_then(|f| f.as_str())
+                .is_none_or(|f| !dropped.contains(f))
+        });
+    }
+    for (chunk, (items, bytes)) in rewritten {
+        set_chunk_entry(&mut index, &parsed.chunks[*chunk].filename, *items, *bytes);
+    }
+    Ok(index)
+}
+
+pub(crate) fn delete_sync(
+    parsed: &ParsedIndex,
+    global_indices: &[u64],
+    confirm: bool,
+    progress: &Progress,
+    caches: &RewriteCaches,
+    workspace: &Workspace,
+) -> AppResult<DeleteReport> {
+    ensure_rewritable(parsed)?;
+    let removals = removals_by_chunk(parsed, global_indices)?;
+    let total: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+    let removed_items: u64 = removals.values().map(|r| r.len() as u64).sum();
+    if removed_items == 0 {
+        return Err(AppError::Invalid("no items selected".into()));
+    }
+    if removed_items == total {
+        return Err(AppError::Invalid(
+            "this would remove every item; delete the dataset instead".into(),
+        ));
+    }
+    let mut chunks: Vec<ChunkEdit> = removals
+        .iter()
+        .map(|(chunk, removed)| {
+            let before = parsed.chunks[*chunk].chunk_size;
+            ChunkEdit {
+                filename: parsed.chunks[*chunk].filename.clone(),
+                items_before: before,
+                items_after: before - removed.len() as u32,
+                dropped: removed.len() as u32 == before,
+            }
+        })
+        .collect();
+    let report = |chunks, applied, backup_dir| DeleteReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        applied,
+        removed_items,
+        remaining_items: total - removed_items,
+        chunks,
+        backup_dir,
+    };
+    if !confirm {
+        return Ok(report(chunks, false, None));
+    }
+    ensure_writable(&parsed.root_dir, &workspace.dataset_dirs(parsed))?;
+
+    let plan: Vec<_> = removals.iter().collect();
+    let steps = plan.len() as u64;
+    progress.report("rewriting", 0, steps);
+    let written = par_map(&plan, Some((progress, "rewriting")), |(chunk, removed)| {
+        rewrite_chunk(parsed, &parsed.chunks[**chunk].filename, removed)
+    });
+    let mut rewritten = Vec::with_capacity(plan.len());
+    let mut failure = None;
+    for ((chunk, _), result) in plan.iter().zip(written) {
+        match result {
+            Ok(sizes) => rewritten.push((**chunk, sizes)),
+            Err(e) => failure = failure.or(Some(e)),
+        }
+    }
+    let index_partial = match failure {
+        Some(e) => Err(e),
+        None => updated_index(parsed, &rewritten)
+            .and_then(|index| write_index_partial(&parsed.source, &index)),
+    };
+    let index_partial = match index_partial {
+        Ok(partial) => partial,
+        Err(e) => {
+            // Nothing has been swapped in yet; leave the dataset as it was.
+            for (chunk, _) in &plan {
+                if let Ok(path) = parsed.chunk_path(&parsed.chunks[**chunk].filename) {
+                    let _ = fs::remove_file(partial_path(&path));
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    progress.report("replacing", steps, steps);
+    let backup = Backup::create(&parsed.root_dir)?;
+    for (chunk, (items, _)) in &rewritten {
+        let path = parsed.chunk_path(&parsed.chunks[*chunk].filename)?;
+        if *items == 0 {
+            backup.stash(&path)?;
+        } else {
+            backup.replace(&path, &partial_path(&path))?;
+        }
+        caches.forget_chunk(&path);
+    }
+    backup.replace(&parsed.source, &index_partial)?;
+    caches.refresh_index(parsed, workspace);
+    progress.report("done", steps, steps);
+
+    for (edit, (_, (items, _))) in chunks.iter_mut().zip(&rewritten) {
+        edit.items_after = *items;
+        edit.dropped = *items == 0;
+    }
+    Ok(report(
+        chunks,
+        true,
+        Some(backup.dir().display().to_string()),
+    ))
+}
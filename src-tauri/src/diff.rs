@@ -0,0 +1,317 @@
+use crate::dedup::ItemLocation;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_item_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+use xxhash_rust::xxh3::xxh3_128;
+
+/// Caps each item list in the report; counts stay exact.
+const MAX_LISTED_ITEMS: usize = 500;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    key: String,
+    a: Option<serde_json::Value>,
+    b: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkChange {
+    filename: String,
+    a_items: Option<u32>,
+    b_items: Option<u32>,
+    a_bytes: Option<u64>,
+    b_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedItem {
+    global_index: u64,
+    a: ItemLocation,
+    b: ItemLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemDiff {
+    a_items: u64,
+    b_items: u64,
+    added_count: u64,
+    removed_count: u64,
+    changed_count: u64,
+    added: Vec<ItemLocation>,
+    removed: Vec<ItemLocation>,
+    changed: Vec<ChangedItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetDiff {
+    job_id: String,
+    a_index: String,
+    b_index: String,
+    identical: bool,
+    a_chunk_count: usize,
+    b_chunk_count: usize,
+    a_total_items: u64,
+    b_total_items: u64,
+    config_changes: Vec<ConfigChange>,
+    chunks_added: Vec<String>,
+    chunks_removed: Vec<String>,
+    chunks_changed: Vec<ChunkChange>,
+    items: Option<ItemDiff>,
+}
+
+#[tauri::command]
+pub async fn diff_datasets(
+    app: AppHandle,
+    a_path: String,
+    b_path: String,
+    compare_items: bool,
+    job_id: Option<String>,
+) -> AppResult<DatasetDiff> {
+    let progress = Progress::new(app, job_id, "diff");
+    spawn_blocking(move || {
+        diff_datasets_sync(
+            Path::new(&a_path),
+            Path::new(&b_path),
+            compare_items,
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn diff_datasets_sync(
+    a_path: &Path,
+    b_path: &Path,
+    compare_items: bool,
+    progress: &Progress,
+) -> AppResult<DatasetDiff> {
+    let a = parse_index(a_path)?;
+    let b = parse_index(b_path)?;
+
+    let config_changes = diff_config(&a.config_raw, &b.config_raw);
+    let a_chunks: HashMap<&str, (u32, u64)> = a
+        .chunks
+        .iter()
+        .map(|c| (c.filename.as_str(), (c.chunk_size, c.chunk_bytes)))
+        .collect();
+    let b_chunks: HashMap<&str, (u32, u64)> = b
+        .chunks
+        .iter()
+        .map(|c| (c.filename.as_str(), (c.chunk_size, c.chunk_bytes)))
+        .collect();
+    let chunks_removed: Vec<String> = a
+        .chunks
+        .iter()
+        .filter(|c| !b_chunks.contains_key(c.filename.as_str()))
+        .map(|c| c.filename.clone())
+        .collect();
+    let chunks_added: Vec<String> = b
+        .chunks
+        .iter()
+        .filter(|c| !a_chunks.contains_key(c.filename.as_str()))
+        .map(|c| c.filename.clone())
+        .collect();
+    let chunks_changed: Vec<ChunkChange> = a
+        .chunks
+        .iter()
+        .filter_map(|c| {
+            let (b_items, b_bytes) = *b_chunks.get(c.filename.as_str())?;
+            (b_items != c.chunk_size || b_bytes != c.chunk_bytes).then(|| ChunkChange {
+                filename: c.filename.clone(),
+                a_items: Some(c.chunk_size),
+                b_items: Some(b_items),
+                a_bytes: Some(c.chunk_bytes),
+                b_bytes: Some(b_bytes),
+            })
+        })
+        .collect();
+
+    let items = if compare_items {
+        let total = (a.chunks.len() + b.chunks.len()) as u64;
+        let a_hashes = hash_all_items(&a, progress, 0, total)?;
+        let b_hashes = hash_all_items(&b, progress, a.chunks.len() as u64, total)?;
+        progress.report("comparing", total, total);
+        Some(diff_items(&a_hashes, &b_hashes))
+    } else {
+        None
+    };
+
+    let identical = config_changes.is_empty()
+        && chunks_added.is_empty()
+        && chunks_removed.is_empty()
+        && chunks_changed.is_empty()
+        && items
+            .as_ref()
+            .map(|d| d.added_count + d.removed_count + d.changed_count == 0)
+            .unwrap_or(true);
+    Ok(DatasetDiff {
+        job_id: progress.job_id().to_string(),
+        a_index: a.source.display().to_string(),
+        b_index: b.source.display().to_string(),
+        identical,
+        a_chunk_count: a.chunks.len(),
+        b_chunk_count: b.chunks.len(),
+        a_total_items: a.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        b_total_items: b.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        config_changes,
+        chunks_added,
+        chunks_removed,
+        chunks_changed,
+        items,
+    })
+}
+
+fn diff_config(a: &serde_json::Value, b: &serde_json::Value) -> Vec<ConfigChange> {
+    let empty = serde_json::Map::new();
+    let a_map = a.as_object().unwrap_or(&empty);
+    let b_map = b.as_object().unwrap_or(&empty);
+    let keys: BTreeSet<&String> = a_map.keys().chain(b_map.keys()).collect();
+    keys.into_iter()
+        .filter(|k| a_map.get(*k) != b_map.get(*k))
+        .map(|k| ConfigChange {
+            key: k.clone(),
+            a: a_map.get(k).cloned(),
+            b: b_map.get(k).cloned(),
+        })
+        .collect()
+}
+
+/// Item digests in global order, paired with where each item lives.
+fn hash_all_items(
+    parsed: &ParsedIndex,
+    progress: &Progress,
+    done_before: u64,
+    total: u64,
+) -> AppResult<Vec<(u128, ItemLocation)>> {
+    let mut hashes = Vec::new();
+    for (idx, chunk) in parsed.chunks.iter().enumerate() {
+        progress.report("hashing", done_before + idx as u64, total);
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &chunk.filename, &scratch)?;
+        let (num_items, offsets) = parse_offsets(&access)?;
+        for item in 0..num_items {
+            let bytes = read_item_bytes(&access, &offsets, item)?;
+            let global_index = hashes.len() as u64;
+            hashes.push((
+                xxh3_128(&bytes),
+                ItemLocation {
+                    chunk_filename: chunk.filename.clone(),
+                    item_index: item,
+                    global_index,
+                },
+            ));
+        }
+    }
+    Ok(hashes)
+}
+
+/// Aligns the two item sequences on their hashes the way a line diff aligns text: a
+/// run of inserted items is reported as added without disturbing the items after it,
+/// and an item whose content differs in place is reported as changed.
+fn diff_items(a: &[(u128, ItemLocation)], b: &[(u128, ItemLocation)]) -> ItemDiff {
+    let a_positions = positions_by_hash(a);
+    let b_positions = positions_by_hash(b);
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    let (mut added_count, mut removed_count, mut changed_count) = (0u64, 0u64, 0u64);
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let ((a_hash, a_loc), (b_hash, b_loc)) = (&a[i], &b[j]);
+        if a_hash == b_hash {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        // How far ahead each side's item reappears on the other side, if it does.
+        let a_ahead = next_position(&b_positions, *a_hash, j).map(|at| at - j);
+        let b_ahead = next_position(&a_positions, *b_hash, i).map(|at| at - i);
+        match (a_ahead, b_ahead) {
+            (None, None) => {
+                changed_count += 1;
+                if changed.len() < MAX_LISTED_ITEMS {
+                    changed.push(ChangedItem {
+                        global_index: a_loc.global_index,
+                        a: a_loc.clone(),
+                        b: b_loc.clone(),
+                    });
+                }
+                i += 1;
+                j += 1;
+            }
+            (Some(a_gap), Some(b_gap)) if b_gap < a_gap => {
+                removed_count += 1;
+                if removed.len() < MAX_LISTED_ITEMS {
+                    removed.push(a_loc.clone());
+                }
+                i += 1;
+            }
+            (Some(_), _) => {
+                added_count += 1;
+                if added.len() < MAX_LISTED_ITEMS {
+                    added.push(b_loc.clone());
+                }
+                j += 1;
+            }
+            (None, Some(_)) => {
+                removed_count += 1;
+                if removed.len() < MAX_LISTED_ITEMS {
+                    removed.push(a_loc.clone());
+                }
+                i += 1;
+            }
+        }
+    }
+    for (_, loc) in &a[i..] {
+        removed_count += 1;
+        if removed.len() < MAX_LISTED_ITEMS {
+            removed.push(loc.clone());
+        }
+    }
+    for (_, loc) in &b[j..] {
+        added_count += 1;
+        if added.len() < MAX_LISTED_ITEMS {
+            added.push(loc.clone());
+        }
+    }
+
+    ItemDiff {
+        a_items: a.len() as u64,
+        b_items: b.len() as u64,
+        added_count,
+        removed_count,
+        changed_count,
+        added,
+        removed,
+        changed,
+    }
+}
+
+/// Ascending positions of every hash in `items`.
+fn positions_by_hash(items: &[(u128, ItemLocation)]) -> HashMap<u128, Vec<usize>> {
+    let mut positions: HashMap<u128, Vec<usize>> = HashMap::new();
+    for (pos, (hash, _)) in items.iter().enumerate() {
+        positions.entry(*hash).or_default().push(pos);
+    }
+    positions
+}
+
+/// First position at or after `from` holding `hash`.
+fn next_position(positions: &HashMap<u128, Vec<usize>>, hash: u128, from: usize) -> Option<usize> {
+    let list = positions.get(&hash)?;
+    list.get(list.partition_point(|p| *p < from)).copied()
+}
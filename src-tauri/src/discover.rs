@@ -0,0 +1,162 @@
+use crate::litdata::{is_index_file_name, parse_index, resolve_index_path, AppError, AppResult};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Directory levels below `root` searched when the caller does not say.
+const DEFAULT_MAX_DEPTH: usize = 12;
+/// Directories listed between progress events while walking; the total is unknown.
+const WALK_REPORT_EVERY: u64 = 256;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredDataset {
+    index_path: String,
+    /// Directory holding the index, relative to the scanned root.
+    relative_dir: String,
+    chunk_count: usize,
+    item_count: u64,
+    total_bytes: u64,
+    data_format: Vec<String>,
+    compression: Option<String>,
+    /// Set when the index was found but could not be parsed.
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetScan {
+    root: String,
+    directories_scanned: u64,
+    /// Directories that could not be listed, usually for lack of permission.
+    unreadable: Vec<String>,
+    datasets: Vec<DiscoveredDataset>,
+}
+
+struct Walk {
+    index_files: Vec<PathBuf>,
+    directories_scanned: u64,
+    unreadable: Vec<String>,
+}
+
+/// Finds one index per directory under `root`. Hidden directories and symlinked
+/// directories are skipped so the walk cannot loop.
+fn walk(root: &Path, max_depth: usize, progress: &Progress) -> Walk {
+    let mut out = Walk {
+        index_files: Vec::new(),
+        directories_scanned: 0,
+        unreadable: Vec::new(),
+    };
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                out.unreadable.push(dir.display().to_string());
+                continue;
+            }
+        };
+        out.directories_scanned += 1;
+        if out.directories_scanned.is_multiple_of(WALK_REPORT_EVERY) {
+            progress.report("walk", out.directories_scanned, 0);
+        }
+        let mut has_index = false;
+        for entry in entries.flatten() {
+            let Ok(kind) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            if kind.is_file() && is_index_file_name(name) {
+                has_index = true;
+            } else if kind.is_dir() && !name.starts_with('.') && depth < max_depth {
+                stack.push((entry.path(), depth + 1));
+            }
+        }
+        if has_index {
+            // Prefers the merged index.json over per-worker partial indexes.
+            if let Ok(index) = resolve_index_path(&dir) {
+                out.index_files.push(index);
+            }
+        }
+    }
+    out.index_files.sort();
+    out
+}
+
+fn summarize(root: &Path, index_path: &Path) -> DiscoveredDataset {
+    let relative_dir = index_path
+        .parent()
+        .and_then(|dir| dir.strip_prefix(root).ok())
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let mut summary = DiscoveredDataset {
+        index_path: index_path.display().to_string(),
+        relative_dir,
+        chunk_count: 0,
+        item_count: 0,
+        total_bytes: 0,
+        data_format: Vec::new(),
+        compression: None,
+        error: None,
+    };
+    match parse_index(index_path) {
+        Ok(parsed) => {
+            summary.chunk_count = parsed.chunks.len();
+            summary.item_count = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+            summary.total_bytes = parsed.chunks.iter().map(|c| c.chunk_bytes).sum();
+            summary.data_format = parsed.config.data_format.unwrap_or_default();
+            summary.compression = parsed.config.compression;
+        }
+        Err(e) => summary.error = Some(e.to_string()),
+    }
+    summary
+}
+
+/// Walks `root` for optimized datasets and summarizes each from its index alone;
+/// chunk files are not opened.
+#[tauri::command]
+pub async fn scan_for_datasets(
+    app: AppHandle,
+    root: String,
+    max_depth: Option<usize>,
+    job_id: Option<String>,
+) -> AppResult<DatasetScan> {
+    let root = PathBuf::from(root.trim());
+    let progress = Progress::new(app, job_id, "discover");
+    spawn_blocking(move || scan_for_datasets_sync(&root, max_depth, &progress))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn scan_for_datasets_sync(
+    root: &Path,
+    max_depth: Option<usize>,
+    progress: &Progress,
+) -> AppResult<DatasetScan> {
+    if !root.is_dir() {
+        return Err(AppError::Missing(root.display().to_string()));
+    }
+    let found = walk(root, max_depth.unwrap_or(DEFAULT_MAX_DEPTH), progress);
+    let datasets = par_map(&found.index_files, Some((progress, "summarize")), |path| {
+        summarize(root, path)
+    });
+    tracing::info!(
+        root = %root.display(),
+        datasets = datasets.len(),
+        "scanned for datasets"
+    );
+    Ok(DatasetScan {
+        root: root.display().to_string(),
+        directories_scanned: found.directories_scanned,
+        unreadable: found.unreadable,
+        datasets,
+    })
+}
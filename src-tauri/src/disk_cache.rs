@@ -0,0 +1,104 @@
+use crate::litdata::AppResult;
+use crate::settings;
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use xxhash_rust::xxh3::xxh3_128;
+
+/// Default total size of spilled chunks kept on disk; the least recently used go first.
+pub(crate) const DEFAULT_DISK_CACHE_BUDGET: u64 = 4 * 1024 * 1024 * 1024;
+
+pub(crate) fn cache_dir() -> PathBuf {
+    settings::temp_root().join("chunk-cache")
+}
+
+/// Entry name for a compressed chunk; any change to its size or mtime yields a new name.
+fn entry_path(chunk_path: &Path) -> Option<PathBuf> {
+    let meta = fs::metadata(chunk_path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    let canonical = fs::canonicalize(chunk_path).unwrap_or_else(|_| chunk_path.to_path_buf());
+    let key = format!("{}\0{}\0{}", canonical.display(), mtime, meta.len());
+    Some(cache_dir().join(format!("{:032x}.bin", xxh3_128(key.as_bytes()))))
+}
+
+/// Decompressed bytes spilled by an earlier session, if still valid.
+pub(crate) fn load(chunk_path: &Path) -> Option<Vec<u8>> {
+    let entry = entry_path(chunk_path)?;
+    let data = fs::read(&entry).ok()?;
+    // Refresh the mtime so pruning treats this entry as recently used.
+    if let Ok(file) = File::options().write(true).open(&entry) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(data)
+}
+
+/// Best-effort spill; a failure only costs a later decompression.
+pub(crate) fn store(chunk_path: &Path, data: &[u8]) {
+    let budget = settings::current().disk_cache_budget_bytes;
+    if data.len() as u64 > budget {
+        return;
+    }
+    let Some(entry) = entry_path(chunk_path) else {
+        return;
+    };
+    if fs::create_dir_all(cache_dir()).is_err() {
+        return;
+    }
+    let partial = entry.with_extension("part");
+    if fs::write(&partial, data).is_err() || fs::rename(&partial, &entry).is_err() {
+        let _ = fs::remove_file(&partial);
+        return;
+    }
+    prune(budget);
+}
+
+/// Number and total size of spilled chunks currently on disk.
+pub(crate) fn usage() -> (usize, u64) {
+    let Ok(dir) = fs::read_dir(cache_dir()) else {
+        return (0, 0);
+    };
+    dir.filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "bin"))
+        .filter_map(|e| e.metadata().ok())
+        .fold((0, 0), |(count, bytes), meta| {
+            (count + 1, bytes + meta.len())
+        })
+}
+
+pub(crate) fn clear() -> AppResult<()> {
+    match fs::remove_dir_all(cache_dir()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn prune(budget: u64) {
+    let Ok(dir) = fs::read_dir(cache_dir()) else {
+        return;
+    };
+    let mut entries: Vec<(SystemTime, u64, PathBuf)> = dir
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "bin"))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((meta.modified().ok()?, meta.len(), e.path()))
+        })
+        .collect();
+    let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    entries.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in entries {
+        if total <= budget {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= len;
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::http::{parse_url, Http};
+use crate::litdata::{resolve_index_path, AppError, AppResult};
+use crate::progress::Progress;
+use crate::rewrite::partial_path;
+use crate::scan::par_map;
+use crate::security::confine_to_root;
+use crate::settings;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const HASH_BUF_BYTES: usize = 1024 * 1024;
+
+/// Where an object's bytes came from.
+enum ObjectStore {
+    Dir(PathBuf),
+    Http(tauri::Url),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DvcSource {
+    /// The `.dvc` file describing the dataset.
+    pointer: String,
+    repo_root: String,
+    md5: String,
+    remote: Option<String>,
+    remote_url: Option<String>,
+    /// Directory to open: the checked-out copy, or one assembled from cached objects.
+    local_path: String,
+    /// Whether `local_path` is the repository's own checkout.
+    checked_out: bool,
+    files: usize,
+    /// Objects taken from the repository's DVC cache.
+    from_cache: usize,
+    /// Objects fetched from the remote.
+    fetched: usize,
+    fetched_bytes: u64,
+}
+
+/// Entry of a directory object (`<md5>.dir`).
+#[derive(Deserialize)]
+struct DirEntry {
+    md5: String,
+    relpath: String,
+}
+
+/// Opens a DVC-tracked dataset by its `.dvc` file or tracked path. A checkout that holds
+/// an index is used as-is; otherwise the directory is assembled from the repository's
+/// cache and the configured remote (`remote`, else `core.remote`), verifying each
+/// object's md5. Local and HTTP remotes can be fetched from.
+#[tauri::command]
+pub async fn resolve_dvc(
+    app: AppHandle,
+    path: String,
+    remote: Option<String>,
+    job_id: Option<String>,
+) -> AppResult<DvcSource> {
+    let progress = Progress::new(app, job_id, "dvc");
+    spawn_blocking(move || {
+        resolve_sync(
+            Path::new(path.trim()),
+            remote.as_deref().map(str::trim).filter(|r| !r.is_empty()),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// The `.dvc` file for `path`: `path` itself, or `<path>.dvc` beside it.
+fn pointer_for(path: &Path) -> AppResult<PathBuf> {
+    if path.extension().is_some_and(|e| e == "dvc") && path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    let name = path
+        .file_name()
+        .map(|n| format!("{}.dvc", n.to_string_lossy()))
+        .ok_or_else(|| AppError::Invalid(format!("{} names no file", path.display())))?;
+    let pointer = path.with_file_name(name);
+    if pointer.is_file() {
+        Ok(pointer)
+    } else {
+        Err(AppError::Invalid(format!(
+            "{} is not tracked by DVC; {} does not exist",
+            path.display(),
+            pointer.display()
+        )))
+    }
+}
+
+/// `md5` and `path` of the first output in a `.dvc` file.
+fn parse_pointer(text: &str) -> Option<(String, String)> {
+    let mut md5 = None;
+    let mut path = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        // A second list entry starts the next output.
+        if trimmed.starts_with("- ") && (md5.is_some() || path.is_some()) {
+            break;
+        }
+        let Some((key, value)) = trimmed.trim_start_matches("- ").split_once(':') else {
+            continue;
+        };
+        let value = value
+            .trim()
+            .trim_matches(|c| c == '\'' || c == '"')
+            .to_string();
+        match key.trim() {
+            "md5" if md5.is_none() => md5 = Some(value),
+            "path" if path.is_none() => path = Some(value),
+            _ => {}
+        }
+    }
+    Some((md5?, path?))
+}
+
+/// Sections of DVC's INI config; `['remote "name"']` becomes `remote "name"`.
+fn parse_config(text: &str, into: &mut HashMap<String, HashMap<String, String>>) {
+    let mut section = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().trim_matches('\'').to_string();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            into.entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+}
+
+/// Whether `md5` is a hash as DVC writes it: 32 lowercase hex digits, with `.dir` for
+/// directory objects. Anything else must not reach a path or URL.
+fn is_md5(md5: &str) -> bool {
+    let digest = md5.strip_suffix(".dir").unwrap_or(md5);
+    digest.len() == 32
+        && digest
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Paths of an object in a cache or remote: the DVC 3 layout first, then DVC 2's.
+fn object_paths(md5: &str) -> [String; 2] {
+    let (prefix, rest) = md5.split_at(2.min(md5.len()));
+    [
+        format!("files/md5/{prefix}/{rest}"),
+        format!("{prefix}/{rest}"),
+    ]
+}
+
+fn hash_file(path: &Path) -> AppResult<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0u8; HASH_BUF_BYTES];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Copies object `md5` into `dest`, from the local cache when it has it, else from the
+/// remote, and checks its hash. Returns the bytes fetched from the remote.
+fn place_object(
+    md5: &str,
+    dest: &Path,
+    cache: &Path,
+    remote: Option<&ObjectStore>,
+    http: &Http,
+) -> AppResult<Option<u64>> {
+    if !is_md5(md5) {
+        return Err(AppError::Invalid(format!(
+            "object {md5:?} is not an md5 hash"
+        )));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = partial_path(dest);
+    let mut fetched = None;
+    let cached = object_paths(md5)
+        .iter()
+        .map(|p| cache.join(p))
+        .find(|p| p.is_file());
+    match (cached, remote) {
+        (Some(cached), _) => {
+            if fs::hard_link(&cached, &partial).is_err() {
+                fs::copy(&cached, &partial)?;
+            }
+        }
+        (None, Some(ObjectStore::Dir(dir))) => {
+            let found = object_paths(md5)
+                .iter()
+                .map(|p| dir.join(p))
+                .find(|p| p.is_file())
+                .ok_or_else(|| AppError::Missing(format!("object {md5} in {}", dir.display())))?;
+            fetched = Some(fs::copy(found, &partial)?);
+        }
+        (None, Some(ObjectStore::Http(base))) => {
+            let mut last = None;
+            for path in object_paths(md5) {
+                let url = base
+                    .join(&path)
+                    .map_err(|e| AppError::Invalid(e.to_string()))?;
+                match http.download(&url, None, &partial) {
+                    Ok(bytes) => {
+                        fetched = Some(bytes);
+                        break;
+                    }
+                    Err(AppError::Missing(e)) => last = Some(AppError::Missing(e)),
+                    Err(e) => return Err(e),
+                }
+            }
+            if fetched.is_none() {
+                return Err(last.unwrap_or_else(|| AppError::Missing(md5.to_string())));
+            }
+        }
+        (None, None) => {
+            return Err(AppError::Missing(format!(
+                "object {md5} is not in the DVC cache and no remote is configured"
+            )))
+        }
+    }
+    let expected = md5.trim_end_matches(".dir");
+    let actual = hash_file(&partial)?;
+    if actual != expected {
+        let _ = fs::remove_file(&partial);
+        return Err(AppError::Invalid(format!(
+            "object {md5} does not match its hash ({actual})"
+        )));
+    }
+    fs::rename(&partial, dest)?;
+    Ok(fetched)
+}
+
+/// The remote to fetch from, by name or `core.remote`, with its URL resolved against
+/// the `.dvc` directory like DVC does.
+fn remote_store(
+    config: &HashMap<String, HashMap<String, String>>,
+    dvc_dir: &Path,
+    requested: Option<&str>,
+) -> AppResult<Option<(String, String, ObjectStore)>> {
+    let name = match requested {
+        Some(name) => name.to_string(),
+        None => match config.get("core").and_then(|c| c.get("remote")) {
+            Some(name) => name.clone(),
+            None => return Ok(None),
+        },
+    };
+    let url = config
+        .get(&format!("remote \"{name}\""))
+        .and_then(|r| r.get("url"))
+        .ok_or_else(|| AppError::Invalid(format!("no DVC remote named {name:?}")))?
+        .clone();
+    let store = if url.starts_with("http://") || url.starts_with("https://") {
+        ObjectStore::Http(parse_url(&format!("{}/", url.trim_end_matches('/')))?)
+    } else if let Some(rest) = url.strip_prefix("file://") {
+        ObjectStore::Dir(PathBuf::from(rest))
+    } else if url.contains("://") {
+        return Err(AppError::Invalid(format!(
+            "remote {name} is {url}; only local and HTTP remotes can be fetched from"
+        )));
+    } else {
+        ObjectStore::Dir(dvc_dir.join(&url))
+    };
+    Ok(Some((name, url, store)))
+}
+
+pub(crate) fn resolve_sync(
+    path: &Path,
+    remote: Option<&str>,
+    progress: &Progress,
+) -> AppResult<DvcSource> {
+    let pointer = pointer_for(path)?;
+    let pointer_dir = pointer.parent().unwrap_or(Path::new("."));
+    let (md5, out_path) = parse_pointer(&fs::read_to_string(&pointer)?).ok_or_else(|| {
+        AppError::Invalid(format!("{} has no output with an md5", pointer.display()))
+    })?;
+    if !is_md5(&md5) {
+        return Err(AppError::Invalid(format!(
+            "{} has md5 {md5:?}, which is not an md5 hash",
+            pointer.display()
+        )));
+    }
+    if !md5.ends_with(".dir") {
+        return Err(AppError::Invalid(format!(
+            "{} tracks a single file; a dataset is a directory",
+            pointer.display()
+        )));
+    }
+    let repo_root = pointer_dir
+        .ancestors()
+        .find(|dir| dir.join(".dvc").is_dir())
+        .ok_or_else(|| {
+            AppError::Invalid(format!(
+                "{} is not inside a DVC repository",
+                pointer.display()
+            ))
+        })?
+        .to_path_buf();
+    let dvc_dir = repo_root.join(".dvc");
+    let mut config = HashMap::new();
+    for name in ["config", "config.local"] {
+        if let Ok(text) = fs::read_to_string(dvc_dir.join(name)) {
+            parse_config(&text, &mut config);
+        }
+    }
+    let remote = remote_store(&config, &dvc_dir, remote)?;
+    let report =
+        |local_path: &Path, checked_out, files, from_cache, fetched, fetched_bytes| DvcSource {
+            pointer: pointer.display().to_string(),
+            repo_root: repo_root.display().to_string(),
+            md5: md5.clone(),
+            remote: remote.as_ref().map(|(name, _, _)| name.clone()),
+            remote_url: remote.as_ref().map(|(_, url, _)| url.clone()),
+            local_path: local_path.display().to_string(),
+            checked_out,
+            files,
+            from_cache,
+            fetched,
+            fetched_bytes,
+        };
+
+    let checkout = confine_to_root(pointer_dir, &out_path)?;
+    if checkout.is_dir() && resolve_index_path(&checkout).is_ok() {
+        progress.report("done", 1, 1);
+        return Ok(report(&checkout, true, 0, 0, 0, 0));
+    }
+
+    let cache = config
+        .get("cache")
+        .and_then(|c| c.get("dir"))
+        .map_or_else(|| dvc_dir.join("cache"), |dir| dvc_dir.join(dir));
+    let store = remote.as_ref().map(|(_, _, store)| store);
+    let http = Http::new();
+    let dest = settings::temp_root()
+        .join("dvc")
+        .join(md5.trim_end_matches(".dir"));
+    let listing = dest.join(".dir");
+    if !listing.is_file() {
+        place_object(&md5, &listing, &cache, store, &http)?;
+    }
+    let entries: Vec<DirEntry> = serde_json::from_slice(&fs::read(&listing)?)
+        .map_err(|e| AppError::Invalid(format!("directory object {md5}: {e}")))?;
+
+    let total = entries.len() as u64;
+    progress.report("fetching", 0, total);
+    let placed = par_map(&entries, Some((progress, "fetching")), |entry| {
+        let target = confine_to_root(&dest, &entry.relpath)?;
+        if target.is_file() {
+            return Ok((false, None));
+        }
+        place_object(&entry.md5, &target, &cache, store, &http).map(|fetched| (true, fetched))
+    })
+    .into_iter()
+    .collect::<AppResult<Vec<_>>>()?;
+    progress.report("done", total, total);
+
+    if resolve_index_path(&dest).is_err() {
+        return Err(AppError::Invalid(format!(
+            "{} does not track a LitData dataset; it has no index.json",
+            pointer.display()
+        )));
+    }
+    let fetched: Vec<u64> = placed.iter().filter_map(|(_, f)| *f).collect();
+    let from_cache = placed.iter().filter(|(new, f)| *new && f.is_none()).count();
+    Ok(report(
+        &dest,
+        false,
+        entries.len(),
+        from_cache,
+        fetched.len(),
+        fetched.iter().sum(),
+    ))
+}
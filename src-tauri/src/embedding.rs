@@ -0,0 +1,303 @@
+use crate::dedup::ItemLocation;
+use crate::field_sizes::sample_plan;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::tensor::{floats, tensor_elements, Dtype, TensorLayout};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Points returned when the caller does not say; plenty for a scatter plot.
+const DEFAULT_SAMPLE_ITEMS: u32 = 5_000;
+const MAX_SAMPLE_ITEMS: u32 = 100_000;
+const MAX_ITERATIONS: usize = 300;
+/// Stop once both directions move less than this between iterations.
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectedPoint {
+    #[serde(flatten)]
+    location: ItemLocation,
+    x: f64,
+    y: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingProjection {
+    job_id: String,
+    field_index: usize,
+    format: Option<String>,
+    dimensions: usize,
+    total_items: u64,
+    sampled_items: u64,
+    /// Sampled items left out: not a float vector, a different length from the first
+    /// one, or holding NaN or infinity.
+    skipped_items: u64,
+    /// Share of the total variance along each axis.
+    explained_variance: [f64; 2],
+    points: Vec<ProjectedPoint>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Projects a float-vector field onto its first two principal components over up to
+/// `sample_items` items spread across the dataset. `dtype` supplies the element type for
+/// fields whose format does not record it, such as `numpy` or raw `bytes`.
+#[tauri::command]
+pub async fn project_embeddings(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    dtype: Option<String>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<EmbeddingProjection> {
+    let progress = Progress::new(app, job_id, "embedding-pca");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        let dtype = dtype.as_deref().map(Dtype::parse).transpose()?;
+        project_of(
+            &parsed,
+            field_index,
+            dtype,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// The field to project and how to read it: the requested one, or the first whose
+/// format says it holds floats.
+fn embedding_field(
+    formats: &[String],
+    field_index: Option<usize>,
+    dtype: Option<Dtype>,
+) -> AppResult<(usize, TensorLayout)> {
+    if let Some(field) = field_index {
+        let format = formats
+            .get(field)
+            .ok_or_else(|| AppError::Invalid("field index out of range".into()))?;
+        return Ok((
+            field,
+            TensorLayout::of(format).unwrap_or(TensorLayout::Bare(None)),
+        ));
+    }
+    formats
+        .iter()
+        .enumerate()
+        .find_map(|(field, format)| {
+            let layout = TensorLayout::of(format)?;
+            let floats = match layout {
+                TensorLayout::TorchHeader => true,
+                TensorLayout::Bare(Some(declared)) => dtype.unwrap_or(declared).is_float(),
+                TensorLayout::NumpyHeader | TensorLayout::Bare(None) => {
+                    dtype.is_some_and(Dtype::is_float)
+                }
+            };
+            floats.then_some((field, layout))
+        })
+        .ok_or_else(|| AppError::Invalid("no float tensor field; pick one explicitly".into()))
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Makes `vectors` orthonormal in place; a vector that lies in the span of the earlier
+/// ones (the data has lower rank) becomes zero.
+fn orthonormalize(vectors: &mut [Vec<f64>]) {
+    for i in 0..vectors.len() {
+        let before = dot(&vectors[i], &vectors[i]).sqrt();
+        for j in 0..i {
+            let projection = dot(&vectors[i], &vectors[j]);
+            let (done, rest) = vectors.split_at_mut(i);
+            for (v, u) in rest[0].iter_mut().zip(&done[j]) {
+                *v -= projection * u;
+            }
+        }
+        let norm = dot(&vectors[i], &vectors[i]).sqrt();
+        if norm > before * 1e-10 {
+            vectors[i].iter_mut().for_each(|v| *v /= norm);
+        } else {
+            vectors[i].fill(0.0);
+        }
+    }
+}
+
+/// Top two eigenvectors of the covariance of centered `rows`, by orthogonal iteration,
+/// which never forms the `d × d` covariance matrix.
+fn principal_axes(rows: &[Vec<f64>], dims: usize) -> [Vec<f64>; 2] {
+    // A fixed, uneven start so results are reproducible and not orthogonal to the data.
+    let mut axes: Vec<Vec<f64>> = (0..2)
+        .map(|k| {
+            (0..dims)
+                .map(|i| (((i * 7919 + k * 104_729) % 1000) as f64 / 1000.0) - 0.5)
+                .collect()
+        })
+        .collect();
+    orthonormalize(&mut axes);
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = vec![vec![0.0; dims]; 2];
+        for row in rows {
+            for (axis, out) in axes.iter().zip(next.iter_mut()) {
+                let score = dot(row, axis);
+                for (o, r) in out.iter_mut().zip(row) {
+                    *o += score * r;
+                }
+            }
+        }
+        orthonormalize(&mut next);
+        let settled = axes
+            .iter()
+            .zip(&next)
+            .all(|(a, b)| (dot(a, a) - dot(a, b).abs()).abs() < TOLERANCE);
+        axes = next;
+        if settled {
+            break;
+        }
+    }
+    // Eigenvectors have no inherent sign; point the largest component positive so
+    // repeated runs plot the same way up.
+    for axis in axes.iter_mut() {
+        let largest = axis
+            .iter()
+            .copied()
+            .fold(0.0f64, |m, v| if v.abs() > m.abs() { v } else { m });
+        if largest < 0.0 {
+            axis.iter_mut().for_each(|v| *v = -*v);
+        }
+    }
+    let mut axes = axes.into_iter();
+    [
+        axes.next().unwrap_or_default(),
+        axes.next().unwrap_or_default(),
+    ]
+}
+
+pub(crate) fn project_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    dtype: Option<Dtype>,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<EmbeddingProjection> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let (field, layout) = embedding_field(&formats, field_index, dtype)?;
+    let plan = sample_plan(parsed, sample_items.min(MAX_SAMPLE_ITEMS));
+    progress.report("reading", 0, plan.len() as u64);
+    let read = par_map(&plan, Some((progress, "reading")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut vectors = Vec::with_capacity(items.len());
+        for item in items {
+            let (data, _) = read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+            let vector = tensor_elements(&data, layout, dtype)
+                .ok()
+                .and_then(|(body, dtype)| floats(body, dtype))
+                .filter(|v| v.iter().all(|x| x.is_finite()));
+            vectors.push((*item, vector));
+        }
+        AppResult::Ok(vectors)
+    });
+
+    let chunk_starts: Vec<u64> = parsed
+        .chunks
+        .iter()
+        .scan(0u64, |start, c| {
+            let this = *start;
+            *start += c.chunk_size as u64;
+            Some(this)
+        })
+        .collect();
+    let mut locations = Vec::new();
+    let mut rows: Vec<Vec<f64>> = Vec::new();
+    let mut dimensions = 0usize;
+    let mut sampled_items = 0u64;
+    let mut skipped_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(read) {
+        let filename = &parsed.chunks[*chunk].filename;
+        let Ok(vectors) = result else {
+            unreadable_chunks.push(filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (item, vector) in vectors {
+            let Some(vector) = vector.filter(|v| !v.is_empty()) else {
+                skipped_items += 1;
+                continue;
+            };
+            if dimensions == 0 {
+                dimensions = vector.len();
+            }
+            if vector.len() != dimensions {
+                skipped_items += 1;
+                continue;
+            }
+            rows.push(vector.into_iter().map(f64::from).collect());
+            locations.push(ItemLocation {
+                chunk_filename: filename.clone(),
+                item_index: item,
+                global_index: chunk_starts[*chunk] + item as u64,
+            });
+        }
+    }
+    if rows.len() < 2 {
+        return Err(AppError::Invalid(format!(
+            "field {field} has fewer than two readable float vectors"
+        )));
+    }
+
+    progress.report("projecting", plan.len() as u64, plan.len() as u64);
+    let n = rows.len() as f64;
+    let mut mean = vec![0.0; dimensions];
+    for row in &rows {
+        for (m, v) in mean.iter_mut().zip(row) {
+            *m += v / n;
+        }
+    }
+    for row in rows.iter_mut() {
+        for (v, m) in row.iter_mut().zip(&mean) {
+            *v -= m;
+        }
+    }
+    let total_variance: f64 = rows.iter().map(|r| dot(r, r)).sum();
+    let axes = principal_axes(&rows, dimensions);
+    let mut captured = [0.0; 2];
+    let points = rows
+        .iter()
+        .zip(locations)
+        .map(|(row, location)| {
+            let (x, y) = (dot(row, &axes[0]), dot(row, &axes[1]));
+            captured[0] += x * x;
+            captured[1] += y * y;
+            ProjectedPoint { location, x, y }
+        })
+        .collect();
+    let explained_variance = if total_variance > 0.0 {
+        captured.map(|c| c / total_variance)
+    } else {
+        [0.0; 2]
+    };
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+    Ok(EmbeddingProjection {
+        job_id: progress.job_id().to_string(),
+        field_index: field,
+        format: formats.get(field).cloned(),
+        dimensions,
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        skipped_items,
+        explained_variance,
+        points,
+        unreadable_chunks,
+    })
+}
@@ -0,0 +1,211 @@
+use crate::field_sizes::sample_plan;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_SAMPLE_ITEMS: u32 = 2_000;
+/// Shorter payloads cannot reach 8 bits per byte even when random, so they are skipped.
+const MIN_PAYLOAD_BYTES: usize = 1024;
+/// Entropy is measured over at most this much of each payload.
+const MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+/// Bits per byte above which a payload is indistinguishable from random; compressed
+/// and encrypted data land here, text and raw pixels well below.
+const NEAR_RANDOM_BITS: f64 = 7.5;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldEntropy {
+    field_index: usize,
+    format: Option<String>,
+    /// Payloads long enough to measure.
+    measured_items: u64,
+    /// Payloads under 1 KiB, left out.
+    short_items: u64,
+    /// Mean Shannon entropy in bits per byte, weighted by measured bytes; 8 is random.
+    mean_bits_per_byte: Option<f64>,
+    /// Share of measured payloads above 7.5 bits per byte.
+    near_random_fraction: f64,
+    /// Share of the sampled bytes of all fields that this field holds.
+    byte_share: f64,
+    /// Near-random inside zstd chunks: zstd spends time on it and saves nothing.
+    double_compressed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntropyReport {
+    job_id: String,
+    compression: Option<String>,
+    total_items: u64,
+    sampled_items: u64,
+    fields: Vec<FieldEntropy>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Byte entropy of every field over up to `sample_items` items spread across the
+/// dataset, flagging near-random fields stored inside zstd-compressed chunks.
+#[tauri::command]
+pub async fn field_entropy(
+    app: AppHandle,
+    index_path: String,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<EntropyReport> {
+    let progress = Progress::new(app, job_id, "entropy");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        entropy_of(
+            &parsed,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Shannon entropy of `data` in bits per byte.
+fn bits_per_byte(data: &[u8]) -> f64 {
+    let mut counts = [0u64; 256];
+    for b in data {
+        counts[*b as usize] += 1;
+    }
+    let n = data.len() as f64;
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// One field's running totals.
+#[derive(Default, Clone)]
+struct Tally {
+    measured: u64,
+    short: u64,
+    near_random: u64,
+    /// Entropy times measured bytes, for the weighted mean.
+    weighted_bits: f64,
+    measured_bytes: u64,
+    total_bytes: u64,
+}
+
+impl Tally {
+    fn add(&mut self, payload: &[u8]) {
+        self.total_bytes += payload.len() as u64;
+        if payload.len() < MIN_PAYLOAD_BYTES {
+            self.short += 1;
+            return;
+        }
+        let window = &payload[..payload.len().min(MAX_PAYLOAD_BYTES)];
+        let bits = bits_per_byte(window);
+        self.measured += 1;
+        self.measured_bytes += window.len() as u64;
+        self.weighted_bits += bits * window.len() as f64;
+        if bits > NEAR_RANDOM_BITS {
+            self.near_random += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Tally) {
+        self.measured += other.measured;
+        self.short += other.short;
+        self.near_random += other.near_random;
+        self.weighted_bits += other.weighted_bits;
+        self.measured_bytes += other.measured_bytes;
+        self.total_bytes += other.total_bytes;
+    }
+}
+
+pub(crate) fn entropy_of(
+    parsed: &ParsedIndex,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<EntropyReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    if formats.is_empty() {
+        return Err(AppError::Invalid("index declares no data_format".into()));
+    }
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("measuring", 0, plan.len() as u64);
+    let measured = par_map(&plan, Some((progress, "measuring")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut tallies = vec![Tally::default(); formats.len()];
+        for item in items {
+            for (field, tally) in tallies.iter_mut().enumerate() {
+                let (data, _) =
+                    read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                tally.add(&data);
+            }
+        }
+        AppResult::Ok(tallies)
+    });
+
+    let mut tallies = vec![Tally::default(); formats.len()];
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(measured) {
+        let Ok(chunk_tallies) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (total, tally) in tallies.iter_mut().zip(&chunk_tallies) {
+            total.merge(tally);
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let zstd = parsed
+        .config
+        .compression
+        .as_deref()
+        .is_some_and(|c| c.eq_ignore_ascii_case("zstd"));
+    let all_bytes: u64 = tallies.iter().map(|t| t.total_bytes).sum();
+    let fields = tallies
+        .iter()
+        .enumerate()
+        .map(|(field, t)| {
+            let mean_bits_per_byte =
+                (t.measured_bytes > 0).then(|| t.weighted_bits / t.measured_bytes as f64);
+            FieldEntropy {
+                field_index: field,
+                format: formats.get(field).cloned(),
+                measured_items: t.measured,
+                short_items: t.short,
+                mean_bits_per_byte,
+                near_random_fraction: if t.measured > 0 {
+                    t.near_random as f64 / t.measured as f64
+                } else {
+                    0.0
+                },
+                byte_share: if all_bytes > 0 {
+                    t.total_bytes as f64 / all_bytes as f64
+                } else {
+                    0.0
+                },
+                double_compressed: zstd
+                    && mean_bits_per_byte.is_some_and(|bits| bits > NEAR_RANDOM_BITS),
+            }
+        })
+        .collect();
+    Ok(EntropyReport {
+        job_id: progress.job_id().to_string(),
+        compression: parsed.config.compression.clone(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        fields,
+        unreadable_chunks,
+    })
+}
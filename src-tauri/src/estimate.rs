@@ -0,0 +1,198 @@
+use crate::benchmark::{sequential_pass, ChunkBenchmark};
+use crate::litdata::{parse_index, AppError, AppResult, ParsedIndex};
+use crate::progress::Progress;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// The step that dominates a worker's time.
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Bottleneck {
+    /// Reading chunk files from storage.
+    Storage,
+    /// Zstd decompression.
+    Decompression,
+    /// Slicing items out of loaded chunks.
+    ItemReads,
+    /// Fewer chunks than workers, so some workers never get one.
+    TooFewChunks,
+}
+
+/// Share of one worker's time per step; sums to 1.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSplit {
+    storage: f64,
+    decompression: f64,
+    item_reads: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrainingEstimate {
+    job_id: String,
+    index_path: String,
+    batch_size: u32,
+    num_workers: u32,
+    /// Workers that can run at once: capped by CPU cores and by the number of chunks.
+    effective_workers: u32,
+    cpu_cores: u32,
+    total_items: u64,
+    /// Measured: one worker reading the whole dataset.
+    single_worker_items_per_sec: f64,
+    items_per_sec: f64,
+    batches_per_sec: f64,
+    steps_per_epoch: u64,
+    epoch_seconds: f64,
+    /// A worker must load a whole chunk before its first item is ready.
+    first_batch_seconds: f64,
+    time_split: TimeSplit,
+    bottleneck: Bottleneck,
+    /// Chunks that failed to read were left out of the measurement.
+    unreadable_chunks: Vec<String>,
+}
+
+/// Estimates dataloader throughput and epoch time for `batch_size` and `num_workers`
+/// on this machine, from an uncached sequential read of every chunk. Assumes workers
+/// scale linearly up to the CPU core count; storage shared between workers may make
+/// real runs slower than estimated when storage dominates.
+#[tauri::command]
+pub async fn estimate_training_time(
+    app: AppHandle,
+    index_path: String,
+    batch_size: u32,
+    num_workers: u32,
+    job_id: Option<String>,
+) -> AppResult<TrainingEstimate> {
+    let progress = Progress::new(app, job_id, "estimate");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        estimate_of(&parsed, batch_size, num_workers, &progress)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn seconds_for(bytes: u64, mb_per_sec: Option<f64>) -> f64 {
+    match mb_per_sec {
+        Some(rate) if rate > 0.0 => bytes as f64 / MIB / rate,
+        _ => 0.0,
+    }
+}
+
+/// `(storage, decompression, item reads)` seconds one worker spends on `chunk`.
+fn chunk_seconds(chunk: &ChunkBenchmark) -> (f64, f64, f64) {
+    let items = if chunk.sequential_items_per_sec > 0.0 {
+        chunk.items as f64 / chunk.sequential_items_per_sec
+    } else {
+        0.0
+    };
+    (
+        seconds_for(chunk.stored_bytes, chunk.read_mb_per_sec),
+        seconds_for(chunk.decompressed_bytes, chunk.decompress_mb_per_sec),
+        items,
+    )
+}
+
+pub(crate) fn estimate_of(
+    parsed: &ParsedIndex,
+    batch_size: u32,
+    num_workers: u32,
+    progress: &Progress,
+) -> AppResult<TrainingEstimate> {
+    if batch_size == 0 {
+        return Err(AppError::Invalid("batch size must be at least 1".into()));
+    }
+    let (chunks, _) = sequential_pass(parsed, progress);
+    let (readable, failed): (Vec<_>, Vec<_>) = chunks.iter().partition(|c| c.error.is_none());
+    let unreadable_chunks = failed.iter().map(|c| c.filename.clone()).collect();
+    let measured_items: u64 = readable.iter().map(|c| c.items as u64).sum();
+    if measured_items == 0 {
+        return Err(AppError::Invalid("no readable items to measure".into()));
+    }
+
+    let (storage, decompression, item_reads) = readable
+        .iter()
+        .map(|c| chunk_seconds(c))
+        .fold((0.0, 0.0, 0.0), |(s, d, i), (cs, cd, ci)| {
+            (s + cs, d + cd, i + ci)
+        });
+    let worker_seconds = storage + decompression + item_reads;
+    let single_worker_items_per_sec = if worker_seconds > 0.0 {
+        measured_items as f64 / worker_seconds
+    } else {
+        0.0
+    };
+
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1);
+    // `num_workers = 0` loads in the training process itself, like one worker.
+    let requested = num_workers.max(1);
+    let effective_workers = requested.min(cpu_cores).min(readable.len() as u32).max(1);
+    let items_per_sec = single_worker_items_per_sec * effective_workers as f64;
+    let total_items: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+    let epoch_seconds = if items_per_sec > 0.0 {
+        total_items as f64 / items_per_sec
+    } else {
+        0.0
+    };
+
+    // The median chunk's load time, plus one batch of item reads from it.
+    let mut loads: Vec<f64> = readable
+        .iter()
+        .map(|c| {
+            let (s, d, _) = chunk_seconds(c);
+            s + d
+        })
+        .collect();
+    loads.sort_by(f64::total_cmp);
+    let first_batch_seconds = loads[loads.len() / 2]
+        + if single_worker_items_per_sec > 0.0 {
+            batch_size as f64 / single_worker_items_per_sec
+        } else {
+            0.0
+        };
+
+    let share = |part: f64| {
+        if worker_seconds > 0.0 {
+            part / worker_seconds
+        } else {
+            0.0
+        }
+    };
+    let bottleneck = if (readable.len() as u32) < requested.min(cpu_cores) {
+        Bottleneck::TooFewChunks
+    } else if storage >= decompression && storage >= item_reads {
+        Bottleneck::Storage
+    } else if decompression >= item_reads {
+        Bottleneck::Decompression
+    } else {
+        Bottleneck::ItemReads
+    };
+    Ok(TrainingEstimate {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        batch_size,
+        num_workers,
+        effective_workers,
+        cpu_cores,
+        total_items,
+        single_worker_items_per_sec,
+        items_per_sec,
+        batches_per_sec: items_per_sec / batch_size as f64,
+        steps_per_epoch: total_items.div_ceil(batch_size as u64),
+        epoch_seconds,
+        first_batch_seconds,
+        time_split: TimeSplit {
+            storage: share(storage),
+            decompression: share(decompression),
+            item_reads: share(item_reads),
+        },
+        bottleneck,
+        unreadable_chunks,
+    })
+}
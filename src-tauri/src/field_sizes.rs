@@ -0,0 +1,31 @@
+use crate::litdata::{load_chunk_access, parse_index, AppError, AppResult, ChunkCache};
+use crate::progress::Progress;
+use litdata_core::field_sizes::{field_sizes_of, DEFAULT_SAMPLE_ITEMS};
+use std::path::Path;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+pub use litdata_core::field_sizes::FieldSizeReport;
+pub(crate) use litdata_core::field_sizes::{field_sizes, percentile, sample_plan};
+
+/// Distribution of each field's payload size over up to `sample_items` items, spread
+/// evenly across the dataset.
+#[tauri::command]
+pub async fn field_size_distribution(
+    app: AppHandle,
+    index_path: String,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<FieldSizeReport> {
+    let progress = Progress::new(app, job_id, "field-sizes");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        field_sizes_of(
+            &parsed,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            |chunk| load_chunk_access(&parsed, chunk, &ChunkCache::default()),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
@@ -0,0 +1,354 @@
+use crate::litdata::{resolve_index_path, AppError, AppResult};
+use crate::progress::Progress;
+use crate::rewrite::{
+    check_declared_type, compress_chunk, group_into_chunks, pack_chunk, pack_item, write_new_index,
+};
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// What one field of each item holds.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FolderField {
+    /// The file's bytes, stored as `contentFormat`.
+    Contents,
+    /// The file name, as `str`.
+    FileName,
+    /// The path below the source folder with `/` separators, as `str`.
+    RelativePath,
+}
+
+impl FolderField {
+    /// Key in the item dict litdata hands back.
+    fn key(self) -> &'static str {
+        match self {
+            FolderField::Contents => "content",
+            FolderField::FileName => "name",
+            FolderField::RelativePath => "path",
+        }
+    }
+}
+
+/// How files become items, and how items are chunked.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderLayout {
+    fields: Vec<FolderField>,
+    /// data_format for `contents` fields, such as `jpeg` or `str`; `bytes` when empty.
+    content_format: Option<String>,
+    /// Only files with these extensions, compared case-insensitively; all files when empty.
+    extensions: Option<Vec<String>>,
+    /// Items per chunk. Takes precedence over `chunk_bytes`.
+    chunk_size: Option<u32>,
+    chunk_bytes: Option<u64>,
+    /// `zstd`, or empty for uncompressed chunks.
+    compression: Option<String>,
+    /// Write `index.json.zstd` instead of `index.json`.
+    #[serde(default)]
+    compress_index: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderDatasetReport {
+    job_id: String,
+    index_path: String,
+    items: u64,
+    chunk_count: usize,
+    data_format: Vec<String>,
+    /// Uncompressed chunk bytes.
+    input_bytes: u64,
+    /// Bytes written to disk.
+    output_bytes: u64,
+}
+
+/// Packs every file under `source_dir`, one item per file in path order, into a new
+/// litdata dataset in `dest_dir`, like a minimal `litdata.optimize`.
+#[tauri::command]
+pub async fn create_dataset_from_folder(
+    app: AppHandle,
+    source_dir: String,
+    dest_dir: String,
+    layout: FolderLayout,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<FolderDatasetReport> {
+    let progress = Progress::new(app, job_id, "create-dataset");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        create_sync(
+            Path::new(source_dir.trim()),
+            Path::new(dest_dir.trim()),
+            &layout,
+            &progress,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Files under `root` in path order, skipping hidden entries and `skip`.
+fn collect_files(
+    root: &Path,
+    skip: &Path,
+    extensions: &[String],
+) -> AppResult<Vec<(PathBuf, String)>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with('.') || path == skip {
+                continue;
+            }
+            let kind = entry.file_type()?;
+            if kind.is_dir() {
+                stack.push(path);
+            } else if kind.is_file() {
+                let extension = path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if extensions.is_empty() || extensions.contains(&extension) {
+                    let relative = path
+                        .strip_prefix(root)
+                        .unwrap_or(&path)
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.push((path, relative));
+                }
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
+}
+
+/// The fields of the item for one file.
+fn file_fields(
+    fields: &[FolderField],
+    content_format: &str,
+    path: &Path,
+    relative: &str,
+) -> AppResult<Vec<Vec<u8>>> {
+    let contents = if fields.contains(&FolderField::Contents) {
+        let data = fs::read(path)?;
+        check_declared_type(content_format, &data, relative)?;
+        data
+    } else {
+        Vec::new()
+    };
+    let name = relative.rsplit('/').next().unwrap_or(relative);
+    Ok(fields
+        .iter()
+        .map(|field| match field {
+            FolderField::Contents => contents.clone(),
+            FolderField::FileName => name.as_bytes().to_vec(),
+            FolderField::RelativePath => relative.as_bytes().to_vec(),
+        })
+        .collect())
+}
+
+/// litdata's serialized pytree spec for a flat dict of `keys`, so items load back as
+/// dicts rather than bare field lists.
+fn dict_spec(keys: &[String]) -> String {
+    let leaf = serde_json::json!({"type": null, "context": null, "children_spec": []});
+    let spec = serde_json::json!([1, {
+        "type": "builtins.dict",
+        "context": serde_json::to_string(keys).unwrap_or_default(),
+        "children_spec": vec![leaf; keys.len()],
+    }]);
+    spec.to_string()
+}
+
+/// Distinct dict keys for `fields`, numbering repeats.
+fn field_keys(fields: &[FolderField]) -> Vec<String> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let repeats = fields[..i].iter().filter(|f| *f == field).count();
+            match repeats {
+                0 => field.key().to_string(),
+                n => format!("{}_{n}", field.key()),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn create_sync(
+    source_dir: &Path,
+    dest_dir: &Path,
+    layout: &FolderLayout,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<FolderDatasetReport> {
+    if layout.fields.is_empty() {
+        return Err(AppError::Invalid("choose at least one field".into()));
+    }
+    if dest_dir.as_os_str().is_empty() {
+        return Err(AppError::Invalid("destination directory is empty".into()));
+    }
+    let source_dir = fs::canonicalize(source_dir)?;
+    if fs::canonicalize(dest_dir).ok().as_ref() == Some(&source_dir) {
+        return Err(AppError::Invalid(
+            "destination must differ from the source folder".into(),
+        ));
+    }
+    if dest_dir.is_dir() && resolve_index_path(dest_dir).is_ok() {
+        return Err(AppError::Invalid(format!(
+            "{} already holds a dataset",
+            dest_dir.display()
+        )));
+    }
+    let compression = match layout.compression.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(c) if c.eq_ignore_ascii_case("zstd") => Some("zstd"),
+        Some(other) => return Err(AppError::UnsupportedCompression(other.to_string())),
+    };
+    ensure_writable(dest_dir, &workspace.open_dirs())?;
+
+    let extensions: Vec<String> = layout
+        .extensions
+        .iter()
+        .flatten()
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    let dest_in_source = fs::canonicalize(dest_dir).unwrap_or_else(|_| dest_dir.to_path_buf());
+    let files = collect_files(&source_dir, &dest_in_source, &extensions)?;
+    if files.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "no matching files under {}",
+            source_dir.display()
+        )));
+    }
+    let content_format = layout
+        .content_format
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .unwrap_or("bytes");
+    let data_format: Vec<String> = layout
+        .fields
+        .iter()
+        .map(|field| match field {
+            FolderField::Contents => content_format.to_string(),
+            FolderField::FileName | FolderField::RelativePath => "str".to_string(),
+        })
+        .collect();
+
+    // Sizes come from metadata so chunks can be planned before any file is read.
+    let lens = files
+        .iter()
+        .map(|(path, relative)| {
+            let contents = fs::metadata(path)?.len();
+            let name = relative.rsplit('/').next().unwrap_or(relative).len() as u64;
+            Ok(layout
+                .fields
+                .iter()
+                .map(|field| match field {
+                    FolderField::Contents => contents,
+                    FolderField::FileName => name,
+                    FolderField::RelativePath => relative.len() as u64,
+                } + 4)
+                .sum())
+        })
+        .collect::<AppResult<Vec<u64>>>()?;
+    let groups = group_into_chunks(layout.chunk_size, layout.chunk_bytes, &lens);
+    let suffix = match compression {
+        Some(c) => format!(".{c}.bin"),
+        None => ".bin".into(),
+    };
+    let plan: Vec<_> = groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, range)| (format!("chunk-0-{i}{suffix}"), range))
+        .collect();
+
+    fs::create_dir_all(dest_dir)?;
+    let total = plan.len() as u64;
+    progress.report("packing", 0, total);
+    let written = par_map(&plan, Some((progress, "packing")), |(name, range)| {
+        let items = files[range.clone()]
+            .iter()
+            .zip(range.clone())
+            .map(|((path, relative), item)| {
+                pack_item(
+                    item as u64,
+                    &file_fields(&layout.fields, content_format, path, relative)?,
+                )
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        let raw = pack_chunk(items.iter().map(Vec::as_slice));
+        let stored = compress_chunk(&raw, compression)?;
+        fs::write(dest_dir.join(name), &stored)?;
+        Ok((raw.len() as u64, stored.len() as u64))
+    });
+    let sizes = match written.into_iter().collect::<AppResult<Vec<_>>>() {
+        Ok(sizes) => sizes,
+        Err(e) => {
+            for (name, _) in &plan {
+                let _ = fs::remove_file(dest_dir.join(name));
+            }
+            // Only succeeds when the directory was created for this run.
+            let _ = fs::remove_dir(dest_dir);
+            return Err(e);
+        }
+    };
+
+    let chunks: Vec<serde_json::Value> = plan
+        .iter()
+        .zip(&sizes)
+        .map(|((name, range), (raw, _))| {
+            serde_json::json!({
+                "chunk_bytes": raw,
+                "chunk_size": range.len(),
+                "dim": null,
+                "filename": name,
+            })
+        })
+        .collect();
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default();
+    let index = serde_json::json!({
+        "chunks": chunks,
+        "config": {
+            "chunk_bytes": if layout.chunk_size.is_some() { None } else { layout.chunk_bytes },
+            "chunk_size": layout.chunk_size,
+            "compression": compression,
+            "data_format": data_format,
+            "data_spec": dict_spec(&field_keys(&layout.fields)),
+            "encryption": null,
+            "item_loader": "PyTreeLoader",
+        },
+        "updated_at": updated_at.to_string(),
+    });
+    let index_path = write_new_index(dest_dir, &index, layout.compress_index)?;
+    progress.report("done", total, total);
+
+    Ok(FolderDatasetReport {
+        job_id: progress.job_id().to_string(),
+        index_path: index_path.display().to_string(),
+        items: files.len() as u64,
+        chunk_count: plan.len(),
+        data_format,
+        input_bytes: sizes.iter().map(|(raw, _)| raw).sum(),
+        output_bytes: sizes.iter().map(|(_, stored)| stored).sum(),
+    })
+}
@@ -0,0 +1,17 @@
+use crate::litdata::{parse_index, AppError, AppResult};
+use litdata_core::health::health_of;
+use std::path::Path;
+use tauri::async_runtime::spawn_blocking;
+
+pub use litdata_core::health::HealthReport;
+
+#[tauri::command]
+pub async fn dataset_health(index_path: String) -> AppResult<HealthReport> {
+    spawn_blocking(move || dataset_health_sync(Path::new(&index_path)))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn dataset_health_sync(index_path: &Path) -> AppResult<HealthReport> {
+    health_of(&parse_index(index_path)?)
+}
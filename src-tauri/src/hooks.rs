@@ -0,0 +1,164 @@
+//! User-configured commands run after `open_leaf` or `export_field` writes a field to
+//! disk, e.g. to pipe it into a custom visualizer. Rules live in settings.
+
+use crate::litdata::{AppError, AppResult};
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, process::Command};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    Open,
+    Export,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRule {
+    /// Shown in results and logs; defaults to the program.
+    #[serde(default)]
+    name: Option<String>,
+    /// Events the rule fires on; empty means every event.
+    #[serde(default)]
+    events: Vec<HookEvent>,
+    /// File extensions (without the dot) the rule applies to; empty means all.
+    #[serde(default)]
+    extensions: Vec<String>,
+    program: String,
+    /// Arguments, with `{path}`, `{ext}`, `{index}`, `{chunk}`, `{item}` and `{field}`
+    /// replaced by the written file and the field it came from.
+    #[serde(default)]
+    args: Vec<String>,
+    /// Extra environment variables, with the same placeholders.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+}
+
+/// The field a hook runs for.
+pub(crate) struct HookContext<'a> {
+    pub(crate) event: HookEvent,
+    pub(crate) path: &'a Path,
+    pub(crate) ext: &'a str,
+    pub(crate) index_path: &'a Path,
+    pub(crate) chunk_filename: &'a str,
+    pub(crate) item_index: u32,
+    pub(crate) field_index: usize,
+}
+
+impl HookContext<'_> {
+    fn expand(&self, template: &str) -> String {
+        template
+            .replace("{path}", &self.path.display().to_string())
+            .replace("{ext}", self.ext)
+            .replace("{index}", &self.index_path.display().to_string())
+            .replace("{chunk}", self.chunk_filename)
+            .replace("{item}", &self.item_index.to_string())
+            .replace("{field}", &self.field_index.to_string())
+    }
+
+    /// Variables every hook gets, so scripts need not parse arguments.
+    fn env(&self) -> [(&'static str, String); 7] {
+        [
+            (
+                "LITDATA_EVENT",
+                match self.event {
+                    HookEvent::Open => "open".into(),
+                    HookEvent::Export => "export".into(),
+                },
+            ),
+            ("LITDATA_FILE", self.path.display().to_string()),
+            ("LITDATA_EXT", self.ext.to_string()),
+            ("LITDATA_INDEX", self.index_path.display().to_string()),
+            ("LITDATA_CHUNK", self.chunk_filename.to_string()),
+            ("LITDATA_ITEM", self.item_index.to_string()),
+            ("LITDATA_FIELD", self.field_index.to_string()),
+        ]
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutcome {
+    name: String,
+    /// Why the hook could not be started; exit statuses are only logged.
+    error: Option<String>,
+}
+
+fn ext_key(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+impl HookRule {
+    fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.program.clone())
+    }
+
+    fn applies(&self, ctx: &HookContext) -> bool {
+        (self.events.is_empty() || self.events.contains(&ctx.event))
+            && (self.extensions.is_empty()
+                || self
+                    .extensions
+                    .iter()
+                    .any(|e| ext_key(e) == ext_key(ctx.ext)))
+    }
+
+    fn spawn(&self, ctx: &HookContext) -> AppResult<()> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(self.args.iter().map(|a| ctx.expand(a)))
+            .envs(ctx.env())
+            .envs(self.env.iter().map(|(k, v)| (k, ctx.expand(v))));
+        let mut child = command
+            .spawn()
+            .map_err(|e| AppError::Open(format!("{}: {e}", self.program)))?;
+        let name = self.display_name();
+        // Reap the hook when it exits, noting failures for the log viewer.
+        std::thread::spawn(move || match child.wait() {
+            Ok(status) if !status.success() => {
+                tracing::warn!(hook = %name, "hook exited with {status}")
+            }
+            Err(e) => tracing::warn!(hook = %name, "waiting for hook failed: {e}"),
+            Ok(_) => {}
+        });
+        Ok(())
+    }
+}
+
+pub(crate) fn validate_rules(rules: &[HookRule]) -> AppResult<()> {
+    for rule in rules {
+        if rule.program.trim().is_empty() {
+            return Err(AppError::Invalid(format!(
+                "hook {:?} has no program",
+                rule.name.as_deref().unwrap_or_default()
+            )));
+        }
+        if rule.extensions.iter().any(|e| ext_key(e).is_empty()) {
+            return Err(AppError::Invalid(format!(
+                "hook {} lists an empty extension",
+                rule.display_name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Starts every configured hook matching `ctx`. A hook that cannot start is reported,
+/// not raised: the file has already been written.
+pub(crate) fn run(ctx: &HookContext) -> Vec<HookOutcome> {
+    settings::current()
+        .hooks
+        .iter()
+        .filter(|rule| rule.applies(ctx))
+        .map(|rule| {
+            let error = rule.spawn(ctx).err().map(|e| {
+                tracing::warn!(hook = %rule.display_name(), "hook failed to start: {e}");
+                e.to_string()
+            });
+            HookOutcome {
+                name: rule.display_name(),
+                error,
+            }
+        })
+        .collect()
+}
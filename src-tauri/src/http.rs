@@ -0,0 +1,104 @@
+use crate::litdata::{AppError, AppResult};
+use crate::rewrite::partial_path;
+use crate::settings;
+use serde_json::Value;
+use std::{fs, path::Path, time::Duration};
+use tauri::Url;
+
+/// Blocking HTTP with the configured IO timeout for connecting and awaiting responses;
+/// bodies may take as long as they need.
+pub(crate) struct Http {
+    agent: ureq::Agent,
+}
+
+impl Http {
+    pub(crate) fn new() -> Self {
+        let timeout = Some(Duration::from_secs(settings::current().io_timeout_secs));
+        let agent = ureq::Agent::config_builder()
+            .timeout_connect(timeout)
+            .timeout_recv_response(timeout)
+            .build()
+            .new_agent();
+        Http { agent }
+    }
+
+    pub(crate) fn get_json(&self, url: &Url, auth: Option<&str>) -> AppResult<Value> {
+        let mut request = self.agent.get(url.as_str());
+        if let Some(auth) = auth {
+            request = request.header("Authorization", auth);
+        }
+        let mut response = request.call().map_err(|e| http_error(url, e))?;
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| http_error(url, e))?;
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::Invalid(format!("{url} returned invalid JSON: {e}")))
+    }
+
+    pub(crate) fn post_json(
+        &self,
+        url: &Url,
+        auth: Option<&str>,
+        body: &Value,
+    ) -> AppResult<Value> {
+        let mut request = self
+            .agent
+            .post(url.as_str())
+            .header("Content-Type", "application/json");
+        if let Some(auth) = auth {
+            request = request.header("Authorization", auth);
+        }
+        let mut response = request
+            .send(body.to_string())
+            .map_err(|e| http_error(url, e))?;
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| http_error(url, e))?;
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::Invalid(format!("{url} returned invalid JSON: {e}")))
+    }
+
+    /// Streams `url` into `dest` through a partial file; returns the bytes written.
+    pub(crate) fn download(&self, url: &Url, auth: Option<&str>, dest: &Path) -> AppResult<u64> {
+        let mut request = self.agent.get(url.as_str());
+        if let Some(auth) = auth {
+            request = request.header("Authorization", auth);
+        }
+        let response = request.call().map_err(|e| http_error(url, e))?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let partial = partial_path(dest);
+        let written = std::io::copy(
+            &mut response.into_body().into_reader(),
+            &mut fs::File::create(&partial)?,
+        );
+        match written {
+            Ok(written) => {
+                fs::rename(&partial, dest)?;
+                Ok(written)
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&partial);
+                Err(AppError::Io(format!("{url}: {e}")))
+            }
+        }
+    }
+}
+
+fn http_error(url: &Url, err: ureq::Error) -> AppError {
+    match err {
+        ureq::Error::StatusCode(401 | 403) => {
+            AppError::Invalid(format!("{url} rejected the credentials"))
+        }
+        ureq::Error::StatusCode(404) => AppError::Missing(url.to_string()),
+        ureq::Error::Timeout(_) => AppError::Timeout(url.to_string()),
+        other => AppError::Io(format!("{url}: {other}")),
+    }
+}
+
+pub(crate) fn parse_url(raw: &str) -> AppResult<Url> {
+    Url::parse(raw).map_err(|e| AppError::Invalid(format!("{raw}: {e}")))
+}
@@ -0,0 +1,338 @@
+use crate::field_sizes::{percentile, sample_plan};
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_SAMPLE_ITEMS: u32 = 2_000;
+/// Most common resolutions listed per field; the rest only count toward the totals.
+const TOP_RESOLUTIONS: usize = 50;
+const IMAGE_FORMATS: [&str; 8] = ["pil", "jpeg", "jpg", "png", "webp", "gif", "bmp", "image"];
+/// Upper bounds of the aspect-ratio (width / height) buckets, with their labels.
+const ASPECT_BUCKETS: [(f64, &str); 8] = [
+    (0.5, "taller than 1:2"),
+    (0.75, "1:2 to 3:4"),
+    (0.95, "3:4 to 1:1"),
+    (1.05, "square"),
+    (1.34, "1:1 to 4:3"),
+    (1.78, "4:3 to 16:9"),
+    (2.0, "16:9 to 2:1"),
+    (f64::INFINITY, "wider than 2:1"),
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionCount {
+    width: u32,
+    height: u32,
+    count: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AspectBucket {
+    label: &'static str,
+    count: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionStats {
+    min: u32,
+    p10: u32,
+    p50: u32,
+    p90: u32,
+    max: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFieldStats {
+    field_index: usize,
+    format: Option<String>,
+    images: u64,
+    /// Sampled payloads in this field that were not a recognized image.
+    unrecognized_items: u64,
+    /// Encodings seen, e.g. `jpeg`, `png`.
+    encodings: BTreeMap<&'static str, u64>,
+    distinct_resolutions: usize,
+    /// Most common first, at most 50.
+    resolutions: Vec<ResolutionCount>,
+    width: DimensionStats,
+    height: DimensionStats,
+    aspect_ratios: Vec<AspectBucket>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageResolutionReport {
+    job_id: String,
+    total_items: u64,
+    sampled_items: u64,
+    unreadable_chunks: Vec<String>,
+    /// Fields where at least one sampled item is an image.
+    fields: Vec<ImageFieldStats>,
+}
+
+/// Width × height distribution and aspect-ratio buckets of image fields over a sample of
+/// `sample_items` items (default 2,000), from image headers only.
+#[tauri::command]
+pub async fn image_resolution_stats(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<ImageResolutionReport> {
+    let progress = Progress::new(app, job_id, "image-stats");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        image_stats_of(
+            &parsed,
+            field_index,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn be16(data: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn le16(data: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn le24(data: &[u8], at: usize) -> Option<u32> {
+    let b = data.get(at..at + 3)?;
+    Some(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+}
+
+/// Frame size from the first start-of-frame marker.
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        while *data.get(pos)? != 0xFF {
+            pos += 1;
+        }
+        while *data.get(pos)? == 0xFF {
+            pos += 1;
+        }
+        let marker = *data.get(pos)?;
+        pos += 1;
+        match marker {
+            // Standalone markers carry no length.
+            0x01 | 0xD0..=0xD7 => continue,
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return Some((be16(data, pos + 5)?, be16(data, pos + 3)?));
+            }
+            _ => pos += be16(data, pos)? as usize,
+        }
+    }
+}
+
+/// litdata's `pil` serializer: `u32` width, height and mode length, the mode, then
+/// raw pixels.
+fn pil_raw_size(data: &[u8]) -> Option<(u32, u32)> {
+    let word = |at: usize| Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let (w, h, mode_len) = (word(0)?, word(4)?, word(8)? as usize);
+    let mode = data.get(12..12 + mode_len)?;
+    (mode_len > 0 && mode.iter().all(|b| b.is_ascii_alphanumeric() || *b == b';')).then_some((w, h))
+}
+
+/// Encoding and `(width, height)` from the leading bytes of an image; `format` is the
+/// field's `data_format` entry.
+fn sniff_dimensions(data: &[u8], format: &str) -> Option<(&'static str, u32, u32)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.get(12..16) == Some(b"IHDR") {
+        let w = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+        let h = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+        return Some(("png", w, h));
+    }
+    if data.starts_with(&[0xFF, 0xD8]) {
+        let (w, h) = jpeg_size(data)?;
+        return Some(("jpeg", w, h));
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some(("gif", le16(data, 6)?, le16(data, 8)?));
+    }
+    if data.starts_with(b"BM") {
+        let w = i32::from_le_bytes(data.get(18..22)?.try_into().ok()?);
+        let h = i32::from_le_bytes(data.get(22..26)?.try_into().ok()?);
+        // Negative heights mark top-down bitmaps.
+        return Some(("bmp", w.unsigned_abs(), h.unsigned_abs()));
+    }
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        let (w, h) = match data.get(12..16)? {
+            b"VP8 " => (le16(data, 26)? & 0x3FFF, le16(data, 28)? & 0x3FFF),
+            b"VP8L" => {
+                let b = data.get(21..25)?;
+                let w = 1 + (b[0] as u32 | (b[1] as u32 & 0x3F) << 8);
+                let h = 1 + (b[1] as u32 >> 6 | (b[2] as u32) << 2 | (b[3] as u32 & 0x0F) << 10);
+                (w, h)
+            }
+            b"VP8X" => (1 + le24(data, 24)?, 1 + le24(data, 27)?),
+            _ => return None,
+        };
+        return Some(("webp", w, h));
+    }
+    if format.eq_ignore_ascii_case("pil") {
+        let (w, h) = pil_raw_size(data)?;
+        return Some(("raw", w, h));
+    }
+    None
+}
+
+/// Fields worth sniffing: declared image formats, plus raw byte fields.
+pub(crate) fn candidate_fields(
+    formats: &[String],
+    field_index: Option<usize>,
+) -> AppResult<Vec<usize>> {
+    if let Some(field) = field_index {
+        if field >= formats.len() {
+            return Err(AppError::Invalid("field index out of range".into()));
+        }
+        return Ok(vec![field]);
+    }
+    Ok(formats
+        .iter()
+        .enumerate()
+        .filter(|(_, fmt)| {
+            let lower = fmt.to_lowercase();
+            lower == "bytes" || lower == "bin" || IMAGE_FORMATS.iter().any(|f| lower.contains(f))
+        })
+        .map(|(field, _)| field)
+        .collect())
+}
+
+fn dimension_stats(mut values: Vec<u32>) -> DimensionStats {
+    values.sort_unstable();
+    DimensionStats {
+        min: values[0],
+        p10: percentile(&values, 10),
+        p50: percentile(&values, 50),
+        p90: percentile(&values, 90),
+        max: values[values.len() - 1],
+    }
+}
+
+#[derive(Default)]
+struct FieldTally {
+    unrecognized: u64,
+    encodings: BTreeMap<&'static str, u64>,
+    resolutions: BTreeMap<(u32, u32), u64>,
+}
+
+pub(crate) fn image_stats_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<ImageResolutionReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = candidate_fields(&formats, field_index)?;
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("sniffing", 0, plan.len() as u64);
+    let sniffed = par_map(&plan, Some((progress, "sniffing")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut found = Vec::with_capacity(items.len() * fields.len());
+        for item in items {
+            for &field in &fields {
+                let (data, _) =
+                    read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                found.push((field, sniff_dimensions(&data, &formats[field])));
+            }
+        }
+        AppResult::Ok(found)
+    });
+
+    let mut tallies: BTreeMap<usize, FieldTally> = BTreeMap::new();
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(sniffed) {
+        let Ok(found) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (field, dims) in found {
+            let tally = tallies.entry(field).or_default();
+            match dims {
+                Some((encoding, w, h)) => {
+                    *tally.encodings.entry(encoding).or_default() += 1;
+                    *tally.resolutions.entry((w, h)).or_default() += 1;
+                }
+                None => tally.unrecognized += 1,
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let fields = tallies
+        .into_iter()
+        .filter(|(_, tally)| !tally.resolutions.is_empty())
+        .map(|(field_index, tally)| {
+            let mut widths = Vec::new();
+            let mut heights = Vec::new();
+            let mut aspect = [0u64; ASPECT_BUCKETS.len()];
+            for (&(w, h), &count) in &tally.resolutions {
+                widths.extend(std::iter::repeat_n(w, count as usize));
+                heights.extend(std::iter::repeat_n(h, count as usize));
+                let ratio = if h == 0 {
+                    f64::INFINITY
+                } else {
+                    w as f64 / h as f64
+                };
+                let bucket = ASPECT_BUCKETS
+                    .iter()
+                    .position(|(upper, _)| ratio <= *upper)
+                    .unwrap_or(ASPECT_BUCKETS.len() - 1);
+                aspect[bucket] += count;
+            }
+            let mut resolutions: Vec<ResolutionCount> = tally
+                .resolutions
+                .iter()
+                .map(|(&(width, height), &count)| ResolutionCount {
+                    width,
+                    height,
+                    count,
+                })
+                .collect();
+            resolutions.sort_by_key(|r| std::cmp::Reverse(r.count));
+            let distinct_resolutions = resolutions.len();
+            resolutions.truncate(TOP_RESOLUTIONS);
+            ImageFieldStats {
+                field_index,
+                format: formats.get(field_index).cloned(),
+                images: widths.len() as u64,
+                unrecognized_items: tally.unrecognized,
+                encodings: tally.encodings,
+                distinct_resolutions,
+                resolutions,
+                width: dimension_stats(widths),
+                height: dimension_stats(heights),
+                aspect_ratios: ASPECT_BUCKETS
+                    .iter()
+                    .zip(aspect)
+                    .map(|((_, label), count)| AspectBucket { label, count })
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(ImageResolutionReport {
+        job_id: progress.job_id().to_string(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        unreadable_chunks,
+        fields,
+    })
+}
@@ -0,0 +1,127 @@
+use crate::aio;
+use crate::litdata::{parse_index, parse_index_async, AppResult, ParsedIndex};
+use litdata_core::archive;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+/// Parsed indexes kept between commands; older entries are dropped past this count.
+const MAX_CACHED_INDEXES: usize = 8;
+
+/// (mtime, len) of the file a cached parse came from.
+pub(crate) type Stamp = (Option<SystemTime>, u64);
+
+struct CachedIndex {
+    parsed: Arc<ParsedIndex>,
+    stamp: Stamp,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct IndexCacheState {
+    entries: HashMap<PathBuf, CachedIndex>,
+    clock: u64,
+}
+
+/// Managed-state cache of `parse_index` results, keyed by canonical path and invalidated
+/// when the resolved index file's mtime or size changes.
+#[derive(Clone, Default)]
+pub struct IndexCache {
+    inner: Arc<Mutex<IndexCacheState>>,
+}
+
+pub(crate) fn stamp(path: &Path) -> Stamp {
+    // An index inside an archive changes with the archive.
+    fs::metadata(archive::backing_file(path))
+        .map(|m| (m.modified().ok(), m.len()))
+        .unwrap_or((None, 0))
+}
+
+impl IndexCache {
+    pub(crate) fn get(&self, index_path: &Path) -> AppResult<Arc<ParsedIndex>> {
+        let key = fs::canonicalize(index_path).unwrap_or_else(|_| index_path.to_path_buf());
+        if let Some((parsed, cached_stamp)) = self.cached(&key) {
+            if cached_stamp == stamp(&parsed.source) {
+                self.touch(&key);
+                return Ok(parsed);
+            }
+        }
+        let parsed = Arc::new(parse_index(index_path)?);
+        let source_stamp = stamp(&parsed.source);
+        Ok(self.insert(key, parsed, source_stamp))
+    }
+
+    /// `get` for async commands: the staleness check and the index read go through tokio.
+    pub(crate) async fn get_async(&self, index_path: &Path) -> AppResult<Arc<ParsedIndex>> {
+        let key = aio::canonicalize(index_path)
+            .await
+            .unwrap_or_else(|_| index_path.to_path_buf());
+        if let Some((parsed, cached_stamp)) = self.cached(&key) {
+            if cached_stamp == aio::stamp(&parsed.source).await {
+                self.touch(&key);
+                return Ok(parsed);
+            }
+        }
+        let parsed = Arc::new(parse_index_async(index_path).await?);
+        let source_stamp = aio::stamp(&parsed.source).await;
+        Ok(self.insert(key, parsed, source_stamp))
+    }
+
+    /// Drops every parse read from `index_file`, after the viewer rewrote it in place
+    /// within the stamp's mtime resolution.
+    pub(crate) fn forget(&self, index_file: &Path) {
+        if let Ok(mut guard) = self.inner.lock() {
+            guard
+                .entries
+                .retain(|_, entry| entry.parsed.source != index_file);
+        }
+    }
+
+    fn cached(&self, key: &Path) -> Option<(Arc<ParsedIndex>, Stamp)> {
+        let guard = self.inner.lock().ok()?;
+        guard
+            .entries
+            .get(key)
+            .map(|entry| (entry.parsed.clone(), entry.stamp))
+    }
+
+    fn touch(&self, key: &Path) {
+        if let Ok(mut guard) = self.inner.lock() {
+            guard.clock += 1;
+            let now = guard.clock;
+            if let Some(entry) = guard.entries.get_mut(key) {
+                entry.last_used = now;
+            }
+        }
+    }
+
+    fn insert(&self, key: PathBuf, parsed: Arc<ParsedIndex>, stamp: Stamp) -> Arc<ParsedIndex> {
+        if let Ok(mut guard) = self.inner.lock() {
+            if guard.entries.len() >= MAX_CACHED_INDEXES && !guard.entries.contains_key(&key) {
+                if let Some(oldest) = guard
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone())
+                {
+                    guard.entries.remove(&oldest);
+                }
+            }
+            guard.clock += 1;
+            let last_used = guard.clock;
+            guard.entries.insert(
+                key,
+                CachedIndex {
+                    parsed: parsed.clone(),
+                    stamp,
+                    last_used,
+                },
+            );
+        }
+        parsed
+    }
+}
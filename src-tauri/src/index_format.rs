@@ -0,0 +1,117 @@
+use crate::litdata::{parse_index, read_index_file, AppError, AppResult};
+use crate::rewrite::{ensure_plain_index, is_compressed_index, partial_path, Backup};
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::async_runtime::spawn_blocking;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexConversion {
+    /// The index to open from now on.
+    index_path: String,
+    previous_path: String,
+    compressed: bool,
+    /// `false` when the index was already in the requested format.
+    converted: bool,
+    bytes_before: u64,
+    bytes_after: u64,
+    /// Where the previous index file was moved.
+    backup_dir: Option<String>,
+}
+
+/// Rewrites a dataset's index as `index.json.zstd` when `compressed`, or back to plain
+/// `index.json`, keeping its content unchanged. The previous file is moved to a backup
+/// directory so only one index remains for discovery to pick up.
+#[tauri::command]
+pub async fn convert_index_format(
+    index_path: String,
+    compressed: bool,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<IndexConversion> {
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || convert_sync(Path::new(&index_path), compressed, &workspace))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// `source` renamed for the requested format: `.zstd` appended, or a `.zstd`/`.zst`
+/// suffix dropped.
+fn converted_path(source: &Path, compressed: bool) -> PathBuf {
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = if compressed {
+        format!("{name}.zstd")
+    } else {
+        let lower = name.to_lowercase();
+        [".zstd", ".zst"]
+            .iter()
+            .find(|suffix| lower.ends_with(*suffix))
+            .map_or(name.clone(), |suffix| {
+                name[..name.len() - suffix.len()].to_string()
+            })
+    };
+    source.with_file_name(name)
+}
+
+pub(crate) fn convert_sync(
+    index_path: &Path,
+    compressed: bool,
+    workspace: &Workspace,
+) -> AppResult<IndexConversion> {
+    let parsed = parse_index(index_path)?;
+    ensure_plain_index(&parsed)?;
+    let source = &parsed.source;
+    let bytes_before = fs::metadata(source)?.len();
+    if is_compressed_index(source) == compressed {
+        return Ok(IndexConversion {
+            index_path: source.display().to_string(),
+            previous_path: source.display().to_string(),
+            compressed,
+            converted: false,
+            bytes_before,
+            bytes_after: bytes_before,
+            backup_dir: None,
+        });
+    }
+    let target = converted_path(source, compressed);
+    if target.exists() {
+        return Err(AppError::Invalid(format!(
+            "{} already exists; remove it or convert that file instead",
+            target.display()
+        )));
+    }
+    ensure_writable(&target, &workspace.dataset_dirs(&parsed))?;
+
+    let text = read_index_file(source)?;
+    let bytes = if compressed {
+        zstd::stream::encode_all(text.as_bytes(), 0)?
+    } else {
+        text.into_bytes()
+    };
+    let partial = partial_path(&target);
+    fs::write(&partial, &bytes)?;
+    fs::rename(&partial, &target)?;
+    // The new file must load back before the old one is moved aside.
+    if let Err(e) = parse_index(&target) {
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+    let backup = Backup::create(&parsed.root_dir)?;
+    backup.stash(source)?;
+    Ok(IndexConversion {
+        index_path: target.display().to_string(),
+        previous_path: source.display().to_string(),
+        compressed,
+        converted: true,
+        bytes_before,
+        bytes_after: bytes.len() as u64,
+        backup_dir: Some(backup.dir().display().to_string()),
+    })
+}
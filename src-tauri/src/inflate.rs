@@ -0,0 +1,81 @@
+use crate::progress::Progress;
+use rayon::prelude::*;
+use std::{
+    io,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Compressed chunks below this size decode on one thread; splitting isn't worth it.
+const PARALLEL_MIN_BYTES: usize = 16 * 1024 * 1024;
+/// Refuse to trust frame headers that together claim more than this much output.
+const MAX_DECLARED_BYTES: u64 = 64 * 1024 * 1024 * 1024;
+
+/// Skippable frames (e.g. a seek table) carry metadata only.
+fn is_skippable(frame: &[u8]) -> bool {
+    frame
+        .get(0..4)
+        .map(|m| u32::from_le_bytes([m[0], m[1], m[2], m[3]]) & 0xFFFF_FFF0 == 0x184D_2A50)
+        .unwrap_or(false)
+}
+
+/// Data frames in `buf`, each with its decompressed size when the frame header records
+/// one; `None` when the stream cannot be walked frame by frame.
+pub(crate) fn data_frames(buf: &[u8]) -> Option<Vec<(&[u8], Option<u64>)>> {
+    let mut frames = Vec::new();
+    let mut pos = 0usize;
+    while pos < buf.len() {
+        let rest = &buf[pos..];
+        let len = zstd::zstd_safe::find_frame_compressed_size(rest).ok()?;
+        if len == 0 {
+            return None;
+        }
+        let frame = &rest[..len];
+        if !is_skippable(frame) {
+            frames.push((frame, zstd::zstd_safe::get_frame_content_size(frame).ok()?));
+        }
+        pos += len;
+    }
+    Some(frames)
+}
+
+/// Data frames in `buf` with their declared decompressed sizes, or `None` when the
+/// stream cannot be split safely (a single frame, a damaged frame, or unknown sizes).
+fn frame_plan(buf: &[u8]) -> Option<Vec<(&[u8], usize)>> {
+    let mut declared = 0u64;
+    let frames = data_frames(buf)?
+        .into_iter()
+        .map(|(frame, size)| {
+            let size = size?;
+            declared = declared.checked_add(size)?;
+            Some((frame, usize::try_from(size).ok()?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (frames.len() > 1 && declared <= MAX_DECLARED_BYTES).then_some(frames)
+}
+
+/// Decodes independent zstd frames concurrently. Returns `None` when the input should go
+/// through the sequential streaming decoder instead.
+pub(crate) fn decompress_frames(
+    compressed: &[u8],
+    progress: Option<&Progress>,
+) -> Option<io::Result<Vec<u8>>> {
+    if compressed.len() < PARALLEL_MIN_BYTES {
+        return None;
+    }
+    let frames = frame_plan(compressed)?;
+    let total = compressed.len() as u64;
+    let consumed = AtomicU64::new(0);
+    let parts = frames
+        .par_iter()
+        .map(|(frame, size)| {
+            let part = zstd::bulk::decompress(frame, *size);
+            let done =
+                consumed.fetch_add(frame.len() as u64, Ordering::Relaxed) + frame.len() as u64;
+            if let Some(progress) = progress {
+                progress.report("decompressing", done, total);
+            }
+            part
+        })
+        .collect::<io::Result<Vec<_>>>();
+    Some(parts.map(|parts| parts.concat()))
+}
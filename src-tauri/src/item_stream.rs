@@ -0,0 +1,96 @@
+use crate::litdata::{
+    item_meta_range, load_chunk_access_async, parse_offsets, AppError, AppResult, ChunkCache,
+    ErrorContext, ItemMeta, ResultExt,
+};
+use crate::prefetch;
+use crate::progress::Progress;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::corruption::explain_corruption;
+use serde::Serialize;
+use tauri::{async_runtime::spawn_blocking, AppHandle, Emitter};
+
+/// Event carrying one page of `ItemMeta`; listeners filter by `jobId`.
+pub const ITEM_PAGE_EVENT: &str = "chunk-items-page";
+const DEFAULT_PAGE_SIZE: u32 = 2000;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPage {
+    job_id: String,
+    chunk_filename: String,
+    start_item: u32,
+    total_items: u32,
+    items: Vec<ItemMeta>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemStreamSummary {
+    job_id: String,
+    total_items: u32,
+    pages: u32,
+}
+
+/// Like `list_chunk_items`, but delivers the items as `chunk-items-page` events while the
+/// chunk is still being parsed, so large chunks can render before the scan finishes.
+/// Pages go only to the calling window.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_chunk_items(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    handle: DatasetHandle,
+    chunk_filename: String,
+    page_size: Option<u32>,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<ItemStreamSummary> {
+    let progress = Progress::new(app.clone(), job_id, "items");
+    let parsed = workspace.get(handle)?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, Some(&progress))
+        .await
+        .within(|| context.clone())?;
+    prefetch::prefetch_next(&parsed, &chunk_filename, &cache);
+    let window = window.label().to_string();
+    let sidecar = workspace.sidecar(handle)?;
+    spawn_blocking(move || {
+        let explain = |e: AppError| explain_corruption(e, &access, &chunk_filename);
+        let (num_items, offsets) = parse_offsets(&access).map_err(explain)?;
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+        let mut pages = 0u32;
+        let mut start = 0u32;
+        while start < num_items || pages == 0 {
+            let end = start.saturating_add(page_size).min(num_items);
+            let mut items = item_meta_range(&access, &offsets, parsed.format_len(), start..end)
+                .map_err(explain)?;
+            if let Some(sidecar) = &sidecar {
+                sidecar.join(&parsed, &chunk_filename, &mut items);
+            }
+            // Delivery is best-effort, like progress; the summary still reports the totals.
+            let _ = app.emit_to(
+                window.as_str(),
+                ITEM_PAGE_EVENT,
+                ItemPage {
+                    job_id: progress.job_id().to_string(),
+                    chunk_filename: chunk_filename.clone(),
+                    start_item: start,
+                    total_items: num_items,
+                    items,
+                },
+            );
+            pages += 1;
+            progress.report("listing", end as u64, num_items as u64);
+            start = end;
+        }
+        Ok(ItemStreamSummary {
+            job_id: progress.job_id().to_string(),
+            total_items: num_items,
+            pages,
+        })
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
+}
@@ -0,0 +1,202 @@
+use crate::field_sizes::sample_plan;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+use whatlang::Lang;
+
+const DEFAULT_SAMPLE_ITEMS: u32 = 2_000;
+/// Detection looks at this many leading chars; more rarely changes the answer.
+const MAX_DETECT_CHARS: usize = 2_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageShare {
+    /// ISO 639-3, e.g. `eng`.
+    code: &'static str,
+    name: &'static str,
+    count: u64,
+    /// Share of the detected items.
+    fraction: f64,
+    mean_confidence: f64,
+    /// Detections whatlang itself calls unreliable, typically short or mixed texts.
+    unreliable: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldLanguages {
+    field_index: usize,
+    format: Option<String>,
+    detected_items: u64,
+    /// Empty, not UTF-8, or without enough letters to tell.
+    undetected_items: u64,
+    /// Most common first.
+    languages: Vec<LanguageShare>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageReport {
+    job_id: String,
+    total_items: u64,
+    sampled_items: u64,
+    fields: Vec<FieldLanguages>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Language distribution of each text field over up to `sample_items` items spread
+/// across the dataset, or of `field_index` alone.
+#[tauri::command]
+pub async fn detect_languages(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<LanguageReport> {
+    let progress = Progress::new(app, job_id, "languages");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        languages_of(
+            &parsed,
+            field_index,
+            sample_items.unwrap_or(DEFAULT_SAMPLE_ITEMS),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Text fields; a requested field is read as text whatever its format.
+fn text_fields(formats: &[String], field_index: Option<usize>) -> AppResult<Vec<usize>> {
+    if let Some(field) = field_index {
+        if field >= formats.len() {
+            return Err(AppError::Invalid("field index out of range".into()));
+        }
+        return Ok(vec![field]);
+    }
+    let fields: Vec<usize> = formats
+        .iter()
+        .enumerate()
+        .filter(|(_, fmt)| matches!(fmt.to_lowercase().as_str(), "str" | "string" | "text"))
+        .map(|(field, _)| field)
+        .collect();
+    if fields.is_empty() {
+        return Err(AppError::Invalid(
+            "no text field; pick one explicitly".into(),
+        ));
+    }
+    Ok(fields)
+}
+
+fn detect(data: &[u8]) -> Option<whatlang::Info> {
+    let text = std::str::from_utf8(data).ok()?;
+    let end = text
+        .char_indices()
+        .nth(MAX_DETECT_CHARS)
+        .map_or(text.len(), |(at, _)| at);
+    whatlang::detect(&text[..end])
+}
+
+/// One language's running totals within a field.
+#[derive(Default)]
+struct Tally {
+    count: u64,
+    confidence: f64,
+    unreliable: u64,
+}
+
+pub(crate) fn languages_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<LanguageReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = text_fields(&formats, field_index)?;
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("detecting", 0, plan.len() as u64);
+    // Per item and field, in `fields` order.
+    let detected = par_map(&plan, Some((progress, "detecting")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut found = Vec::with_capacity(items.len() * fields.len());
+        for item in items {
+            for &field in &fields {
+                let (data, _) =
+                    read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                found.push(detect(&data));
+            }
+        }
+        AppResult::Ok(found)
+    });
+
+    let mut tallies: Vec<HashMap<Lang, Tally>> = fields.iter().map(|_| HashMap::new()).collect();
+    let mut undetected = vec![0u64; fields.len()];
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(detected) {
+        let Ok(found) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (slot, info) in found.into_iter().enumerate() {
+            let slot = slot % fields.len();
+            let Some(info) = info else {
+                undetected[slot] += 1;
+                continue;
+            };
+            let tally = tallies[slot].entry(info.lang()).or_default();
+            tally.count += 1;
+            tally.confidence += info.confidence();
+            if !info.is_reliable() {
+                tally.unreliable += 1;
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let fields = fields
+        .iter()
+        .zip(tallies)
+        .zip(undetected)
+        .map(|((&field, tally), undetected_items)| {
+            let detected_items: u64 = tally.values().map(|t| t.count).sum();
+            let mut languages: Vec<LanguageShare> = tally
+                .into_iter()
+                .map(|(lang, t)| LanguageShare {
+                    code: lang.code(),
+                    name: lang.eng_name(),
+                    count: t.count,
+                    fraction: t.count as f64 / detected_items as f64,
+                    mean_confidence: t.confidence / t.count as f64,
+                    unreliable: t.unreliable,
+                })
+                .collect();
+            languages.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(b.code)));
+            FieldLanguages {
+                field_index: field,
+                format: formats.get(field).cloned(),
+                detected_items,
+                undetected_items,
+                languages,
+            }
+        })
+        .collect();
+    Ok(LanguageReport {
+        job_id: progress.job_id().to_string(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        fields,
+        unreadable_chunks,
+    })
+}
@@ -0,0 +1,288 @@
+//! Tauri commands behind the LitData Viewer app, built on the `litdata-core` reader.
+
+mod aio;
+mod annotations;
+mod append;
+mod artifacts;
+mod audio;
+mod audio_stats;
+mod balance;
+mod benchmark;
+mod bookmarks;
+mod bundle;
+mod checksum;
+mod class_balance;
+mod class_names;
+mod compression;
+mod content_types;
+mod corruption;
+mod decoders;
+mod dedup;
+mod deeplink;
+mod delete;
+mod diff;
+mod discover;
+mod disk_cache;
+mod dvc;
+mod embedding;
+mod entropy;
+mod estimate;
+mod field_sizes;
+mod from_folder;
+mod health;
+mod hooks;
+mod http;
+mod image_stats;
+mod index_cache;
+mod index_format;
+mod inflate;
+mod item_stream;
+mod language;
+mod litdata;
+mod logging;
+mod media_server;
+mod memory;
+mod merge;
+mod navigate;
+mod near_dupes;
+mod notes;
+mod offsets_cache;
+mod order;
+mod palette;
+mod playlist;
+mod prefetch;
+mod progress;
+mod quick_look;
+mod recent;
+mod recompress;
+mod redact;
+mod renumber;
+mod repair;
+mod replace;
+mod reveal;
+mod rewrite;
+mod salvage;
+mod scan;
+mod schema;
+mod security;
+mod seekable;
+mod server;
+mod settings;
+mod sidecar;
+mod size_outliers;
+mod tensor;
+mod text_stats;
+mod trace;
+mod usage;
+mod validate;
+mod windows;
+mod workspace;
+
+use annotations::export_annotations;
+use append::append_items;
+use artifacts::resolve_artifact;
+use audio_stats::audio_corpus_stats;
+use balance::chunk_balance;
+use benchmark::benchmark_dataset;
+use bookmarks::{add_bookmark, get_bookmarks, remove_bookmark};
+use bundle::export_share_bundle;
+use checksum::{generate_checksum_manifest, verify_checksum_manifest};
+use class_balance::class_balance;
+use class_names::{clear_class_names, get_class_names, import_class_names};
+use compression::compression_report;
+use content_types::content_type_census;
+use corruption::scan_chunk_corruption;
+use decoders::{decode_field, install_decoder_plugin, list_decoder_plugins, remove_decoder_plugin};
+use dedup::find_duplicate_items;
+use deeplink::take_pending_open_requests;
+use delete::delete_items;
+use diff::diff_datasets;
+use discover::scan_for_datasets;
+use dvc::resolve_dvc;
+use embedding::project_embeddings;
+use entropy::field_entropy;
+use estimate::estimate_training_time;
+use field_sizes::field_size_distribution;
+use from_folder::create_dataset_from_folder;
+use health::dataset_health;
+use image_stats::image_resolution_stats;
+use index_cache::IndexCache;
+use index_format::convert_index_format;
+use item_stream::stream_chunk_items;
+use language::detect_languages;
+use litdata::{
+    clear_cache, export_field, get_cache_stats, list_chunk_items, open_chunk_files, open_dataset,
+    open_index_shards, open_leaf, peek_field, read_field_range, set_chunk_cache_budget, ChunkCache,
+};
+use logging::get_recent_logs;
+use media_server::{list_shared_media, share_media, unshare_media};
+use memory::{get_memory_stats, set_memory_limit};
+use merge::merge_datasets;
+use navigate::resolve_relative_item;
+use near_dupes::find_near_duplicate_images;
+use notes::{get_dataset_notes, set_dataset_note};
+use offsets_cache::OffsetsCache;
+use order::locate_training_batch;
+use palette::fuzzy_find;
+use playlist::export_audio_playlist;
+use quick_look::quick_look_field;
+use recent::{get_pinned_datasets, get_recent_datasets, pin_dataset, remove_recent, unpin_dataset};
+use recompress::recompress_dataset;
+use redact::redact_dataset;
+use renumber::renumber_chunks;
+use repair::repair_index;
+use replace::replace_field;
+use reveal::reveal_in_file_manager;
+use salvage::{peek_salvaged_field, salvage_chunk};
+use schema::infer_schema;
+use security::set_allow_external_chunks;
+use server::{get_api_server_status, start_api_server, stop_api_server};
+use settings::{get_settings, update_settings};
+use sidecar::{clear_sidecar, load_sidecar};
+use size_outliers::find_size_outliers;
+use tauri::Manager;
+use text_stats::text_length_stats;
+use trace::{clear_recent_spans, get_recent_spans};
+use usage::{clear_usage_stats, get_usage_stats};
+use validate::validate_dataset;
+use windows::{open_viewer_window, take_window_open_request};
+use workspace::{close_dataset, list_open_datasets, Workspace};
+
+/// Starts the desktop app.
+pub fn run() {
+    trace::install();
+    let app = tauri::Builder::default()
+        // Must come first so a second launch hands over its arguments before anything
+        // else initializes.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            deeplink::handle_args(app, argv.get(1..).unwrap_or_default(), false);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_store::Builder::default().build())
+        .manage(ChunkCache::default())
+        .manage(IndexCache::default())
+        .manage(OffsetsCache::default())
+        .manage(Workspace::default())
+        .on_window_event(windows::on_window_event)
+        .setup(|app| {
+            logging::init(app.handle());
+            settings::init(app.handle(), &app.state::<ChunkCache>());
+            memory::guard_archives();
+            deeplink::init(app.handle());
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            open_dataset,
+            open_chunk_files,
+            open_index_shards,
+            list_chunk_items,
+            peek_field,
+            read_field_range,
+            open_leaf,
+            export_field,
+            validate_dataset,
+            generate_checksum_manifest,
+            verify_checksum_manifest,
+            scan_chunk_corruption,
+            find_duplicate_items,
+            diff_datasets,
+            set_allow_external_chunks,
+            salvage_chunk,
+            peek_salvaged_field,
+            infer_schema,
+            dataset_health,
+            locate_training_batch,
+            get_cache_stats,
+            clear_cache,
+            set_chunk_cache_budget,
+            recompress_dataset,
+            stream_chunk_items,
+            get_memory_stats,
+            set_memory_limit,
+            benchmark_dataset,
+            get_recent_spans,
+            clear_recent_spans,
+            get_recent_datasets,
+            remove_recent,
+            get_settings,
+            update_settings,
+            list_open_datasets,
+            close_dataset,
+            get_recent_logs,
+            reveal_in_file_manager,
+            get_bookmarks,
+            add_bookmark,
+            remove_bookmark,
+            fuzzy_find,
+            resolve_relative_item,
+            start_api_server,
+            stop_api_server,
+            get_api_server_status,
+            scan_for_datasets,
+            get_pinned_datasets,
+            pin_dataset,
+            unpin_dataset,
+            get_dataset_notes,
+            set_dataset_note,
+            take_pending_open_requests,
+            list_decoder_plugins,
+            install_decoder_plugin,
+            remove_decoder_plugin,
+            decode_field,
+            get_usage_stats,
+            clear_usage_stats,
+            open_viewer_window,
+            take_window_open_request,
+            field_size_distribution,
+            chunk_balance,
+            audio_corpus_stats,
+            image_resolution_stats,
+            text_length_stats,
+            class_balance,
+            find_near_duplicate_images,
+            project_embeddings,
+            find_size_outliers,
+            compression_report,
+            estimate_training_time,
+            field_entropy,
+            detect_languages,
+            content_type_census,
+            delete_items,
+            append_items,
+            replace_field,
+            create_dataset_from_folder,
+            merge_datasets,
+            renumber_chunks,
+            convert_index_format,
+            load_sidecar,
+            clear_sidecar,
+            repair_index,
+            redact_dataset,
+            resolve_artifact,
+            resolve_dvc,
+            share_media,
+            unshare_media,
+            list_shared_media,
+            export_annotations,
+            import_class_names,
+            get_class_names,
+            clear_class_names,
+            export_audio_playlist,
+            quick_look_field,
+            export_share_bundle
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+    app.run(|app, event| {
+        #[cfg(target_os = "macos")]
+        if let tauri::RunEvent::Opened { urls } = &event {
+            deeplink::handle_opened(app, urls);
+        }
+        if let tauri::RunEvent::Exit = event {
+            if let Err(e) = usage::flush(app) {
+                tracing::warn!("could not save usage stats: {e}");
+            }
+        }
+    });
+}
@@ -1,98 +1,435 @@
-use hex::encode as hex_encode;
-use serde::{Deserialize, Serialize};
+use crate::aio;
+use crate::audio::{transcode_to_wav, AudioConversion};
+use crate::class_names;
+use crate::disk_cache;
+use crate::hooks::{self, HookContext, HookEvent, HookOutcome};
+use crate::index_cache::IndexCache;
+use crate::inflate;
+use crate::memory;
+use crate::offsets_cache::OffsetsCache;
+use crate::prefetch;
+use crate::progress::{Progress, ProgressReader};
+use crate::recent::{self, RecentDataset};
+use crate::scan;
+use crate::security::{confine_to_root, ensure_writable};
+use crate::settings;
+use crate::usage;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::archive;
+use litdata_core::chunk::field_span;
+use litdata_core::corruption::explain_corruption;
+use litdata_core::decompress_chunk;
+use litdata_core::index::{
+    find_chunk_index, parse_index_bytes, parse_index_file, same_chunk_name, ChunkLocation,
+    IndexConfig, IndexMetadata,
+};
+use litdata_core::long_path::{long_path, plain_path};
+use litdata_core::prefix::StreamPrefix;
+use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
-    fs::{self, File},
-    io::{Read, Seek, SeekFrom},
+    fs,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
-use tauri::async_runtime::spawn_blocking;
-use thiserror::Error;
+use tauri::{async_runtime::spawn_blocking, ipc::Response, AppHandle, Emitter};
+
+// The reading code lives in `litdata-core`; it is re-exported here so the commands
+// keep importing it from one place.
+pub use litdata_core::item::{FieldPreview, ItemMeta};
+pub(crate) use litdata_core::{
+    chunk::{read_le_u32, OffsetWidth},
+    guess_ext,
+    health::file_identity,
+    index::{
+        chunk_compression, index_shards, is_chunk_path, is_index_file_name, probe_chunk_header,
+        probe_compressed_chunk_items, read_index_file,
+    },
+    item::{build_preview, collect_item_meta, item_meta_range, leaf_file_name},
+    parse_index, parse_offsets, read_field_bytes, read_item_bytes, resolve_index_path,
+    sniff_content_type, split_fields, ChunkAccess, ParsedIndex, RawChunk, ResultExt,
+};
+pub use litdata_core::{AppError, AppResult, ErrorContext};
+
+/// Defaults for the matching `settings` fields.
+pub(crate) const DEFAULT_PREVIEW_BYTES: usize = 2048;
+pub(crate) const MAX_CACHE_BYTES: usize = 128 * 1024 * 1024;
+pub(crate) const DEFAULT_CACHE_BUDGET: usize = 512 * 1024 * 1024;
+/// Partially decoded zstd chunks kept open for previews.
+const MAX_OPEN_STREAMS: usize = 4;
+/// Indexes with at most this many chunks have existence checked before `open_dataset`
+/// returns; larger ones are checked in the background.
+const INLINE_EXISTENCE_CHECKS: usize = 256;
+const EXISTENCE_BATCH: usize = 512;
+/// Event carrying a `ChunkExistenceBatch`; listeners filter by `indexPath`.
+pub const CHUNK_EXISTENCE_EVENT: &str = "chunk-existence";
+
+/// `path` with symlinks resolved, so a dataset reached through a link shares cache
+/// entries with its target. A file that no longer exists keeps its resolved directory.
+pub(crate) fn resolved_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = fs::canonicalize(path) {
+        return canonical;
+    }
+    match (
+        path.parent().and_then(|dir| fs::canonicalize(dir).ok()),
+        path.file_name(),
+    ) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
 
-const PREVIEW_BYTES: usize = 2048;
-const MAX_CACHE_BYTES: usize = 128 * 1024 * 1024;
+/// `ChunkCache` key for the chunk at `chunk_path`.
+pub(crate) fn chunk_cache_key(chunk_path: &Path) -> String {
+    resolved_path(chunk_path).display().to_string()
+}
 
-#[derive(Clone, Default)]
+/// Decompressed chunks, evicted least-recently-used once their total exceeds the budget.
+#[derive(Clone)]
 pub struct ChunkCache {
-    inner: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    inner: Arc<Mutex<CacheState>>,
+    loading: Arc<Mutex<HashMap<String, LoadSlot>>>,
 }
 
-impl ChunkCache {
-    fn fetch(&self, key: &str) -> Option<Vec<u8>> {
-        self.inner.lock().ok()?.get(key).cloned()
+/// Result of one in-flight decompression, shared with callers that asked for the same
+/// chunk while it was running. Stays `None` if the load failed.
+type LoadSlot = Arc<Mutex<Option<Arc<[u8]>>>>;
+
+struct CacheEntry {
+    data: Arc<[u8]>,
+    last_used: u64,
+}
+
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    streams: HashMap<String, (StreamPrefix, u64)>,
+    budget: usize,
+    /// Chunks larger than this are never cached whole.
+    entry_limit: usize,
+    used: usize,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    evicted_bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    budget_bytes: u64,
+    used_bytes: u64,
+    entries: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    evicted_bytes: u64,
+    disk_entries: usize,
+    disk_bytes: u64,
+    open_streams: usize,
+    stream_bytes: u64,
+}
+
+impl Default for ChunkCache {
+    fn default() -> Self {
+        ChunkCache::with_budget(DEFAULT_CACHE_BUDGET)
     }
+}
 
-    fn maybe_store(&self, key: &str, data: Vec<u8>) {
-        if data.len() <= MAX_CACHE_BYTES {
-            if let Ok(mut guard) = self.inner.lock() {
-                guard.insert(key.to_string(), data);
+impl CacheState {
+    fn evict_until(&mut self, budget: usize) {
+        while self.used > budget {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.used -= entry.data.len();
+                memory::track_cached(0, entry.data.len() as u64);
+                self.evictions += 1;
+                self.evicted_bytes += entry.data.len() as u64;
             }
         }
     }
-}
 
-pub type AppResult<T> = Result<T, AppError>;
+    fn stats(&self) -> CacheStats {
+        let (disk_entries, disk_bytes) = disk_cache::usage();
+        CacheStats {
+            budget_bytes: self.budget as u64,
+            used_bytes: self.used as u64,
+            entries: self.entries.len(),
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            evicted_bytes: self.evicted_bytes,
+            disk_entries,
+            disk_bytes,
+            open_streams: self.streams.len(),
+            stream_bytes: self
+                .streams
+                .values()
+                .map(|(s, _)| s.decoded_len() as u64)
+                .sum(),
+        }
+    }
+}
 
-#[derive(Error, Debug, Serialize)]
-#[serde(tag = "code", content = "message")]
-pub enum AppError {
-    #[error("invalid request: {0}")]
-    Invalid(String),
-    #[error("not found: {0}")]
-    Missing(String),
-    #[error("unsupported compression: {0}")]
-    UnsupportedCompression(String),
-    #[error("malformed chunk")]
-    MalformedChunk,
-    #[error("io error: {0}")]
-    Io(String),
-    #[error("task error: {0}")]
-    Task(String),
-    #[error("open error: {0}")]
-    Open(String),
+impl memory::Reclaim for Mutex<CacheState> {
+    fn reclaim(&self, bytes: u64) -> u64 {
+        let Ok(mut guard) = self.lock() else {
+            return 0;
+        };
+        let before = guard.used;
+        let freed = usize::try_from(bytes).unwrap_or(usize::MAX);
+        guard.evict_until(before.saturating_sub(freed));
+        (before - guard.used) as u64
+    }
 }
 
-impl From<std::io::Error> for AppError {
-    fn from(value: std::io::Error) -> Self {
-        AppError::Io(value.to_string())
+impl Drop for CacheState {
+    fn drop(&mut self) {
+        memory::track_cached(0, self.used as u64);
     }
 }
 
-fn read_le_u32(bytes: &[u8]) -> AppResult<u32> {
-    let buf: [u8; 4] = bytes.try_into().map_err(|_| AppError::MalformedChunk)?;
-    Ok(u32::from_le_bytes(buf))
+impl ChunkCache {
+    pub(crate) fn with_budget(budget: usize) -> Self {
+        let cache = ChunkCache {
+            inner: Arc::new(Mutex::new(CacheState {
+                entries: HashMap::new(),
+                streams: HashMap::new(),
+                budget,
+                entry_limit: MAX_CACHE_BYTES,
+                used: 0,
+                clock: 0,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+                evicted_bytes: 0,
+            })),
+            loading: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let reclaimer: Arc<dyn memory::Reclaim> = cache.inner.clone();
+        memory::register_reclaimer(Arc::downgrade(&reclaimer));
+        cache
+    }
+
+    /// Runs `load` for `key` unless another thread is already doing so, in which case
+    /// this waits and shares its result. A failed load is retried by each waiter so that
+    /// every caller sees its own error.
+    pub(crate) fn load_once(
+        &self,
+        key: &str,
+        load: impl FnOnce() -> AppResult<Arc<[u8]>>,
+    ) -> AppResult<Arc<[u8]>> {
+        let slot = {
+            let mut loading = self
+                .loading
+                .lock()
+                .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+            loading.entry(key.to_string()).or_default().clone()
+        };
+        let mut result = slot
+            .lock()
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+        if let Some(buf) = result.as_ref() {
+            return Ok(buf.clone());
+        }
+        let loaded = load();
+        if let Ok(buf) = &loaded {
+            *result = Some(buf.clone());
+        }
+        drop(result);
+        if let Ok(mut loading) = self.loading.lock() {
+            if loading.get(key).is_some_and(|s| Arc::ptr_eq(s, &slot)) {
+                loading.remove(key);
+            }
+        }
+        loaded
+    }
+
+    /// Shared handle to a cached chunk; no bytes are copied.
+    pub(crate) fn fetch(&self, key: &str) -> Option<Arc<[u8]>> {
+        let mut guard = self.inner.lock().ok()?;
+        guard.clock += 1;
+        let now = guard.clock;
+        match guard.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = now;
+                let data = entry.data.clone();
+                guard.hits += 1;
+                Some(data)
+            }
+            None => {
+                guard.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn maybe_store(&self, key: &str, data: Arc<[u8]>) {
+        let Ok(mut guard) = self.inner.lock() else {
+            return;
+        };
+        if data.len() > guard.entry_limit.min(guard.budget) {
+            return;
+        }
+        guard.streams.remove(key);
+        if let Some(old) = guard.entries.remove(key) {
+            guard.used -= old.data.len();
+            memory::track_cached(0, old.data.len() as u64);
+        }
+        let budget = guard.budget - data.len();
+        guard.evict_until(budget);
+        guard.clock += 1;
+        guard.used += data.len();
+        memory::track_cached(data.len() as u64, 0);
+        let last_used = guard.clock;
+        guard
+            .entries
+            .insert(key.to_string(), CacheEntry { data, last_used });
+    }
+
+    /// Drops `key` after its chunk file was rewritten in place.
+    pub(crate) fn forget(&self, key: &str) {
+        let Ok(mut guard) = self.inner.lock() else {
+            return;
+        };
+        guard.streams.remove(key);
+        if let Some(old) = guard.entries.remove(key) {
+            guard.used -= old.data.len();
+            memory::track_cached(0, old.data.len() as u64);
+        }
+    }
+
+    /// Whether `len` decompressed bytes for `key` could be cached without evicting `keep`.
+    /// Already-cached keys report `false`, as there is nothing left to do for them.
+    pub(crate) fn has_room_for(&self, key: &str, len: usize, keep: &str) -> bool {
+        let Ok(guard) = self.inner.lock() else {
+            return false;
+        };
+        if guard.entries.contains_key(key) || len > guard.entry_limit {
+            return false;
+        }
+        let kept = guard.entries.get(keep).map_or(0, |e| e.data.len());
+        len.saturating_add(kept) <= guard.budget
+    }
+
+    /// Shared lazily-decoded stream for `key`, opened with `open` on first use.
+    pub(crate) fn stream(
+        &self,
+        key: &str,
+        open: impl FnOnce() -> AppResult<StreamPrefix>,
+    ) -> AppResult<StreamPrefix> {
+        let mut guard = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+        guard.clock += 1;
+        let now = guard.clock;
+        if let Some((stream, last_used)) = guard.streams.get_mut(key) {
+            *last_used = now;
+            return Ok(stream.clone());
+        }
+        let stream = open()?;
+        if guard.streams.len() >= MAX_OPEN_STREAMS {
+            if let Some(oldest) = guard
+                .streams
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone())
+            {
+                guard.streams.remove(&oldest);
+            }
+        }
+        guard.streams.insert(key.to_string(), (stream.clone(), now));
+        Ok(stream)
+    }
+
+    pub(crate) fn set_budget(&self, budget: usize) -> AppResult<CacheStats> {
+        let mut guard = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+        guard.budget = budget;
+        guard.evict_until(budget);
+        Ok(guard.stats())
+    }
+
+    pub(crate) fn set_entry_limit(&self, limit: usize) -> AppResult<()> {
+        let mut guard = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+        guard.entry_limit = limit;
+        Ok(())
+    }
+
+    /// Drops every entry; hit/miss counters keep accumulating.
+    pub(crate) fn clear(&self) -> AppResult<CacheStats> {
+        let mut guard = self
+            .inner
+            .lock()
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))?;
+        guard.entries.clear();
+        guard.streams.clear();
+        memory::track_cached(0, guard.used as u64);
+        guard.used = 0;
+        Ok(guard.stats())
+    }
+
+    pub(crate) fn stats(&self) -> AppResult<CacheStats> {
+        self.inner
+            .lock()
+            .map(|guard| guard.stats())
+            .map_err(|_| AppError::Task("chunk cache lock poisoned".into()))
+    }
 }
 
-#[derive(Deserialize)]
-struct IndexFile {
-    chunks: Vec<RawChunk>,
-    config: IndexConfig,
+#[tauri::command]
+pub async fn get_cache_stats(cache: tauri::State<'_, ChunkCache>) -> AppResult<CacheStats> {
+    let cache_handle = (*cache).clone();
+    spawn_blocking(move || cache_handle.stats())
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
 }
 
-#[derive(Deserialize, Clone, Serialize)]
-struct IndexConfig {
-    compression: Option<String>,
-    chunk_size: Option<u32>,
-    chunk_bytes: Option<u64>,
-    data_format: Option<Vec<String>>,
-    data_spec: Option<String>,
+/// Frees the in-memory chunk cache and, when asked, the on-disk spill directory.
+#[tauri::command]
+pub async fn clear_cache(
+    include_disk: bool,
+    cache: tauri::State<'_, ChunkCache>,
+) -> AppResult<CacheStats> {
+    let cache_handle = (*cache).clone();
+    spawn_blocking(move || {
+        if include_disk {
+            disk_cache::clear()?;
+        }
+        cache_handle.clear()
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
 }
 
-#[derive(Deserialize)]
-struct RawChunk {
-    filename: String,
-    chunk_bytes: u64,
-    chunk_size: u32,
-    dim: Option<u32>,
+/// Changes the total decompressed-bytes budget, evicting immediately if it shrank.
+#[tauri::command]
+pub async fn set_chunk_cache_budget(
+    budget_bytes: u64,
+    cache: tauri::State<'_, ChunkCache>,
+) -> AppResult<CacheStats> {
+    let budget = usize::try_from(budget_bytes)
+        .map_err(|_| AppError::Invalid("cache budget exceeds addressable memory".into()))?;
+    cache.set_budget(budget)
 }
 
-struct ParsedIndex {
-    root_dir: PathBuf,
-    source: PathBuf,
-    config: IndexConfig,
-    config_raw: serde_json::Value,
-    chunks: Vec<RawChunk>,
+/// `path` as shown in the UI and stored in lists: without the `\\?\` prefix that
+/// `long_path` adds for I/O.
+fn shown_path(path: &Path) -> String {
+    plain_path(path).display().to_string()
 }
 
 #[derive(Serialize)]
@@ -100,15 +437,36 @@ struct ParsedIndex {
 pub struct ChunkSummary {
     filename: String,
     path: String,
+    /// Directory or index.json the chunk came from in a merged view.
+    source: Option<String>,
     chunk_size: u32,
     chunk_bytes: u64,
     dim: Option<u32>,
+    /// `None` while a large index is still being checked in the background.
+    exists: Option<bool>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkExistence {
+    filename: String,
     exists: bool,
 }
 
+/// One batch of background existence checks for `open_dataset`.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkExistenceBatch {
+    index_path: String,
+    results: Vec<ChunkExistence>,
+    checked: usize,
+    total: usize,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexSummary {
+    handle: DatasetHandle,
     index_path: String,
     root_dir: String,
     data_format: Vec<String>,
@@ -116,749 +474,1212 @@ pub struct IndexSummary {
     chunk_size: Option<u32>,
     chunk_bytes: Option<u64>,
     config_raw: serde_json::Value,
+    /// Index format version, absent for indexes that predate recording it.
+    format_version: Option<u32>,
+    updated_at: Option<String>,
+    litdata_version: Option<String>,
     chunks: Vec<ChunkSummary>,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FieldMeta {
-    field_index: usize,
+pub struct ExportedField {
+    path: String,
     size: u32,
+    transcoded: bool,
+    hooks: Vec<HookOutcome>,
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ItemMeta {
-    item_index: u32,
-    total_bytes: u64,
-    fields: Vec<FieldMeta>,
+/// `parse_index` with the index file read through tokio. Standalone chunks and
+/// archived datasets are still read on the blocking pool.
+#[tracing::instrument(skip_all, fields(path = %index_path.display()))]
+pub(crate) async fn parse_index_async(index_path: &Path) -> AppResult<ParsedIndex> {
+    let path = index_path.to_path_buf();
+    if is_chunk_path(&path) || archive::split_archive_path(&path).is_some() {
+        return spawn_blocking(move || parse_index(&path))
+            .await
+            .map_err(|e| AppError::Task(e.to_string()))?;
+    }
+    let resolved = spawn_blocking(move || resolve_index_path(&path))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))??;
+    let raw = aio::read(&resolved).await?;
+    parse_index_bytes(resolved, raw)
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FieldPreview {
-    preview_text: Option<String>,
-    hex_snippet: String,
-    guessed_ext: Option<String>,
-    is_binary: bool,
-    size: u32,
+/// Returns the index summary. Small indexes come back with every chunk's existence
+/// checked; larger ones return immediately with `exists: null` and stream the answers
+/// as `chunk-existence` events, since stat-ing 100k chunks on a network mount takes
+/// minutes.
+#[tauri::command]
+pub async fn open_dataset(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    index_path: String,
+    indexes: tauri::State<'_, IndexCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<IndexSummary> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    let handle = workspace.open_in(parsed.clone(), window.label())?;
+    let ParsedIndex {
+        root_dir,
+        source,
+        config,
+        config_raw,
+        chunks,
+        metadata,
+        ..
+    } = &*parsed;
+    let data_format = config.data_format.clone().unwrap_or_default();
+    let check_inline = chunks.len() <= INLINE_EXISTENCE_CHECKS;
+    let mut summaries = Vec::with_capacity(chunks.len());
+    let mut pending = Vec::new();
+    for c in chunks {
+        let full = parsed.chunk_path(&c.filename)?;
+        let exists = if check_inline {
+            Some(aio::exists(&full).await || archive::contains(&full))
+        } else {
+            pending.push((c.filename.clone(), full.clone()));
+            None
+        };
+        summaries.push(ChunkSummary {
+            filename: c.filename.clone(),
+            path: shown_path(&full),
+            source: None,
+            chunk_size: c.chunk_size,
+            chunk_bytes: c.chunk_bytes,
+            dim: c.dim,
+            exists,
+        });
+    }
+    let valid = check_inline.then(|| summaries.iter().all(|s| s.exists == Some(true)));
+    let recent = RecentDataset::new(
+        shown_path(source),
+        chunks.len(),
+        chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        valid,
+    );
+    tracing::info!(
+        index = %source.display(),
+        chunks = chunks.len(),
+        handle,
+        "opened dataset"
+    );
+    let recorder = app.clone();
+    // The recent list is a convenience; failing to update it must not fail the load.
+    if let Ok(Err(e)) = spawn_blocking(move || recent::record(&recorder, recent)).await {
+        tracing::warn!("could not update recent datasets: {e}");
+    }
+    let recorder = app.clone();
+    let opened = shown_path(source);
+    if let Ok(Err(e)) = spawn_blocking(move || usage::record_open(&recorder, &opened)).await {
+        tracing::warn!("could not update usage stats: {e}");
+    }
+    if !pending.is_empty() {
+        let index_path = shown_path(source);
+        let window = window.label().to_string();
+        tauri::async_runtime::spawn(resolve_existence(app, window, index_path, pending));
+    }
+    Ok(IndexSummary {
+        handle,
+        index_path: shown_path(source),
+        root_dir: shown_path(root_dir),
+        data_format,
+        compression: config.compression.clone(),
+        chunk_size: config.chunk_size,
+        chunk_bytes: config.chunk_bytes,
+        config_raw: config_raw.clone(),
+        format_version: metadata.format_version,
+        updated_at: metadata.updated_at.clone(),
+        litdata_version: metadata.litdata_version.clone(),
+        chunks: summaries,
+    })
 }
 
-enum ChunkAccess {
-    File(PathBuf),
-    Memory(Vec<u8>),
+/// Stats `pending` chunks a batch at a time, emitting each batch as it completes.
+/// Batches go only to `window`, which opened the dataset.
+async fn resolve_existence(
+    app: AppHandle,
+    window: String,
+    index_path: String,
+    pending: Vec<(String, PathBuf)>,
+) {
+    let total = pending.len();
+    let mut checked = 0;
+    let mut all_exist = true;
+    for batch in pending.chunks(EXISTENCE_BATCH) {
+        let batch = batch.to_vec();
+        let Ok(results) = spawn_blocking(move || {
+            scan::par_map(&batch, None, |(filename, path)| ChunkExistence {
+                filename: filename.clone(),
+                exists: path.exists() || archive::contains(path),
+            })
+        })
+        .await
+        else {
+            return;
+        };
+        checked += results.len();
+        all_exist &= results.iter().all(|r| r.exists);
+        // Best-effort, like progress: a closed window just stops listening.
+        let _ = app.emit_to(
+            window.as_str(),
+            CHUNK_EXISTENCE_EVENT,
+            ChunkExistenceBatch {
+                index_path: index_path.clone(),
+                results,
+                checked,
+                total,
+            },
+        );
+    }
+    if !all_exist {
+        tracing::warn!(index = %index_path, "dataset has missing chunk files");
+    }
+    let recorder = app.clone();
+    let _ = spawn_blocking(move || recent::set_valid(&recorder, &index_path, all_exist)).await;
+}
+
+/// Opens chunk files and whole dataset directories as one virtual dataset, the way
+/// litdata merges the outputs of several optimize runs. Config comes from the first
+/// index.json found next to the inputs; every source must agree on `data_format`, and
+/// on compression, which directories without an index take from their chunk names.
+#[tauri::command]
+pub async fn open_chunk_files(
+    window: tauri::WebviewWindow,
+    paths: Vec<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<IndexSummary> {
+    let workspace = (*workspace).clone();
+    let window = window.label().to_string();
+    spawn_blocking(move || open_chunk_files_sync(paths, &workspace, &window))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// One directory's contribution to a merged view.
+struct MergeSource {
+    dir: PathBuf,
+    /// Chunk files picked individually, relative to `dir`; `None` takes every chunk in
+    /// the directory.
+    selected: Option<Vec<PathBuf>>,
 }
 
-impl ChunkAccess {
-    fn read_exact_at(&self, offset: u64, len: usize) -> AppResult<Vec<u8>> {
-        match self {
-            ChunkAccess::File(path) => {
-                let mut fp = File::open(path)?;
-                fp.seek(SeekFrom::Start(offset))?;
-                let mut buf = vec![0u8; len];
-                fp.read_exact(&mut buf)?;
-                Ok(buf)
+fn group_merge_sources(paths: &[String]) -> AppResult<Vec<MergeSource>> {
+    let mut sources: Vec<MergeSource> = Vec::new();
+    for p in paths {
+        let path = PathBuf::from(p.trim());
+        if path.is_dir() {
+            match sources.iter_mut().find(|s| s.dir == path) {
+                Some(source) => source.selected = None,
+                None => sources.push(MergeSource {
+                    dir: path,
+                    selected: None,
+                }),
             }
-            ChunkAccess::Memory(buf) => {
-                let end = offset
-                    .checked_add(len as u64)
-                    .ok_or(AppError::MalformedChunk)? as usize;
-                if end > buf.len() {
-                    return Err(AppError::MalformedChunk);
+            continue;
+        }
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(AppError::Invalid(format!("not a chunk file: {p}")));
+        };
+        // A chunk in a subdirectory of its dataset is grouped under the index's
+        // directory, by the nested name the index lists it as.
+        let (dir, name) = match find_chunk_index(&path) {
+            Some((index, listed)) => match index.parent() {
+                Some(root) => (root.to_path_buf(), listed),
+                None => (dir.to_path_buf(), PathBuf::from(name)),
+            },
+            None => (dir.to_path_buf(), PathBuf::from(name)),
+        };
+        match sources.iter_mut().find(|s| s.dir == dir) {
+            Some(MergeSource {
+                selected: Some(names),
+                ..
+            }) => {
+                if !names.iter().any(|n| same_chunk_name(n, &name)) {
+                    names.push(name);
                 }
-                Ok(buf[offset as usize..end].to_vec())
             }
+            Some(_) => {}
+            None => sources.push(MergeSource {
+                dir,
+                selected: Some(vec![name]),
+            }),
         }
     }
+    Ok(sources)
 }
 
-fn parse_index(index_path: &Path) -> AppResult<ParsedIndex> {
-    if is_chunk_path(index_path) {
-        if let Some(found) = find_neighbor_index(index_path) {
-            return parse_index(&found);
+/// Subdirectory levels `list_chunk_files` descends into.
+const MAX_LISTED_NESTING: usize = 2;
+
+/// Chunk files inside `dir` and its subdirectories, for directories without an index,
+/// as paths relative to `dir` like `shard_00/chunk-0-0.bin`.
+fn list_chunk_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut names = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), PathBuf::new(), 0)];
+    while let Some((current, prefix, depth)) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if depth == 0 => return Err(e.into()),
+            Err(_) => continue,
+        };
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            let relative = prefix.join(name);
+            if path.is_dir() && depth < MAX_LISTED_NESTING {
+                pending.push((path.clone(), relative, depth + 1));
+            } else if path.is_file() && is_chunk_path(&path) {
+                names.push(relative);
+            }
         }
-        return parse_chunk_only(index_path);
-    }
-
-    let resolved = resolve_index_path(index_path)?;
-    let content = read_index_file(&resolved)?;
-    let parsed: IndexFile = serde_json::from_str(&content)
-        .map_err(|e| AppError::Invalid(format!("index.json parse error: {e}")))?;
-    let config = parsed.config;
-    let config_raw = serde_json::to_value(&config).unwrap_or(serde_json::Value::Null);
-    let root_dir = resolved
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| PathBuf::from("."));
-    Ok(ParsedIndex {
-        root_dir,
-        source: resolved,
-        config,
-        config_raw,
-        chunks: parsed.chunks,
-    })
+    }
+    names.sort();
+    Ok(names)
 }
 
-fn parse_chunk_only(index_path: &Path) -> AppResult<ParsedIndex> {
-    let root_dir = index_path
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| PathBuf::from("."));
-    let mut file = File::open(index_path)?;
-    let size = file.metadata()?.len();
-
-    let mut num_buf = [0u8; 4];
-    file.read_exact(&mut num_buf)?;
-    let num_items = read_le_u32(&num_buf)?;
-
-    let offsets_len = (num_items as usize + 1) * 4;
-    let mut offsets = vec![0u8; offsets_len];
-    file.read_exact(&mut offsets)?;
-
-    let chunk = RawChunk {
-        filename: index_path
-            .file_name()
-            .and_then(|f| f.to_str())
-            .unwrap_or("chunk.bin")
-            .to_string(),
-        chunk_bytes: size,
-        chunk_size: num_items.max(1),
-        dim: None,
-    };
-    let fallback_config = IndexConfig {
-        compression: None,
-        chunk_size: Some(num_items.max(1)),
-        chunk_bytes: Some(size),
-        data_format: Some(vec!["bytes".into()]),
-        data_spec: None,
-    };
-    Ok(ParsedIndex {
-        root_dir,
-        source: index_path.to_path_buf(),
-        config: fallback_config.clone(),
-        config_raw: serde_json::to_value(fallback_config).unwrap_or(serde_json::Value::Null),
-        chunks: vec![chunk],
-    })
+/// `relative` as a chunk filename, `/`-separated. Bytes that are not UTF-8 are replaced
+/// here, for display only: the chunk's location keeps the real path.
+fn chunk_display_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
-fn is_chunk_path(path: &Path) -> bool {
-    path.extension()
-        .and_then(|e| e.to_str())
-        .map(|ext| ext.eq_ignore_ascii_case("bin") || ext.eq_ignore_ascii_case("zst"))
-        .unwrap_or(false)
-        || path
-            .file_name()
-            .and_then(|f| f.to_str())
-            .map(|name| name.contains(".bin"))
-            .unwrap_or(false)
-}
-
-fn find_neighbor_index(chunk_path: &Path) -> Option<PathBuf> {
-    let parent = chunk_path.parent()?;
-    let candidates = [
-        "index.json",
-        "index.json.zstd",
-        "index.json.zst",
-        "0.index.json",
-        "0.index.json.zstd",
-        "0.index.json.zst",
-    ];
-    for name in candidates {
-        let candidate = parent.join(name);
-        if candidate.exists() {
-            return Some(candidate);
-        }
+/// Name for a chunk in the merged view: its own file name unless an earlier source
+/// already used it, then qualified by its directory.
+fn merged_chunk_name(taken: &HashSet<String>, dir: &Path, filename: &str) -> String {
+    if !taken.contains(filename) {
+        return filename.to_string();
     }
-    let mut globbed: Vec<PathBuf> = std::fs::read_dir(parent)
-        .ok()?
-        .filter_map(|e| e.ok().map(|e2| e2.path()))
-        .filter(|p| {
-            p.file_name()
-                .and_then(|f| f.to_str())
-                .map(|name| name.ends_with(".index.json") || name.contains(".index.json."))
-                .unwrap_or(false)
-        })
-        .collect();
-    globbed.sort();
-    globbed.into_iter().next()
-}
-
-fn resolve_index_path(path: &Path) -> AppResult<PathBuf> {
-    if path.is_file() {
-        return Ok(path.to_path_buf());
-    }
-    if path.is_dir() {
-        let candidates = [
-            "index.json",
-            "index.json.zstd",
-            "index.json.zst",
-            "0.index.json",
-            "0.index.json.zstd",
-            "0.index.json.zst",
-        ];
-        let mut globbed: Vec<PathBuf> = std::fs::read_dir(path)
-            .ok()
-            .into_iter()
-            .flatten()
-            .filter_map(|e| e.ok().map(|e2| e2.path()))
-            .filter(|p| {
-                p.file_name()
-                    .and_then(|f| f.to_str())
-                    .map(|name| name.ends_with(".index.json") || name.contains(".index.json."))
-                    .unwrap_or(false)
-            })
-            .collect();
-        globbed.sort();
-        for name in candidates {
-            let candidate = path.join(name);
-            if candidate.exists() {
-                return Ok(candidate);
+    let label = dir
+        .file_name()
+        .map_or_else(|| "source".into(), |f| f.to_string_lossy());
+    let mut candidate = format!("{label}/{filename}");
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{label}-{n}/{filename}");
+        n += 1;
+    }
+    candidate
+}
+
+/// Fails when `path` disagrees with `first`, the source merged before it, on a setting
+/// every source of one dataset must share.
+fn ensure_same_setting<T: PartialEq + std::fmt::Debug>(
+    what: &str,
+    path: &Path,
+    theirs: &T,
+    first: &Path,
+    ours: &T,
+) -> AppResult<()> {
+    if theirs == ours {
+        return Ok(());
+    }
+    Err(AppError::Invalid(format!(
+        "{} has {what} {theirs:?}, but {} has {ours:?}",
+        path.display(),
+        first.display(),
+    )))
+}
+
+fn open_chunk_files_sync(
+    paths: Vec<String>,
+    workspace: &Workspace,
+    window: &str,
+) -> AppResult<IndexSummary> {
+    if paths.is_empty() {
+        return Err(AppError::Invalid("no chunk paths provided".into()));
+    }
+    let sources = group_merge_sources(&paths)?;
+    let mut raw_chunks: Vec<RawChunk> = Vec::new();
+    let mut chunk_locations: HashMap<String, ChunkLocation> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut seen_files: HashMap<String, PathBuf> = HashMap::new();
+    let mut index_path: Option<PathBuf> = None;
+    let mut config: Option<(IndexConfig, serde_json::Value, PathBuf)> = None;
+    // Compression of the first source, and the index or chunk it was read from.
+    let mut compression: Option<(Option<String>, PathBuf)> = None;
+    let mut check_compression = |theirs: Option<String>, path: &Path| match &compression {
+        Some((ours, first)) => ensure_same_setting("compression", path, &theirs, first, ours),
+        None => {
+            compression = Some((theirs, path.to_path_buf()));
+            Ok(())
+        }
+    };
+    for source in &sources {
+        let found_index = match &source.selected {
+            None => resolve_index_path(&source.dir).ok().filter(|p| p.is_file()),
+            Some(names) => find_chunk_index(&source.dir.join(&names[0])).map(|(index, _)| index),
+        };
+        // Each chunk with the file it reads from.
+        let mut listed: Vec<(RawChunk, PathBuf)> = Vec::new();
+        let mut provenance = source.dir.clone();
+        // Set when the source has an index; its chunks are compressed as it says.
+        let mut index_compression: Option<Option<String>> = None;
+        if let Some(found) = found_index {
+            let parsed = parse_index_file(&found)?;
+            match &config {
+                Some((first, _, first_index)) => ensure_same_setting(
+                    "data_format",
+                    &found,
+                    &parsed.config.data_format,
+                    first_index,
+                    &first.data_format,
+                )?,
+                None => {
+                    config = Some((
+                        parsed.config.clone(),
+                        parsed.config_raw.clone(),
+                        found.clone(),
+                    ));
+                }
             }
+            check_compression(parsed.config.compression.clone(), &found)?;
+            index_compression = Some(parsed.config.compression.clone());
+            for chunk in parsed.chunks {
+                let picked = match &source.selected {
+                    None => true,
+                    Some(names) => names.iter().any(|n| same_chunk_name(n, &chunk.filename)),
+                };
+                if picked {
+                    let path = confine_to_root(&source.dir, &chunk.filename)?;
+                    listed.push((chunk, path));
+                }
+            }
+            index_path.get_or_insert_with(|| found.clone());
+            provenance = found;
         }
-        if let Some(first) = globbed.first() {
-            return Ok(first.to_path_buf());
+        let names = match &source.selected {
+            Some(names) => names.clone(),
+            None if listed.is_empty() => list_chunk_files(&source.dir)?,
+            None => Vec::new(),
+        };
+        for name in names {
+            if listed
+                .iter()
+                .any(|(c, _)| same_chunk_name(&c.filename, &name))
+            {
+                continue;
+            }
+            // Names found on disk, so joined as they are rather than parsed as text.
+            let path = long_path(&source.dir.join(&name));
+            let compressed_as = match &index_compression {
+                Some(compression) => compression.clone(),
+                None => {
+                    let derived = chunk_compression(&path).map(String::from);
+                    check_compression(derived.clone(), &path)?;
+                    derived
+                }
+            };
+            // A compressed chunk's inflated size is unknown until it is read.
+            let (num_items, size) = match compressed_as {
+                Some(_) => (probe_compressed_chunk_items(&path)?, 0),
+                None => probe_chunk_header(&path)?,
+            };
+            let chunk = RawChunk {
+                filename: chunk_display_name(&name),
+                chunk_bytes: size,
+                chunk_size: num_items.max(1),
+                dim: None,
+            };
+            listed.push((chunk, path));
         }
-    } else if let Some(parent) = path.parent() {
-        let base = path.file_stem().and_then(|s| s.to_str()).unwrap_or("index");
-        let candidates = [
-            path.to_path_buf(),
-            path.with_extension("json"),
-            path.with_extension("json.zstd"),
-            path.with_extension("json.zst"),
-            parent.join(format!("{base}.json")),
-            parent.join(format!("{base}.json.zstd")),
-            parent.join(format!("{base}.json.zst")),
-        ];
-        for candidate in candidates {
-            if candidate.exists() {
-                return Ok(candidate);
+        for (mut chunk, path) in listed {
+            if is_duplicate_file(&mut seen_files, &path, &chunk.filename) {
+                continue;
             }
+            chunk.filename = merged_chunk_name(&taken, &source.dir, &chunk.filename);
+            taken.insert(chunk.filename.clone());
+            chunk_locations.insert(
+                chunk.filename.clone(),
+                ChunkLocation {
+                    path,
+                    source: provenance.clone(),
+                },
+            );
+            raw_chunks.push(chunk);
         }
     }
-    Err(AppError::Missing(path.display().to_string()))
-}
-
-fn read_index_file(path: &Path) -> AppResult<String> {
-    let ext = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-    if ext.contains("zst") {
-        let file = File::open(path)?;
-        let mut decoder = zstd::stream::Decoder::new(file)?;
-        let mut s = String::new();
-        decoder.read_to_string(&mut s)?;
-        Ok(s)
-    } else {
-        Ok(fs::read_to_string(path)?)
+    if raw_chunks.is_empty() {
+        return Err(AppError::Invalid(
+            "no chunk files found in the selected paths".into(),
+        ));
     }
-}
 
-fn parse_index_file(path: &Path) -> AppResult<ParsedIndex> {
-    let content = read_index_file(path)?;
-    let parsed: IndexFile = serde_json::from_str(&content)
-        .map_err(|e| AppError::Invalid(format!("index.json parse error: {e}")))?;
-    let config = parsed.config.clone();
-    let config_raw = serde_json::to_value(&config).unwrap_or(serde_json::Value::Null);
-    let root_dir = path
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| PathBuf::from("."));
-    Ok(ParsedIndex {
+    let (config, config_raw) = match config {
+        Some((config, raw, _)) => (config, raw),
+        None => {
+            let config = IndexConfig {
+                compression: compression.and_then(|(compression, _)| compression),
+                chunk_size: None,
+                chunk_bytes: None,
+                data_format: Some(vec!["bytes".into()]),
+                data_spec: None,
+            };
+            let raw = serde_json::json!({
+                "source": "multi-bin",
+                "data_format": config.data_format,
+                "compression": config.compression,
+            });
+            (config, raw)
+        }
+    };
+    let root_dir = sources[0].dir.clone();
+    let resolved_index_path = index_path.unwrap_or_else(|| PathBuf::from(&paths[0]));
+    let multi_source = sources.len() > 1;
+    let parsed = ParsedIndex {
         root_dir,
-        source: path.to_path_buf(),
+        source: resolved_index_path,
         config,
         config_raw,
-        chunks: parsed.chunks,
-    })
+        chunks: raw_chunks,
+        metadata: IndexMetadata::default(),
+        chunk_locations,
+    };
+    let summary = open_merged(parsed, multi_source, workspace, window)?;
+    if multi_source {
+        tracing::info!(
+            sources = sources.len(),
+            handle = summary.handle,
+            "opened merged dataset"
+        );
+    }
+    Ok(summary)
 }
 
-#[tauri::command]
-pub async fn load_index(index_path: String) -> AppResult<IndexSummary> {
-    let path = PathBuf::from(index_path);
-    spawn_blocking(move || load_index_sync(path))
-        .await
-        .map_err(|e| AppError::Task(e.to_string()))?
+/// Whether `path` is a file already in a merged view, such as the same directory picked
+/// once directly and once through a symlink. Records it otherwise.
+fn is_duplicate_file(seen: &mut HashMap<String, PathBuf>, path: &Path, filename: &str) -> bool {
+    let Some(identity) = file_identity(path) else {
+        return false;
+    };
+    match seen.get(&identity) {
+        Some(first) => {
+            tracing::warn!(
+                chunk = filename,
+                same_as = %first.display(),
+                "skipping chunk that is the same file as an earlier one"
+            );
+            true
+        }
+        None => {
+            seen.insert(identity, path.to_path_buf());
+            false
+        }
+    }
 }
 
-fn load_index_sync(index_path: PathBuf) -> AppResult<IndexSummary> {
-    parse_index(&index_path).and_then(
-        |ParsedIndex {
-             root_dir,
-             source,
-             config,
-             config_raw,
-             chunks,
-         }| {
-            let data_format = config.data_format.clone().unwrap_or_default();
-            let mut summaries = Vec::with_capacity(chunks.len());
-            for c in chunks {
-                let full = root_dir.join(&c.filename);
-                let exists = full.exists();
-                summaries.push(ChunkSummary {
-                    filename: c.filename,
-                    path: full.display().to_string(),
-                    chunk_size: c.chunk_size,
-                    chunk_bytes: c.chunk_bytes,
-                    dim: c.dim,
-                    exists,
-                });
+/// Registers a view built from `chunk_locations` and summarizes it, naming each chunk's
+/// source index when `show_sources` is set.
+fn open_merged(
+    parsed: ParsedIndex,
+    show_sources: bool,
+    workspace: &Workspace,
+    window: &str,
+) -> AppResult<IndexSummary> {
+    let chunks = parsed
+        .chunks
+        .iter()
+        .map(|c| {
+            let location = &parsed.chunk_locations[&c.filename];
+            ChunkSummary {
+                filename: c.filename.clone(),
+                path: shown_path(&location.path),
+                source: show_sources.then(|| shown_path(&location.source)),
+                chunk_size: c.chunk_size,
+                chunk_bytes: c.chunk_bytes,
+                dim: c.dim,
+                exists: Some(location.path.is_file()),
             }
-            Ok(IndexSummary {
-                index_path: source.display().to_string(),
-                root_dir: root_dir.display().to_string(),
-                data_format,
-                compression: config.compression.clone(),
-                chunk_size: config.chunk_size,
-                chunk_bytes: config.chunk_bytes,
-                config_raw,
-                chunks: summaries,
-            })
-        },
-    )
+        })
+        .collect();
+    let data_format = parsed.config.data_format.clone().unwrap_or_default();
+    let summary_config = parsed.config.clone();
+    let config_raw = parsed.config_raw.clone();
+    let metadata = parsed.metadata.clone();
+    let index_path = shown_path(&parsed.source);
+    let root_dir = shown_path(&parsed.root_dir);
+    let handle = workspace.open_in(Arc::new(parsed), window)?;
+    Ok(IndexSummary {
+        handle,
+        index_path,
+        root_dir,
+        data_format,
+        compression: summary_config.compression,
+        chunk_size: summary_config.chunk_size,
+        chunk_bytes: summary_config.chunk_bytes,
+        config_raw,
+        format_version: metadata.format_version,
+        updated_at: metadata.updated_at,
+        litdata_version: metadata.litdata_version,
+        chunks,
+    })
 }
 
+/// Opens every per-worker `N.index.json` in `dir_path` as one dataset, for outputs of a
+/// distributed optimize that were never merged into a single `index.json`. Each chunk's
+/// summary names the index it came from. The shards must agree on `data_format` and
+/// compression; config comes from the first.
 #[tauri::command]
-pub async fn load_chunk_list(paths: Vec<String>) -> AppResult<IndexSummary> {
-    spawn_blocking(move || load_chunk_list_sync(paths))
+pub async fn open_index_shards(
+    window: tauri::WebviewWindow,
+    dir_path: String,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<IndexSummary> {
+    let workspace = (*workspace).clone();
+    let window = window.label().to_string();
+    spawn_blocking(move || open_index_shards_sync(Path::new(dir_path.trim()), &workspace, &window))
         .await
         .map_err(|e| AppError::Task(e.to_string()))?
 }
 
-fn load_chunk_list_sync(paths: Vec<String>) -> AppResult<IndexSummary> {
-    if paths.is_empty() {
-        return Err(AppError::Invalid("no chunk paths provided".into()));
+fn open_index_shards_sync(
+    dir: &Path,
+    workspace: &Workspace,
+    window: &str,
+) -> AppResult<IndexSummary> {
+    if !dir.is_dir() {
+        return Err(AppError::Invalid(format!(
+            "{} is not a directory",
+            dir.display()
+        )));
     }
+    let shards = index_shards(dir);
+    let Some(first_path) = shards.first().cloned() else {
+        return Err(AppError::Invalid(format!(
+            "no N.index.json files in {}",
+            dir.display()
+        )));
+    };
+    let mut parsed_shards = shards
+        .iter()
+        .map(|shard| parse_index_file(shard))
+        .collect::<AppResult<Vec<_>>>()?;
+    let first = parsed_shards.remove(0);
     let mut raw_chunks: Vec<RawChunk> = Vec::new();
-    let mut root_dir = None;
-    let mut name_to_path: HashMap<String, PathBuf> = HashMap::new();
-    let mut index_path: Option<PathBuf> = None;
-    let mut data_format: Vec<String> = vec!["bytes".into()];
-    let mut compression: Option<String> = None;
-    let mut chunk_size: Option<u32> = None;
-    let mut chunk_bytes: Option<u64> = None;
-    let mut config_raw: Option<serde_json::Value> = None;
-    for p in &paths {
-        let path = PathBuf::from(p);
-        if root_dir.is_none() {
-            root_dir = path.parent().map(|pp| pp.to_path_buf());
-        }
-        if let Some(name) = path
-            .file_name()
-            .and_then(|f| f.to_str())
-            .map(|s| s.to_string())
-        {
-            name_to_path.insert(name, path.clone());
-        }
-    }
-    if let Some(found_index_path) = find_neighbor_index(Path::new(&paths[0])) {
-        let parsed = parse_index_file(&found_index_path)?;
-        data_format = parsed
-            .config
-            .data_format
-            .clone()
-            .unwrap_or_else(|| data_format.clone());
-        compression = parsed.config.compression.clone();
-        chunk_size = parsed.config.chunk_size;
-        chunk_bytes = parsed.config.chunk_bytes;
-        config_raw = Some(parsed.config_raw.clone());
-        index_path = Some(found_index_path);
-        root_dir = Some(parsed.root_dir.clone());
-        let selected: HashSet<String> = name_to_path.keys().cloned().collect();
-        for c in parsed.chunks {
-            if selected.contains(&c.filename) {
-                raw_chunks.push(c);
+    let mut chunk_locations: HashMap<String, ChunkLocation> = HashMap::new();
+    let mut seen_files: HashMap<String, PathBuf> = HashMap::new();
+    let (config, config_raw) = (first.config.clone(), first.config_raw.clone());
+    let metadata = first.metadata.clone();
+    for (shard, parsed) in shards
+        .iter()
+        .zip(std::iter::once(first).chain(parsed_shards))
+    {
+        ensure_same_setting(
+            "data_format",
+            shard,
+            &parsed.config.data_format,
+            &first_path,
+            &config.data_format,
+        )?;
+        ensure_same_setting(
+            "compression",
+            shard,
+            &parsed.config.compression,
+            &first_path,
+            &config.compression,
+        )?;
+        for chunk in parsed.chunks {
+            // Shards share the directory, so a repeated name is the same file.
+            if chunk_locations.contains_key(&chunk.filename) {
+                tracing::warn!(
+                    chunk = %chunk.filename,
+                    index = %shard.display(),
+                    "chunk listed by more than one index shard"
+                );
+                continue;
+            }
+            let path = confine_to_root(dir, &chunk.filename)?;
+            if is_duplicate_file(&mut seen_files, &path, &chunk.filename) {
+                continue;
             }
+            chunk_locations.insert(
+                chunk.filename.clone(),
+                ChunkLocation {
+                    path,
+                    source: shard.clone(),
+                },
+            );
+            raw_chunks.push(chunk);
         }
     }
+    if raw_chunks.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "the index shards in {} list no chunks",
+            dir.display()
+        )));
+    }
+    let parsed = ParsedIndex {
+        root_dir: dir.to_path_buf(),
+        source: first_path,
+        config,
+        config_raw,
+        chunks: raw_chunks,
+        metadata,
+        chunk_locations,
+    };
+    let summary = open_merged(parsed, true, workspace, window)?;
+    tracing::info!(
+        shards = shards.len(),
+        handle = summary.handle,
+        "opened index shards"
+    );
+    Ok(summary)
+}
 
-    let root_dir = root_dir.unwrap_or_else(|| PathBuf::from("."));
-
-    let covered: HashSet<String> = raw_chunks.iter().map(|c| c.filename.clone()).collect();
-    for (name, path) in &name_to_path {
-        if covered.contains(name) {
-            continue;
+pub(crate) fn load_chunk_access(
+    parsed: &ParsedIndex,
+    chunk_filename: &str,
+    cache: &ChunkCache,
+) -> AppResult<ChunkAccess> {
+    let chunk_path = parsed.chunk_path(chunk_filename)?;
+    let archived = archive::contains(&chunk_path);
+    if !archived && !chunk_path.exists() {
+        return Err(AppError::Missing(chunk_path.display().to_string()));
+    }
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(ref c) if c == "zstd" => {
+            let key = chunk_cache_key(&chunk_path);
+            if let Some(buf) = cache.fetch(&key) {
+                return Ok(ChunkAccess::Memory(buf));
+            }
+            if let Some(buf) = disk_cache::load(&chunk_path) {
+                let buf: Arc<[u8]> = buf.into();
+                cache.maybe_store(&key, buf.clone());
+                return Ok(ChunkAccess::Memory(buf));
+            }
+            let compressed = if archived {
+                archive::read_member(&chunk_path)?
+            } else {
+                fs::read(&chunk_path)?
+            };
+            let declared_len = parsed.declared_chunk_bytes(chunk_filename);
+            inflate_chunk(
+                &chunk_path,
+                chunk_filename,
+                &compressed,
+                declared_len,
+                cache,
+                None,
+            )
+            .map(ChunkAccess::Memory)
         }
-        let info = fs::metadata(path)?;
-        let size = info.len();
-        let mut file = File::open(path)?;
-        let mut num_buf = [0u8; 4];
-        file.read_exact(&mut num_buf)?;
-        let num_items = read_le_u32(&num_buf)?.max(1);
-        let offsets_len = (num_items as usize + 1) * 4;
-        let mut offsets = vec![0u8; offsets_len];
-        file.read_exact(&mut offsets)?;
-        raw_chunks.push(RawChunk {
-            filename: name.clone(),
-            chunk_bytes: size,
-            chunk_size: num_items,
-            dim: None,
-        });
+        Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None if archived => archive::member_access(&chunk_path)?
+            .ok_or_else(|| AppError::Missing(chunk_path.display().to_string())),
+        None => Ok(ChunkAccess::File(chunk_path)),
     }
-
-    let config_raw_default_fmt = data_format.clone();
-    let config_raw = config_raw.unwrap_or_else(|| {
-        serde_json::json!({
-            "source": "multi-bin",
-            "data_format": config_raw_default_fmt,
-        })
-    });
-
-    let resolved_index_path = index_path.unwrap_or_else(|| PathBuf::from(&paths[0]));
-
-    Ok(IndexSummary {
-        index_path: resolved_index_path.display().to_string(),
-        root_dir: root_dir.display().to_string(),
-        data_format,
-        compression,
-        chunk_size,
-        chunk_bytes,
-        config_raw,
-        chunks: raw_chunks
-            .into_iter()
-            .map(|c| {
-                let path = name_to_path
-                    .get(&c.filename)
-                    .cloned()
-                    .unwrap_or_else(|| root_dir.join(&c.filename));
-                ChunkSummary {
-                    filename: c.filename,
-                    path: path.display().to_string(),
-                    chunk_size: c.chunk_size,
-                    chunk_bytes: c.chunk_bytes,
-                    dim: c.dim,
-                    exists: true,
-                }
-            })
-            .collect(),
-    })
 }
 
-fn load_chunk_access(
+/// `load_chunk_access` for async commands: the chunk is read through tokio and only
+/// decompression runs on the blocking pool, reported under the `decompressing` phase.
+pub(crate) async fn load_chunk_access_async(
     parsed: &ParsedIndex,
     chunk_filename: &str,
     cache: &ChunkCache,
+    progress: Option<&Progress>,
 ) -> AppResult<ChunkAccess> {
-    let chunk_path = parsed.root_dir.join(chunk_filename);
-    if !chunk_path.exists() {
+    let chunk_path = parsed.chunk_path(chunk_filename)?;
+    let archived = archive::contains(&chunk_path);
+    if !archived && !aio::exists(&chunk_path).await {
         return Err(AppError::Missing(chunk_path.display().to_string()));
     }
     match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
         Some(ref c) if c == "zstd" => {
-            let key = chunk_path.display().to_string();
+            // The same key `inflate_chunk` stores under; resolving links may block.
+            let key_path = chunk_path.clone();
+            let key = spawn_blocking(move || chunk_cache_key(&key_path))
+                .await
+                .map_err(|e| AppError::Task(e.to_string()))?;
             if let Some(buf) = cache.fetch(&key) {
                 return Ok(ChunkAccess::Memory(buf));
             }
-            let file = File::open(&chunk_path)?;
-            let mut decoder = zstd::stream::Decoder::new(file)?;
-            let mut buf = Vec::new();
-            decoder
-                .read_to_end(&mut buf)
-                .map_err(|e| AppError::Invalid(format!("decompressing chunk: {e}")))?;
-            cache.maybe_store(&key, buf.clone());
-            Ok(ChunkAccess::Memory(buf))
+            let spill_path = chunk_path.clone();
+            let spilled = spawn_blocking(move || disk_cache::load(&spill_path))
+                .await
+                .map_err(|e| AppError::Task(e.to_string()))?;
+            if let Some(buf) = spilled {
+                let buf: Arc<[u8]> = buf.into();
+                cache.maybe_store(&key, buf.clone());
+                return Ok(ChunkAccess::Memory(buf));
+            }
+            let compressed = if archived {
+                let member = chunk_path.clone();
+                spawn_blocking(move || archive::read_member(&member))
+                    .await
+                    .map_err(|e| AppError::Task(e.to_string()))??
+            } else {
+                aio::read(&chunk_path).await?
+            };
+            let declared_len = parsed.declared_chunk_bytes(chunk_filename);
+            let chunk_filename = chunk_filename.to_string();
+            let cache = cache.clone();
+            let progress = progress.cloned();
+            spawn_blocking(move || {
+                inflate_chunk(
+                    &chunk_path,
+                    &chunk_filename,
+                    &compressed,
+                    declared_len,
+                    &cache,
+                    progress.as_ref(),
+                )
+            })
+            .await
+            .map_err(|e| AppError::Task(e.to_string()))?
+            .map(ChunkAccess::Memory)
         }
         Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None if archived => spawn_blocking(move || archive::member_access(&chunk_path))
+            .await
+            .map_err(|e| AppError::Task(e.to_string()))??
+            .ok_or_else(|| AppError::Missing(chunk_filename.to_string())),
         None => Ok(ChunkAccess::File(chunk_path)),
     }
 }
 
-fn parse_offsets(access: &ChunkAccess) -> AppResult<(u32, Vec<u32>)> {
-    let num_buf = access.read_exact_at(0, 4)?;
-    let num_items = read_le_u32(&num_buf)?;
-    let offsets_len = (num_items as usize + 1) * 4;
-    let offsets_buf = access.read_exact_at(4, offsets_len)?;
-    let mut offsets = Vec::with_capacity(num_items as usize + 1);
-    for chunk in offsets_buf.chunks_exact(4) {
-        offsets.push(read_le_u32(chunk)?);
+/// Decompresses a zstd chunk and records the result in both cache tiers. The index's
+/// `chunk_bytes` is reserved against the memory limit while the chunk inflates, and
+/// concurrent requests for the same chunk share a single decompression.
+#[tracing::instrument(skip_all, fields(chunk = chunk_filename, compressed = compressed.len()))]
+fn inflate_chunk(
+    chunk_path: &Path,
+    chunk_filename: &str,
+    compressed: &[u8],
+    declared_len: u64,
+    cache: &ChunkCache,
+    progress: Option<&Progress>,
+) -> AppResult<Arc<[u8]>> {
+    let key = chunk_cache_key(chunk_path);
+    cache.load_once(&key, || {
+        let _reservation = memory::reserve(declared_len.max(compressed.len() as u64))?;
+        // Multi-frame chunks inflate frame-by-frame in parallel; anything else, including
+        // damaged streams, takes the sequential path so truncation is still diagnosed.
+        let buf = match inflate::decompress_frames(compressed, progress) {
+            Some(Ok(buf)) => buf,
+            _ => {
+                let reader = ProgressReader::new(
+                    compressed,
+                    progress,
+                    "decompressing",
+                    compressed.len() as u64,
+                );
+                decompress_chunk(chunk_filename, reader)?
+            }
+        };
+        disk_cache::store(chunk_path, &buf);
+        let buf: Arc<[u8]> = buf.into();
+        cache.maybe_store(&key, buf.clone());
+        Ok(buf)
+    })
+}
+
+/// Like `load_chunk_access`, but a zstd chunk that isn't cached yet is decoded only as
+/// far as reads need, so a preview doesn't pay for the whole chunk.
+#[tracing::instrument(skip_all, fields(chunk = chunk_filename))]
+pub(crate) fn load_chunk_prefix(
+    parsed: &ParsedIndex,
+    chunk_filename: &str,
+    cache: &ChunkCache,
+) -> AppResult<ChunkAccess> {
+    let is_zstd = parsed
+        .config
+        .compression
+        .as_ref()
+        .is_some_and(|c| c.eq_ignore_ascii_case("zstd"));
+    let declared_len = parsed.declared_chunk_bytes(chunk_filename);
+    if !is_zstd || declared_len == 0 {
+        return load_chunk_access(parsed, chunk_filename, cache);
+    }
+    let chunk_path = parsed.chunk_path(chunk_filename)?;
+    if archive::split_archive_path(&chunk_path).is_some() {
+        return load_chunk_access(parsed, chunk_filename, cache);
+    }
+    if !chunk_path.exists() {
+        return Err(AppError::Missing(chunk_path.display().to_string()));
+    }
+    let key = chunk_cache_key(&chunk_path);
+    if let Some(buf) = cache.fetch(&key) {
+        return Ok(ChunkAccess::Memory(buf));
     }
-    Ok((num_items, offsets))
+    if let Some(buf) = disk_cache::load(&chunk_path) {
+        let buf: Arc<[u8]> = buf.into();
+        cache.maybe_store(&key, buf.clone());
+        return Ok(ChunkAccess::Memory(buf));
+    }
+    let stream = cache.stream(&key, || StreamPrefix::open(&chunk_path, declared_len))?;
+    Ok(ChunkAccess::Stream(stream))
 }
 
 #[tauri::command]
 pub async fn list_chunk_items(
-    index_path: String,
+    app: AppHandle,
+    handle: DatasetHandle,
     chunk_filename: String,
+    job_id: Option<String>,
     cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
 ) -> AppResult<Vec<ItemMeta>> {
-    let path = PathBuf::from(index_path);
-    let cache_handle = (*cache).clone();
-    spawn_blocking(move || list_chunk_items_sync(path, chunk_filename, &cache_handle))
+    let progress = Progress::new(app, job_id, "items");
+    let parsed = workspace.get(handle)?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, Some(&progress))
         .await
-        .map_err(|e| AppError::Task(e.to_string()))?
-}
-
-fn list_chunk_items_sync(
-    index_path: PathBuf,
-    chunk_filename: String,
-    cache: &ChunkCache,
-) -> AppResult<Vec<ItemMeta>> {
-    let parsed = parse_index(&index_path)?;
-    let access = load_chunk_access(&parsed, &chunk_filename, cache)?;
-    let format_len = parsed
-        .config
-        .data_format
-        .as_ref()
-        .map(|v| v.len())
-        .unwrap_or(0);
-    let header_len = format_len * 4;
-    let (num_items, offsets) = parse_offsets(&access)?;
-    let mut items = Vec::with_capacity(num_items as usize);
-    for item_idx in 0..num_items {
-        let start = offsets[item_idx as usize];
-        let end = offsets[item_idx as usize + 1];
-        if end < start {
-            return Err(AppError::MalformedChunk);
-        }
-        let mut sizes = Vec::new();
-        if header_len > 0 {
-            let head = access.read_exact_at(start as u64, header_len)?;
-            for j in 0..format_len {
-                let pos = j * 4;
-                sizes.push(read_le_u32(&head[pos..pos + 4])?);
-            }
+        .within(|| context.clone())?;
+    prefetch::prefetch_next(&parsed, &chunk_filename, &cache);
+    let format_len = parsed.format_len();
+    let sidecar = workspace.sidecar(handle)?;
+    spawn_blocking(move || {
+        let mut items = collect_item_meta(&access, format_len)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
+        if let Some(sidecar) = sidecar {
+            sidecar.join(&parsed, &chunk_filename, &mut items);
         }
-        items.push(ItemMeta {
-            item_index: item_idx,
-            total_bytes: (end - start) as u64,
-            fields: sizes
-                .into_iter()
-                .enumerate()
-                .map(|(idx, size)| FieldMeta {
-                    field_index: idx,
-                    size,
-                })
-                .collect(),
-        });
-    }
-    Ok(items)
+        Ok(items)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn peek_field(
-    index_path: String,
+    app: AppHandle,
+    handle: DatasetHandle,
     chunk_filename: String,
     item_index: u32,
     field_index: usize,
     cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
 ) -> AppResult<FieldPreview> {
     let cache_handle = (*cache).clone();
+    let offsets = (*offsets).clone();
+    let parsed = workspace.get(handle)?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
     spawn_blocking(move || {
-        preview_field(
-            &index_path,
+        let mut preview = preview_field(
+            &parsed,
             &chunk_filename,
             item_index,
             field_index,
             &cache_handle,
-        )
+            &offsets,
+        )?;
+        if let (Some(names), Some(text)) = (
+            class_names::for_dataset(&app, &parsed),
+            &preview.preview_text,
+        ) {
+            preview.class_name = names.name(field_index, text).map(str::to_string);
+        }
+        Ok(preview)
     })
     .await
     .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
 }
 
-fn preview_field(
-    index_path: &str,
+pub(crate) fn preview_field(
+    parsed: &ParsedIndex,
     chunk_filename: &str,
     item_index: u32,
     field_index: usize,
     cache: &ChunkCache,
+    offsets: &OffsetsCache,
 ) -> AppResult<FieldPreview> {
-    let parsed = parse_index(Path::new(index_path))?;
     let fmt = parsed.config.data_format.clone().unwrap_or_default();
-    let access = load_chunk_access(&parsed, chunk_filename, cache)?;
+    let access = load_chunk_prefix(parsed, chunk_filename, cache)?;
+    let table = offsets
+        .get(&parsed.chunk_path(chunk_filename)?, &access)
+        .map_err(|e| explain_corruption(e, &access, chunk_filename))?;
     let (data, size) = read_field_bytes(
         &access,
+        &table,
         item_index,
         field_index,
         fmt.len(),
-        Some(PREVIEW_BYTES),
-    )?;
-    let text = String::from_utf8(data.clone()).ok();
-    let guessed_ext = guess_ext(fmt.get(field_index), &data);
-    let hex_snippet = hex_encode(data.iter().take(48).copied().collect::<Vec<u8>>());
-    Ok(FieldPreview {
-        preview_text: text.as_ref().map(|s| s.chars().take(400).collect()),
-        hex_snippet,
-        guessed_ext,
-        is_binary: text.is_none(),
-        size,
+        Some(settings::current().preview_bytes),
+    )
+    .map_err(|e| explain_corruption(e, &access, chunk_filename))?;
+    usage::record_bytes(&parsed.source, data.len());
+    Ok(build_preview(data, size, fmt.get(field_index)))
+}
+
+/// Raw bytes `[offset, offset + length)` of a field, sent over Tauri's binary IPC channel
+/// so media payloads skip JSON encoding. `length` defaults to the rest of the field.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn read_field_range(
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: usize,
+    offset: Option<u64>,
+    length: Option<u64>,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
+) -> AppResult<Response> {
+    let cache_handle = (*cache).clone();
+    let offsets = (*offsets).clone();
+    let parsed = workspace.get(handle)?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    spawn_blocking(move || {
+        let _span = tracing::info_span!(
+            "read_field_range",
+            chunk = %chunk_filename,
+            item = item_index,
+            field = field_index
+        )
+        .entered();
+        let access = load_chunk_prefix(&parsed, &chunk_filename, &cache_handle)?;
+        let explain = |e: AppError| explain_corruption(e, &access, &chunk_filename);
+        let table = offsets
+            .get(&parsed.chunk_path(&chunk_filename)?, &access)
+            .map_err(explain)?;
+        let (start, size) = field_span(
+            &access,
+            &table,
+            item_index,
+            field_index,
+            parsed.format_len(),
+        )
+        .map_err(explain)?;
+        let offset = offset.unwrap_or(0);
+        if offset > size as u64 {
+            return Err(AppError::Invalid(format!(
+                "offset {offset} is past the end of a {size}-byte field"
+            )));
+        }
+        let available = size as u64 - offset;
+        let len = length.map_or(available, |l| l.min(available));
+        let data = access
+            .read_exact_at(start + offset, len as usize)
+            .map_err(explain)
+            .within(|| ErrorContext::default().offset(start + offset))?;
+        usage::record_bytes(&parsed.source, data.len());
+        Ok(Response::new(data))
     })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
 }
 
 #[tauri::command]
 pub async fn open_leaf(
-    index_path: String,
+    handle: DatasetHandle,
     chunk_filename: String,
     item_index: u32,
     field_index: usize,
     cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
 ) -> AppResult<String> {
-    let cache_handle = (*cache).clone();
+    let parsed = workspace.get(handle)?;
+    ensure_writable(&settings::temp_root(), &workspace.dataset_dirs(&parsed))?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, None)
+        .await
+        .within(|| context.clone())?;
+    let offsets = (*offsets).clone();
     spawn_blocking(move || {
-        let path = PathBuf::from(&index_path);
+        let table = offsets
+            .get(&parsed.chunk_path(&chunk_filename)?, &access)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
         open_leaf_inner(
-            &path,
+            &access,
+            &table,
+            parsed.config.data_format.as_deref().unwrap_or_default(),
+            &parsed.source,
             &chunk_filename,
             item_index,
             field_index,
-            &cache_handle,
         )
     })
     .await
     .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
 }
 
 fn open_leaf_inner(
+    access: &ChunkAccess,
+    table: &(u32, Vec<u64>),
+    fmt: &[String],
     index_path: &Path,
     chunk_filename: &str,
     item_index: u32,
     field_index: usize,
-    cache: &ChunkCache,
 ) -> AppResult<String> {
-    let parsed = parse_index(index_path)?;
-    let fmt = parsed.config.data_format.clone().unwrap_or_default();
-    let access = load_chunk_access(&parsed, chunk_filename, cache)?;
-    let (data, size) = read_field_bytes(&access, item_index, field_index, fmt.len(), None)?;
-    let ext = guess_ext(fmt.get(field_index), &data).unwrap_or_else(|| "bin".into());
-    let temp_dir = std::env::temp_dir().join("litdata-viewer");
-    fs::create_dir_all(&temp_dir)?;
-    let out = temp_dir.join(format!(
-        "{}-i{}-f{}.{}",
-        sanitize(chunk_filename),
+    let (out, ext, size) = materialize_leaf(
+        access,
+        table,
+        fmt,
+        index_path,
+        chunk_filename,
         item_index,
         field_index,
-        ext
-    ));
-    fs::write(&out, data)?;
-    open::that_detached(&out).map_err(|e| AppError::Open(e.to_string()))?;
+    )?;
+    match settings::viewer_for(&ext) {
+        Some(viewer) => viewer.launch(&out)?,
+        None => open::that_detached(&out).map_err(|e| AppError::Open(e.to_string()))?,
+    }
+    hooks::run(&HookContext {
+        event: HookEvent::Open,
+        path: &out,
+        ext: &ext,
+        index_path,
+        chunk_filename,
+        item_index,
+        field_index,
+    });
     Ok(format!("{} ({} bytes)", out.display(), size))
 }
 
-fn read_field_bytes(
+/// Writes a field to the temp folder under a name carrying its guessed extension.
+/// Returns the file, the extension and the field size.
+pub(crate) fn materialize_leaf(
     access: &ChunkAccess,
+    table: &(u32, Vec<u64>),
+    fmt: &[String],
+    index_path: &Path,
+    chunk_filename: &str,
     item_index: u32,
     field_index: usize,
-    format_len: usize,
-    limit: Option<usize>,
-) -> AppResult<(Vec<u8>, u32)> {
-    let header_len = format_len * 4;
-    let (num_items, offsets) = parse_offsets(access)?;
-    if item_index >= num_items {
-        return Err(AppError::Invalid("item index out of range".into()));
-    }
-    let start = offsets[item_index as usize];
-    let end = offsets[item_index as usize + 1];
-    if end < start {
-        return Err(AppError::MalformedChunk);
-    }
-    let header = if header_len > 0 {
-        Some(access.read_exact_at(start as u64, header_len)?)
-    } else {
-        None
-    };
-    let mut sizes = Vec::new();
-    if let Some(head) = header {
-        for j in 0..format_len {
-            let pos = j * 4;
-            sizes.push(read_le_u32(&head[pos..pos + 4])?);
-        }
-    }
-    if field_index >= sizes.len() {
-        return Err(AppError::Invalid("field index out of range".into()));
-    }
-    let mut cursor = start as u64 + header_len as u64;
-    for (idx, sz) in sizes.iter().enumerate() {
-        if idx == field_index {
-            let desired = limit.map(|l| l.min(*sz as usize)).unwrap_or(*sz as usize);
-            let data = access.read_exact_at(cursor, desired)?;
-            return Ok((data, *sz));
-        }
-        cursor += *sz as u64;
-    }
-    Err(AppError::MalformedChunk)
+) -> AppResult<(PathBuf, String, u32)> {
+    let (data, size) = read_field_bytes(access, table, item_index, field_index, fmt.len(), None)
+        .map_err(|e| explain_corruption(e, access, chunk_filename))?;
+    usage::record_bytes(index_path, data.len());
+    let ext = guess_ext(fmt.get(field_index), &data).unwrap_or_else(|| "bin".into());
+    let temp_dir = settings::temp_root();
+    fs::create_dir_all(&temp_dir)?;
+    let out = temp_dir.join(leaf_file_name(
+        chunk_filename,
+        item_index,
+        field_index,
+        &ext,
+    ));
+    fs::write(&out, data)?;
+    Ok((out, ext, size))
 }
 
-fn guess_ext(data_format: Option<&String>, data: &[u8]) -> Option<String> {
-    if let Some(fmt) = data_format {
-        let fmt_lower = fmt.to_lowercase();
-        if fmt_lower == "bytes" || fmt_lower == "bin" {
-            if let Some(magic) = detect_magic_ext(data) {
-                return Some(magic);
-            }
-            return Some("bin".into());
-        }
-    }
-    if let Some(fmt) = data_format {
-        if let Some((_, subtype)) = fmt.split_once(':') {
-            if !subtype.is_empty() {
-                return Some(subtype.trim().trim_start_matches('.').to_string());
-            }
-        }
-        if let Some((_, ext)) = fmt.rsplit_once('.') {
-            if !ext.is_empty() {
-                return Some(ext.to_string());
-            }
-        }
-        let fmt_lower = fmt.to_lowercase();
-        let map = [
-            ("jpeg", "jpg"),
-            ("jpg", "jpg"),
-            ("pil", "png"),
-            ("png", "png"),
-            ("tiff", "tiff"),
-            ("str", "txt"),
-            ("string", "txt"),
-            ("int", "txt"),
-            ("float", "txt"),
-            ("bool", "txt"),
-            ("bytes", "bin"),
-            ("audio", "wav"),
-        ];
-        if let Some((_, ext)) = map.iter().find(|(k, _)| *k == fmt_lower) {
-            return Some((*ext).into());
-        }
-        if fmt_lower.contains("wav") {
-            return Some("wav".into());
-        }
-        if fmt_lower.contains("mp3") {
-            return Some("mp3".into());
-        }
-        if fmt_lower.contains("flac") {
-            return Some("flac".into());
-        }
-    }
-    if let Some(magic_ext) = detect_magic_ext(data) {
-        return Some(magic_ext);
-    }
-    if std::str::from_utf8(data)
-        .map(|s| s.trim().len() > 0)
-        .unwrap_or(false)
-    {
-        return Some("txt".into());
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_field(
+    app: AppHandle,
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: usize,
+    dest_path: String,
+    audio: Option<AudioConversion>,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
+) -> AppResult<ExportedField> {
+    if dest_path.trim().is_empty() {
+        return Err(AppError::Invalid("export destination is empty".into()));
     }
-    infer::get(data).map(|t| t.extension().to_string())
-}
-
-fn sanitize(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
-        .collect()
+    let progress = Progress::new(app, job_id, "export");
+    let parsed = workspace.get(handle)?;
+    ensure_writable(
+        Path::new(dest_path.trim()),
+        &workspace.dataset_dirs(&parsed),
+    )?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, Some(&progress))
+        .await
+        .within(|| context.clone())?;
+    let offsets = (*offsets).clone();
+    spawn_blocking(move || {
+        let table = offsets
+            .get(&parsed.chunk_path(&chunk_filename)?, &access)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
+        export_field_inner(
+            &access,
+            &table,
+            parsed.config.data_format.as_deref().unwrap_or_default(),
+            &parsed.source,
+            &chunk_filename,
+            item_index,
+            field_index,
+            Path::new(dest_path.trim()),
+            audio.as_ref(),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
 }
 
-fn detect_magic_ext(data: &[u8]) -> Option<String> {
-    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
-        return Some("wav".into());
-    }
-    if data.len() >= 3 && &data[0..3] == b"ID3" {
-        return Some("mp3".into());
-    }
-    if data.len() >= 2 && data[0] == 0xFF && (data[1] & 0xE0) == 0xE0 {
-        return Some("mp3".into());
+#[allow(clippy::too_many_arguments)]
+fn export_field_inner(
+    access: &ChunkAccess,
+    table: &(u32, Vec<u64>),
+    fmt: &[String],
+    index_path: &Path,
+    chunk_filename: &str,
+    item_index: u32,
+    field_index: usize,
+    dest: &Path,
+    audio: Option<&AudioConversion>,
+    progress: &Progress,
+) -> AppResult<ExportedField> {
+    let (data, size) = read_field_bytes(access, table, item_index, field_index, fmt.len(), None)
+        .map_err(|e| explain_corruption(e, access, chunk_filename))?;
+    usage::record_bytes(index_path, data.len());
+    let guessed = guess_ext(fmt.get(field_index), &data);
+    let ext = match audio {
+        Some(_) => "wav".to_string(),
+        None => guessed.clone().unwrap_or_else(|| "bin".into()),
+    };
+    let out = if dest.is_dir() {
+        dest.join(leaf_file_name(
+            chunk_filename,
+            item_index,
+            field_index,
+            &ext,
+        ))
+    } else {
+        dest.to_path_buf()
+    };
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)?;
     }
-    if data.len() >= 4 && &data[0..4] == b"fLaC" {
-        return Some("flac".into());
+    progress.report("writing", 0, 1);
+    match audio {
+        Some(conversion) => transcode_to_wav(&data, guessed.as_deref(), conversion, &out)?,
+        None => fs::write(&out, &data)?,
     }
-    None
+    progress.report("done", 1, 1);
+    let hooks = hooks::run(&HookContext {
+        event: HookEvent::Export,
+        path: &out,
+        ext: &ext,
+        index_path,
+        chunk_filename,
+        item_index,
+        field_index,
+    });
+    Ok(ExportedField {
+        path: out.display().to_string(),
+        size,
+        transcoded: audio.is_some(),
+        hooks,
+    })
 }
@@ -0,0 +1,188 @@
+use crate::litdata::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const LOG_FILE: &str = "litdata-viewer.log";
+/// The live file is rotated to `.1` once it passes this size.
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+/// Rotated files kept besides the live one; the oldest is deleted on rotation.
+const MAX_ROTATED_FILES: usize = 4;
+const DEFAULT_RECENT_LOGS: usize = 500;
+
+/// One JSON line of the log file.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    timestamp_ms: u64,
+    level: String,
+    target: String,
+    message: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    fields: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogDump {
+    log_dir: String,
+    records: Vec<LogRecord>,
+}
+
+struct LogSink {
+    dir: PathBuf,
+    file: File,
+    written: u64,
+}
+
+fn sink() -> &'static Mutex<Option<LogSink>> {
+    static SINK: OnceLock<Mutex<Option<LogSink>>> = OnceLock::new();
+    SINK.get_or_init(|| Mutex::new(None))
+}
+
+fn rotated(dir: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        dir.join(LOG_FILE)
+    } else {
+        dir.join(format!("{LOG_FILE}.{n}"))
+    }
+}
+
+fn open_live(dir: &Path) -> std::io::Result<LogSink> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rotated(dir, 0))?;
+    let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok(LogSink {
+        dir: dir.to_path_buf(),
+        file,
+        written,
+    })
+}
+
+fn rotate(sink: &mut LogSink) -> std::io::Result<()> {
+    let _ = fs::remove_file(rotated(&sink.dir, MAX_ROTATED_FILES));
+    for n in (0..MAX_ROTATED_FILES).rev() {
+        let from = rotated(&sink.dir, n);
+        if from.exists() {
+            fs::rename(&from, rotated(&sink.dir, n + 1))?;
+        }
+    }
+    *sink = open_live(&sink.dir)?;
+    Ok(())
+}
+
+/// Opens the log file in the app log dir. Events emitted before this are dropped.
+pub(crate) fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_log_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    if let (Ok(live), Ok(mut guard)) = (open_live(&dir), sink().lock()) {
+        *guard = Some(live);
+    }
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!("panic: {info}");
+        default_hook(info);
+    }));
+}
+
+/// Appends one record; logging failures are swallowed so they never fail a command.
+pub(crate) fn write(level: &str, target: &str, message: String, fields: String) {
+    let Ok(mut guard) = sink().lock() else {
+        return;
+    };
+    let Some(live) = guard.as_mut() else {
+        return;
+    };
+    let record = LogRecord {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message,
+        fields,
+    };
+    let Ok(mut line) = serde_json::to_vec(&record) else {
+        return;
+    };
+    line.push(b'\n');
+    if live.written + line.len() as u64 > MAX_LOG_BYTES && rotate(live).is_err() {
+        return;
+    }
+    if live.file.write_all(&line).is_ok() {
+        live.written += line.len() as u64;
+    }
+}
+
+fn read_records(path: &Path) -> Vec<LogRecord> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// Newest-first log records across the live and rotated files, for attaching to bug
+/// reports. `level` keeps records at that severity or worse.
+#[tauri::command]
+pub async fn get_recent_logs(limit: Option<usize>, level: Option<String>) -> AppResult<LogDump> {
+    let dir = sink()
+        .lock()
+        .map_err(|_| AppError::Task("log lock poisoned".into()))?
+        .as_ref()
+        .map(|s| s.dir.clone())
+        .ok_or_else(|| AppError::Invalid("logging is not initialised".into()))?;
+    let min_rank = match level.as_deref() {
+        Some(l) => {
+            severity(l).ok_or_else(|| AppError::Invalid(format!("unknown log level {l:?}")))?
+        }
+        None => severity("TRACE").unwrap_or_default(),
+    };
+    let limit = limit.unwrap_or(DEFAULT_RECENT_LOGS);
+    spawn_blocking(move || {
+        let mut records = Vec::new();
+        for n in 0..=MAX_ROTATED_FILES {
+            if records.len() >= limit {
+                break;
+            }
+            let mut chunk = read_records(&rotated(&dir, n));
+            chunk.retain(|r| severity(&r.level).is_some_and(|rank| rank <= min_rank));
+            records.extend(chunk.into_iter().rev());
+        }
+        records.truncate(limit);
+        Ok(LogDump {
+            log_dir: dir.display().to_string(),
+            records,
+        })
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// 0 for ERROR up to 4 for TRACE.
+fn severity(level: &str) -> Option<u8> {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => Some(0),
+        "WARN" => Some(1),
+        "INFO" => Some(2),
+        "DEBUG" => Some(3),
+        "TRACE" => Some(4),
+        _ => None,
+    }
+}
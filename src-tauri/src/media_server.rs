@@ -0,0 +1,448 @@
+//! Short-lived loopback URLs for a single field or chunk file, so notebooks and other
+//! tools can fetch the exact bytes the viewer is showing. Each share has its own random
+//! token in the URL path (media elements cannot send headers) and expires on its own;
+//! the listener stops once nothing is shared.
+
+use crate::litdata::{
+    guess_ext, leaf_file_name, load_chunk_access_async, read_field_bytes, AppError, AppResult,
+    ChunkCache, ErrorContext, ResultExt,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::server::{
+    new_token, read_request, reject_busy, write_response, ConnectionSlot, HttpRequest, ACCEPT_POLL,
+    CONNECTION_TIMEOUT,
+};
+use crate::usage;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::archive;
+use litdata_core::chunk::span_len;
+use litdata_core::corruption::explain_corruption;
+use litdata_core::ChunkAccess;
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tauri::async_runtime::spawn_blocking;
+
+pub(crate) const DEFAULT_TTL_SECS: u64 = 10 * 60;
+const MAX_TTL_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedMedia {
+    token: String,
+    /// `http://127.0.0.1:<port>/media/<token>/<file name>`; anyone holding it can read.
+    url: String,
+    file_name: String,
+    content_type: String,
+    bytes: u64,
+    /// Unix seconds after which the URL stops answering.
+    expires_at: u64,
+}
+
+#[derive(Clone)]
+enum Payload {
+    /// A field's bytes, captured when it was shared.
+    Bytes(Arc<[u8]>),
+    /// A chunk file, served as stored on disk.
+    File(PathBuf),
+    /// A chunk stored uncompressed inside an archive: its span of the archive file.
+    Slice { path: PathBuf, offset: u64 },
+}
+
+struct Share {
+    payload: Payload,
+    info: SharedMedia,
+    expires: Instant,
+}
+
+struct MediaServer {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    shares: HashMap<String, Share>,
+}
+
+static MEDIA: Mutex<Option<MediaServer>> = Mutex::new(None);
+
+fn lock() -> AppResult<MutexGuard<'static, Option<MediaServer>>> {
+    MEDIA
+        .lock()
+        .map_err(|_| AppError::Task("media server lock poisoned".into()))
+}
+
+/// Drops expired shares, stopping the listener when none are left.
+fn prune(guard: &mut Option<MediaServer>) {
+    let now = Instant::now();
+    if let Some(server) = guard.as_mut() {
+        server.shares.retain(|_, share| share.expires > now);
+        if server.shares.is_empty() {
+            server.stop.store(true, Ordering::Relaxed);
+            *guard = None;
+            tracing::info!("media server stopped");
+        }
+    }
+}
+
+fn content_type_for(data: &[u8]) -> String {
+    match infer::get(data) {
+        Some(kind) => kind.mime_type().to_string(),
+        None if std::str::from_utf8(data).is_ok() => "text/plain; charset=utf-8".into(),
+        None => "application/octet-stream".into(),
+    }
+}
+
+/// A `Range: bytes=...` header as `(start, len)` within `total`. `Ok(None)` means serve
+/// everything (no header, or a form this server does not split, like multiple ranges);
+/// `Err` means the range lies outside the payload.
+fn parse_range(header: Option<&str>, total: u64) -> Result<Option<(u64, u64)>, ()> {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+    let (first, last) = if start.is_empty() {
+        let suffix: u64 = end.parse().map_err(|_| ())?;
+        if suffix == 0 {
+            return Err(());
+        }
+        (total.saturating_sub(suffix), total.saturating_sub(1))
+    } else {
+        let first: u64 = start.parse().map_err(|_| ())?;
+        let last = match end {
+            "" => total.saturating_sub(1),
+            end => end
+                .parse::<u64>()
+                .map_err(|_| ())?
+                .min(total.saturating_sub(1)),
+        };
+        (first, last)
+    };
+    if first >= total || last < first {
+        return Err(());
+    }
+    Ok(Some((first, last - first + 1)))
+}
+
+fn send(mut stream: &TcpStream, request: &HttpRequest, payload: &Payload, info: &SharedMedia) {
+    let total = info.bytes;
+    let (status, start, len) = match parse_range(request.range.as_deref(), total) {
+        Ok(None) => ("200 OK", 0, total),
+        Ok(Some((start, len))) => ("206 Partial Content", start, len),
+        Err(()) => {
+            let _ = write!(
+                stream,
+                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{total}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            return;
+        }
+    };
+    let mut head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {}\r\nContent-Length: {len}\r\nContent-Disposition: inline; filename=\"{}\"\r\nAccept-Ranges: bytes\r\nCache-Control: no-store\r\nConnection: close\r\n",
+        info.content_type, info.file_name
+    );
+    if status.starts_with("206") {
+        head.push_str(&format!(
+            "Content-Range: bytes {start}-{}/{total}\r\n",
+            start + len - 1
+        ));
+    }
+    head.push_str("\r\n");
+    // The client may already be gone; there is nobody left to report to.
+    if stream.write_all(head.as_bytes()).is_err() || request.method == "HEAD" {
+        return;
+    }
+    let sent = match payload {
+        Payload::Bytes(data) => stream.write_all(&data[start as usize..(start + len) as usize]),
+        Payload::File(path) => copy_range(path, start, len, stream),
+        Payload::Slice { path, offset } => copy_range(path, offset + start, len, stream),
+    };
+    if let Err(e) = sent {
+        tracing::debug!("media response interrupted: {e}");
+    }
+}
+
+fn copy_range(path: &Path, start: u64, len: u64, mut stream: &TcpStream) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    io::copy(&mut file.take(len), &mut stream).map(|_| ())
+}
+
+fn handle_connection(stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(CONNECTION_TIMEOUT));
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) => {
+            return write_response(
+                &stream,
+                "400 Bad Request",
+                &json!({ "error": e.to_string() }),
+            )
+        }
+    };
+    if request.method != "GET" && request.method != "HEAD" {
+        return write_response(
+            &stream,
+            "405 Method Not Allowed",
+            &json!({ "error": "only GET and HEAD are served" }),
+        );
+    }
+    let path = request.path.split('?').next().unwrap_or_default();
+    let token = path
+        .strip_prefix("/media/")
+        .and_then(|rest| rest.split('/').next());
+    let found = lock().ok().and_then(|mut guard| {
+        prune(&mut guard);
+        let share = guard.as_ref()?.shares.get(token?)?;
+        Some((share.payload.clone(), share.info.clone()))
+    });
+    match found {
+        Some((payload, info)) => send(&stream, &request, &payload, &info),
+        None => write_response(
+            &stream,
+            "404 Not Found",
+            &json!({ "error": "unknown or expired media token" }),
+        ),
+    }
+}
+
+fn serve(listener: TcpListener, stop: Arc<AtomicBool>) {
+    let active = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // Accepted sockets inherit non-blocking mode from the listener.
+                if stream.set_nonblocking(false).is_err() {
+                    continue;
+                }
+                let Some(slot) = ConnectionSlot::acquire(&active) else {
+                    reject_busy(&stream);
+                    continue;
+                };
+                thread::spawn(move || {
+                    let _slot = slot;
+                    handle_connection(stream)
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if let Ok(mut guard) = lock() {
+                    prune(&mut guard);
+                }
+                thread::sleep(ACCEPT_POLL);
+            }
+            Err(e) => {
+                tracing::warn!("media server accept failed: {e}");
+                thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+impl SharedMedia {
+    pub(crate) fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Shares bytes a caller already read, like a field, for `ttl` seconds.
+pub(crate) fn share_bytes(data: Vec<u8>, file_name: String, ttl: u64) -> AppResult<SharedMedia> {
+    let content_type = content_type_for(&data);
+    let bytes = data.len() as u64;
+    publish(
+        Payload::Bytes(data.into()),
+        file_name,
+        content_type,
+        bytes,
+        ttl,
+    )
+}
+
+/// Registers `payload`, starting the listener if it is not running.
+fn publish(
+    payload: Payload,
+    file_name: String,
+    content_type: String,
+    bytes: u64,
+    ttl: u64,
+) -> AppResult<SharedMedia> {
+    let mut guard = lock()?;
+    prune(&mut guard);
+    if guard.is_none() {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        thread::Builder::new()
+            .name("media-server".into())
+            .spawn(move || serve(listener, flag))?;
+        tracing::info!(address = %address, "media server started");
+        *guard = Some(MediaServer {
+            address,
+            stop,
+            shares: HashMap::new(),
+        });
+    }
+    let Some(server) = guard.as_mut() else {
+        return Err(AppError::Task("media server did not start".into()));
+    };
+    let ttl = ttl.clamp(1, MAX_TTL_SECS);
+    let token = new_token()?;
+    let info = SharedMedia {
+        url: format!("http://{}/media/{token}/{file_name}", server.address),
+        token: token.clone(),
+        file_name,
+        content_type,
+        bytes,
+        expires_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() + ttl)
+            .unwrap_or_default(),
+    };
+    server.shares.insert(
+        token,
+        Share {
+            payload,
+            info: info.clone(),
+            expires: Instant::now() + Duration::from_secs(ttl),
+        },
+    );
+    Ok(info)
+}
+
+/// The chunk file at `path` as stored, and its size. A chunk inside an archive is read
+/// through the archive: stored members as their span of it, compressed ones inflated.
+fn chunk_payload(path: PathBuf) -> AppResult<(Payload, u64)> {
+    match archive::member_access(&path)? {
+        Some(ChunkAccess::Slice { path, offset, len }) => {
+            Ok((Payload::Slice { path, offset }, len))
+        }
+        Some(ChunkAccess::Memory(data)) => {
+            let bytes = data.len() as u64;
+            Ok((Payload::Bytes(data), bytes))
+        }
+        Some(access) => {
+            let data = access.read_exact_at(0, span_len(0, access.len()?)?)?;
+            let bytes = data.len() as u64;
+            Ok((Payload::Bytes(data.into()), bytes))
+        }
+        None => {
+            let bytes = std::fs::metadata(&path)
+                .map_err(|_| AppError::Missing(path.display().to_string()))?
+                .len();
+            Ok((Payload::File(path), bytes))
+        }
+    }
+}
+
+/// Serves one field (`item_index` and `field_index`) or, with neither, the whole chunk
+/// file as stored, at a loopback URL that expires after `ttl_secs` (10 minutes by
+/// default, at most a day). Field bytes are captured now, so the URL keeps returning
+/// what the viewer showed even if the dataset changes.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn share_media(
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: Option<u32>,
+    field_index: Option<usize>,
+    ttl_secs: Option<u64>,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
+) -> AppResult<SharedMedia> {
+    let parsed = workspace.get(handle)?;
+    let ttl = ttl_secs.unwrap_or(DEFAULT_TTL_SECS);
+    let (item_index, field_index) = match (item_index, field_index) {
+        (Some(item), Some(field)) => (item, field),
+        (None, None) => {
+            let path = parsed.chunk_path(&chunk_filename)?;
+            let (payload, bytes) = spawn_blocking(move || chunk_payload(path))
+                .await
+                .map_err(|e| AppError::Task(e.to_string()))??;
+            let file_name = Path::new(&chunk_filename)
+                .file_name()
+                .map(|n| n.to_string_lossy())
+                .unwrap_or_default()
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+                    _ => '-',
+                })
+                .collect();
+            let content_type = "application/octet-stream".to_string();
+            return publish(payload, file_name, content_type, bytes, ttl);
+        }
+        _ => {
+            return Err(AppError::Invalid(
+                "share a field with both item and field index, or neither for the chunk".into(),
+            ))
+        }
+    };
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, None)
+        .await
+        .within(|| context.clone())?;
+    let offsets = (*offsets).clone();
+    spawn_blocking(move || {
+        let table = offsets
+            .get(&parsed.chunk_path(&chunk_filename)?, &access)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
+        let fmt = parsed.config.data_format.as_deref().unwrap_or_default();
+        let (data, _) = read_field_bytes(&access, &table, item_index, field_index, fmt.len(), None)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
+        usage::record_bytes(&parsed.source, data.len());
+        let ext = guess_ext(fmt.get(field_index), &data).unwrap_or_else(|| "bin".into());
+        let file_name = leaf_file_name(&chunk_filename, item_index, field_index, &ext);
+        share_bytes(data, file_name, ttl)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
+}
+
+/// Revokes one shared URL, or all of them without a token. Returns how many were removed.
+#[tauri::command]
+pub async fn unshare_media(token: Option<String>) -> AppResult<usize> {
+    let mut guard = lock()?;
+    let removed = match (guard.as_mut(), token) {
+        (None, _) => 0,
+        (Some(server), Some(token)) => usize::from(server.shares.remove(token.trim()).is_some()),
+        (Some(server), None) => std::mem::take(&mut server.shares).len(),
+    };
+    prune(&mut guard);
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn list_shared_media() -> AppResult<Vec<SharedMedia>> {
+    let mut guard = lock()?;
+    prune(&mut guard);
+    let mut shares: Vec<SharedMedia> = guard
+        .as_ref()
+        .map(|server| server.shares.values().map(|s| s.info.clone()).collect())
+        .unwrap_or_default();
+    shares.sort_by_key(|s| s.expires_at);
+    Ok(shares)
+}
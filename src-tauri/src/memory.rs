@@ -0,0 +1,190 @@
+use crate::litdata::{AppError, AppResult};
+use litdata_core::archive;
+use serde::Serialize;
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, Weak},
+    time::Duration,
+};
+
+/// Resident decompressed bytes allowed across every chunk cache and in-flight load.
+pub(crate) const DEFAULT_MEMORY_LIMIT: u64 = 2 * 1024 * 1024 * 1024;
+/// How long a load waits for other loads to release memory before giving up.
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct GuardState {
+    limit: u64,
+    cached: u64,
+    in_flight: u64,
+}
+
+impl GuardState {
+    fn resident(&self) -> u64 {
+        self.cached.saturating_add(self.in_flight)
+    }
+}
+
+struct MemoryGuard {
+    state: Mutex<GuardState>,
+    freed: Condvar,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    limit_bytes: u64,
+    cached_bytes: u64,
+    in_flight_bytes: u64,
+}
+
+fn guard() -> &'static MemoryGuard {
+    static GUARD: OnceLock<MemoryGuard> = OnceLock::new();
+    GUARD.get_or_init(|| MemoryGuard {
+        state: Mutex::new(GuardState {
+            limit: DEFAULT_MEMORY_LIMIT,
+            cached: 0,
+            in_flight: 0,
+        }),
+        freed: Condvar::new(),
+    })
+}
+
+fn lock() -> AppResult<MutexGuard<'static, GuardState>> {
+    guard()
+        .state
+        .lock()
+        .map_err(|_| AppError::Task("memory guard lock poisoned".into()))
+}
+
+/// A cache of decompressed bytes that gives memory back when a load needs it.
+pub(crate) trait Reclaim: Send + Sync {
+    /// Drops least-recently-used data until about `bytes` are freed; returns what was freed.
+    fn reclaim(&self, bytes: u64) -> u64;
+}
+
+fn reclaimers() -> &'static Mutex<Vec<Weak<dyn Reclaim>>> {
+    static RECLAIMERS: OnceLock<Mutex<Vec<Weak<dyn Reclaim>>>> = OnceLock::new();
+    RECLAIMERS.get_or_init(Default::default)
+}
+
+/// Lets `reserve` evict from `cache` instead of waiting on bytes only it would free.
+pub(crate) fn register_reclaimer(cache: Weak<dyn Reclaim>) {
+    if let Ok(mut list) = reclaimers().lock() {
+        list.retain(|c| c.strong_count() > 0);
+        list.push(cache);
+    }
+}
+
+/// Asks the live caches to free `needed` bytes between them.
+fn reclaim(mut needed: u64) {
+    let caches: Vec<Arc<dyn Reclaim>> = match reclaimers().lock() {
+        Ok(list) => list.iter().filter_map(Weak::upgrade).collect(),
+        Err(_) => return,
+    };
+    for cache in caches {
+        if needed == 0 {
+            break;
+        }
+        needed = needed.saturating_sub(cache.reclaim(needed));
+    }
+}
+
+/// Memory held by one in-flight operation; released on drop.
+pub(crate) struct Reservation {
+    bytes: u64,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if let Ok(mut state) = guard().state.lock() {
+            state.in_flight = state.in_flight.saturating_sub(self.bytes);
+        }
+        guard().freed.notify_all();
+    }
+}
+
+/// Claims `bytes` for an operation about to materialise decompressed data. Cached
+/// chunks are evicted first to make room; after that it waits while other loads hold
+/// the memory, and fails with `AppError::MemoryLimit` if the request can never fit or
+/// nothing is released in time.
+pub(crate) fn reserve(bytes: u64) -> AppResult<Reservation> {
+    let state = lock()?;
+    if bytes > state.limit {
+        return Err(AppError::MemoryLimit {
+            requested: bytes,
+            limit: state.limit,
+        });
+    }
+    let shortfall = state
+        .resident()
+        .saturating_add(bytes)
+        .saturating_sub(state.limit);
+    // Caches report their evictions back through `track_cached`, so the lock is released
+    // while they run.
+    let state = if shortfall > 0 {
+        drop(state);
+        reclaim(shortfall);
+        lock()?
+    } else {
+        state
+    };
+    let (mut state, waited) = guard()
+        .freed
+        .wait_timeout_while(state, QUEUE_TIMEOUT, |s| {
+            s.resident().saturating_add(bytes) > s.limit
+        })
+        .map_err(|_| AppError::Task("memory guard lock poisoned".into()))?;
+    if waited.timed_out() {
+        return Err(AppError::MemoryLimit {
+            requested: bytes,
+            limit: state.limit,
+        });
+    }
+    state.in_flight += bytes;
+    Ok(Reservation { bytes })
+}
+
+/// Makes members inflated out of zip and tar archives reserve memory like chunk loads.
+pub(crate) fn guard_archives() {
+    archive::set_reserve_hook(|bytes| Ok(Box::new(reserve(bytes)?)));
+}
+
+/// Chunk caches report what they hold so reservations account for it.
+pub(crate) fn track_cached(added: u64, removed: u64) {
+    if let Ok(mut state) = guard().state.lock() {
+        state.cached = state.cached.saturating_add(added).saturating_sub(removed);
+    }
+    if removed > 0 {
+        guard().freed.notify_all();
+    }
+}
+
+fn stats(state: &GuardState) -> MemoryStats {
+    MemoryStats {
+        limit_bytes: state.limit,
+        cached_bytes: state.cached,
+        in_flight_bytes: state.in_flight,
+    }
+}
+
+#[tauri::command]
+pub async fn get_memory_stats() -> AppResult<MemoryStats> {
+    lock().map(|state| stats(&state))
+}
+
+/// Changes the resident-memory cap; loads already running keep their reservations.
+pub(crate) fn set_limit(limit_bytes: u64) -> AppResult<MemoryStats> {
+    if limit_bytes == 0 {
+        return Err(AppError::Invalid("memory limit must be positive".into()));
+    }
+    let mut state = lock()?;
+    state.limit = limit_bytes;
+    let snapshot = stats(&state);
+    drop(state);
+    guard().freed.notify_all();
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn set_memory_limit(limit_bytes: u64) -> AppResult<MemoryStats> {
+    set_limit(limit_bytes)
+}
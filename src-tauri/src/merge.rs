@@ -0,0 +1,238 @@
+use crate::litdata::{
+    is_chunk_path, parse_index, resolve_index_path, AppError, AppResult, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::rewrite::{index_document, sequential_chunk_name, write_new_index};
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedSource {
+    index_path: String,
+    chunks: usize,
+    items: u64,
+    /// New name of the source's first chunk; the rest follow in order.
+    first_chunk: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    job_id: String,
+    index_path: String,
+    sources: Vec<MergedSource>,
+    chunk_count: usize,
+    total_items: u64,
+    /// Chunks hard-linked rather than copied.
+    linked_chunks: usize,
+    copied_bytes: u64,
+}
+
+/// Combines datasets with the same `data_format`, compression and `data_spec` into one
+/// index.json in `dest_dir`, like litdata's `merge_datasets`. Chunks are renumbered in
+/// source order and hard-linked when `link` is set and the filesystem allows it,
+/// otherwise copied. `compress_index` writes `index.json.zstd` instead.
+#[tauri::command]
+pub async fn merge_datasets(
+    app: AppHandle,
+    index_paths: Vec<String>,
+    dest_dir: String,
+    link: bool,
+    compress_index: Option<bool>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<MergeReport> {
+    let progress = Progress::new(app, job_id, "merge");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let sources = index_paths
+            .iter()
+            .map(|path| parse_index(Path::new(path.trim())))
+            .collect::<AppResult<Vec<_>>>()?;
+        merge_sync(
+            &sources,
+            Path::new(dest_dir.trim()),
+            link,
+            compress_index.unwrap_or(false),
+            &progress,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Fails unless `other` stores items exactly like `first`, so its chunks can be reused
+/// byte for byte.
+fn ensure_compatible(first: &ParsedIndex, other: &ParsedIndex) -> AppResult<()> {
+    let (a, b) = (&first.config, &other.config);
+    let mismatch = if a.data_format != b.data_format {
+        Some("data_format")
+    } else if a.compression.as_deref().map(str::to_lowercase)
+        != b.compression.as_deref().map(str::to_lowercase)
+    {
+        Some("compression")
+    } else if a.data_spec != b.data_spec {
+        Some("data_spec")
+    } else {
+        None
+    };
+    match mismatch {
+        Some(field) => Err(AppError::Invalid(format!(
+            "{} and {} differ in {field}",
+            first.source.display(),
+            other.source.display()
+        ))),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn merge_sync(
+    sources: &[ParsedIndex],
+    dest_dir: &Path,
+    link: bool,
+    compress_index: bool,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<MergeReport> {
+    let Some(first) = sources.first() else {
+        return Err(AppError::Invalid("no datasets to merge".into()));
+    };
+    if sources.len() < 2 {
+        return Err(AppError::Invalid("choose at least two datasets".into()));
+    }
+    if dest_dir.as_os_str().is_empty() {
+        return Err(AppError::Invalid("destination directory is empty".into()));
+    }
+    if dest_dir.is_dir() && resolve_index_path(dest_dir).is_ok() {
+        return Err(AppError::Invalid(format!(
+            "{} already holds a dataset",
+            dest_dir.display()
+        )));
+    }
+    let dest = fs::canonicalize(dest_dir).ok();
+    let mut protected = workspace.open_dirs();
+    for parsed in sources {
+        if is_chunk_path(&parsed.source) {
+            return Err(AppError::Invalid(format!(
+                "{} is a standalone chunk; merging needs index.json files",
+                parsed.source.display()
+            )));
+        }
+        ensure_compatible(first, parsed)?;
+        let is_dest = |dir: &PathBuf| fs::canonicalize(dir).ok().is_some_and(|d| Some(d) == dest);
+        if parsed.dataset_dirs().iter().any(is_dest) {
+            return Err(AppError::Invalid(
+                "destination must differ from every source directory".into(),
+            ));
+        }
+        protected.extend(parsed.dataset_dirs());
+    }
+    ensure_writable(dest_dir, &protected)?;
+
+    // (source position, chunk position, new filename) in merged order.
+    let plan: Vec<(usize, usize, String)> = sources
+        .iter()
+        .enumerate()
+        .flat_map(|(source, parsed)| (0..parsed.chunks.len()).map(move |chunk| (source, chunk)))
+        .enumerate()
+        .map(|(number, (source, chunk))| {
+            let name = sequential_chunk_name(&sources[source].chunks[chunk].filename, number);
+            (source, chunk, name)
+        })
+        .collect();
+
+    fs::create_dir_all(dest_dir)?;
+    let total = plan.len() as u64;
+    progress.report("copying", 0, total);
+    let placed = par_map(
+        &plan,
+        Some((progress, "copying")),
+        |(source, chunk, name)| -> AppResult<Option<u64>> {
+            let parsed = &sources[*source];
+            let from = parsed.chunk_path(&parsed.chunks[*chunk].filename)?;
+            let to = dest_dir.join(name);
+            if link && fs::hard_link(&from, &to).is_ok() {
+                return Ok(None);
+            }
+            Ok(Some(fs::copy(&from, &to)?))
+        },
+    );
+    let placed = match placed.into_iter().collect::<AppResult<Vec<_>>>() {
+        Ok(placed) => placed,
+        Err(e) => {
+            for (_, _, name) in &plan {
+                let _ = fs::remove_file(dest_dir.join(name));
+            }
+            // Only succeeds when the directory was created for this run.
+            let _ = fs::remove_dir(dest_dir);
+            return Err(e);
+        }
+    };
+
+    let chunks: Vec<serde_json::Value> = plan
+        .iter()
+        .map(|(source, chunk, name)| {
+            let raw = &sources[*source].chunks[*chunk];
+            serde_json::json!({
+                "chunk_bytes": raw.chunk_bytes,
+                "chunk_size": raw.chunk_size,
+                "dim": raw.dim,
+                "filename": name,
+            })
+        })
+        .collect();
+    // The first index supplies the config, keeping fields this tree does not model.
+    let mut index = match index_document(first) {
+        Ok(index) if index.is_object() => index,
+        _ => serde_json::json!({ "config": first.config_raw }),
+    };
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default();
+    if let Some(object) = index.as_object_mut() {
+        object.insert("chunks".into(), chunks.into());
+        object.insert("updated_at".into(), updated_at.to_string().into());
+    }
+    let index_path = write_new_index(dest_dir, &index, compress_index)?;
+    progress.report("done", total, total);
+
+    let mut next_chunk = 0;
+    let sources_report = sources
+        .iter()
+        .map(|parsed| {
+            let first_chunk = plan.get(next_chunk).map(|(_, _, name)| name.clone());
+            let first_chunk = first_chunk.filter(|_| !parsed.chunks.is_empty());
+            next_chunk += parsed.chunks.len();
+            MergedSource {
+                index_path: parsed.source.display().to_string(),
+                chunks: parsed.chunks.len(),
+                items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+                first_chunk,
+            }
+        })
+        .collect();
+    Ok(MergeReport {
+        job_id: progress.job_id().to_string(),
+        index_path: index_path.display().to_string(),
+        sources: sources_report,
+        chunk_count: plan.len(),
+        total_items: sources
+            .iter()
+            .flat_map(|parsed| &parsed.chunks)
+            .map(|c| c.chunk_size as u64)
+            .sum(),
+        linked_chunks: placed.iter().filter(|p| p.is_none()).count(),
+        copied_bytes: placed.iter().flatten().sum(),
+    })
+}
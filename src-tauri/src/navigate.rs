@@ -0,0 +1,61 @@
+use crate::litdata::{AppError, AppResult};
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeItem {
+    chunk_filename: String,
+    item_index: u32,
+    /// Position of the item across the whole dataset, in chunk order.
+    global_index: u64,
+    total_items: u64,
+    /// The step ran past the first or last item and stopped there.
+    clamped: bool,
+}
+
+/// The item `delta` steps away from `current_item` of `current_chunk`, crossing chunk
+/// boundaries in index order. Each chunk contributes its own item count, so a short
+/// final chunk and empty chunks are handled without assuming a uniform chunk size.
+#[tauri::command]
+pub async fn resolve_relative_item(
+    handle: DatasetHandle,
+    current_chunk: String,
+    current_item: u32,
+    delta: i64,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<RelativeItem> {
+    let parsed = workspace.get(handle)?;
+    let mut starts = Vec::with_capacity(parsed.chunks.len());
+    let mut total_items = 0u64;
+    for chunk in &parsed.chunks {
+        starts.push(total_items);
+        total_items += chunk.chunk_size as u64;
+    }
+    let position = parsed
+        .chunks
+        .iter()
+        .position(|c| c.filename == current_chunk)
+        .ok_or_else(|| {
+            AppError::Missing(format!("chunk {current_chunk} is not in this dataset"))
+        })?;
+    if current_item >= parsed.chunks[position].chunk_size {
+        return Err(AppError::Invalid(format!(
+            "item {current_item} is past the end of {current_chunk}"
+        )));
+    }
+    // `current_item` is in range, so the dataset has at least one item to clamp to.
+    let current = starts[position] + current_item as u64;
+    let wanted = current as i128 + delta as i128;
+    let target = wanted.clamp(0, total_items as i128 - 1) as u64;
+    // The last chunk starting at or before the target; empty chunks share their start
+    // with the following chunk, so this is never one of them.
+    let chunk_index = starts.partition_point(|&start| start <= target) - 1;
+    Ok(RelativeItem {
+        chunk_filename: parsed.chunks[chunk_index].filename.clone(),
+        item_index: (target - starts[chunk_index]) as u32,
+        global_index: target,
+        total_items,
+        clamped: wanted != target as i128,
+    })
+}
@@ -0,0 +1,395 @@
+use crate::dedup::ItemLocation;
+use crate::image_stats::candidate_fields;
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::settings;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use image::{imageops::FilterType, DynamicImage, GrayImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Cursor, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Hashes within this many differing bits are near-duplicates by default.
+const DEFAULT_MAX_DISTANCE: u32 = 6;
+/// Past a quarter of the bits, unrelated images start to match.
+const MAX_DISTANCE_LIMIT: u32 = 16;
+/// Groups returned, largest first; the rest are only counted.
+const MAX_GROUPS: usize = 500;
+/// Groups whose items get thumbnails.
+const THUMBNAIL_GROUPS: usize = 50;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PerceptualHash {
+    /// Brightness gradients between neighbouring pixels; fast, robust to rescaling.
+    #[default]
+    Dhash,
+    /// Low-frequency DCT coefficients; also robust to mild edits and recompression.
+    Phash,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearDuplicate {
+    #[serde(flatten)]
+    location: ItemLocation,
+    hash: String,
+    /// Differing bits from the group's first item.
+    distance: u32,
+    /// JPEG no larger than the thumbnail size setting, base64-encoded for a data URL.
+    thumbnail_base64: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearDuplicateGroup {
+    field_index: usize,
+    items: Vec<NearDuplicate>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearDuplicateReport {
+    job_id: String,
+    algorithm: PerceptualHash,
+    max_distance: u32,
+    scanned_items: u64,
+    hashed_images: u64,
+    /// Payloads that look like an image but failed to decode.
+    undecodable_images: u64,
+    /// Items beyond the first of every group.
+    near_duplicate_items: u64,
+    group_count: usize,
+    /// Largest first, at most 500.
+    groups: Vec<NearDuplicateGroup>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Hashes every image in the dataset (or in one field) perceptually and groups items
+/// whose hashes differ in at most `max_distance` bits.
+#[tauri::command]
+pub async fn find_near_duplicate_images(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    algorithm: Option<PerceptualHash>,
+    max_distance: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<NearDuplicateReport> {
+    let progress = Progress::new(app, job_id, "near-dupes");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        near_duplicates_of(
+            &parsed,
+            field_index,
+            algorithm.unwrap_or_default(),
+            max_distance.unwrap_or(DEFAULT_MAX_DISTANCE),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// litdata's `pil` serializer: width, height and mode length as u32, the mode name, then
+/// `Image.tobytes()`.
+fn pil_raw_image(data: &[u8]) -> Option<DynamicImage> {
+    let word = |at: usize| Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let (w, h, mode_len) = (word(0)?, word(4)?, word(8)? as usize);
+    let pixels = data.get(12 + mode_len..)?.to_vec();
+    match data.get(12..12 + mode_len)? {
+        b"L" => image::GrayImage::from_raw(w, h, pixels).map(DynamicImage::ImageLuma8),
+        b"RGB" => image::RgbImage::from_raw(w, h, pixels).map(DynamicImage::ImageRgb8),
+        b"RGBA" => image::RgbaImage::from_raw(w, h, pixels).map(DynamicImage::ImageRgba8),
+        _ => None,
+    }
+}
+
+/// `None` when the payload is not an image; `Some(None)` when it is but won't decode.
+fn decode(data: &[u8]) -> Option<Option<DynamicImage>> {
+    if image::guess_format(data).is_ok() {
+        return Some(image::load_from_memory(data).ok());
+    }
+    pil_raw_image(data).map(Some)
+}
+
+fn gray(image: &DynamicImage, width: u32, height: u32) -> GrayImage {
+    image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_luma8()
+}
+
+fn dhash(image: &DynamicImage) -> u64 {
+    let small = gray(image, 9, 8);
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let bit = small.get_pixel(x + 1, y)[0] > small.get_pixel(x, y)[0];
+            hash = hash << 1 | bit as u64;
+        }
+    }
+    hash
+}
+
+/// The 8×8 lowest frequencies of a 32×32 DCT-II, each compared with their median.
+fn phash(image: &DynamicImage) -> u64 {
+    const N: usize = 32;
+    let small = gray(image, N as u32, N as u32);
+    let basis: Vec<[f64; N]> = (0..8)
+        .map(|u| {
+            std::array::from_fn(|x| {
+                (std::f64::consts::PI * (2 * x + 1) as f64 * u as f64 / (2 * N) as f64).cos()
+            })
+        })
+        .collect();
+    let mut coefficients = [0f64; 64];
+    for (v, row_basis) in basis.iter().enumerate() {
+        for (u, col_basis) in basis.iter().enumerate() {
+            let mut sum = 0.0;
+            for (y, row) in row_basis.iter().enumerate() {
+                for (x, col) in col_basis.iter().enumerate() {
+                    sum += small.get_pixel(x as u32, y as u32)[0] as f64 * row * col;
+                }
+            }
+            coefficients[v * 8 + u] = sum;
+        }
+    }
+    let mut sorted = coefficients;
+    sorted.sort_by(f64::total_cmp);
+    let median = (sorted[31] + sorted[32]) / 2.0;
+    coefficients
+        .iter()
+        .fold(0u64, |hash, c| hash << 1 | (*c > median) as u64)
+}
+
+/// Metric tree over Hamming distance, so each lookup visits only nearby hashes.
+#[derive(Default)]
+struct BkTree {
+    /// `(hash, id, children keyed by distance)`
+    nodes: Vec<(u64, usize, BTreeMap<u32, usize>)>,
+}
+
+impl BkTree {
+    fn insert(&mut self, hash: u64, id: usize) {
+        let new = self.nodes.len();
+        let mut at = 0;
+        while at < self.nodes.len() {
+            let distance = (self.nodes[at].0 ^ hash).count_ones();
+            match self.nodes[at].2.get(&distance) {
+                Some(&child) => at = child,
+                None => {
+                    self.nodes[at].2.insert(distance, new);
+                    break;
+                }
+            }
+        }
+        self.nodes.push((hash, id, BTreeMap::new()));
+    }
+
+    fn within(&self, hash: u64, radius: u32, found: &mut Vec<usize>) {
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(at) = stack.pop() {
+            let (node_hash, id, children) = &self.nodes[at];
+            let distance = (node_hash ^ hash).count_ones();
+            if distance <= radius {
+                found.push(*id);
+            }
+            let low = distance.saturating_sub(radius);
+            stack.extend(children.range(low..=distance + radius).map(|(_, c)| *c));
+        }
+    }
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Groups of ids whose hashes are linked by chains of matches within `radius`.
+fn cluster(hashes: &[u64], radius: u32) -> Vec<Vec<usize>> {
+    let mut tree = BkTree::default();
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+    let mut found = Vec::new();
+    for (id, hash) in hashes.iter().enumerate() {
+        found.clear();
+        tree.within(*hash, radius, &mut found);
+        for &other in &found {
+            let (a, b) = (find(&mut parent, id), find(&mut parent, other));
+            parent[a.max(b)] = a.min(b);
+        }
+        tree.insert(*hash, id);
+    }
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for id in 0..hashes.len() {
+        let root = find(&mut parent, id);
+        groups.entry(root).or_default().push(id);
+    }
+    groups.into_values().filter(|g| g.len() > 1).collect()
+}
+
+fn thumbnail(image: &DynamicImage, size: u32) -> Option<String> {
+    let mut out = Cursor::new(Vec::new());
+    let small = if image.width() <= size && image.height() <= size {
+        image.to_rgb8()
+    } else {
+        image.thumbnail(size, size).to_rgb8()
+    };
+    DynamicImage::ImageRgb8(small)
+        .write_to(&mut out, ImageFormat::Jpeg)
+        .ok()?;
+    Some(STANDARD.encode(out.into_inner()))
+}
+
+/// Re-reads the items of the first groups to attach thumbnails.
+fn attach_thumbnails(parsed: &ParsedIndex, groups: &mut [NearDuplicateGroup]) {
+    let size = settings::current().thumbnail_size;
+    let format_len = parsed.format_len();
+    // Per chunk: `(group, position in group, field, item)`.
+    let mut wanted: BTreeMap<String, Vec<(usize, usize, usize, u32)>> = BTreeMap::new();
+    for (g, group) in groups.iter().enumerate().take(THUMBNAIL_GROUPS) {
+        for (i, item) in group.items.iter().enumerate() {
+            wanted
+                .entry(item.location.chunk_filename.clone())
+                .or_default()
+                .push((g, i, group.field_index, item.location.item_index));
+        }
+    }
+    let wanted: Vec<_> = wanted.into_iter().collect();
+    let rendered = par_map(&wanted, None, |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, chunk, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut thumbnails = Vec::with_capacity(items.len());
+        for &(g, i, field, item) in items {
+            let (data, _) = read_field_bytes(&access, &table, item, field, format_len, None)?;
+            let thumb = decode(&data)
+                .flatten()
+                .and_then(|img| thumbnail(&img, size));
+            thumbnails.push((g, i, thumb));
+        }
+        AppResult::Ok(thumbnails)
+    });
+    for (g, i, thumb) in rendered.into_iter().flatten().flatten() {
+        groups[g].items[i].thumbnail_base64 = thumb;
+    }
+}
+
+pub(crate) fn near_duplicates_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    algorithm: PerceptualHash,
+    max_distance: u32,
+    progress: &Progress,
+) -> AppResult<NearDuplicateReport> {
+    if max_distance > MAX_DISTANCE_LIMIT {
+        return Err(AppError::Invalid(format!(
+            "max distance must be at most {MAX_DISTANCE_LIMIT} bits"
+        )));
+    }
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = candidate_fields(&formats, field_index)?;
+    let total = parsed.chunks.len() as u64;
+    progress.report("hashing", 0, total);
+    let hashed = par_map(&parsed.chunks, Some((progress, "hashing")), |chunk| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &chunk.filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut hashes = Vec::new();
+        let mut undecodable = 0u64;
+        for item in 0..table.0 {
+            for &field in &fields {
+                let (data, _) =
+                    read_field_bytes(&access, &table, item, field, formats.len(), None)?;
+                match decode(&data) {
+                    Some(Some(image)) => {
+                        let hash = match algorithm {
+                            PerceptualHash::Dhash => dhash(&image),
+                            PerceptualHash::Phash => phash(&image),
+                        };
+                        hashes.push((field, item, hash));
+                    }
+                    Some(None) => undecodable += 1,
+                    None => {}
+                }
+            }
+        }
+        AppResult::Ok((table.0, hashes, undecodable))
+    });
+
+    // Per field: hashes with where they came from.
+    let mut per_field: BTreeMap<usize, (Vec<u64>, Vec<ItemLocation>)> = BTreeMap::new();
+    let mut scanned_items = 0u64;
+    let mut undecodable_images = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    let mut global_base = 0u64;
+    for (chunk, result) in parsed.chunks.iter().zip(hashed) {
+        let Ok((num_items, hashes, undecodable)) = result else {
+            unreadable_chunks.push(chunk.filename.clone());
+            global_base += chunk.chunk_size as u64;
+            continue;
+        };
+        for (field, item, hash) in hashes {
+            let (field_hashes, locations) = per_field.entry(field).or_default();
+            field_hashes.push(hash);
+            locations.push(ItemLocation {
+                chunk_filename: chunk.filename.clone(),
+                item_index: item,
+                global_index: global_base + item as u64,
+            });
+        }
+        scanned_items += num_items as u64;
+        undecodable_images += undecodable;
+        global_base += num_items as u64;
+    }
+    progress.report("grouping", total, total);
+
+    let hashed_images = per_field.values().map(|(h, _)| h.len() as u64).sum();
+    let mut groups: Vec<NearDuplicateGroup> = Vec::new();
+    for (field_index, (hashes, locations)) in per_field {
+        for ids in cluster(&hashes, max_distance) {
+            let first = hashes[ids[0]];
+            groups.push(NearDuplicateGroup {
+                field_index,
+                items: ids
+                    .into_iter()
+                    .map(|id| NearDuplicate {
+                        location: locations[id].clone(),
+                        hash: format!("{:016x}", hashes[id]),
+                        distance: (hashes[id] ^ first).count_ones(),
+                        thumbnail_base64: None,
+                    })
+                    .collect(),
+            });
+        }
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.items.len()));
+    let group_count = groups.len();
+    let near_duplicate_items = groups.iter().map(|g| g.items.len() as u64 - 1).sum();
+    groups.truncate(MAX_GROUPS);
+    progress.report("thumbnails", total, total);
+    attach_thumbnails(parsed, &mut groups);
+    progress.report("done", total, total);
+    Ok(NearDuplicateReport {
+        job_id: progress.job_id().to_string(),
+        algorithm,
+        max_distance,
+        scanned_items,
+        hashed_images,
+        undecodable_images,
+        near_duplicate_items,
+        group_count,
+        groups,
+        unreadable_chunks,
+    })
+}
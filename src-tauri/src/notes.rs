@@ -0,0 +1,148 @@
+use crate::index_cache::IndexCache;
+use crate::litdata::{AppError, AppResult, ParsedIndex};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+use xxhash_rust::xxh3::xxh3_128;
+
+const NOTES_DIR: &str = "notes";
+
+/// Serializes read-modify-write cycles on note files.
+static NOTES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    text: String,
+    updated_at: u64,
+}
+
+/// Notes for one dataset: one for the dataset as a whole plus one per chunk.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetNotes {
+    fingerprint: String,
+    /// Where the dataset was last seen; notes follow the content, not the path.
+    index_path: String,
+    dataset: Option<Note>,
+    #[serde(default)]
+    chunks: BTreeMap<String, Note>,
+}
+
+/// Identifies a dataset by its index contents, so notes survive the dataset being
+/// moved, copied or mounted at a different path.
+pub(crate) fn fingerprint(parsed: &ParsedIndex) -> String {
+    let mut key = serde_json::json!([
+        parsed.config.data_format,
+        parsed.config.compression,
+        parsed.chunks.len(),
+    ])
+    .to_string();
+    for chunk in &parsed.chunks {
+        key.push_str(&format!(
+            "\n{}\t{}\t{}",
+            chunk.filename, chunk.chunk_size, chunk.chunk_bytes
+        ));
+    }
+    format!("{:032x}", xxh3_128(key.as_bytes()))
+}
+
+fn notes_path(app: &AppHandle, fingerprint: &str) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir.join(NOTES_DIR).join(format!("{fingerprint}.json")))
+}
+
+/// A missing or unreadable file is treated as no notes rather than failing the caller.
+fn read_notes(path: &Path) -> Option<DatasetNotes> {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+}
+
+fn write_notes(path: &Path, notes: &DatasetNotes) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(notes)
+        .map_err(|e| AppError::Invalid(format!("serializing notes: {e}")))?;
+    let partial = path.with_extension("json.part");
+    fs::write(&partial, json)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn load(app: &AppHandle, parsed: &ParsedIndex) -> AppResult<(PathBuf, DatasetNotes)> {
+    let fingerprint = fingerprint(parsed);
+    let path = notes_path(app, &fingerprint)?;
+    let mut notes = read_notes(&path).unwrap_or_else(|| DatasetNotes {
+        fingerprint,
+        ..DatasetNotes::default()
+    });
+    notes.index_path = parsed.source.display().to_string();
+    Ok((path, notes))
+}
+
+#[tauri::command]
+pub async fn get_dataset_notes(
+    app: AppHandle,
+    index_path: String,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<DatasetNotes> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    spawn_blocking(move || Ok(load(&app, &parsed)?.1))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Sets the dataset note, or the note for `chunk_filename` when given. Empty text
+/// removes the note.
+#[tauri::command]
+pub async fn set_dataset_note(
+    app: AppHandle,
+    index_path: String,
+    chunk_filename: Option<String>,
+    text: String,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<DatasetNotes> {
+    let parsed = indexes.get_async(Path::new(&index_path)).await?;
+    if let Some(chunk) = &chunk_filename {
+        if !parsed.chunks.iter().any(|c| &c.filename == chunk) {
+            return Err(AppError::Missing(format!("chunk {chunk} in {index_path}")));
+        }
+    }
+    let note = (!text.trim().is_empty()).then(|| Note {
+        text,
+        updated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+    });
+    spawn_blocking(move || {
+        let _guard = NOTES_LOCK
+            .lock()
+            .map_err(|_| AppError::Task("notes lock poisoned".into()))?;
+        let (path, mut notes) = load(&app, &parsed)?;
+        match (chunk_filename, note) {
+            (None, note) => notes.dataset = note,
+            (Some(chunk), Some(note)) => {
+                notes.chunks.insert(chunk, note);
+            }
+            (Some(chunk), None) => {
+                notes.chunks.remove(&chunk);
+            }
+        }
+        write_notes(&path, &notes)?;
+        Ok(notes)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
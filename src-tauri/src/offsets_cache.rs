@@ -0,0 +1,96 @@
+use crate::index_cache::{stamp, Stamp};
+use crate::litdata::{parse_offsets, resolved_path, AppResult, ChunkAccess};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Total offset entries kept across chunks (8 bytes each); least recently used go first.
+const MAX_CACHED_OFFSETS: usize = 8 * 1024 * 1024;
+
+/// A chunk's item count and its `num_items + 1` item offsets.
+pub(crate) type ChunkOffsets = Arc<(u32, Vec<u64>)>;
+
+struct CachedOffsets {
+    offsets: ChunkOffsets,
+    stamp: Stamp,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct OffsetsCacheState {
+    entries: HashMap<PathBuf, CachedOffsets>,
+    held: usize,
+    clock: u64,
+}
+
+/// Managed-state cache of `parse_offsets` results, keyed by chunk path with symlinks
+/// resolved and invalidated when the chunk file's mtime or size changes, so repeated
+/// field reads skip re-parsing tables that reach megabytes for million-item chunks.
+#[derive(Clone, Default)]
+pub struct OffsetsCache {
+    inner: Arc<Mutex<OffsetsCacheState>>,
+}
+
+impl OffsetsCache {
+    pub(crate) fn get(&self, chunk_path: &Path, access: &ChunkAccess) -> AppResult<ChunkOffsets> {
+        let current = stamp(chunk_path);
+        let chunk_path = resolved_path(chunk_path);
+        let chunk_path = chunk_path.as_path();
+        if let Ok(mut guard) = self.inner.lock() {
+            guard.clock += 1;
+            let now = guard.clock;
+            if let Some(entry) = guard.entries.get_mut(chunk_path) {
+                if entry.stamp == current {
+                    entry.last_used = now;
+                    return Ok(entry.offsets.clone());
+                }
+            }
+        }
+
+        let offsets: ChunkOffsets = Arc::new(parse_offsets(access)?);
+        let len = offsets.1.len();
+        if let Ok(mut guard) = self.inner.lock() {
+            if let Some(old) = guard.entries.remove(chunk_path) {
+                guard.held -= old.offsets.1.len();
+            }
+            if len <= MAX_CACHED_OFFSETS {
+                while guard.held + len > MAX_CACHED_OFFSETS {
+                    let Some(oldest) = guard
+                        .entries
+                        .iter()
+                        .min_by_key(|(_, e)| e.last_used)
+                        .map(|(k, _)| k.clone())
+                    else {
+                        break;
+                    };
+                    if let Some(evicted) = guard.entries.remove(&oldest) {
+                        guard.held -= evicted.offsets.1.len();
+                    }
+                }
+                guard.held += len;
+                let last_used = guard.clock;
+                guard.entries.insert(
+                    chunk_path.to_path_buf(),
+                    CachedOffsets {
+                        offsets: offsets.clone(),
+                        stamp: current,
+                        last_used,
+                    },
+                );
+            }
+        }
+        Ok(offsets)
+    }
+
+    /// Drops `chunk_path`'s table after its chunk file was rewritten in place.
+    pub(crate) fn forget(&self, chunk_path: &Path) {
+        let chunk_path = resolved_path(chunk_path);
+        if let Ok(mut guard) = self.inner.lock() {
+            if let Some(old) = guard.entries.remove(chunk_path.as_path()) {
+                guard.held -= old.offsets.1.len();
+            }
+        }
+    }
+}
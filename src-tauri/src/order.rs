@@ -0,0 +1,398 @@
+use crate::litdata::{parse_index, AppError, AppResult, ParsedIndex};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::async_runtime::spawn_blocking;
+
+const DEFAULT_SEED: u32 = 42;
+/// litdata starts counting epochs at 1.
+const DEFAULT_EPOCH: u32 = 1;
+
+/// StreamingDataset / DataLoader settings that determine the sample order.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationConfig {
+    shuffle: bool,
+    seed: Option<u32>,
+    epoch: Option<u32>,
+    num_workers: u32,
+    batch_size: u32,
+    drop_last: bool,
+    world_size: Option<u32>,
+    rank: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleLocation {
+    position: u32,
+    global_index: u64,
+    chunk_index: usize,
+    chunk_filename: String,
+    item_index: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchLocation {
+    step: u64,
+    worker: u32,
+    batch_index: u64,
+    total_steps: u64,
+    worker_batches: Vec<u64>,
+    samples: Vec<SampleLocation>,
+}
+
+/// MT19937 seeded the way numpy's legacy `RandomState` seeds it, so shuffles match litdata.
+pub(crate) struct Mt19937 {
+    state: [u32; 624],
+    pos: usize,
+}
+
+impl Mt19937 {
+    pub(crate) fn from_seed(seed: u32) -> Self {
+        let mut state = [0u32; 624];
+        state[0] = seed;
+        for i in 1..624 {
+            let prev = state[i - 1];
+            state[i] = 1812433253u32
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { state, pos: 624 }
+    }
+
+    /// `RandomState([a, b, ...])`.
+    fn from_key(key: &[u32]) -> Self {
+        let mut mt = Mt19937::from_seed(19650218);
+        let s = &mut mt.state;
+        let (mut i, mut j) = (1usize, 0usize);
+        for _ in 0..624.max(key.len()) {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= 624 {
+                s[0] = s[623];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..623 {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1566083941)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= 624 {
+                s[0] = s[623];
+                i = 1;
+            }
+        }
+        s[0] = 0x8000_0000;
+        mt
+    }
+
+    fn twist(&mut self) {
+        for i in 0..624 {
+            let y = (self.state[i] & 0x8000_0000) | (self.state[(i + 1) % 624] & 0x7fff_ffff);
+            let mut next = self.state[(i + 397) % 624] ^ (y >> 1);
+            if y & 1 != 0 {
+                next ^= 0x9908_b0df;
+            }
+            self.state[i] = next;
+        }
+        self.pos = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.pos >= 624 {
+            self.twist();
+        }
+        let mut y = self.state[self.pos];
+        self.pos += 1;
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c_5680;
+        y ^= (y << 15) & 0xefc6_0000;
+        y ^ (y >> 18)
+    }
+
+    /// Uniform value in `0..=max` by masked rejection, as numpy's `random_interval`.
+    pub(crate) fn interval(&mut self, max: u32) -> u32 {
+        if max == 0 {
+            return 0;
+        }
+        let mut mask = max;
+        mask |= mask >> 1;
+        mask |= mask >> 2;
+        mask |= mask >> 4;
+        mask |= mask >> 8;
+        mask |= mask >> 16;
+        loop {
+            let value = self.next_u32() & mask;
+            if value <= max {
+                return value;
+            }
+        }
+    }
+
+    /// In-place equivalent of `RandomState.permutation`.
+    fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.interval(i as u32) as usize;
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Global item range `[begin, end)` handed to one worker, and the chunk it comes from.
+struct Interval {
+    chunk_index: usize,
+    begin: u64,
+    end: u64,
+}
+
+struct Plan {
+    /// Chunk start offsets in the global item numbering, plus the total at the end.
+    chunk_starts: Vec<u64>,
+    workers: Vec<Vec<Interval>>,
+}
+
+impl IterationConfig {
+    fn seed(&self) -> u32 {
+        self.seed.unwrap_or(DEFAULT_SEED)
+    }
+
+    fn epoch(&self) -> u32 {
+        self.epoch.unwrap_or(DEFAULT_EPOCH)
+    }
+
+    fn num_workers(&self) -> u32 {
+        self.num_workers.max(1)
+    }
+
+    fn world_size(&self) -> u32 {
+        self.world_size.unwrap_or(1).max(1)
+    }
+}
+
+/// Mirrors litdata's chunk shuffle and `_associate_chunks_and_intervals_to_workers`.
+fn plan_workers(parsed: &ParsedIndex, config: &IterationConfig) -> AppResult<Plan> {
+    let mut chunk_starts = Vec::with_capacity(parsed.chunks.len() + 1);
+    let mut total = 0u64;
+    for chunk in &parsed.chunks {
+        chunk_starts.push(total);
+        total += chunk.chunk_size as u64;
+    }
+    chunk_starts.push(total);
+
+    let mut chunk_order: Vec<usize> = (0..parsed.chunks.len()).collect();
+    if config.shuffle {
+        let seed = config.seed().checked_add(config.epoch()).ok_or_else(|| {
+            AppError::Invalid("seed + epoch must fit in 32 bits to match numpy".into())
+        })?;
+        Mt19937::from_seed(seed).shuffle(&mut chunk_order);
+    }
+
+    let total_workers = (config.world_size() * config.num_workers()) as u64;
+    let batch = config.batch_size.max(1) as u64;
+    let mut quota: Vec<u64> = (0..total_workers)
+        .map(|w| {
+            let share = total / total_workers;
+            if w == total_workers - 1 && !config.drop_last {
+                share + total % total_workers
+            } else if config.drop_last {
+                share / batch * batch
+            } else {
+                share
+            }
+        })
+        .collect();
+
+    let mut workers: Vec<Vec<Interval>> = (0..total_workers).map(|_| Vec::new()).collect();
+    let mut rank = 0usize;
+    for chunk_index in chunk_order {
+        let mut begin = chunk_starts[chunk_index];
+        let end = chunk_starts[chunk_index + 1];
+        while begin < end && rank < workers.len() {
+            if quota[rank] == 0 {
+                rank += 1;
+                continue;
+            }
+            let take = (end - begin).min(quota[rank]);
+            workers[rank].push(Interval {
+                chunk_index,
+                begin,
+                end: begin + take,
+            });
+            quota[rank] -= take;
+            begin += take;
+        }
+    }
+    Ok(Plan {
+        chunk_starts,
+        workers,
+    })
+}
+
+/// Items in positions `from..to` of one worker's stream, in the order the worker yields them.
+fn worker_items(
+    intervals: &[Interval],
+    config: &IterationConfig,
+    from: u64,
+    to: u64,
+) -> Vec<(usize, u64)> {
+    let mut out = Vec::new();
+    let mut cursor = 0u64;
+    for (position, interval) in intervals.iter().enumerate() {
+        let len = interval.end - interval.begin;
+        if cursor + len > from && cursor < to {
+            let mut indexes: Vec<u64> = (interval.begin..interval.end).collect();
+            if config.shuffle {
+                Mt19937::from_key(&[config.seed(), config.epoch(), position as u32])
+                    .shuffle(&mut indexes);
+            }
+            let lo = from.saturating_sub(cursor) as usize;
+            let hi = (to - cursor).min(len) as usize;
+            out.extend(indexes[lo..hi].iter().map(|g| (interval.chunk_index, *g)));
+        }
+        cursor += len;
+        if cursor >= to {
+            break;
+        }
+    }
+    out
+}
+
+/// Steps taken before round `round`: every worker with batches left contributes one per round.
+fn steps_before_round(worker_batches: &[u64], round: u64) -> u64 {
+    worker_batches.iter().map(|b| (*b).min(round)).sum()
+}
+
+/// DataLoader visits workers round-robin, skipping the ones that have run dry.
+fn locate_step(worker_batches: &[u64], step: u64) -> Option<(u32, u64)> {
+    let total: u64 = worker_batches.iter().sum();
+    if step >= total {
+        return None;
+    }
+    let (mut lo, mut hi) = (0u64, worker_batches.iter().copied().max().unwrap_or(0));
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if steps_before_round(worker_batches, mid) <= step {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    let offset = step - steps_before_round(worker_batches, lo);
+    let worker = worker_batches
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| **b > lo)
+        .nth(offset as usize)?
+        .0;
+    Some((worker as u32, lo))
+}
+
+fn step_of(worker_batches: &[u64], worker: u32, batch_index: u64) -> u64 {
+    steps_before_round(worker_batches, batch_index)
+        + worker_batches[..worker as usize]
+            .iter()
+            .filter(|b| **b > batch_index)
+            .count() as u64
+}
+
+/// Maps a training step, or a worker's batch, back to the samples it contained.
+#[tauri::command]
+pub async fn locate_training_batch(
+    index_path: String,
+    config: IterationConfig,
+    step: Option<u64>,
+    worker: Option<u32>,
+    batch_index: Option<u64>,
+) -> AppResult<BatchLocation> {
+    spawn_blocking(move || {
+        locate_training_batch_sync(Path::new(&index_path), &config, step, worker, batch_index)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn locate_training_batch_sync(
+    index_path: &Path,
+    config: &IterationConfig,
+    step: Option<u64>,
+    worker: Option<u32>,
+    batch_index: Option<u64>,
+) -> AppResult<BatchLocation> {
+    let rank = config.rank.unwrap_or(0);
+    if rank >= config.world_size() {
+        return Err(AppError::Invalid(format!(
+            "rank {rank} is outside a world of {}",
+            config.world_size()
+        )));
+    }
+    let parsed = parse_index(index_path)?;
+    let plan = plan_workers(&parsed, config)?;
+    let batch = config.batch_size.max(1) as u64;
+    let first = (rank * config.num_workers()) as usize;
+    let local = &plan.workers[first..first + config.num_workers() as usize];
+    let worker_batches: Vec<u64> = local
+        .iter()
+        .map(|intervals| {
+            let items: u64 = intervals.iter().map(|i| i.end - i.begin).sum();
+            if config.drop_last {
+                items / batch
+            } else {
+                items.div_ceil(batch)
+            }
+        })
+        .collect();
+    let total_steps: u64 = worker_batches.iter().sum();
+
+    let (worker, batch_index) = match (step, worker, batch_index) {
+        (Some(step), _, _) => locate_step(&worker_batches, step).ok_or_else(|| {
+            AppError::Invalid(format!(
+                "step {step} is past the end of the epoch ({total_steps} steps)"
+            ))
+        })?,
+        (None, Some(worker), Some(batch_index)) => {
+            let batches = worker_batches.get(worker as usize).ok_or_else(|| {
+                AppError::Invalid(format!("worker {worker} is outside num_workers"))
+            })?;
+            if batch_index >= *batches {
+                return Err(AppError::Invalid(format!(
+                    "worker {worker} only produces {batches} batches"
+                )));
+            }
+            (worker, batch_index)
+        }
+        _ => {
+            return Err(AppError::Invalid(
+                "provide a step, or both a worker and a batch index".into(),
+            ))
+        }
+    };
+
+    let from = batch_index * batch;
+    let samples = worker_items(&local[worker as usize], config, from, from + batch)
+        .into_iter()
+        .enumerate()
+        .map(|(position, (chunk_index, global_index))| SampleLocation {
+            position: position as u32,
+            global_index,
+            chunk_index,
+            chunk_filename: parsed.chunks[chunk_index].filename.clone(),
+            item_index: (global_index - plan.chunk_starts[chunk_index]) as u32,
+        })
+        .collect();
+    Ok(BatchLocation {
+        step: step_of(&worker_batches, worker, batch_index),
+        worker,
+        batch_index,
+        total_steps,
+        worker_batches,
+        samples,
+    })
+}
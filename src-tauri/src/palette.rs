@@ -0,0 +1,224 @@
+use crate::bookmarks::{self, Bookmark};
+use crate::litdata::{AppError, AppResult};
+use crate::recent;
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_MATCH_LIMIT: usize = 50;
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const BOUNDARY_BONUS: i64 = 10;
+/// Skipped characters cost this much each, up to `MAX_GAP_PENALTY` per gap.
+const GAP_PENALTY: i64 = 1;
+const MAX_GAP_PENALTY: i64 = 8;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    Chunk,
+    Bookmark,
+    Tag,
+    RecentDataset,
+}
+
+/// One palette entry. `positions` are the char indices of `label` that matched, for
+/// highlighting; the optional ids say where selecting the entry should navigate.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteMatch {
+    kind: MatchKind,
+    label: String,
+    detail: Option<String>,
+    score: i64,
+    positions: Vec<usize>,
+    handle: Option<DatasetHandle>,
+    index_path: Option<String>,
+    chunk_filename: Option<String>,
+    item_index: Option<u32>,
+    field_index: Option<usize>,
+}
+
+impl PaletteMatch {
+    fn new(kind: MatchKind, label: String) -> Self {
+        PaletteMatch {
+            kind,
+            label,
+            detail: None,
+            score: 0,
+            positions: Vec::new(),
+            handle: None,
+            index_path: None,
+            chunk_filename: None,
+            item_index: None,
+            field_index: None,
+        }
+    }
+
+    fn bookmark(bookmark: &Bookmark) -> Self {
+        let target = match bookmark.field_index {
+            Some(field) => format!(
+                "{} · item {} · field {field}",
+                bookmark.chunk_filename, bookmark.item_index
+            ),
+            None => format!("{} · item {}", bookmark.chunk_filename, bookmark.item_index),
+        };
+        let (label, detail) = match &bookmark.label {
+            Some(label) => (label.clone(), target),
+            None => (target, bookmark.index_path.clone()),
+        };
+        PaletteMatch {
+            detail: Some(detail),
+            index_path: Some(bookmark.index_path.clone()),
+            chunk_filename: Some(bookmark.chunk_filename.clone()),
+            item_index: Some(bookmark.item_index),
+            field_index: bookmark.field_index,
+            ..PaletteMatch::new(MatchKind::Bookmark, label)
+        }
+    }
+}
+
+fn is_boundary(prev: Option<char>, current: char) -> bool {
+    match prev {
+        None => true,
+        Some(p) => {
+            matches!(p, '/' | '\\' | '_' | '-' | '.' | ' ' | ':')
+                || (p.is_lowercase() && current.is_uppercase())
+                || (p.is_alphabetic() && current.is_ascii_digit())
+        }
+    }
+}
+
+/// Scores `query` as a case-insensitive subsequence of `candidate`, rewarding runs and
+/// matches at word boundaries. Each occurrence of the first query char is tried as a
+/// starting point and the best greedy match wins.
+pub(crate) fn fuzzy_score(query: &[char], candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let first = *query.first()?;
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|&i| lower[i] == first) {
+        let mut positions = Vec::with_capacity(query.len());
+        let mut pos = start;
+        for &q in query {
+            while pos < lower.len() && lower[pos] != q {
+                pos += 1;
+            }
+            if pos == lower.len() {
+                break;
+            }
+            positions.push(pos);
+            pos += 1;
+        }
+        if positions.len() < query.len() {
+            // Later starts only see a shorter suffix, so they cannot match either.
+            break;
+        }
+        let mut score = 0;
+        let mut prev: Option<usize> = None;
+        for &p in &positions {
+            score += MATCH_SCORE;
+            if is_boundary(p.checked_sub(1).map(|i| chars[i]), chars[p]) {
+                score += BOUNDARY_BONUS;
+            }
+            match prev {
+                Some(q) if q + 1 == p => score += CONSECUTIVE_BONUS,
+                Some(q) => score -= (GAP_PENALTY * (p - q - 1) as i64).min(MAX_GAP_PENALTY),
+                None => score -= (GAP_PENALTY * p as i64).min(MAX_GAP_PENALTY),
+            }
+            prev = Some(p);
+        }
+        // Among equal matches, shorter candidates are the more specific ones.
+        score -= (chars.len() / 8) as i64;
+        if best.as_ref().is_none_or(|(s, _)| score > *s) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+fn scored(mut entry: PaletteMatch, query: &[char]) -> Option<PaletteMatch> {
+    let (score, positions) = fuzzy_score(query, &entry.label)?;
+    entry.score = score;
+    entry.positions = positions;
+    Some(entry)
+}
+
+/// Ranked matches for a command palette across open datasets' chunk filenames,
+/// bookmarks, bookmark tags, and recently opened datasets. An empty query lists
+/// recent datasets and bookmarks unranked.
+#[tauri::command]
+pub async fn fuzzy_find(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<Vec<PaletteMatch>> {
+    let open = workspace.snapshot()?;
+    spawn_blocking(move || {
+        let limit = limit.unwrap_or(DEFAULT_MATCH_LIMIT);
+        let query: Vec<char> = query
+            .trim()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+        let recents = recent::list(&app)?;
+        let saved = bookmarks::list(&app)?;
+
+        let mut candidates = Vec::new();
+        for dataset in &recents {
+            candidates.push(PaletteMatch {
+                detail: Some(format!("{} chunks", dataset.chunk_count)),
+                index_path: Some(dataset.index_path.clone()),
+                ..PaletteMatch::new(MatchKind::RecentDataset, dataset.index_path.clone())
+            });
+        }
+        candidates.extend(saved.iter().map(PaletteMatch::bookmark));
+        if query.is_empty() {
+            candidates.truncate(limit);
+            return Ok(candidates);
+        }
+
+        let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in saved.iter().flat_map(|b| b.tags.iter()) {
+            *tags.entry(tag.as_str()).or_default() += 1;
+        }
+        candidates.extend(tags.into_iter().map(|(tag, count)| PaletteMatch {
+            detail: Some(format!(
+                "{count} bookmark{}",
+                if count == 1 { "" } else { "s" }
+            )),
+            ..PaletteMatch::new(MatchKind::Tag, tag.to_string())
+        }));
+        let mut matches: Vec<PaletteMatch> = candidates
+            .into_iter()
+            .filter_map(|entry| scored(entry, &query))
+            .collect();
+        for (handle, parsed) in &open {
+            let dataset = parsed.source.display().to_string();
+            matches.extend(parsed.chunks.iter().filter_map(|chunk| {
+                let (score, positions) = fuzzy_score(&query, &chunk.filename)?;
+                Some(PaletteMatch {
+                    detail: Some(format!("{} items · {dataset}", chunk.chunk_size)),
+                    score,
+                    positions,
+                    handle: Some(*handle),
+                    index_path: Some(dataset.clone()),
+                    chunk_filename: Some(chunk.filename.clone()),
+                    ..PaletteMatch::new(MatchKind::Chunk, chunk.filename.clone())
+                })
+            }));
+        }
+        matches.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.label.cmp(&b.label)));
+        matches.truncate(limit);
+        Ok(matches)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
@@ -0,0 +1,232 @@
+use crate::audio::probe_header;
+use crate::audio_stats::AUDIO_EXTS;
+use crate::litdata::{
+    guess_ext, leaf_file_name, load_chunk_access, parse_offsets, read_field_bytes, AppError,
+    AppResult, ChunkAccess, ChunkCache, ParsedIndex,
+};
+use crate::media_server::{share_bytes, DEFAULT_TTL_SECS};
+use crate::progress::Progress;
+use crate::rewrite::locate;
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::settings;
+use crate::usage;
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Where playlist entries point.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistSource {
+    /// Files written to the temp folder, like `open_leaf`.
+    #[default]
+    Files,
+    /// Loopback URLs from the media server, which expire like any shared field.
+    Http,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    global_index: u64,
+    reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistReport {
+    job_id: String,
+    playlist_path: String,
+    entries: usize,
+    /// Summed from container headers; entries without a length are left out.
+    total_seconds: f64,
+    skipped: Vec<SkippedEntry>,
+    /// Media tokens of `http` entries, for `unshare_media`.
+    tokens: Vec<String>,
+    opened: bool,
+}
+
+struct Entry {
+    title: String,
+    seconds: Option<f64>,
+    location: String,
+    token: Option<String>,
+}
+
+/// Writes an M3U playlist of the audio field `field_index` for the items at
+/// `global_indices`, in that order, and opens it in the default player when `open` is
+/// set. Items whose field is not audio are skipped.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_audio_playlist(
+    app: AppHandle,
+    handle: DatasetHandle,
+    global_indices: Vec<u64>,
+    field_index: usize,
+    source: Option<PlaylistSource>,
+    open: bool,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<PlaylistReport> {
+    let progress = Progress::new(app, job_id, "playlist");
+    let parsed = workspace.get(handle)?;
+    ensure_writable(&settings::temp_root(), &workspace.dataset_dirs(&parsed))?;
+    spawn_blocking(move || {
+        playlist_sync(
+            &parsed,
+            &global_indices,
+            field_index,
+            source.unwrap_or_default(),
+            open,
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn read_entry(
+    parsed: &ParsedIndex,
+    access: &ChunkAccess,
+    table: &(u32, Vec<u64>),
+    chunk_filename: &str,
+    item: u32,
+    field_index: usize,
+    source: PlaylistSource,
+) -> AppResult<Entry> {
+    let fmt = parsed.config.data_format.as_deref().unwrap_or_default();
+    let (data, _) = read_field_bytes(access, table, item, field_index, fmt.len(), None)?;
+    let ext = guess_ext(fmt.get(field_index), &data)
+        .filter(|ext| AUDIO_EXTS.contains(&ext.as_str()))
+        .ok_or_else(|| AppError::Invalid("field is not audio".into()))?;
+    let seconds = probe_header(&data, Some(&ext))?.seconds;
+    usage::record_bytes(&parsed.source, data.len());
+    let file_name = leaf_file_name(chunk_filename, item, field_index, &ext);
+    let (location, token) = match source {
+        PlaylistSource::Files => {
+            let out = settings::temp_root().join(&file_name);
+            fs::write(&out, &data)?;
+            (out.display().to_string(), None)
+        }
+        PlaylistSource::Http => {
+            let shared = share_bytes(data, file_name, DEFAULT_TTL_SECS)?;
+            (shared.url().to_string(), Some(shared.token().to_string()))
+        }
+    };
+    Ok(Entry {
+        title: format!("{chunk_filename} #{item}"),
+        seconds,
+        location,
+        token,
+    })
+}
+
+pub(crate) fn playlist_sync(
+    parsed: &ParsedIndex,
+    global_indices: &[u64],
+    field_index: usize,
+    source: PlaylistSource,
+    open: bool,
+    progress: &Progress,
+) -> AppResult<PlaylistReport> {
+    if global_indices.is_empty() {
+        return Err(AppError::Invalid("select at least one item".into()));
+    }
+    if field_index >= parsed.format_len() {
+        return Err(AppError::Invalid("field index out of range".into()));
+    }
+    // Items grouped by chunk so each chunk is read once.
+    let mut by_chunk: BTreeMap<usize, Vec<(u64, u32)>> = BTreeMap::new();
+    for global in global_indices {
+        let (chunk, item) = locate(parsed, *global)?;
+        by_chunk.entry(chunk).or_default().push((*global, item));
+    }
+    let groups: Vec<(usize, Vec<(u64, u32)>)> = by_chunk.into_iter().collect();
+    fs::create_dir_all(settings::temp_root())?;
+    let total = groups.len() as u64;
+    progress.report("exporting", 0, total);
+    let read = par_map(&groups, Some((progress, "exporting")), |(chunk, items)| {
+        let filename = &parsed.chunks[*chunk].filename;
+        let scratch = ChunkCache::default();
+        let loaded = load_chunk_access(parsed, filename, &scratch)
+            .and_then(|access| parse_offsets(&access).map(|table| (access, table)));
+        items
+            .iter()
+            .map(|(global, item)| {
+                let entry = match &loaded {
+                    Ok((access, table)) => {
+                        read_entry(parsed, access, table, filename, *item, field_index, source)
+                            .map_err(|e| e.to_string())
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                (*global, entry)
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut entries: HashMap<u64, Result<Entry, String>> = read.into_iter().flatten().collect();
+
+    let mut playlist = String::from("#EXTM3U\n");
+    let mut listed = 0;
+    let mut total_seconds = 0.0;
+    let mut skipped = Vec::new();
+    let mut tokens = Vec::new();
+    for global in global_indices {
+        match entries.remove(global) {
+            Some(Ok(entry)) => {
+                let length = entry.seconds.map_or(-1, |s| s.ceil() as i64);
+                total_seconds += entry.seconds.unwrap_or_default();
+                playlist.push_str(&format!(
+                    "#EXTINF:{length},{}\n{}\n",
+                    entry.title, entry.location
+                ));
+                tokens.extend(entry.token);
+                listed += 1;
+            }
+            Some(Err(reason)) => skipped.push(SkippedEntry {
+                global_index: *global,
+                reason,
+            }),
+            // A repeated index is listed once.
+            None => {}
+        }
+    }
+    progress.report("done", total, total);
+    if listed == 0 {
+        return Err(AppError::Invalid(format!(
+            "none of the selected items has audio in field {field_index}"
+        )));
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = settings::temp_root().join(format!("playlist-{stamp}.m3u"));
+    fs::write(&path, playlist)?;
+    if open {
+        open_playlist(&path)?;
+    }
+    Ok(PlaylistReport {
+        job_id: progress.job_id().to_string(),
+        playlist_path: path.display().to_string(),
+        entries: listed,
+        total_seconds,
+        skipped,
+        tokens,
+        opened: open,
+    })
+}
+
+fn open_playlist(path: &Path) -> AppResult<()> {
+    match settings::viewer_for("m3u") {
+        Some(viewer) => viewer.launch(path),
+        None => open::that_detached(path).map_err(|e| AppError::Open(e.to_string())),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::litdata::{chunk_cache_key, load_chunk_access, ChunkCache, ParsedIndex};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, OnceLock},
+    thread,
+};
+
+/// Chunk paths currently being decompressed in the background.
+fn in_flight() -> &'static Mutex<HashSet<String>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// After `chunk_filename` is opened, decompresses the chunk that follows it into `cache`
+/// on a background thread so sequential browsing doesn't wait on zstd.
+///
+/// Only zstd chunks are worth it, and only when the next chunk fits in the cache
+/// without evicting the one the user is looking at. Failures are dropped; the foreground
+/// load reports them if the user actually opens that chunk.
+pub(crate) fn prefetch_next(parsed: &Arc<ParsedIndex>, chunk_filename: &str, cache: &ChunkCache) {
+    let is_zstd = parsed
+        .config
+        .compression
+        .as_ref()
+        .is_some_and(|c| c.eq_ignore_ascii_case("zstd"));
+    if !is_zstd {
+        return;
+    }
+    let Some(pos) = parsed
+        .chunks
+        .iter()
+        .position(|c| c.filename == chunk_filename)
+    else {
+        return;
+    };
+    let Some(next) = parsed.chunks.get(pos + 1) else {
+        return;
+    };
+    let (Ok(current_path), Ok(next_path)) = (
+        parsed.chunk_path(chunk_filename),
+        parsed.chunk_path(&next.filename),
+    ) else {
+        return;
+    };
+    let key = chunk_cache_key(&next_path);
+    let keep = chunk_cache_key(&current_path);
+    let Ok(len) = usize::try_from(next.chunk_bytes) else {
+        return;
+    };
+    if !cache.has_room_for(&key, len, &keep) {
+        return;
+    }
+    let claimed = in_flight()
+        .lock()
+        .map(|mut pending| pending.insert(key.clone()))
+        .unwrap_or(false);
+    if !claimed {
+        return;
+    }
+
+    let parsed = Arc::clone(parsed);
+    let cache = cache.clone();
+    let next_filename = next.filename.clone();
+    let spawned = thread::Builder::new().name("chunk-prefetch".into()).spawn({
+        let key = key.clone();
+        move || {
+            let _ = load_chunk_access(&parsed, &next_filename, &cache);
+            if let Ok(mut pending) = in_flight().lock() {
+                pending.remove(&key);
+            }
+        }
+    });
+    if spawned.is_err() {
+        if let Ok(mut pending) = in_flight().lock() {
+            pending.remove(&key);
+        }
+    }
+}
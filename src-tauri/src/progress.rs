@@ -0,0 +1,117 @@
+use litdata_core::progress::{percent, ReportProgress};
+use serde::Serialize;
+use std::{
+    io::{self, Read},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{AppHandle, Emitter};
+
+/// Minimum bytes between two reports from a `ProgressReader`.
+const READ_REPORT_STEP: u64 = 4 * 1024 * 1024;
+
+/// Event carrying progress for long-running jobs; listeners filter by `jobId`.
+pub const PROGRESS_EVENT: &str = "job-progress";
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    job_id: String,
+    phase: String,
+    done: u64,
+    total: u64,
+    percent: f64,
+}
+
+#[derive(Clone)]
+pub struct Progress {
+    app: AppHandle,
+    job_id: String,
+}
+
+/// Uses the caller-provided job id, or derives one from `kind` and the current time.
+fn job_id_for(job_id: Option<String>, kind: &str) -> String {
+    job_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default();
+            format!("{kind}-{millis}")
+        })
+}
+
+impl Progress {
+    pub fn new(app: AppHandle, job_id: Option<String>, kind: &str) -> Self {
+        Progress {
+            app,
+            job_id: job_id_for(job_id, kind),
+        }
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn report(&self, phase: &str, done: u64, total: u64) {
+        // Progress is best-effort; a closed window must not fail the job.
+        let _ = self.app.emit(
+            PROGRESS_EVENT,
+            ProgressEvent {
+                job_id: self.job_id.clone(),
+                phase: phase.to_string(),
+                done,
+                total,
+                percent: percent(done, total),
+            },
+        );
+    }
+}
+
+impl ReportProgress for Progress {
+    fn report(&self, phase: &str, done: u64, total: u64) {
+        Progress::report(self, phase, done, total);
+    }
+}
+
+/// Reports bytes pulled through the wrapped reader under `phase`.
+pub(crate) struct ProgressReader<'a, R> {
+    inner: R,
+    progress: Option<&'a Progress>,
+    phase: &'static str,
+    done: u64,
+    total: u64,
+    reported: u64,
+}
+
+impl<'a, R: Read> ProgressReader<'a, R> {
+    pub(crate) fn new(
+        inner: R,
+        progress: Option<&'a Progress>,
+        phase: &'static str,
+        total: u64,
+    ) -> Self {
+        ProgressReader {
+            inner,
+            progress,
+            phase,
+            done: 0,
+            total,
+            reported: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.done += n as u64;
+        if let Some(progress) = self.progress {
+            if self.done - self.reported >= READ_REPORT_STEP || self.done == self.total {
+                self.reported = self.done;
+                progress.report(self.phase, self.done, self.total);
+            }
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::litdata::{
+    load_chunk_access_async, materialize_leaf, AppError, AppResult, ChunkCache, ErrorContext,
+    ResultExt,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::security::ensure_writable;
+use crate::settings;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::corruption::explain_corruption;
+use std::path::Path;
+use tauri::async_runtime::spawn_blocking;
+
+/// Writes the field to the temp folder with its guessed extension and shows it in a
+/// Quick Look panel, like pressing space in Finder, instead of launching an app. Only
+/// available on macOS. Returns the temp file's path.
+#[tauri::command]
+pub async fn quick_look_field(
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: usize,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+    offsets: tauri::State<'_, OffsetsCache>,
+) -> AppResult<String> {
+    if !cfg!(target_os = "macos") {
+        return Err(AppError::Open(
+            "Quick Look is only available on macOS".into(),
+        ));
+    }
+    let parsed = workspace.get(handle)?;
+    ensure_writable(&settings::temp_root(), &workspace.dataset_dirs(&parsed))?;
+    let context = ErrorContext::chunk(&parsed, &chunk_filename)
+        .item(item_index)
+        .field(field_index);
+    let access = load_chunk_access_async(&parsed, &chunk_filename, &cache, None)
+        .await
+        .within(|| context.clone())?;
+    let offsets = (*offsets).clone();
+    spawn_blocking(move || {
+        let table = offsets
+            .get(&parsed.chunk_path(&chunk_filename)?, &access)
+            .map_err(|e| explain_corruption(e, &access, &chunk_filename))?;
+        let (out, _, _) = materialize_leaf(
+            &access,
+            &table,
+            parsed.config.data_format.as_deref().unwrap_or_default(),
+            &parsed.source,
+            &chunk_filename,
+            item_index,
+            field_index,
+        )?;
+        show(&out)?;
+        Ok(out.display().to_string())
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+    .within(|| context)
+}
+
+/// `qlmanage -p` runs until the panel is closed, so it is reaped on its own thread.
+#[cfg(target_os = "macos")]
+fn show(path: &Path) -> AppResult<()> {
+    use std::process::{Command, Stdio};
+    let mut child = Command::new("qlmanage")
+        .arg("-p")
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| AppError::Open(format!("qlmanage: {e}")))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn show(_path: &Path) -> AppResult<()> {
+    Err(AppError::Open(
+        "Quick Look is only available on macOS".into(),
+    ))
+}
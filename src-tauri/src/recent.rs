@@ -0,0 +1,224 @@
+use crate::litdata::{AppError, AppResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const RECENT_FILE: &str = "recent-datasets.json";
+const MAX_RECENT: usize = 20;
+const PINNED_FILE: &str = "pinned-datasets.json";
+
+/// Serializes read-modify-write cycles on the recent and pinned lists.
+static RECENT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentDataset {
+    pub(crate) index_path: String,
+    opened_at: u64,
+    pub(crate) chunk_count: usize,
+    item_count: u64,
+    /// Whether every chunk was on disk when last opened; `None` if not yet known.
+    valid: Option<bool>,
+}
+
+impl RecentDataset {
+    pub(crate) fn new(
+        index_path: String,
+        chunk_count: usize,
+        item_count: u64,
+        valid: Option<bool>,
+    ) -> Self {
+        RecentDataset {
+            index_path,
+            opened_at: now_millis(),
+            chunk_count,
+            item_count,
+            valid,
+        }
+    }
+}
+
+/// A dataset kept on the start page regardless of how long ago it was opened.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedDataset {
+    index_path: String,
+    display_name: Option<String>,
+    /// CSS hex color such as `#10b981`.
+    color: Option<String>,
+    pinned_at: u64,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn data_file(app: &AppHandle, name: &str) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir.join(name))
+}
+
+fn recent_path(app: &AppHandle) -> AppResult<PathBuf> {
+    data_file(app, RECENT_FILE)
+}
+
+fn lock() -> AppResult<std::sync::MutexGuard<'static, ()>> {
+    RECENT_LOCK
+        .lock()
+        .map_err(|_| AppError::Task("recent datasets lock poisoned".into()))
+}
+
+/// A missing or unreadable list is treated as empty rather than failing the caller.
+fn read_list<T: DeserializeOwned>(path: &PathBuf) -> Vec<T> {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_list<T: Serialize>(path: &PathBuf, list: &[T]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(list)
+        .map_err(|e| AppError::Invalid(format!("serializing dataset list: {e}")))?;
+    let partial = path.with_extension("json.part");
+    fs::write(&partial, json)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Moves `entry` to the front of the recent list, dropping the oldest past the cap.
+pub(crate) fn record(app: &AppHandle, entry: RecentDataset) -> AppResult<()> {
+    let path = recent_path(app)?;
+    let _guard = lock()?;
+    let mut list: Vec<RecentDataset> = read_list(&path);
+    list.retain(|d| d.index_path != entry.index_path);
+    list.insert(0, entry);
+    list.truncate(MAX_RECENT);
+    write_list(&path, &list)
+}
+
+/// Fills in validity once a background existence check finishes.
+pub(crate) fn set_valid(app: &AppHandle, index_path: &str, valid: bool) -> AppResult<()> {
+    let path = recent_path(app)?;
+    let _guard = lock()?;
+    let mut list: Vec<RecentDataset> = read_list(&path);
+    let Some(entry) = list.iter_mut().find(|d| d.index_path == index_path) else {
+        return Ok(());
+    };
+    entry.valid = Some(valid);
+    write_list(&path, &list)
+}
+
+/// Recently opened datasets, most recent first.
+pub(crate) fn list(app: &AppHandle) -> AppResult<Vec<RecentDataset>> {
+    Ok(read_list(&recent_path(app)?))
+}
+
+#[tauri::command]
+pub async fn get_recent_datasets(app: AppHandle) -> AppResult<Vec<RecentDataset>> {
+    spawn_blocking(move || list(&app))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn remove_recent(app: AppHandle, index_path: String) -> AppResult<Vec<RecentDataset>> {
+    spawn_blocking(move || {
+        let path = recent_path(&app)?;
+        let _guard = lock()?;
+        let mut list: Vec<RecentDataset> = read_list(&path);
+        list.retain(|d| d.index_path != index_path);
+        write_list(&path, &list)?;
+        Ok(list)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn valid_color(color: &str) -> bool {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Pinned datasets in the order they were pinned.
+pub(crate) fn pinned(app: &AppHandle) -> AppResult<Vec<PinnedDataset>> {
+    Ok(read_list(&data_file(app, PINNED_FILE)?))
+}
+
+#[tauri::command]
+pub async fn get_pinned_datasets(app: AppHandle) -> AppResult<Vec<PinnedDataset>> {
+    spawn_blocking(move || pinned(&app))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Pins `index_path`, or updates the name and color of an existing pin in place.
+#[tauri::command]
+pub async fn pin_dataset(
+    app: AppHandle,
+    index_path: String,
+    display_name: Option<String>,
+    color: Option<String>,
+) -> AppResult<Vec<PinnedDataset>> {
+    let display_name = display_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let color = color
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if let Some(color) = &color {
+        if !valid_color(color) {
+            return Err(AppError::Invalid(format!(
+                "color {color:?} is not a hex color like #10b981"
+            )));
+        }
+    }
+    spawn_blocking(move || {
+        let path = data_file(&app, PINNED_FILE)?;
+        let _guard = lock()?;
+        let mut list: Vec<PinnedDataset> = read_list(&path);
+        match list.iter_mut().find(|p| p.index_path == index_path) {
+            Some(existing) => {
+                existing.display_name = display_name;
+                existing.color = color;
+            }
+            None => list.push(PinnedDataset {
+                index_path,
+                display_name,
+                color,
+                pinned_at: now_millis(),
+            }),
+        }
+        write_list(&path, &list)?;
+        Ok(list)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn unpin_dataset(app: AppHandle, index_path: String) -> AppResult<Vec<PinnedDataset>> {
+    spawn_blocking(move || {
+        let path = data_file(&app, PINNED_FILE)?;
+        let _guard = lock()?;
+        let mut list: Vec<PinnedDataset> = read_list(&path);
+        list.retain(|p| p.index_path != index_path);
+        write_list(&path, &list)?;
+        Ok(list)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
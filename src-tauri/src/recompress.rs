@@ -0,0 +1,143 @@
+use crate::litdata::{
+    is_chunk_path, load_chunk_access, parse_index, read_index_file, AppError, AppResult,
+    ChunkAccess, ChunkCache,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::security::{confine_to_root, ensure_writable, relative_chunk_name};
+use crate::seekable::{write_seekable, DEFAULT_FRAME_SIZE};
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_LEVEL: i32 = 3;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecompressReport {
+    job_id: String,
+    index_path: String,
+    chunk_count: usize,
+    input_bytes: u64,
+    output_bytes: u64,
+    frame_size: usize,
+}
+
+/// litdata names compressed chunks `<stem>.zstd.bin`.
+fn zstd_chunk_name(filename: &str) -> String {
+    if filename.contains(".zstd.") {
+        return filename.to_string();
+    }
+    match filename.strip_suffix(".bin") {
+        Some(stem) => format!("{stem}.zstd.bin"),
+        None => format!("{filename}.zstd"),
+    }
+}
+
+/// Rewrites every chunk of a dataset into `dest_dir` as seekable zstd, alongside an
+/// index.json that points at the new files.
+#[tauri::command]
+pub async fn recompress_dataset(
+    app: AppHandle,
+    index_path: String,
+    dest_dir: String,
+    frame_size: Option<usize>,
+    level: Option<i32>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<RecompressReport> {
+    let progress = Progress::new(app, job_id, "recompress");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        recompress_sync(
+            Path::new(&index_path),
+            Path::new(dest_dir.trim()),
+            frame_size.unwrap_or(DEFAULT_FRAME_SIZE),
+            level.unwrap_or(DEFAULT_LEVEL),
+            &progress,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn recompress_sync(
+    index_path: &Path,
+    dest_dir: &Path,
+    frame_size: usize,
+    level: i32,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<RecompressReport> {
+    let parsed = parse_index(index_path)?;
+    if is_chunk_path(&parsed.source) {
+        return Err(AppError::Invalid(
+            "recompression needs an index.json, not a standalone chunk".into(),
+        ));
+    }
+    if dest_dir.as_os_str().is_empty() {
+        return Err(AppError::Invalid("destination directory is empty".into()));
+    }
+    if fs::canonicalize(dest_dir).ok() == fs::canonicalize(&parsed.root_dir).ok() {
+        return Err(AppError::Invalid(
+            "destination must differ from the dataset directory".into(),
+        ));
+    }
+    ensure_writable(dest_dir, &workspace.dataset_dirs(&parsed))?;
+    fs::create_dir_all(dest_dir)?;
+
+    let total = parsed.chunks.len() as u64;
+    progress.report("compressing", 0, total);
+    let results = par_map(&parsed.chunks, Some((progress, "compressing")), |chunk| {
+        let scratch = ChunkCache::default();
+        let data = match load_chunk_access(&parsed, &chunk.filename, &scratch)? {
+            ChunkAccess::Memory(buf) => buf,
+            other => other.read_exact_at(0, other.len()? as usize)?.into(),
+        };
+        let name = relative_chunk_name(&parsed.root_dir, &chunk.filename);
+        let target = confine_to_root(dest_dir, &zstd_chunk_name(&name))?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = BufWriter::new(File::create(&target)?);
+        let written = write_seekable(&data, frame_size, level, &mut out)?;
+        out.flush()?;
+        Ok((data.len() as u64, written))
+    })
+    .into_iter()
+    .collect::<AppResult<Vec<(u64, u64)>>>()?;
+
+    let mut index: serde_json::Value = serde_json::from_str(&read_index_file(&parsed.source)?)
+        .map_err(|e| AppError::Invalid(format!("index.json parse error: {e}")))?;
+    if let Some(config) = index.get_mut("config").and_then(|c| c.as_object_mut()) {
+        config.insert("compression".into(), "zstd".into());
+    }
+    if let Some(chunks) = index.get_mut("chunks").and_then(|c| c.as_array_mut()) {
+        for chunk in chunks.iter_mut().filter_map(|c| c.as_object_mut()) {
+            if let Some(name) = chunk.get("filename").and_then(|f| f.as_str()) {
+                let renamed = zstd_chunk_name(&relative_chunk_name(&parsed.root_dir, name));
+                chunk.insert("filename".into(), renamed.into());
+            }
+        }
+    }
+    let out_index = dest_dir.join("index.json");
+    let json = serde_json::to_vec(&index)
+        .map_err(|e| AppError::Invalid(format!("serializing index: {e}")))?;
+    fs::write(&out_index, json)?;
+    progress.report("done", total, total);
+
+    Ok(RecompressReport {
+        job_id: progress.job_id().to_string(),
+        index_path: out_index.display().to_string(),
+        chunk_count: results.len(),
+        input_bytes: results.iter().map(|(i, _)| i).sum(),
+        output_bytes: results.iter().map(|(_, o)| o).sum(),
+        frame_size,
+    })
+}
@@ -0,0 +1,271 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, split_fields, AppError, AppResult, ChunkAccess,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::rewrite::{
+    encode_chunk, encode_index, ensure_rewritable, index_document, is_compressed_index, pack_chunk,
+    pack_item, relativize_chunk_names, set_chunk_entry,
+};
+use crate::scan::par_map;
+use crate::security::{confine_to_root, ensure_writable, relative_chunk_name};
+use crate::workspace::Workspace;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fs, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// One find-and-replace applied to text fields, in the `regex` crate's syntax.
+/// `replacement` may refer to capture groups as `$1` or `${name}`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRule {
+    pattern: String,
+    replacement: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleHits {
+    pattern: String,
+    matches: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactReport {
+    job_id: String,
+    index_path: String,
+    fields: Vec<usize>,
+    chunk_count: usize,
+    items_changed: u64,
+    rules: Vec<RuleHits>,
+}
+
+/// Writes a copy of the dataset to `dest_dir` with `rules` applied, in order, to each
+/// text field (or to `fields`). Other fields and the original dataset are untouched.
+#[tauri::command]
+pub async fn redact_dataset(
+    app: AppHandle,
+    index_path: String,
+    dest_dir: String,
+    rules: Vec<RedactionRule>,
+    fields: Option<Vec<usize>>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<RedactReport> {
+    let progress = Progress::new(app, job_id, "redact");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        redact_sync(
+            &parsed,
+            Path::new(dest_dir.trim()),
+            &rules,
+            fields.as_deref(),
+            &progress,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Fields to redact: `requested`, or every text field.
+fn target_fields(formats: &[String], requested: Option<&[usize]>) -> AppResult<Vec<usize>> {
+    if let Some(requested) = requested {
+        if let Some(bad) = requested.iter().find(|f| **f >= formats.len()) {
+            return Err(AppError::Invalid(format!(
+                "field {bad} is out of range; data_format declares {}",
+                formats.len()
+            )));
+        }
+        return Ok(requested.to_vec());
+    }
+    let fields: Vec<usize> = formats
+        .iter()
+        .enumerate()
+        .filter(|(_, fmt)| matches!(fmt.to_lowercase().as_str(), "str" | "string" | "text"))
+        .map(|(field, _)| field)
+        .collect();
+    if fields.is_empty() {
+        return Err(AppError::Invalid(
+            "no text field; pick one explicitly".into(),
+        ));
+    }
+    Ok(fields)
+}
+
+/// What redacting one chunk wrote and found.
+struct ChunkOutcome {
+    raw_len: u64,
+    items: u32,
+    items_changed: u64,
+    matches: Vec<u64>,
+}
+
+/// `first_item` is the global index of the chunk's first item.
+fn redact_chunk(
+    parsed: &ParsedIndex,
+    filename: &str,
+    first_item: u64,
+    dest_dir: &Path,
+    rules: &[(Regex, &str)],
+    fields: &[usize],
+) -> AppResult<ChunkOutcome> {
+    let scratch = ChunkCache::default();
+    let access = load_chunk_access(parsed, filename, &scratch)?;
+    let (num_items, offsets) = parse_offsets(&access)?;
+    let data = match access {
+        ChunkAccess::Memory(buf) => buf,
+        other => other.read_exact_at(0, other.len()? as usize)?.into(),
+    };
+    let format_len = parsed.format_len();
+    let mut matches = vec![0u64; rules.len()];
+    let mut items_changed = 0;
+    let mut items = Vec::with_capacity(num_items as usize);
+    for item in 0..num_items as usize {
+        let bytes = data
+            .get(offsets[item] as usize..offsets[item + 1] as usize)
+            .ok_or(AppError::MalformedChunk)?;
+        let mut values: Vec<Cow<[u8]>> = split_fields(bytes, format_len)?
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect();
+        let mut changed = false;
+        for field in fields {
+            for (rule, (regex, replacement)) in rules.iter().enumerate() {
+                let found = regex.find_iter(&values[*field]).count() as u64;
+                if found == 0 {
+                    continue;
+                }
+                matches[rule] += found;
+                changed = true;
+                let replaced = regex
+                    .replace_all(&values[*field], replacement.as_bytes())
+                    .into_owned();
+                values[*field] = Cow::Owned(replaced);
+            }
+        }
+        if changed {
+            items_changed += 1;
+            items.push(pack_item(first_item + item as u64, &values)?);
+        } else {
+            items.push(bytes.to_vec());
+        }
+    }
+    let raw = pack_chunk(items.iter().map(Vec::as_slice));
+    let target = confine_to_root(dest_dir, &relative_chunk_name(&parsed.root_dir, filename))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&target, encode_chunk(&raw, parsed)?)?;
+    Ok(ChunkOutcome {
+        raw_len: raw.len() as u64,
+        items: num_items,
+        items_changed,
+        matches,
+    })
+}
+
+pub(crate) fn redact_sync(
+    parsed: &ParsedIndex,
+    dest_dir: &Path,
+    rules: &[RedactionRule],
+    fields: Option<&[usize]>,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<RedactReport> {
+    ensure_rewritable(parsed)?;
+    if rules.is_empty() {
+        return Err(AppError::Invalid("no redaction rules given".into()));
+    }
+    let compiled = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            Regex::new(&rule.pattern)
+                .map(|regex| (regex, rule.replacement.as_str()))
+                .map_err(|e| AppError::Invalid(format!("rule {i}: {e}")))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = target_fields(&formats, fields)?;
+    if dest_dir.as_os_str().is_empty() {
+        return Err(AppError::Invalid("destination directory is empty".into()));
+    }
+    if fs::canonicalize(dest_dir).ok() == fs::canonicalize(&parsed.root_dir).ok() {
+        return Err(AppError::Invalid(
+            "destination must differ from the dataset directory".into(),
+        ));
+    }
+    let index_name = parsed.source.file_name().unwrap_or_default();
+    if dest_dir.join(index_name).exists() {
+        return Err(AppError::Invalid(format!(
+            "{} already holds a dataset",
+            dest_dir.display()
+        )));
+    }
+    ensure_writable(dest_dir, &workspace.dataset_dirs(parsed))?;
+    fs::create_dir_all(dest_dir)?;
+
+    let total = parsed.chunks.len() as u64;
+    progress.report("redacting", 0, total);
+    let starts: Vec<(&str, u64)> = parsed
+        .chunks
+        .iter()
+        .scan(0u64, |start, chunk| {
+            let first = *start;
+            *start += chunk.chunk_size as u64;
+            Some((chunk.filename.as_str(), first))
+        })
+        .collect();
+    let outcomes = par_map(
+        &starts,
+        Some((progress, "redacting")),
+        |(filename, first)| redact_chunk(parsed, filename, *first, dest_dir, &compiled, &fields),
+    );
+    let outcomes = match outcomes.into_iter().collect::<AppResult<Vec<_>>>() {
+        Ok(outcomes) => outcomes,
+        Err(e) => {
+            for chunk in &parsed.chunks {
+                let name = relative_chunk_name(&parsed.root_dir, &chunk.filename);
+                if let Ok(path) = confine_to_root(dest_dir, &name) {
+                    let _ = fs::remove_file(path);
+                }
+            }
+            // Only succeeds when the directory was created for this run.
+            let _ = fs::remove_dir(dest_dir);
+            return Err(e);
+        }
+    };
+
+    let mut index = index_document(parsed)?;
+    for (chunk, outcome) in parsed.chunks.iter().zip(&outcomes) {
+        set_chunk_entry(&mut index, &chunk.filename, outcome.items, outcome.raw_len);
+    }
+    // Absolute filenames would still point into the source dataset.
+    relativize_chunk_names(&mut index, &parsed.root_dir);
+    let index_path = dest_dir.join(index_name);
+    fs::write(
+        &index_path,
+        encode_index(&index, is_compressed_index(&parsed.source))?,
+    )?;
+    progress.report("done", total, total);
+
+    Ok(RedactReport {
+        job_id: progress.job_id().to_string(),
+        index_path: index_path.display().to_string(),
+        fields,
+        chunk_count: outcomes.len(),
+        items_changed: outcomes.iter().map(|o| o.items_changed).sum(),
+        rules: rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| RuleHits {
+                pattern: rule.pattern.clone(),
+                matches: outcomes.iter().map(|o| o.matches[i]).sum(),
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,166 @@
+use crate::litdata::{chunk_cache_key, parse_index, AppError, AppResult, ChunkCache, ParsedIndex};
+use crate::progress::Progress;
+use crate::rewrite::{
+    ensure_plain_index, index_document, partial_path, sequential_chunk_name, write_index_partial,
+    Backup,
+};
+use crate::security::ensure_writable;
+use crate::validate::{validate_parsed, ValidationReport};
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkRename {
+    from: String,
+    to: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenumberReport {
+    job_id: String,
+    index_path: String,
+    /// `false` for the preview returned without `confirm`; nothing was renamed.
+    applied: bool,
+    renames: Vec<ChunkRename>,
+    /// Chunks already named for their position.
+    unchanged: usize,
+    /// Where the replaced index.json was moved.
+    backup_dir: Option<String>,
+    /// The renamed dataset, validated from its new index.json.
+    validation: Option<ValidationReport>,
+}
+
+/// Renames chunks to `chunk-0-<i>` in index order, keeping their extensions, and
+/// rewrites index.json to match, then validates the result. Without `confirm` it only
+/// lists the renames. The previous index.json is moved to a backup directory.
+#[tauri::command]
+pub async fn renumber_chunks(
+    app: AppHandle,
+    index_path: String,
+    confirm: bool,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<RenumberReport> {
+    let progress = Progress::new(app, job_id, "renumber");
+    let cache = (*cache).clone();
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        renumber_sync(&parsed, confirm, &progress, &cache, &workspace)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Moves each `(from, to)` pair, undoing the moves already made if one fails.
+fn move_all(moves: &[(PathBuf, PathBuf)]) -> AppResult<()> {
+    for (done, (from, to)) in moves.iter().enumerate() {
+        if let Err(e) = fs::rename(from, to) {
+            for (from, to) in moves[..done].iter().rev() {
+                let _ = fs::rename(to, from);
+            }
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn renumber_sync(
+    parsed: &ParsedIndex,
+    confirm: bool,
+    progress: &Progress,
+    cache: &ChunkCache,
+    workspace: &Workspace,
+) -> AppResult<RenumberReport> {
+    ensure_plain_index(parsed)?;
+    let renames: Vec<ChunkRename> = parsed
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| ChunkRename {
+            from: chunk.filename.clone(),
+            to: sequential_chunk_name(&chunk.filename, i),
+        })
+        .filter(|rename| rename.from != rename.to)
+        .collect();
+    let unchanged = parsed.chunks.len() - renames.len();
+    let report = |renames, applied, backup_dir, validation| RenumberReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        applied,
+        renames,
+        unchanged,
+        backup_dir,
+        validation,
+    };
+    if !confirm || renames.is_empty() {
+        return Ok(report(renames, false, None, None));
+    }
+    ensure_writable(&parsed.root_dir, &workspace.dataset_dirs(parsed))?;
+
+    // A target name may belong to another chunk that is itself being renamed, but
+    // never to a file outside the dataset.
+    let current: HashSet<_> = parsed
+        .chunks
+        .iter()
+        .map(|c| parsed.chunk_path(&c.filename))
+        .collect::<AppResult<_>>()?;
+    let mut staged = Vec::with_capacity(renames.len());
+    let mut placed = Vec::with_capacity(renames.len());
+    for rename in &renames {
+        let from = parsed.chunk_path(&rename.from)?;
+        let to = parsed.chunk_path(&rename.to)?;
+        if to.exists() && !current.contains(&to) {
+            return Err(AppError::Invalid(format!(
+                "{} already exists and is not part of the dataset",
+                to.display()
+            )));
+        }
+        // Staged under a temporary name first so swapped names do not collide.
+        staged.push((from, partial_path(&to)));
+        placed.push((partial_path(&to), to));
+    }
+
+    let total = renames.len() as u64;
+    progress.report("renaming", 0, total);
+    let mut index = index_document(parsed)?;
+    if let Some(entries) = index.get_mut("chunks").and_then(|c| c.as_array_mut()) {
+        for entry in entries.iter_mut().filter_map(|e| e.as_object_mut()) {
+            let Some(name) = entry.get("filename").and_then(|f| f.as_str()) else {
+                continue;
+            };
+            if let Some(rename) = renames.iter().find(|r| r.from == name) {
+                entry.insert("filename".into(), rename.to.clone().into());
+            }
+        }
+    }
+    let index_partial = write_index_partial(&parsed.source, &index)?;
+    if let Err(e) = move_all(&staged) {
+        let _ = fs::remove_file(&index_partial);
+        return Err(e);
+    }
+    for (from, _) in &staged {
+        cache.forget(&chunk_cache_key(from));
+    }
+    move_all(&placed)?;
+    let backup = Backup::create(&parsed.root_dir)?;
+    backup.replace(&parsed.source, &index_partial)?;
+    progress.report("renaming", total, total);
+
+    let validation = validate_parsed(&parse_index(&parsed.source)?, progress)?;
+    Ok(report(
+        renames,
+        true,
+        Some(backup.dir().display().to_string()),
+        Some(validation),
+    ))
+}
@@ -0,0 +1,184 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, AppError, AppResult, ChunkCache, ParsedIndex,
+    RawChunk,
+};
+use crate::progress::Progress;
+use crate::rewrite::{
+    ensure_plain_index, index_document, set_chunk_entry, write_index_partial, Backup,
+};
+use crate::scan::par_map;
+use crate::security::ensure_writable;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::{collections::HashSet, path::Path};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// `chunk_size` and `chunk_bytes` now match the file.
+    Corrected,
+    /// The file is missing, so the entry leaves the index.
+    Dropped,
+    /// Needs more than an index fix, such as a truncated chunk; the entry is kept.
+    Unrepairable,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkRepair {
+    filename: String,
+    action: RepairAction,
+    declared_items: u32,
+    actual_items: Option<u32>,
+    declared_bytes: u64,
+    /// Decompressed size.
+    actual_bytes: Option<u64>,
+    reason: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    job_id: String,
+    index_path: String,
+    /// `false` for the preview returned without `confirm`; nothing was written.
+    applied: bool,
+    checked_chunks: usize,
+    /// Chunks whose entry is wrong, in index order.
+    repairs: Vec<ChunkRepair>,
+    /// Where the replaced index.json was moved.
+    backup_dir: Option<String>,
+}
+
+/// Recomputes each chunk's item count and decompressed size from its file and rewrites
+/// index.json where they disagree, as after an interrupted optimize run. Entries for
+/// missing files are dropped when `drop_missing` is set. Without `confirm` it only
+/// reports what would change; the previous index.json is moved to a backup directory.
+#[tauri::command]
+pub async fn repair_index(
+    app: AppHandle,
+    index_path: String,
+    drop_missing: bool,
+    confirm: bool,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<RepairReport> {
+    let progress = Progress::new(app, job_id, "repair");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        repair_sync(&parsed, drop_missing, confirm, &progress, &workspace)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// What the file behind `chunk` says its entry should be; `None` when it already agrees.
+fn inspect_chunk(
+    parsed: &ParsedIndex,
+    chunk: &RawChunk,
+    drop_missing: bool,
+) -> Option<ChunkRepair> {
+    let mut repair = ChunkRepair {
+        filename: chunk.filename.clone(),
+        action: RepairAction::Unrepairable,
+        declared_items: chunk.chunk_size,
+        actual_items: None,
+        declared_bytes: chunk.chunk_bytes,
+        actual_bytes: None,
+        reason: None,
+    };
+    // A throwaway cache keeps a full-dataset scan from pinning every chunk in memory.
+    let scratch = ChunkCache::default();
+    let measured = load_chunk_access(parsed, &chunk.filename, &scratch).and_then(|access| {
+        let bytes = access.len()?;
+        let (items, offsets) = parse_offsets(&access)?;
+        Ok((items, bytes, offsets.last().copied().unwrap_or_default()))
+    });
+    match measured {
+        Err(AppError::Missing(path)) if drop_missing => {
+            repair.action = RepairAction::Dropped;
+            repair.reason = Some(format!("{path} does not exist"));
+        }
+        Err(e) => repair.reason = Some(e.to_string()),
+        Ok((items, bytes, end)) => {
+            repair.actual_items = Some(items);
+            repair.actual_bytes = Some(bytes);
+            if end > bytes {
+                repair.reason = Some(format!(
+                    "truncated: items end at byte {end}, the chunk holds {bytes}"
+                ));
+            } else if items == chunk.chunk_size && bytes == chunk.chunk_bytes {
+                return None;
+            } else {
+                repair.action = RepairAction::Corrected;
+            }
+        }
+    }
+    Some(repair)
+}
+
+pub(crate) fn repair_sync(
+    parsed: &ParsedIndex,
+    drop_missing: bool,
+    confirm: bool,
+    progress: &Progress,
+    workspace: &Workspace,
+) -> AppResult<RepairReport> {
+    ensure_plain_index(parsed)?;
+    let total = parsed.chunks.len() as u64;
+    progress.report("checking", 0, total);
+    let repairs: Vec<ChunkRepair> =
+        par_map(&parsed.chunks, Some((progress, "checking")), |chunk| {
+            inspect_chunk(parsed, chunk, drop_missing)
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+    progress.report("done", total, total);
+
+    let fixes = || {
+        repairs
+            .iter()
+            .filter(|r| r.action != RepairAction::Unrepairable)
+    };
+    let dropped: HashSet<&str> = fixes()
+        .filter(|r| r.action == RepairAction::Dropped)
+        .map(|r| r.filename.as_str())
+        .collect();
+    if !dropped.is_empty() && dropped.len() == parsed.chunks.len() {
+        return Err(AppError::Invalid(
+            "every chunk file is missing; nothing would remain in the index".into(),
+        ));
+    }
+    let mut backup_dir = None;
+    if confirm && fixes().next().is_some() {
+        ensure_writable(&parsed.root_dir, &workspace.dataset_dirs(parsed))?;
+        let mut index = index_document(parsed)?;
+        if let Some(entries) = index.get_mut("chunks").and_then(|c| c.as_array_mut()) {
+            entries.retain(|e| {
+                e.get("filename")
+                    .and_then(|f| f.as_str())
+                    .is_none_or(|f| !dropped.contains(f))
+            });
+        }
+        for repair in fixes().filter(|r| r.action == RepairAction::Corrected) {
+            if let (Some(items), Some(bytes)) = (repair.actual_items, repair.actual_bytes) {
+                set_chunk_entry(&mut index, &repair.filename, items, bytes);
+            }
+        }
+        let partial = write_index_partial(&parsed.source, &index)?;
+        let backup = Backup::create(&parsed.root_dir)?;
+        backup.replace(&parsed.source, &partial)?;
+        backup_dir = Some(backup.dir().display().to_string());
+    }
+    Ok(RepairReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        applied: backup_dir.is_some(),
+        checked_chunks: parsed.chunks.len(),
+        repairs,
+        backup_dir,
+    })
+}
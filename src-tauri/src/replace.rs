@@ -0,0 +1,165 @@
+use crate::append::{load_source, FieldSource};
+use crate::index_cache::IndexCache;
+use crate::litdata::{
+    load_chunk_access, parse_offsets, split_fields, AppError, AppResult, ChunkAccess, ChunkCache,
+    ParsedIndex,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::progress::Progress;
+use crate::rewrite::{
+    check_declared_type, encode_chunk, ensure_rewritable, index_document, locate, pack_chunk,
+    pack_item, partial_path, set_chunk_entry, write_index_partial, Backup, RewriteCaches,
+};
+use crate::security::ensure_writable;
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::Serialize;
+use std::fs;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceReport {
+    job_id: String,
+    index_path: String,
+    global_index: u64,
+    field_index: usize,
+    chunk_filename: String,
+    item_index: u32,
+    old_bytes: u64,
+    new_bytes: u64,
+    /// Uncompressed size of the rewritten chunk, as recorded in index.json.
+    chunk_bytes: u64,
+    /// Where the replaced chunk and index.json were moved.
+    backup_dir: String,
+}
+
+/// Swaps field `field_index` of item `global_index` for `source`, rewriting the chunk
+/// holding it and that chunk's entry in index.json. The replaced files are moved to a
+/// timestamped backup directory under the dataset root, and the dataset's open handles
+/// move to the rewritten index.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn replace_field(
+    app: AppHandle,
+    handle: DatasetHandle,
+    global_index: u64,
+    field_index: usize,
+    source: FieldSource,
+    job_id: Option<String>,
+    cache: tauri::State<'_, ChunkCache>,
+    offsets: tauri::State<'_, OffsetsCache>,
+    indexes: tauri::State<'_, IndexCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<ReplaceReport> {
+    let progress = Progress::new(app, job_id, "replace-field");
+    let caches = RewriteCaches {
+        chunks: (*cache).clone(),
+        offsets: (*offsets).clone(),
+        indexes: (*indexes).clone(),
+    };
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = workspace.get(handle)?;
+        replace_sync(
+            &parsed,
+            global_index,
+            field_index,
+            &source,
+            &progress,
+            &caches,
+            &workspace,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+pub(crate) fn replace_sync(
+    parsed: &ParsedIndex,
+    global_index: u64,
+    field_index: usize,
+    source: &FieldSource,
+    progress: &Progress,
+    caches: &RewriteCaches,
+    workspace: &Workspace,
+) -> AppResult<ReplaceReport> {
+    ensure_rewritable(parsed)?;
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let format = formats.get(field_index).ok_or_else(|| {
+        AppError::Invalid(format!(
+            "field {field_index} is out of range; data_format declares {}",
+            formats.len()
+        ))
+    })?;
+    let (chunk, item) = locate(parsed, global_index)?;
+    let replacement = load_source(source)?;
+    check_declared_type(format, &replacement, "the replacement")?;
+    ensure_writable(&parsed.root_dir, &workspace.dataset_dirs(parsed))?;
+
+    progress.report("rewriting", 0, 2);
+    let filename = &parsed.chunks[chunk].filename;
+    let scratch = ChunkCache::default();
+    let access = load_chunk_access(parsed, filename, &scratch)?;
+    let (num_items, offsets) = parse_offsets(&access)?;
+    let data = match access {
+        ChunkAccess::Memory(buf) => buf,
+        other => other.read_exact_at(0, other.len()? as usize)?.into(),
+    };
+    let items = (0..num_items as usize)
+        .map(|i| {
+            data.get(offsets[i] as usize..offsets[i + 1] as usize)
+                .ok_or(AppError::MalformedChunk)
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    let original = items
+        .get(item as usize)
+        .copied()
+        .ok_or(AppError::MalformedChunk)?;
+    let mut fields = split_fields(original, formats.len())?;
+    let old_bytes = fields[field_index].len() as u64;
+    fields[field_index] = &replacement;
+    let edited = pack_item(global_index, &fields)?;
+    let raw = pack_chunk(items.iter().enumerate().map(|(i, bytes)| {
+        if i == item as usize {
+            edited.as_slice()
+        } else {
+            *bytes
+        }
+    }));
+
+    let path = parsed.chunk_path(filename)?;
+    let chunk_partial = partial_path(&path);
+    let written = (|| {
+        fs::write(&chunk_partial, encode_chunk(&raw, parsed)?)?;
+        let mut index = index_document(parsed)?;
+        set_chunk_entry(&mut index, filename, num_items, raw.len() as u64);
+        write_index_partial(&parsed.source, &index)
+    })();
+    let index_partial = match written {
+        Ok(partial) => partial,
+        Err(e) => {
+            let _ = fs::remove_file(&chunk_partial);
+            return Err(e);
+        }
+    };
+
+    progress.report("replacing", 1, 2);
+    let backup = Backup::create(&parsed.root_dir)?;
+    backup.replace(&path, &chunk_partial)?;
+    caches.forget_chunk(&path);
+    backup.replace(&parsed.source, &index_partial)?;
+    caches.refresh_index(parsed, workspace);
+    progress.report("done", 2, 2);
+    Ok(ReplaceReport {
+        job_id: progress.job_id().to_string(),
+        index_path: parsed.source.display().to_string(),
+        global_index,
+        field_index,
+        chunk_filename: filename.clone(),
+        item_index: item,
+        old_bytes,
+        new_bytes: replacement.len() as u64,
+        chunk_bytes: raw.len() as u64,
+        backup_dir: backup.dir().display().to_string(),
+    })
+}
@@ -0,0 +1,89 @@
+use crate::litdata::{AppError, AppResult};
+use std::path::{Path, PathBuf};
+use tauri::async_runtime::spawn_blocking;
+
+/// Shows `path` selected in the platform file manager, falling back to opening its
+/// parent directory when the platform has no way to highlight a single file.
+#[tauri::command]
+pub async fn reveal_in_file_manager(path: String) -> AppResult<()> {
+    let target = PathBuf::from(path.trim());
+    spawn_blocking(move || {
+        let target = target
+            .canonicalize()
+            .map_err(|e| AppError::Missing(format!("{}: {e}", target.display())))?;
+        reveal(&target)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(target: &Path) -> AppResult<()> {
+    run(std::process::Command::new("open").arg("-R").arg(target))
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(target: &Path) -> AppResult<()> {
+    use std::os::windows::process::CommandExt;
+    // explorer rejects canonicalized `\\?\` paths, wants `/select,"<path>"` as one raw
+    // argument, and exits 1 even on success.
+    let plain = litdata_core::long_path::plain_path(target);
+    std::process::Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", plain.display()))
+        .spawn()
+        .map(drop)
+        .map_err(|e| AppError::Open(e.to_string()))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reveal(target: &Path) -> AppResult<()> {
+    // Nautilus, Dolphin, Nemo and others implement the freedesktop FileManager1 interface.
+    let shown = run(std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(target)))
+        .arg("string:"));
+    if shown.is_ok() {
+        return Ok(());
+    }
+    let folder = if target.is_dir() {
+        target
+    } else {
+        target.parent().unwrap_or(target)
+    };
+    open::that_detached(folder).map_err(|e| AppError::Open(e.to_string()))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run(command: &mut std::process::Command) -> AppResult<()> {
+    let status = command
+        .status()
+        .map_err(|e| AppError::Open(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(AppError::Open(format!("file manager exited with {status}")))
+    }
+}
+
+/// `file://` URI for an absolute path, percent-encoding everything outside the
+/// unreserved set.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = String::from("file://");
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    uri
+}
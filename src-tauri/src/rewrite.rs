@@ -0,0 +1,361 @@
+//! Shared pieces for commands that rewrite a dataset in place: packing chunks in
+//! litdata's layout, moving replaced files aside, and swapping new files in.
+
+use crate::index_cache::IndexCache;
+use crate::litdata::{
+    chunk_cache_key, read_index_file, sniff_content_type, AppError, AppResult, ChunkCache,
+    OffsetWidth, ParsedIndex,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::schema::expected_content_type;
+use crate::security::{confine_to_root, relative_chunk_name};
+use crate::workspace::Workspace;
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Chunk size when neither `chunk_size` nor `chunk_bytes` is given.
+const DEFAULT_CHUNK_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Hidden, so dataset discovery does not list the backed-up index as a dataset.
+const BACKUP_DIR: &str = ".litdata-viewer-backup";
+
+/// Packs item payloads into litdata's chunk layout: item count, `count + 1` absolute
+/// offsets, then the items back to back.
+pub(crate) fn pack_chunk<'a>(items: impl ExactSizeIterator<Item = &'a [u8]> + Clone) -> Vec<u8> {
+    let count = items.len() as u32;
+    let payload: u64 = items.clone().map(|item| item.len() as u64).sum();
+    let narrow_end = OffsetWidth::U32.table_end(count);
+    let width = if narrow_end + payload <= u32::MAX as u64 {
+        OffsetWidth::U32
+    } else {
+        OffsetWidth::U64
+    };
+    let table_end = width.table_end(count);
+    let mut out = Vec::with_capacity((table_end + payload) as usize);
+    out.extend_from_slice(&count.to_le_bytes());
+    let mut offset = table_end;
+    for len in std::iter::once(0).chain(items.clone().map(|item| item.len() as u64)) {
+        offset += len;
+        match width {
+            OffsetWidth::U32 => out.extend_from_slice(&(offset as u32).to_le_bytes()),
+            OffsetWidth::U64 => out.extend_from_slice(&offset.to_le_bytes()),
+        }
+    }
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// One item in litdata's layout: each field's `u32` size, then the fields back to back.
+/// `item` only labels the error for a field too large for its size header.
+pub(crate) fn pack_item<F: AsRef<[u8]>>(item: u64, fields: &[F]) -> AppResult<Vec<u8>> {
+    let payload: usize = fields.iter().map(|f| f.as_ref().len()).sum();
+    let mut packed = Vec::with_capacity(fields.len() * 4 + payload);
+    for (index, field) in fields.iter().enumerate() {
+        let len = field.as_ref().len();
+        let size = u32::try_from(len).map_err(|_| {
+            AppError::Invalid(format!(
+                "item {item} field {index} is {len} bytes; litdata fields hold at most {} bytes",
+                u32::MAX
+            ))
+        })?;
+        packed.extend_from_slice(&size.to_le_bytes());
+    }
+    for field in fields {
+        packed.extend_from_slice(field.as_ref());
+    }
+    Ok(packed)
+}
+
+/// Rejects a payload whose magic bytes contradict the content type `format` promises;
+/// `label` names the payload in the error.
+pub(crate) fn check_declared_type(format: &str, data: &[u8], label: &str) -> AppResult<()> {
+    let Some(expected) = expected_content_type(format) else {
+        return Ok(());
+    };
+    let found = sniff_content_type(data);
+    if found != expected && found != "empty" {
+        return Err(AppError::Invalid(format!(
+            "{label} looks like {found}, but data_format declares {format}"
+        )));
+    }
+    Ok(())
+}
+
+/// Chunk bytes as stored under the dataset's `compression`.
+pub(crate) fn encode_chunk(raw: &[u8], parsed: &ParsedIndex) -> AppResult<Vec<u8>> {
+    compress_chunk(raw, parsed.config.compression.as_deref())
+}
+
+/// Chunk bytes as stored under `compression`, as litdata names it in index.json.
+pub(crate) fn compress_chunk(raw: &[u8], compression: Option<&str>) -> AppResult<Vec<u8>> {
+    match compression.map(|c| c.to_lowercase()) {
+        // Level 0 is zstd's default, which litdata's compressor also uses.
+        Some(c) if c == "zstd" => Ok(zstd::stream::encode_all(raw, 0)?),
+        Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None => Ok(raw.to_vec()),
+    }
+}
+
+/// Groups items, given their packed sizes, into chunks of at most `chunk_size` items
+/// or, failing that, about `chunk_bytes` bytes, as litdata's writer does.
+pub(crate) fn group_into_chunks(
+    chunk_size: Option<u32>,
+    chunk_bytes: Option<u64>,
+    item_lens: &[u64],
+) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0u64;
+    for (i, item_len) in item_lens.iter().enumerate() {
+        let len = item_len + 4;
+        let full = match chunk_size {
+            Some(limit) => i - start >= limit.max(1) as usize,
+            None => i > start && bytes + len > chunk_bytes.unwrap_or(DEFAULT_CHUNK_BYTES),
+        };
+        if full {
+            chunks.push(start..i);
+            start = i;
+            bytes = 0;
+        }
+        bytes += len;
+    }
+    if start < item_lens.len() {
+        chunks.push(start..item_lens.len());
+    }
+    chunks
+}
+
+/// Rejects datasets whose files this tree cannot edit in place: standalone chunks and
+/// merged views.
+pub(crate) fn ensure_plain_index(parsed: &ParsedIndex) -> AppResult<()> {
+    if crate::litdata::is_chunk_path(&parsed.source) {
+        return Err(AppError::Invalid(
+            "rewriting needs an index.json, not a standalone chunk".into(),
+        ));
+    }
+    if !parsed.chunk_locations.is_empty() {
+        return Err(AppError::Invalid(
+            "a merged view spans several directories and cannot be rewritten".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects datasets this tree cannot rewrite: those [`ensure_plain_index`] rejects and
+/// compressions it cannot write back.
+pub(crate) fn ensure_rewritable(parsed: &ParsedIndex) -> AppResult<()> {
+    ensure_plain_index(parsed)?;
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(c) if c != "zstd" => Err(AppError::UnsupportedCompression(c)),
+        _ => Ok(()),
+    }
+}
+
+/// The dataset's index.json as an editable document, keeping fields this tree does
+/// not model.
+pub(crate) fn index_document(parsed: &ParsedIndex) -> AppResult<serde_json::Value> {
+    serde_json::from_str(&read_index_file(&parsed.source)?)
+        .map_err(|e| AppError::Invalid(format!("index.json parse error: {e}")))
+}
+
+/// Dataset-wide item index as `(chunk position, item within chunk)`.
+pub(crate) fn locate(parsed: &ParsedIndex, global_index: u64) -> AppResult<(usize, u32)> {
+    let mut chunk_start = 0u64;
+    for (position, chunk) in parsed.chunks.iter().enumerate() {
+        let size = chunk.chunk_size as u64;
+        if global_index < chunk_start + size {
+            return Ok((position, (global_index - chunk_start) as u32));
+        }
+        chunk_start += size;
+    }
+    Err(AppError::Invalid(format!(
+        "item {global_index} is out of range; the dataset has {chunk_start} items"
+    )))
+}
+
+/// Records a rewritten chunk's item count and uncompressed size in `index`.
+pub(crate) fn set_chunk_entry(
+    index: &mut serde_json::Value,
+    filename: &str,
+    items: u32,
+    bytes: u64,
+) {
+    let entry = index
+        .get_mut("chunks")
+        .and_then(|c| c.as_array_mut())
+        .and_then(|entries| {
+            entries
+                .iter_mut()
+                .find(|e| e.get("filename").and_then(|f| f.as_str()) == Some(filename))
+        })
+        .and_then(|e| e.as_object_mut());
+    if let Some(entry) = entry {
+        entry.insert("chunk_size".into(), items.into());
+        entry.insert("chunk_bytes".into(), bytes.into());
+    }
+}
+
+/// Rewrites absolute chunk filenames in `index` to their names relative to `root`, for
+/// an index written into a new directory.
+pub(crate) fn relativize_chunk_names(index: &mut serde_json::Value, root: &Path) {
+    let Some(entries) = index.get_mut("chunks").and_then(|c| c.as_array_mut()) else {
+        return;
+    };
+    for entry in entries.iter_mut().filter_map(|e| e.as_object_mut()) {
+        if let Some(name) = entry.get("filename").and_then(|f| f.as_str()) {
+            let relative = relative_chunk_name(root, name);
+            entry.insert("filename".into(), relative.into());
+        }
+    }
+}
+
+/// `chunk-0-{number}` with the extensions of `filename`, such as `.zstd.bin`.
+pub(crate) fn sequential_chunk_name(filename: &str, number: usize) -> String {
+    let base = filename.rsplit('/').next().unwrap_or(filename);
+    let suffix = base.find('.').map_or("", |dot| &base[dot..]);
+    format!("chunk-0-{number}{suffix}")
+}
+
+/// Path `target` is written to before being swapped in.
+pub(crate) fn partial_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    target.with_file_name(name)
+}
+
+/// Whether `path` names a zstd-compressed index, as `read_index_file` decides it.
+pub(crate) fn is_compressed_index(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.to_lowercase().contains("zst"))
+}
+
+/// `index` serialized for an index file, zstd-compressed when `compressed`.
+pub(crate) fn encode_index(index: &serde_json::Value, compressed: bool) -> AppResult<Vec<u8>> {
+    let json = serde_json::to_vec(index)
+        .map_err(|e| AppError::Invalid(format!("serializing index: {e}")))?;
+    if compressed {
+        Ok(zstd::stream::encode_all(&json[..], 0)?)
+    } else {
+        Ok(json)
+    }
+}
+
+/// Writes `index` as a new dataset's `index.json`, or `index.json.zstd` when
+/// `compressed`.
+pub(crate) fn write_new_index(
+    dest_dir: &Path,
+    index: &serde_json::Value,
+    compressed: bool,
+) -> AppResult<PathBuf> {
+    let name = if compressed {
+        "index.json.zstd"
+    } else {
+        "index.json"
+    };
+    let path = dest_dir.join(name);
+    fs::write(&path, encode_index(index, compressed)?)?;
+    Ok(path)
+}
+
+/// Writes `index` next to `source` for [`Backup::replace`], compressing it when the
+/// source index is compressed.
+pub(crate) fn write_index_partial(source: &Path, index: &serde_json::Value) -> AppResult<PathBuf> {
+    let partial = partial_path(source);
+    fs::write(&partial, encode_index(index, is_compressed_index(source))?)?;
+    Ok(partial)
+}
+
+/// The caches a rewrite in place leaves stale, cloned from the app's managed state.
+#[derive(Clone)]
+pub(crate) struct RewriteCaches {
+    pub(crate) chunks: ChunkCache,
+    pub(crate) offsets: OffsetsCache,
+    pub(crate) indexes: IndexCache,
+}
+
+impl RewriteCaches {
+    /// Forgets `chunk_path` after its file was replaced or moved away.
+    pub(crate) fn forget_chunk(&self, chunk_path: &Path) {
+        self.chunks.forget(&chunk_cache_key(chunk_path));
+        self.offsets.forget(chunk_path);
+    }
+
+    /// Forgets the dataset's index after it was rewritten and points the handles open on
+    /// it at a fresh parse. The files are already swapped in, so a failed re-parse is
+    /// logged rather than raised.
+    pub(crate) fn refresh_index(&self, parsed: &ParsedIndex, workspace: &Workspace) {
+        self.indexes.forget(&parsed.source);
+        match self
+            .indexes
+            .get(&parsed.source)
+            .and_then(|fresh| workspace.refresh(fresh))
+        {
+            Ok(refreshed) => tracing::debug!(refreshed, "refreshed rewritten dataset"),
+            Err(e) => tracing::warn!(
+                index = %parsed.source.display(),
+                "re-reading rewritten dataset: {e}"
+            ),
+        }
+    }
+}
+
+/// A timestamped directory under the dataset root that replaced files are moved into,
+/// keeping their paths relative to the root.
+pub(crate) struct Backup {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl Backup {
+    pub(crate) fn create(root: &Path) -> AppResult<Self> {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let base = root.join(BACKUP_DIR);
+        let mut dir = base.join(stamp.to_string());
+        let mut n = 1;
+        while dir.exists() {
+            dir = base.join(format!("{stamp}-{n}"));
+            n += 1;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(Backup {
+            root: root.to_path_buf(),
+            dir,
+        })
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Moves `original` into the backup.
+    pub(crate) fn stash(&self, original: &Path) -> AppResult<()> {
+        let relative = original.strip_prefix(&self.root).map_err(|_| {
+            AppError::Invalid(format!("{} is outside the dataset", original.display()))
+        })?;
+        let kept = confine_to_root(&self.dir, &relative.to_string_lossy())?;
+        if let Some(parent) = kept.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(original, kept)?;
+        Ok(())
+    }
+
+    /// Moves `original` into the backup and `partial` into its place.
+    pub(crate) fn replace(&self, original: &Path, partial: &Path) -> AppResult<()> {
+        if original.exists() {
+            self.stash(original)?;
+        }
+        fs::rename(partial, original)?;
+        Ok(())
+    }
+}
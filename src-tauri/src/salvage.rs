@@ -0,0 +1,287 @@
+use crate::index_cache::IndexCache;
+use crate::litdata::{
+    build_preview, chunk_cache_key, read_le_u32, AppError, AppResult, ChunkCache, FieldPreview,
+    OffsetWidth, ParsedIndex,
+};
+use crate::settings;
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+use tauri::async_runtime::spawn_blocking;
+
+/// Salvage works on an in-memory copy of the chunk; refuse anything larger than this.
+const MAX_SALVAGE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SalvageSource {
+    /// Located through the intact prefix of the offsets table.
+    Offsets,
+    /// Located by scanning for a plausible size header.
+    Scan,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SalvagedItem {
+    salvage_index: u32,
+    start: u64,
+    end: u64,
+    field_sizes: Vec<u32>,
+    source: SalvageSource,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SalvageReport {
+    chunk_filename: String,
+    chunk_len: u64,
+    stream_truncated: bool,
+    offsets_items: u32,
+    scanned_items: u32,
+    unrecovered_bytes: u64,
+    items: Vec<SalvagedItem>,
+}
+
+fn salvage_key(parsed: &ParsedIndex, chunk_filename: &str) -> AppResult<String> {
+    Ok(format!(
+        "salvage:{}",
+        chunk_cache_key(&parsed.chunk_path(chunk_filename)?)
+    ))
+}
+
+/// Loads as much of the chunk as can be read, tolerating a truncated zstd stream.
+fn load_salvage_bytes(parsed: &ParsedIndex, chunk_filename: &str) -> AppResult<(Vec<u8>, bool)> {
+    let path = parsed.chunk_path(chunk_filename)?;
+    if !path.exists() {
+        return Err(AppError::Missing(path.display().to_string()));
+    }
+    if fs::metadata(&path)?.len() > MAX_SALVAGE_BYTES {
+        return Err(AppError::Invalid(format!(
+            "chunk is larger than the {MAX_SALVAGE_BYTES}-byte salvage limit"
+        )));
+    }
+    match parsed.config.compression.as_ref().map(|c| c.to_lowercase()) {
+        Some(ref c) if c == "zstd" => {
+            let mut decoder = zstd::stream::Decoder::new(File::open(&path)?)?;
+            let mut buf = Vec::new();
+            let truncated = decoder
+                .by_ref()
+                .take(MAX_SALVAGE_BYTES)
+                .read_to_end(&mut buf)
+                .is_err();
+            Ok((buf, truncated))
+        }
+        Some(other) => Err(AppError::UnsupportedCompression(other)),
+        None => Ok((fs::read(&path)?, false)),
+    }
+}
+
+/// Cheap content check for declared field types; `None` when the type gives no signal.
+fn field_matches_format(data_format: &str, bytes: &[u8]) -> Option<bool> {
+    let fmt = data_format.to_lowercase();
+    if fmt.contains("jpeg") || fmt.contains("jpg") {
+        return Some(bytes.starts_with(&[0xFF, 0xD8, 0xFF]));
+    }
+    if fmt.contains("png") {
+        return Some(bytes.starts_with(b"\x89PNG"));
+    }
+    if fmt.contains("wav") {
+        return Some(bytes.starts_with(b"RIFF"));
+    }
+    if fmt.contains("flac") {
+        return Some(bytes.starts_with(b"fLaC"));
+    }
+    if fmt == "str" || fmt == "string" {
+        return Some(std::str::from_utf8(bytes).is_ok());
+    }
+    None
+}
+
+/// Field sizes from the header at `pos`, if the item they describe fits in `buf`.
+fn header_fits(buf: &[u8], pos: usize, format_len: usize) -> Option<(Vec<u32>, usize)> {
+    let header_len = format_len * 4;
+    let head = buf.get(pos..pos.checked_add(header_len)?)?;
+    let mut sizes = Vec::with_capacity(format_len);
+    let mut end = pos + header_len;
+    for raw in head.chunks_exact(4) {
+        let size = read_le_u32(raw).ok()?;
+        end = end.checked_add(size as usize)?;
+        sizes.push(size);
+    }
+    (end <= buf.len()).then_some((sizes, end))
+}
+
+/// Accepts an item at `pos` when its header fits and every typed field looks like its type.
+/// Untyped layouts additionally require the following bytes to start another plausible item.
+fn plausible_item(buf: &[u8], pos: usize, data_format: &[String]) -> Option<(Vec<u32>, usize)> {
+    let (sizes, end) = header_fits(buf, pos, data_format.len())?;
+    if sizes.iter().all(|s| *s == 0) {
+        return None;
+    }
+    let mut cursor = pos + data_format.len() * 4;
+    let mut typed = false;
+    for (fmt, size) in data_format.iter().zip(&sizes) {
+        let field = &buf[cursor..cursor + *size as usize];
+        match field_matches_format(fmt, field) {
+            Some(false) => return None,
+            Some(true) => typed = true,
+            None => {}
+        }
+        cursor += *size as usize;
+    }
+    if !typed && end != buf.len() && header_fits(buf, end, data_format.len()).is_none() {
+        return None;
+    }
+    Some((sizes, end))
+}
+
+/// Items reachable through the leading, still-consistent part of the offsets table.
+fn trusted_items(buf: &[u8], format_len: usize) -> (Vec<SalvagedItem>, usize) {
+    let len = buf.len() as u64;
+    let Some(num_items) = buf.get(0..4).and_then(|b| read_le_u32(b).ok()) else {
+        return (Vec::new(), buf.len());
+    };
+    let width = OffsetWidth::detect(num_items, &buf[4..buf.len().min(12)], len);
+    let table_end = width.table_end(num_items);
+    if table_end > len {
+        return (Vec::new(), 4);
+    }
+    let step = width.entry_size() as usize;
+    let offset_at = |i: usize| width.decode(&buf[4 + i * step..4 + (i + 1) * step]);
+    let mut items = Vec::new();
+    let mut resume = table_end as usize;
+    for i in 0..num_items as usize {
+        let (Ok(start), Ok(end)) = (offset_at(i), offset_at(i + 1)) else {
+            break;
+        };
+        if (i == 0 && start != table_end) || end < start || end > len {
+            break;
+        }
+        let sizes = if format_len > 0 {
+            match header_fits(buf, start as usize, format_len) {
+                Some((sizes, item_end)) if item_end as u64 == end => sizes,
+                _ => break,
+            }
+        } else {
+            Vec::new()
+        };
+        items.push(SalvagedItem {
+            salvage_index: i as u32,
+            start,
+            end,
+            field_sizes: sizes,
+            source: SalvageSource::Offsets,
+        });
+        resume = end as usize;
+    }
+    (items, resume)
+}
+
+#[tauri::command]
+pub async fn salvage_chunk(
+    index_path: String,
+    chunk_filename: String,
+    cache: tauri::State<'_, ChunkCache>,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<SalvageReport> {
+    let cache_handle = (*cache).clone();
+    let indexes = (*indexes).clone();
+    spawn_blocking(move || {
+        salvage_chunk_sync(
+            Path::new(&index_path),
+            &chunk_filename,
+            &cache_handle,
+            &indexes,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn salvage_chunk_sync(
+    index_path: &Path,
+    chunk_filename: &str,
+    cache: &ChunkCache,
+    indexes: &IndexCache,
+) -> AppResult<SalvageReport> {
+    let parsed = indexes.get(index_path)?;
+    let data_format = parsed.config.data_format.clone().unwrap_or_default();
+    let (buf, stream_truncated) = load_salvage_bytes(&parsed, chunk_filename)?;
+
+    let (mut items, mut pos) = trusted_items(&buf, data_format.len());
+    let offsets_items = items.len() as u32;
+    if !data_format.is_empty() {
+        while pos + data_format.len() * 4 <= buf.len() {
+            match plausible_item(&buf, pos, &data_format) {
+                Some((sizes, end)) => {
+                    items.push(SalvagedItem {
+                        salvage_index: items.len() as u32,
+                        start: pos as u64,
+                        end: end as u64,
+                        field_sizes: sizes,
+                        source: SalvageSource::Scan,
+                    });
+                    pos = end;
+                }
+                None => pos += 1,
+            }
+        }
+    }
+    let recovered: u64 = items.iter().map(|i| i.end - i.start).sum();
+    let report = SalvageReport {
+        chunk_filename: chunk_filename.to_string(),
+        chunk_len: buf.len() as u64,
+        stream_truncated,
+        offsets_items,
+        scanned_items: items.len() as u32 - offsets_items,
+        unrecovered_bytes: (buf.len() as u64).saturating_sub(recovered),
+        items,
+    };
+    cache.maybe_store(&salvage_key(&parsed, chunk_filename)?, buf.into());
+    Ok(report)
+}
+
+/// Previews a field of a salvaged item, addressed by the item's byte offset in the chunk.
+#[tauri::command]
+pub async fn peek_salvaged_field(
+    index_path: String,
+    chunk_filename: String,
+    item_start: u64,
+    field_index: usize,
+    cache: tauri::State<'_, ChunkCache>,
+    indexes: tauri::State<'_, IndexCache>,
+) -> AppResult<FieldPreview> {
+    let cache_handle = (*cache).clone();
+    let indexes = (*indexes).clone();
+    spawn_blocking(move || {
+        let parsed = indexes.get(Path::new(&index_path))?;
+        let data_format = parsed.config.data_format.clone().unwrap_or_default();
+        let key = salvage_key(&parsed, &chunk_filename)?;
+        let buf = match cache_handle.fetch(&key) {
+            Some(buf) => buf,
+            None => load_salvage_bytes(&parsed, &chunk_filename)?.0.into(),
+        };
+        let start = usize::try_from(item_start).map_err(|_| AppError::MalformedChunk)?;
+        let (sizes, _) =
+            header_fits(&buf, start, data_format.len()).ok_or(AppError::MalformedChunk)?;
+        if field_index >= sizes.len() {
+            return Err(AppError::Invalid("field index out of range".into()));
+        }
+        let field_start = start
+            + data_format.len() * 4
+            + sizes[..field_index]
+                .iter()
+                .map(|s| *s as usize)
+                .sum::<usize>();
+        let size = sizes[field_index];
+        let take = (size as usize).min(settings::current().preview_bytes);
+        let data = buf[field_start..field_start + take].to_vec();
+        Ok(build_preview(data, size, data_format.get(field_index)))
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
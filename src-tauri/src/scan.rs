@@ -0,0 +1,14 @@
+use crate::progress::Progress;
+use litdata_core::progress::ReportProgress;
+
+/// Maps `f` over `items` on the core scan pool, keeping input order and reporting each
+/// completion under `phase`.
+pub(crate) fn par_map<I, T, F>(items: &[I], progress: Option<(&Progress, &str)>, f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let progress = progress.map(|(progress, phase)| (progress as &dyn ReportProgress, phase));
+    litdata_core::scan::par_map(items, progress, f)
+}
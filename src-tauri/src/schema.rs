@@ -0,0 +1,210 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_item_bytes, sniff_content_type,
+    split_fields, AppError, AppResult, ChunkCache,
+};
+use crate::scan::par_map;
+use crate::validate::detect_field_count;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+use tauri::async_runtime::spawn_blocking;
+
+const DEFAULT_SAMPLES_PER_CHUNK: u32 = 8;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSchema {
+    field_index: usize,
+    declared: Option<String>,
+    content_types: BTreeMap<String, u64>,
+    min_size: u32,
+    median_size: u32,
+    max_size: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMismatch {
+    field_index: usize,
+    declared: String,
+    observed: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkSchemaIssue {
+    filename: String,
+    detected_arity: Option<usize>,
+    mismatched_fields: Vec<FieldMismatch>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaReport {
+    declared_format: Vec<String>,
+    sampled_items: u64,
+    arity_counts: BTreeMap<usize, u64>,
+    fields: Vec<FieldSchema>,
+    inconsistent_chunks: Vec<ChunkSchemaIssue>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Content type a declared `data_format` entry promises, when it promises one.
+pub(crate) fn expected_content_type(data_format: &str) -> Option<&'static str> {
+    let fmt = data_format.to_lowercase();
+    if fmt.contains("jpeg") || fmt.contains("jpg") {
+        Some("jpg")
+    } else if fmt.contains("png") {
+        Some("png")
+    } else if fmt.contains("wav") {
+        Some("wav")
+    } else if fmt.contains("flac") {
+        Some("flac")
+    } else if fmt.contains("mp3") {
+        Some("mp3")
+    } else if fmt == "str" || fmt == "string" {
+        Some("txt")
+    } else {
+        None
+    }
+}
+
+/// Evenly spaced item indices, at most `samples` of them.
+pub(crate) fn sample_indices(num_items: u32, samples: u32) -> Vec<u32> {
+    if samples == 0 || num_items == 0 {
+        return Vec::new();
+    }
+    if num_items <= samples {
+        return (0..num_items).collect();
+    }
+    (0..samples)
+        .map(|i| (i as u64 * num_items as u64 / samples as u64) as u32)
+        .collect()
+}
+
+#[derive(Default)]
+struct FieldAccumulator {
+    content_types: BTreeMap<String, u64>,
+    sizes: Vec<u32>,
+}
+
+#[tauri::command]
+pub async fn infer_schema(
+    index_path: String,
+    samples_per_chunk: Option<u32>,
+) -> AppResult<SchemaReport> {
+    spawn_blocking(move || {
+        infer_schema_sync(
+            Path::new(&index_path),
+            samples_per_chunk.unwrap_or(DEFAULT_SAMPLES_PER_CHUNK),
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn infer_schema_sync(index_path: &Path, samples_per_chunk: u32) -> AppResult<SchemaReport> {
+    let parsed = parse_index(index_path)?;
+    let declared = parsed.config.data_format.clone().unwrap_or_default();
+    let format_len = declared.len();
+    let mut fields: Vec<FieldAccumulator> = (0..format_len).map(|_| Default::default()).collect();
+    let mut arity_counts: BTreeMap<usize, u64> = BTreeMap::new();
+    let mut inconsistent_chunks = Vec::new();
+    let mut unreadable_chunks = Vec::new();
+    let mut sampled_items = 0u64;
+
+    let samples = par_map(&parsed.chunks, None, |chunk| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(&parsed, &chunk.filename, &scratch)?;
+        let (num_items, offsets) = parse_offsets(&access)?;
+        let mut items = Vec::new();
+        for item in sample_indices(num_items, samples_per_chunk) {
+            items.push(read_item_bytes(&access, &offsets, item)?);
+        }
+        AppResult::Ok(items)
+    });
+    for (chunk, sampled) in parsed.chunks.iter().zip(samples) {
+        let Ok(items) = sampled else {
+            unreadable_chunks.push(chunk.filename.clone());
+            continue;
+        };
+
+        let mut chunk_arity = None;
+        let mut observed: BTreeMap<usize, BTreeMap<String, u64>> = BTreeMap::new();
+        for item in &items {
+            sampled_items += 1;
+            let arity = match split_fields(item, format_len) {
+                Ok(parts)
+                    if parts.iter().map(|p| p.len()).sum::<usize>() + format_len * 4
+                        == item.len() =>
+                {
+                    for (idx, part) in parts.iter().enumerate() {
+                        let kind = sniff_content_type(part);
+                        *observed
+                            .entry(idx)
+                            .or_default()
+                            .entry(kind.clone())
+                            .or_default() += 1;
+                        let acc = &mut fields[idx];
+                        *acc.content_types.entry(kind).or_default() += 1;
+                        acc.sizes.push(part.len() as u32);
+                    }
+                    Some(format_len)
+                }
+                _ => detect_field_count(item, item.len() as u64),
+            };
+            if let Some(arity) = arity {
+                *arity_counts.entry(arity).or_default() += 1;
+            }
+            if arity != Some(format_len) {
+                chunk_arity = Some(arity);
+            }
+        }
+
+        let mut mismatched_fields = Vec::new();
+        for (idx, kinds) in &observed {
+            let Some(expected) = declared.get(*idx).and_then(|f| expected_content_type(f)) else {
+                continue;
+            };
+            if let Some((dominant, _)) = kinds.iter().max_by_key(|(_, count)| **count) {
+                if dominant != expected && dominant != "empty" {
+                    mismatched_fields.push(FieldMismatch {
+                        field_index: *idx,
+                        declared: declared[*idx].clone(),
+                        observed: dominant.clone(),
+                    });
+                }
+            }
+        }
+        if chunk_arity.is_some() || !mismatched_fields.is_empty() {
+            inconsistent_chunks.push(ChunkSchemaIssue {
+                filename: chunk.filename.clone(),
+                detected_arity: chunk_arity.flatten(),
+                mismatched_fields,
+            });
+        }
+    }
+
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(idx, mut acc)| {
+            acc.sizes.sort_unstable();
+            FieldSchema {
+                field_index: idx,
+                declared: declared.get(idx).cloned(),
+                content_types: acc.content_types,
+                min_size: acc.sizes.first().copied().unwrap_or(0),
+                median_size: acc.sizes.get(acc.sizes.len() / 2).copied().unwrap_or(0),
+                max_size: acc.sizes.last().copied().unwrap_or(0),
+            }
+        })
+        .collect();
+    Ok(SchemaReport {
+        declared_format: declared,
+        sampled_items,
+        arity_counts,
+        fields,
+        inconsistent_chunks,
+        unreadable_chunks,
+    })
+}
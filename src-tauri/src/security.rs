@@ -0,0 +1,58 @@
+use crate::litdata::{AppError, AppResult};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+pub(crate) use litdata_core::security::{
+    confine_to_root, relative_chunk_name, set_allow_outside_root,
+};
+
+/// Refuses every write into a dataset directory while set.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+/// `path` with symlinks resolved as far as it exists, so a file that is about to be
+/// created compares like its directory.
+fn resolve_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while let Some(parent) = existing.parent() {
+        if let Ok(resolved) = existing.canonicalize() {
+            return rest.iter().rev().fold(resolved, |acc, part| acc.join(part));
+        }
+        if let Some(name) = existing.file_name() {
+            rest.push(name);
+        }
+        existing = parent;
+    }
+    path.to_path_buf()
+}
+
+/// In read-only mode, fails if writing `target` would touch any of `dataset_dirs`.
+pub(crate) fn ensure_writable(target: &Path, dataset_dirs: &[PathBuf]) -> AppResult<()> {
+    if !READ_ONLY.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let target = resolve_existing(target);
+    match dataset_dirs
+        .iter()
+        .find(|dir| target.starts_with(resolve_existing(dir)))
+    {
+        Some(dir) => Err(AppError::ReadOnly(format!(
+            "{} is inside dataset directory {}",
+            target.display(),
+            dir.display()
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_allow_external_chunks(allow: bool) -> AppResult<bool> {
+    set_allow_outside_root(allow);
+    Ok(allow)
+}
@@ -0,0 +1,50 @@
+use crate::litdata::{AppError, AppResult};
+use std::io::Write;
+
+/// Default uncompressed bytes per frame; smaller frames make random reads cheaper.
+pub(crate) const DEFAULT_FRAME_SIZE: usize = 1024 * 1024;
+const SKIPPABLE_SEEK_TABLE_MAGIC: u32 = 0x184D_2A5E;
+const SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+
+/// Writes `data` in the zstd seekable format: independent frames of `frame_size`
+/// uncompressed bytes followed by a skippable seek-table frame. Plain zstd decoders
+/// (including litdata's) read the result as an ordinary multi-frame stream.
+pub(crate) fn write_seekable<W: Write>(
+    data: &[u8],
+    frame_size: usize,
+    level: i32,
+    out: &mut W,
+) -> AppResult<u64> {
+    if frame_size == 0 {
+        return Err(AppError::Invalid("frame size must be positive".into()));
+    }
+    let mut entries = Vec::new();
+    let mut written = 0u64;
+    for frame in data.chunks(frame_size) {
+        let compressed = zstd::bulk::compress(frame, level)?;
+        let too_large = |_| AppError::Invalid("zstd frame exceeds 4 GiB".into());
+        entries.push((
+            u32::try_from(compressed.len()).map_err(too_large)?,
+            u32::try_from(frame.len()).map_err(too_large)?,
+        ));
+        out.write_all(&compressed)?;
+        written += compressed.len() as u64;
+    }
+
+    let num_frames = u32::try_from(entries.len())
+        .map_err(|_| AppError::Invalid("too many zstd frames".into()))?;
+    let table_len = num_frames * 8 + 9;
+    let mut table = Vec::with_capacity(table_len as usize + 8);
+    table.extend_from_slice(&SKIPPABLE_SEEK_TABLE_MAGIC.to_le_bytes());
+    table.extend_from_slice(&table_len.to_le_bytes());
+    for (compressed, decompressed) in entries {
+        table.extend_from_slice(&compressed.to_le_bytes());
+        table.extend_from_slice(&decompressed.to_le_bytes());
+    }
+    table.extend_from_slice(&num_frames.to_le_bytes());
+    // Descriptor: no per-frame checksums.
+    table.push(0);
+    table.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+    out.write_all(&table)?;
+    Ok(written + table.len() as u64)
+}
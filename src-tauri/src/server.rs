@@ -0,0 +1,492 @@
+//! Optional loopback HTTP server exposing a JSON-RPC subset of the app's commands, so
+//! scripts and notebooks can read datasets through the viewer's warm caches.
+
+use crate::index_cache::IndexCache;
+use crate::litdata::{
+    collect_item_meta, load_chunk_access, preview_field, AppError, AppResult, ChunkCache,
+};
+use crate::offsets_cache::OffsetsCache;
+use crate::workspace::{DatasetHandle, Workspace};
+use litdata_core::reader::ChunkInfo;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Largest request body accepted; RPC calls are small.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Largest request line plus headers accepted.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+/// Connections handled at once; further clients get `503` until one finishes.
+pub(crate) const MAX_CONNECTIONS: usize = 32;
+/// Random bytes in an access token.
+const TOKEN_BYTES: usize = 32;
+pub(crate) const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the accept loop checks for a stop request.
+pub(crate) const ACCEPT_POLL: Duration = Duration::from_millis(100);
+/// Workspace owner of handles opened over RPC; window labels never take this form.
+const API_OWNER: &str = "api-server";
+
+/// Application error code for failed calls, in the JSON-RPC implementation-defined range.
+const RPC_APP_ERROR: i64 = -32000;
+const RPC_METHOD_NOT_FOUND: i64 = -32601;
+const RPC_INVALID_PARAMS: i64 = -32602;
+const RPC_PARSE_ERROR: i64 = -32700;
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerStatus {
+    running: bool,
+    address: Option<String>,
+    /// Sent by clients as `Authorization: Bearer <token>`; new on every start.
+    token: Option<String>,
+}
+
+struct RunningServer {
+    status: ApiServerStatus,
+    stop: Arc<AtomicBool>,
+    thread: thread::JoinHandle<()>,
+    workspace: Workspace,
+}
+
+impl RunningServer {
+    /// Stops accepting, waits for the accept loop to release the port, then closes the
+    /// handles clients opened and left open.
+    fn shut_down(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if self.thread.join().is_err() {
+            tracing::warn!("api server thread panicked");
+        }
+        match self.workspace.close_window(API_OWNER) {
+            Ok(closed) if closed > 0 => tracing::info!(closed, "closed api server datasets"),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("closing api server datasets: {e}"),
+        }
+    }
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// Shared state the handlers read, cloned from the app's managed state.
+#[derive(Clone)]
+struct Backend {
+    cache: ChunkCache,
+    indexes: IndexCache,
+    offsets: OffsetsCache,
+    workspace: Workspace,
+    token: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadIndexParams {
+    index_path: String,
+}
+
+#[derive(Deserialize)]
+struct HandleParams {
+    handle: DatasetHandle,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChunkParams {
+    handle: DatasetHandle,
+    chunk_filename: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldParams {
+    handle: DatasetHandle,
+    chunk_filename: String,
+    item_index: u32,
+    field_index: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadedDataset {
+    handle: DatasetHandle,
+    index_path: String,
+    root_dir: String,
+    data_format: Vec<String>,
+    chunks: Vec<ChunkInfo>,
+}
+
+enum RpcFailure {
+    Params(String),
+    Method(String),
+    App(AppError),
+}
+
+impl From<AppError> for RpcFailure {
+    fn from(value: AppError) -> Self {
+        RpcFailure::App(value)
+    }
+}
+
+fn params<T: DeserializeOwned>(value: Value) -> Result<T, RpcFailure> {
+    serde_json::from_value(value).map_err(|e| RpcFailure::Params(e.to_string()))
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, RpcFailure> {
+    serde_json::to_value(value)
+        .map_err(|e| RpcFailure::App(AppError::Invalid(format!("serializing result: {e}"))))
+}
+
+impl Backend {
+    fn call(&self, method: &str, raw: Value) -> Result<Value, RpcFailure> {
+        match method {
+            "list_datasets" => to_value(self.workspace.describe_all()?),
+            "load_index" => {
+                let p: LoadIndexParams = params(raw)?;
+                let parsed = self.indexes.get(Path::new(p.index_path.trim()))?;
+                let handle = self.workspace.open_in(parsed.clone(), API_OWNER)?;
+                to_value(LoadedDataset {
+                    handle,
+                    index_path: parsed.source.display().to_string(),
+                    root_dir: parsed.root_dir.display().to_string(),
+                    data_format: parsed.config.data_format.clone().unwrap_or_default(),
+                    chunks: parsed
+                        .chunks
+                        .iter()
+                        .map(|c| ChunkInfo {
+                            filename: c.filename.clone(),
+                            items: c.chunk_size,
+                            bytes: c.chunk_bytes,
+                        })
+                        .collect(),
+                })
+            }
+            "close_dataset" => {
+                let p: HandleParams = params(raw)?;
+                to_value(self.workspace.close(p.handle)?)
+            }
+            "list_items" => {
+                let p: ChunkParams = params(raw)?;
+                let parsed = self.workspace.get(p.handle)?;
+                let access = load_chunk_access(&parsed, &p.chunk_filename, &self.cache)?;
+                let mut items = collect_item_meta(&access, parsed.format_len())?;
+                if let Some(sidecar) = self.workspace.sidecar(p.handle)? {
+                    sidecar.join(&parsed, &p.chunk_filename, &mut items);
+                }
+                to_value(items)
+            }
+            "peek_field" => {
+                let p: FieldParams = params(raw)?;
+                let parsed = self.workspace.get(p.handle)?;
+                to_value(preview_field(
+                    &parsed,
+                    &p.chunk_filename,
+                    p.item_index,
+                    p.field_index,
+                    &self.cache,
+                    &self.offsets,
+                )?)
+            }
+            other => Err(RpcFailure::Method(other.to_string())),
+        }
+    }
+
+    fn respond(&self, body: &[u8]) -> Value {
+        let request: RpcRequest = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(e) => return rpc_error(Value::Null, RPC_PARSE_ERROR, e.to_string(), None),
+        };
+        match self.call(&request.method, request.params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+            Err(RpcFailure::Params(message)) => {
+                rpc_error(request.id, RPC_INVALID_PARAMS, message, None)
+            }
+            Err(RpcFailure::Method(method)) => rpc_error(
+                request.id,
+                RPC_METHOD_NOT_FOUND,
+                format!("unknown method {method}"),
+                None,
+            ),
+            Err(RpcFailure::App(e)) => {
+                let data = serde_json::to_value(&e).ok();
+                rpc_error(request.id, RPC_APP_ERROR, e.to_string(), data)
+            }
+        }
+    }
+}
+
+fn rpc_error(id: Value, code: i64, message: String, data: Option<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message, "data": data },
+    })
+}
+
+pub(crate) struct HttpRequest {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) authorization: Option<String>,
+    /// Raw `Range` header, e.g. `bytes=0-1023`.
+    pub(crate) range: Option<String>,
+    pub(crate) body: Vec<u8>,
+}
+
+/// Reads one line of the request head, failing once the head outgrows `MAX_HEAD_BYTES`.
+fn read_head_line(head: &mut impl BufRead, line: &mut String) -> AppResult<usize> {
+    line.clear();
+    let read = head.read_line(line)?;
+    if read > 0 && !line.ends_with('\n') {
+        return Err(AppError::Invalid(format!(
+            "request headers exceed {MAX_HEAD_BYTES} bytes"
+        )));
+    }
+    Ok(read)
+}
+
+pub(crate) fn read_request(stream: &TcpStream) -> AppResult<HttpRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut head = (&mut reader).take(MAX_HEAD_BYTES);
+    let mut line = String::new();
+    read_head_line(&mut head, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    let mut range = None;
+    loop {
+        if read_head_line(&mut head, &mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| AppError::Invalid("bad Content-Length".into()))?
+            }
+            "authorization" => authorization = Some(value.trim().to_string()),
+            "range" => range = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(AppError::Invalid(format!(
+            "request body of {content_length} bytes exceeds {MAX_BODY_BYTES}"
+        )));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        range,
+        body,
+    })
+}
+
+pub(crate) fn write_response(mut stream: &TcpStream, status: &str, body: &Value) {
+    let payload = body.to_string();
+    // The client may already be gone; there is nobody left to report to.
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    );
+}
+
+fn handle_connection(stream: TcpStream, backend: &Backend) {
+    let _ = stream.set_read_timeout(Some(CONNECTION_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CONNECTION_TIMEOUT));
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) => {
+            return write_response(
+                &stream,
+                "400 Bad Request",
+                &json!({ "error": e.to_string() }),
+            )
+        }
+    };
+    let expected = format!("Bearer {}", backend.token);
+    let authorized = request
+        .authorization
+        .as_deref()
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), expected.as_bytes()));
+    if !authorized {
+        return write_response(
+            &stream,
+            "401 Unauthorized",
+            &json!({ "error": "missing or wrong bearer token" }),
+        );
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => write_response(&stream, "200 OK", &json!({ "ok": true })),
+        ("POST", "/rpc") => {
+            let _span = tracing::info_span!("api_request").entered();
+            write_response(&stream, "200 OK", &backend.respond(&request.body))
+        }
+        _ => write_response(&stream, "404 Not Found", &json!({ "error": "not found" })),
+    }
+}
+
+/// A slot counted against `MAX_CONNECTIONS`, released when the handler finishes.
+pub(crate) struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    pub(crate) fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| ConnectionSlot(active.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Turns away a client while every connection slot is taken.
+pub(crate) fn reject_busy(stream: &TcpStream) {
+    let _ = stream.set_write_timeout(Some(CONNECTION_TIMEOUT));
+    write_response(
+        stream,
+        "503 Service Unavailable",
+        &json!({ "error": "too many concurrent connections" }),
+    );
+}
+
+fn serve(listener: TcpListener, backend: Backend, stop: Arc<AtomicBool>) {
+    let active = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // Accepted sockets inherit non-blocking mode from the listener.
+                if stream.set_nonblocking(false).is_err() {
+                    continue;
+                }
+                let Some(slot) = ConnectionSlot::acquire(&active) else {
+                    reject_busy(&stream);
+                    continue;
+                };
+                let backend = backend.clone();
+                thread::spawn(move || {
+                    let _slot = slot;
+                    handle_connection(stream, &backend)
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL),
+            Err(e) => {
+                tracing::warn!("api server accept failed: {e}");
+                thread::sleep(ACCEPT_POLL);
+            }
+        }
+    }
+}
+
+/// `TOKEN_BYTES` bytes from the operating system's random source, hex-encoded.
+pub(crate) fn new_token() -> AppResult<String> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Io(format!("system random source unavailable: {e}")))?;
+    Ok(hex::encode(bytes))
+}
+
+/// Compares secrets in time that depends only on their lengths, so a client cannot
+/// learn a token byte by byte from response timing.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn lock() -> AppResult<std::sync::MutexGuard<'static, Option<RunningServer>>> {
+    SERVER
+        .lock()
+        .map_err(|_| AppError::Task("api server lock poisoned".into()))
+}
+
+/// Starts serving on 127.0.0.1 (`port` 0 or unset picks a free one). Restarting replaces
+/// the previous server, its token, and the datasets its clients opened.
+#[tauri::command]
+pub async fn start_api_server(
+    port: Option<u16>,
+    cache: tauri::State<'_, ChunkCache>,
+    indexes: tauri::State<'_, IndexCache>,
+    offsets: tauri::State<'_, OffsetsCache>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<ApiServerStatus> {
+    let mut guard = lock()?;
+    if let Some(previous) = guard.take() {
+        previous.shut_down();
+    }
+    let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port.unwrap_or(0))))?;
+    listener.set_nonblocking(true)?;
+    let address = listener.local_addr()?.to_string();
+    let token = new_token()?;
+    let backend = Backend {
+        cache: (*cache).clone(),
+        indexes: (*indexes).clone(),
+        offsets: (*offsets).clone(),
+        workspace: (*workspace).clone(),
+        token: token.as_str().into(),
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let flag = stop.clone();
+    let thread = thread::Builder::new()
+        .name("api-server".into())
+        .spawn(move || serve(listener, backend, flag))?;
+    tracing::info!(address = %address, "api server started");
+    let status = ApiServerStatus {
+        running: true,
+        address: Some(address),
+        token: Some(token),
+    };
+    *guard = Some(RunningServer {
+        status: status.clone(),
+        stop,
+        thread,
+        workspace: (*workspace).clone(),
+    });
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_api_server() -> AppResult<ApiServerStatus> {
+    if let Some(running) = lock()?.take() {
+        running.shut_down();
+        tracing::info!("api server stopped");
+    }
+    Ok(ApiServerStatus::default())
+}
+
+#[tauri::command]
+pub async fn get_api_server_status() -> AppResult<ApiServerStatus> {
+    Ok(lock()?
+        .as_ref()
+        .map(|running| running.status.clone())
+        .unwrap_or_default())
+}
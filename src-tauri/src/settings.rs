@@ -0,0 +1,257 @@
+use crate::aio::DEFAULT_IO_TIMEOUT_SECS;
+use crate::disk_cache::DEFAULT_DISK_CACHE_BUDGET;
+use crate::hooks::{validate_rules, HookRule};
+use crate::litdata::{
+    AppError, AppResult, ChunkCache, DEFAULT_CACHE_BUDGET, DEFAULT_PREVIEW_BYTES, MAX_CACHE_BYTES,
+};
+use crate::memory::{self, DEFAULT_MEMORY_LIMIT};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, OnceLock, RwLock},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+/// Previews are meant to be glanceable; larger reads should use `read_field_range`.
+const MAX_PREVIEW_BYTES: usize = 1024 * 1024;
+
+/// Options for remote (object store) datasets, kept here so the UI can edit them before
+/// a backend reads them.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteSettings {
+    endpoint: Option<String>,
+    region: Option<String>,
+    profile: Option<String>,
+    anonymous: bool,
+    /// MLflow tracking server for `runs:/` and `mlflow-artifacts:` sources; falls back
+    /// to `MLFLOW_TRACKING_URI`.
+    pub(crate) mlflow_tracking_uri: Option<String>,
+    /// W&B API host; falls back to `WANDB_BASE_URL`, then the hosted service.
+    pub(crate) wandb_base_url: Option<String>,
+}
+
+/// Program that opens fields of one extension in place of the OS default handler.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalViewer {
+    program: String,
+    /// Full argument list, with `{path}` standing for the field file. When empty the
+    /// program is launched the way the OS opens files "with" an application.
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+impl ExternalViewer {
+    pub(crate) fn launch(&self, file: &Path) -> AppResult<()> {
+        if self.args.is_empty() {
+            return open::with_detached(file, self.program.clone())
+                .map_err(|e| AppError::Open(format!("{}: {e}", self.program)));
+        }
+        let path = file.display().to_string();
+        let mut child = Command::new(&self.program)
+            .args(self.args.iter().map(|a| a.replace("{path}", &path)))
+            .spawn()
+            .map_err(|e| AppError::Open(format!("{}: {e}", self.program)))?;
+        // Reap the viewer when it exits instead of leaving a zombie behind.
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+fn viewer_key(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// Viewer configured for `ext`, if any; lookups ignore case and a leading dot.
+pub(crate) fn viewer_for(ext: &str) -> Option<ExternalViewer> {
+    let key = viewer_key(ext);
+    current()
+        .external_viewers
+        .iter()
+        .find(|(k, _)| viewer_key(k) == key)
+        .map(|(_, v)| v.clone())
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub(crate) chunk_cache_budget_bytes: u64,
+    pub(crate) max_cached_chunk_bytes: u64,
+    pub(crate) disk_cache_budget_bytes: u64,
+    pub(crate) memory_limit_bytes: u64,
+    pub(crate) preview_bytes: usize,
+    /// Where opened leaves and the chunk spill cache live; the system temp dir if unset.
+    pub(crate) temp_dir: Option<String>,
+    pub(crate) thumbnail_size: u32,
+    pub(crate) io_timeout_secs: u64,
+    pub(crate) allow_external_chunks: bool,
+    /// Refuse any operation that would write into a dataset directory.
+    pub(crate) read_only: bool,
+    pub(crate) remote: RemoteSettings,
+    /// Extension (without the dot) to the program `open_leaf` launches for it.
+    pub(crate) external_viewers: BTreeMap<String, ExternalViewer>,
+    /// Commands run after a field is opened or exported.
+    pub(crate) hooks: Vec<HookRule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            chunk_cache_budget_bytes: DEFAULT_CACHE_BUDGET as u64,
+            max_cached_chunk_bytes: MAX_CACHE_BYTES as u64,
+            disk_cache_budget_bytes: DEFAULT_DISK_CACHE_BUDGET,
+            memory_limit_bytes: DEFAULT_MEMORY_LIMIT,
+            preview_bytes: DEFAULT_PREVIEW_BYTES,
+            temp_dir: None,
+            thumbnail_size: DEFAULT_THUMBNAIL_SIZE,
+            io_timeout_secs: DEFAULT_IO_TIMEOUT_SECS,
+            allow_external_chunks: false,
+            read_only: false,
+            remote: RemoteSettings::default(),
+            external_viewers: BTreeMap::new(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    fn validate(&self) -> AppResult<()> {
+        let positive = [
+            ("chunk cache budget", self.chunk_cache_budget_bytes),
+            ("max cached chunk size", self.max_cached_chunk_bytes),
+            ("memory limit", self.memory_limit_bytes),
+            ("IO timeout", self.io_timeout_secs),
+        ];
+        if let Some((name, _)) = positive.iter().find(|(_, v)| *v == 0) {
+            return Err(AppError::Invalid(format!("{name} must be positive")));
+        }
+        if self.preview_bytes == 0 || self.preview_bytes > MAX_PREVIEW_BYTES {
+            return Err(AppError::Invalid(format!(
+                "preview size must be between 1 and {MAX_PREVIEW_BYTES} bytes"
+            )));
+        }
+        if !(16..=4096).contains(&self.thumbnail_size) {
+            return Err(AppError::Invalid(
+                "thumbnail size must be between 16 and 4096 pixels".into(),
+            ));
+        }
+        usize::try_from(self.chunk_cache_budget_bytes)
+            .map_err(|_| AppError::Invalid("cache budget exceeds addressable memory".into()))?;
+        if let Some(dir) = self.temp_dir.as_deref() {
+            if !dir.trim().is_empty() && !Path::new(dir.trim()).is_absolute() {
+                return Err(AppError::Invalid(format!(
+                    "temp directory {dir:?} must be an absolute path"
+                )));
+            }
+        }
+        for (ext, viewer) in &self.external_viewers {
+            if viewer_key(ext).is_empty() {
+                return Err(AppError::Invalid("viewer extension is empty".into()));
+            }
+            if viewer.program.trim().is_empty() {
+                return Err(AppError::Invalid(format!(
+                    "no program set for .{}",
+                    viewer_key(ext)
+                )));
+            }
+        }
+        validate_rules(&self.hooks)
+    }
+}
+
+fn store() -> &'static RwLock<Arc<Settings>> {
+    static CURRENT: OnceLock<RwLock<Arc<Settings>>> = OnceLock::new();
+    CURRENT.get_or_init(|| RwLock::new(Arc::new(Settings::default())))
+}
+
+/// Snapshot of the settings in effect right now; cheap to take on hot paths.
+pub(crate) fn current() -> Arc<Settings> {
+    store().read().map(|s| Arc::clone(&s)).unwrap_or_default()
+}
+
+/// Root for files the viewer writes outside datasets.
+pub(crate) fn temp_root() -> PathBuf {
+    current()
+        .temp_dir
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| PathBuf::from(d.trim()))
+        .unwrap_or_else(|| std::env::temp_dir().join("litdata-viewer"))
+}
+
+fn settings_path(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::Io(format!("app config dir unavailable: {e}")))?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Pushes `settings` into the subsystems that hold their own copies.
+fn apply(settings: Settings, cache: &ChunkCache) -> AppResult<Settings> {
+    cache.set_entry_limit(settings.max_cached_chunk_bytes as usize)?;
+    cache.set_budget(settings.chunk_cache_budget_bytes as usize)?;
+    memory::set_limit(settings.memory_limit_bytes)?;
+    security::set_allow_outside_root(settings.allow_external_chunks);
+    security::set_read_only(settings.read_only);
+    let mut current = store()
+        .write()
+        .map_err(|_| AppError::Task("settings lock poisoned".into()))?;
+    *current = Arc::new(settings.clone());
+    Ok(settings)
+}
+
+/// Loads the settings file at startup. A missing or invalid file leaves the defaults.
+pub(crate) fn init(app: &AppHandle, cache: &ChunkCache) {
+    let Ok(path) = settings_path(app) else {
+        return;
+    };
+    let Ok(raw) = fs::read(&path) else {
+        return;
+    };
+    let loaded = serde_json::from_slice::<Settings>(&raw)
+        .map_err(|e| AppError::Invalid(e.to_string()))
+        .and_then(|s| s.validate().map(|_| s))
+        .and_then(|s| apply(s, cache));
+    if let Err(e) = loaded {
+        tracing::warn!(path = %path.display(), "ignoring settings file: {e}");
+    }
+}
+
+#[tauri::command]
+pub async fn get_settings() -> AppResult<Settings> {
+    Ok(Settings::clone(&current()))
+}
+
+/// Validates, persists, and applies a full settings object.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    settings: Settings,
+    cache: tauri::State<'_, ChunkCache>,
+) -> AppResult<Settings> {
+    settings.validate()?;
+    let cache_handle = (*cache).clone();
+    spawn_blocking(move || {
+        let path = settings_path(&app)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_vec_pretty(&settings)
+            .map_err(|e| AppError::Invalid(format!("serializing settings: {e}")))?;
+        let partial = path.with_extension("json.part");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, &path)?;
+        tracing::info!(path = %path.display(), "settings updated");
+        apply(settings, &cache_handle)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
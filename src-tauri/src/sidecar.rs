@@ -0,0 +1,320 @@
+use crate::litdata::{
+    load_chunk_access, parse_offsets, read_field_bytes, AppError, AppResult, ChunkCache, ItemMeta,
+    ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::workspace::{DatasetHandle, Workspace};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Unmatched keys listed in the summary, so a wrong key column is easy to spot.
+const UNMATCHED_EXAMPLES: usize = 5;
+
+/// External labels for a dataset's items, by global index. Loaded from a CSV, TSV or
+/// JSONL file and joined onto item listings; the dataset itself is not touched.
+pub(crate) struct Sidecar {
+    /// Label columns, without the key column.
+    columns: Vec<String>,
+    rows: HashMap<u64, Vec<String>>,
+}
+
+impl Sidecar {
+    /// Fills in `labels` for `items` of `chunk_filename` that have a row.
+    pub(crate) fn join(&self, parsed: &ParsedIndex, chunk_filename: &str, items: &mut [ItemMeta]) {
+        let Some(chunk_start) = chunk_start(parsed, chunk_filename) else {
+            return;
+        };
+        for item in items {
+            let Some(row) = self.rows.get(&(chunk_start + item.item_index as u64)) else {
+                continue;
+            };
+            let labels: BTreeMap<String, String> = self
+                .columns
+                .iter()
+                .zip(row)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(column, value)| (column.clone(), value.clone()))
+                .collect();
+            item.labels = Some(labels);
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarSummary {
+    job_id: String,
+    path: String,
+    key_column: String,
+    /// Label columns, in file order.
+    columns: Vec<String>,
+    rows: usize,
+    /// Items that received labels; a repeated key keeps its last row.
+    matched: usize,
+    /// Rows whose key names no item of the dataset.
+    unmatched: usize,
+    unmatched_examples: Vec<String>,
+}
+
+/// Loads a label file for an open dataset and joins its columns onto item listings.
+/// Rows are matched on `key_column`, which holds global item indices, or, with
+/// `id_field`, the text of that field of each item. Replaces any earlier sidecar.
+#[tauri::command]
+pub async fn load_sidecar(
+    app: AppHandle,
+    handle: DatasetHandle,
+    path: String,
+    key_column: String,
+    id_field: Option<usize>,
+    job_id: Option<String>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<SidecarSummary> {
+    let progress = Progress::new(app, job_id, "sidecar");
+    let workspace = (*workspace).clone();
+    spawn_blocking(move || {
+        let parsed = workspace.get(handle)?;
+        let (sidecar, summary) = load_sync(
+            &parsed,
+            Path::new(path.trim()),
+            key_column.trim(),
+            id_field,
+            &progress,
+        )?;
+        workspace.attach_sidecar(handle, sidecar)?;
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Stops joining labels onto the dataset's items; returns whether one was loaded.
+#[tauri::command]
+pub async fn clear_sidecar(
+    handle: DatasetHandle,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<bool> {
+    workspace.detach_sidecar(handle)
+}
+
+/// Global index of the first item in `chunk_filename`.
+fn chunk_start(parsed: &ParsedIndex, chunk_filename: &str) -> Option<u64> {
+    let mut start = 0u64;
+    for chunk in &parsed.chunks {
+        if chunk.filename == chunk_filename {
+            return Some(start);
+        }
+        start += chunk.chunk_size as u64;
+    }
+    None
+}
+
+/// Records of a CSV-style file, honouring quoted fields with doubled quotes and
+/// embedded newlines. Blank lines are skipped.
+fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    let mut end_record = |record: &mut Vec<String>, field: &mut String| {
+        record.push(std::mem::take(field));
+        if record.len() > 1 || !record[0].is_empty() {
+            records.push(std::mem::take(record));
+        } else {
+            record.clear();
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            '\r' if !quoted => {}
+            '\n' if !quoted => end_record(&mut record, &mut field),
+            c if c == delimiter && !quoted => record.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        end_record(&mut record, &mut field);
+    }
+    records
+}
+
+/// Header and rows of a JSONL file. Columns are every key seen, in first-seen order;
+/// non-string values keep their JSON text.
+fn parse_jsonl(text: &str) -> AppResult<(Vec<String>, Vec<Vec<String>>)> {
+    let mut columns: Vec<String> = Vec::new();
+    let mut objects = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(line)
+            .map_err(|e| AppError::Invalid(format!("line {}: {e}", line_no + 1)))?;
+        let serde_json::Value::Object(object) = value else {
+            return Err(AppError::Invalid(format!(
+                "line {}: expected a JSON object",
+                line_no + 1
+            )));
+        };
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        objects.push(object);
+    }
+    let rows = objects
+        .into_iter()
+        .map(|object| {
+            columns
+                .iter()
+                .map(|column| match object.get(column) {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    Ok((columns, rows))
+}
+
+/// Header and rows of `path`, by extension: `.jsonl`/`.ndjson`, `.tsv`, else CSV.
+fn read_table(path: &Path) -> AppResult<(Vec<String>, Vec<Vec<String>>)> {
+    let text = fs::read_to_string(path)?;
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if extension == "jsonl" || extension == "ndjson" {
+        return parse_jsonl(&text);
+    }
+    let delimiter = if extension == "tsv" { '\t' } else { ',' };
+    let mut records = parse_delimited(&text, delimiter).into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| AppError::Invalid(format!("{} is empty", path.display())))?;
+    let header: Vec<String> = header.into_iter().map(|h| h.trim().to_string()).collect();
+    let width = header.len();
+    let rows = records
+        .map(|mut row| {
+            row.resize(width, String::new());
+            row
+        })
+        .collect();
+    Ok((header, rows))
+}
+
+/// Global index of every item by the trimmed text of field `field`; the first item
+/// wins when ids repeat.
+fn ids_by_field(
+    parsed: &ParsedIndex,
+    field: usize,
+    progress: &Progress,
+) -> AppResult<HashMap<String, u64>> {
+    let format_len = parsed.format_len();
+    if field >= format_len {
+        return Err(AppError::Invalid(format!(
+            "field {field} is out of range; data_format declares {format_len}"
+        )));
+    }
+    progress.report("reading ids", 0, parsed.chunks.len() as u64);
+    let per_chunk = par_map(&parsed.chunks, Some((progress, "reading ids")), |chunk| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &chunk.filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        (0..table.0)
+            .map(|item| {
+                let (data, _) = read_field_bytes(&access, &table, item, field, format_len, None)?;
+                Ok(String::from_utf8_lossy(&data).trim().to_string())
+            })
+            .collect::<AppResult<Vec<_>>>()
+    });
+    let mut ids = HashMap::new();
+    let mut chunk_start = 0u64;
+    for (chunk, result) in parsed.chunks.iter().zip(per_chunk) {
+        for (item, id) in result?.into_iter().enumerate() {
+            ids.entry(id).or_insert(chunk_start + item as u64);
+        }
+        chunk_start += chunk.chunk_size as u64;
+    }
+    Ok(ids)
+}
+
+pub(crate) fn load_sync(
+    parsed: &ParsedIndex,
+    path: &Path,
+    key_column: &str,
+    id_field: Option<usize>,
+    progress: &Progress,
+) -> AppResult<(Sidecar, SidecarSummary)> {
+    let (header, rows) = read_table(path)?;
+    let key = header.iter().position(|h| h == key_column).ok_or_else(|| {
+        AppError::Invalid(format!(
+            "no column {key_column:?}; the file has {}",
+            header.join(", ")
+        ))
+    })?;
+    let ids = id_field
+        .map(|field| ids_by_field(parsed, field, progress))
+        .transpose()?;
+    let total_items: u64 = parsed.chunks.iter().map(|c| c.chunk_size as u64).sum();
+
+    let row_count = rows.len();
+    let mut joined = HashMap::with_capacity(row_count);
+    let mut unmatched = 0;
+    let mut unmatched_examples = Vec::new();
+    for mut row in rows {
+        let key_value = row.remove(key);
+        let key_value = key_value.trim();
+        let global = match &ids {
+            Some(ids) => ids.get(key_value).copied(),
+            None => key_value
+                .parse::<u64>()
+                .ok()
+                .filter(|global| *global < total_items),
+        };
+        match global {
+            Some(global) => {
+                joined.insert(global, row);
+            }
+            None => {
+                unmatched += 1;
+                if unmatched_examples.len() < UNMATCHED_EXAMPLES {
+                    unmatched_examples.push(key_value.to_string());
+                }
+            }
+        }
+    }
+    progress.report("done", 1, 1);
+
+    let mut columns = header;
+    columns.remove(key);
+    let summary = SidecarSummary {
+        job_id: progress.job_id().to_string(),
+        path: path.display().to_string(),
+        key_column: key_column.to_string(),
+        columns: columns.clone(),
+        rows: row_count,
+        matched: joined.len(),
+        unmatched,
+        unmatched_examples,
+    };
+    let sidecar = Sidecar {
+        columns,
+        rows: joined,
+    };
+    Ok((sidecar, summary))
+}
@@ -0,0 +1,232 @@
+use crate::dedup::ItemLocation;
+use crate::field_sizes::{field_sizes, sample_plan};
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, AppError, AppResult, ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use serde::Serialize;
+use std::path::Path;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+const DEFAULT_STD_DEVS: f64 = 3.0;
+/// Outliers returned, most extreme first; the rest are only counted.
+const MAX_OUTLIERS: usize = 1_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeSpread {
+    /// `None` for whole items.
+    field_index: Option<usize>,
+    mean: f64,
+    std_dev: f64,
+    outliers: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeOutlier {
+    #[serde(flatten)]
+    location: ItemLocation,
+    /// `None` when the whole item is the outlier.
+    field_index: Option<usize>,
+    bytes: u64,
+    /// Signed distance from the mean in standard deviations.
+    z_score: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeOutlierReport {
+    job_id: String,
+    std_devs: f64,
+    total_items: u64,
+    scanned_items: u64,
+    /// Items whose size header does not add up to their length; judged on total size only.
+    arity_mismatches: u64,
+    /// Whole items first, then each field.
+    spreads: Vec<SizeSpread>,
+    /// Flagged sizes; one item can count for its total and for several fields.
+    outlier_count: u64,
+    /// Largest `|z_score|` first, at most 1,000.
+    outliers: Vec<SizeOutlier>,
+    unreadable_chunks: Vec<String>,
+}
+
+/// Flags items whose total size, or the size of any field, is more than `std_devs`
+/// standard deviations from the mean. Reads every item's header unless `sample_items`
+/// is given.
+#[tauri::command]
+pub async fn find_size_outliers(
+    app: AppHandle,
+    index_path: String,
+    std_devs: Option<f64>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<SizeOutlierReport> {
+    let progress = Progress::new(app, job_id, "size-outliers");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        size_outliers_of(
+            &parsed,
+            std_devs.unwrap_or(DEFAULT_STD_DEVS),
+            sample_items.unwrap_or(u32::MAX),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// Mean and standard deviation of one measure, accumulated in one pass.
+#[derive(Default)]
+struct Moments {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn std_dev(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            (self.m2 / self.count as f64).sqrt()
+        }
+    }
+}
+
+pub(crate) fn size_outliers_of(
+    parsed: &ParsedIndex,
+    std_devs: f64,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<SizeOutlierReport> {
+    if std_devs.is_nan() || std_devs <= 0.0 {
+        return Err(AppError::Invalid(
+            "standard deviations must be positive".into(),
+        ));
+    }
+    let format_len = parsed.format_len();
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("reading", 0, plan.len() as u64);
+    // Per item: total bytes and, when the header is consistent, each field's bytes.
+    let read = par_map(&plan, Some((progress, "reading")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let (_, offsets) = parse_offsets(&access)?;
+        items
+            .iter()
+            .map(|item| {
+                // Checks `item` against the offsets before they are indexed.
+                let fields = field_sizes(&access, &offsets, *item, format_len)?;
+                let idx = *item as usize;
+                let total = offsets[idx + 1].saturating_sub(offsets[idx]);
+                Ok((*item, total, fields))
+            })
+            .collect::<AppResult<Vec<_>>>()
+    });
+
+    let chunk_starts: Vec<u64> = parsed
+        .chunks
+        .iter()
+        .scan(0u64, |start, c| {
+            let this = *start;
+            *start += c.chunk_size as u64;
+            Some(this)
+        })
+        .collect();
+    let mut items = Vec::new();
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, _), result) in plan.iter().zip(read) {
+        match result {
+            Ok(sizes) => items.extend(sizes.into_iter().map(|s| (*chunk, s))),
+            Err(_) => unreadable_chunks.push(parsed.chunks[*chunk].filename.clone()),
+        }
+    }
+    progress.report("scoring", plan.len() as u64, plan.len() as u64);
+
+    // Slot 0 is the whole item, slot `f + 1` field `f`.
+    let mut moments: Vec<Moments> = (0..=format_len).map(|_| Moments::default()).collect();
+    let mut arity_mismatches = 0u64;
+    for (_, (_, total, fields)) in &items {
+        moments[0].add(*total as f64);
+        match fields {
+            Some(fields) => {
+                for (field, size) in fields.iter().enumerate() {
+                    moments[field + 1].add(*size as f64);
+                }
+            }
+            None => arity_mismatches += 1,
+        }
+    }
+    let z = |slot: usize, bytes: u64| {
+        let spread = moments[slot].std_dev();
+        if spread == 0.0 {
+            0.0
+        } else {
+            (bytes as f64 - moments[slot].mean) / spread
+        }
+    };
+
+    let mut counts = vec![0u64; format_len + 1];
+    let mut outliers = Vec::new();
+    for (chunk, (item, total, fields)) in &items {
+        let measures = std::iter::once((0, *total)).chain(
+            fields
+                .iter()
+                .flatten()
+                .enumerate()
+                .map(|(field, size)| (field + 1, *size as u64)),
+        );
+        for (slot, bytes) in measures {
+            let z_score = z(slot, bytes);
+            if z_score.abs() <= std_devs {
+                continue;
+            }
+            counts[slot] += 1;
+            outliers.push(SizeOutlier {
+                location: ItemLocation {
+                    chunk_filename: parsed.chunks[*chunk].filename.clone(),
+                    item_index: *item,
+                    global_index: chunk_starts[*chunk] + *item as u64,
+                },
+                field_index: slot.checked_sub(1),
+                bytes,
+                z_score,
+            });
+        }
+    }
+    let outlier_count = outliers.len() as u64;
+    outliers.sort_by(|a, b| b.z_score.abs().total_cmp(&a.z_score.abs()));
+    outliers.truncate(MAX_OUTLIERS);
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+    Ok(SizeOutlierReport {
+        job_id: progress.job_id().to_string(),
+        std_devs,
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        scanned_items: items.len() as u64,
+        arity_mismatches,
+        spreads: moments
+            .iter()
+            .zip(counts)
+            .enumerate()
+            .map(|(slot, (m, outliers))| SizeSpread {
+                field_index: slot.checked_sub(1),
+                mean: m.mean,
+                std_dev: m.std_dev(),
+                outliers,
+            })
+            .collect(),
+        outlier_count,
+        outliers,
+        unreadable_chunks,
+    })
+}
@@ -0,0 +1,174 @@
+//! Tensors as litdata's `tensor`, `numpy` and `no_header_*` serializers write them:
+//! little-endian elements in C order, optionally behind a `u32` header of dtype index,
+//! rank and dimensions.
+
+use crate::litdata::{AppError, AppResult};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Dtype {
+    Bool,
+    U8,
+    I8,
+    I16,
+    I32,
+    I64,
+    F16,
+    Bf16,
+    F32,
+    F64,
+    Complex64,
+    Complex128,
+}
+
+impl Dtype {
+    /// litdata's `_TORCH_DTYPES_MAPPING`, which lists aliases (`float`, `half`, `long`, …)
+    /// under their own indices.
+    pub(crate) fn from_torch_index(index: u32) -> Option<Self> {
+        use Dtype::*;
+        const TORCH: [Dtype; 20] = [
+            F32, F32, F64, F64, Complex64, Complex64, Complex128, Complex128, F16, F16, Bf16, U8,
+            I8, I16, I16, I32, I32, I64, I64, Bool,
+        ];
+        TORCH.get(index as usize).copied()
+    }
+
+    /// numpy / torch spellings, e.g. `float32`, `torch.half`, `<f4`.
+    pub(crate) fn parse(name: &str) -> AppResult<Self> {
+        use Dtype::*;
+        let name = name.trim().to_lowercase();
+        let name = name
+            .trim_start_matches("torch.")
+            .trim_start_matches(['<', '=']);
+        Ok(match name {
+            "bool" | "b1" => Bool,
+            "uint8" | "u1" => U8,
+            "int8" | "i1" => I8,
+            "int16" | "short" | "i2" => I16,
+            "int32" | "int" | "i4" => I32,
+            "int64" | "long" | "i8" => I64,
+            "float16" | "half" | "f2" => F16,
+            "bfloat16" => Bf16,
+            "float32" | "float" | "f4" => F32,
+            "float64" | "double" | "f8" => F64,
+            "complex64" | "c8" => Complex64,
+            "complex128" | "c16" => Complex128,
+            _ => return Err(AppError::Invalid(format!("unknown dtype {name}"))),
+        })
+    }
+
+    pub(crate) fn width(self) -> usize {
+        use Dtype::*;
+        match self {
+            Bool | U8 | I8 => 1,
+            I16 | F16 | Bf16 => 2,
+            I32 | F32 => 4,
+            I64 | F64 | Complex64 => 8,
+            Complex128 => 16,
+        }
+    }
+
+    pub(crate) fn is_float(self) -> bool {
+        matches!(self, Dtype::F16 | Dtype::Bf16 | Dtype::F32 | Dtype::F64)
+    }
+}
+
+/// How a field's payload lays out its tensor.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum TensorLayout {
+    /// `tensor`: header whose dtype index is torch's.
+    TorchHeader,
+    /// `numpy`: header whose dtype index depends on the writer's numpy build.
+    NumpyHeader,
+    /// `no_header_tensor:{index}` and friends: bare elements.
+    Bare(Option<Dtype>),
+}
+
+impl TensorLayout {
+    /// `None` when `format` is not a tensor format.
+    pub(crate) fn of(format: &str) -> Option<Self> {
+        let fmt = format.to_lowercase();
+        match fmt.as_str() {
+            "tensor" => return Some(Self::TorchHeader),
+            "numpy" => return Some(Self::NumpyHeader),
+            _ => {}
+        }
+        if let Some(index) = fmt.strip_prefix("no_header_tensor:") {
+            let dtype = index.parse().ok().and_then(Dtype::from_torch_index);
+            return Some(Self::Bare(dtype));
+        }
+        fmt.starts_with("no_header_numpy")
+            .then_some(Self::Bare(None))
+    }
+}
+
+/// Element bytes of a tensor payload and its dtype; `dtype` overrides or supplies what
+/// the layout cannot tell.
+pub(crate) fn tensor_elements(
+    data: &[u8],
+    layout: TensorLayout,
+    dtype: Option<Dtype>,
+) -> AppResult<(&[u8], Dtype)> {
+    let word = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| AppError::Invalid("tensor header truncated".into()))
+    };
+    let (body, declared) = match layout {
+        TensorLayout::Bare(declared) => (data, declared),
+        TensorLayout::TorchHeader | TensorLayout::NumpyHeader => {
+            let rank = word(4)? as usize;
+            let start = 8 + rank * 4;
+            let body = data
+                .get(start..)
+                .ok_or_else(|| AppError::Invalid("tensor header truncated".into()))?;
+            let declared = match layout {
+                TensorLayout::TorchHeader => Dtype::from_torch_index(word(0)?),
+                _ => None,
+            };
+            (body, declared)
+        }
+    };
+    let dtype = dtype.or(declared).ok_or_else(|| {
+        AppError::Invalid("tensor dtype is not recorded in the format; pass it explicitly".into())
+    })?;
+    if body.len() % dtype.width() != 0 {
+        return Err(AppError::Invalid(format!(
+            "{} bytes is not a whole number of {dtype:?} elements",
+            body.len()
+        )));
+    }
+    Ok((body, dtype))
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = (bits as u32 & 0x8000) << 16;
+    let exponent = (bits >> 10) & 0x1F;
+    let mantissa = bits as u32 & 0x3FF;
+    let magnitude = match exponent {
+        0 => (mantissa as f32) * 2f32.powi(-24),
+        0x1F if mantissa == 0 => f32::INFINITY,
+        0x1F => f32::NAN,
+        _ => f32::from_bits(((exponent as u32 + 112) << 23) | (mantissa << 13)),
+    };
+    f32::from_bits(magnitude.to_bits() | sign)
+}
+
+/// Float elements as `f32`; `None` for non-float dtypes.
+pub(crate) fn floats(body: &[u8], dtype: Dtype) -> Option<Vec<f32>> {
+    let words = body.chunks_exact(dtype.width());
+    Some(match dtype {
+        Dtype::F32 => words
+            .map(|w| f32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect(),
+        Dtype::F64 => words
+            .map(|w| f64::from_le_bytes(std::array::from_fn(|i| w[i])) as f32)
+            .collect(),
+        Dtype::F16 => words
+            .map(|w| f16_to_f32(u16::from_le_bytes([w[0], w[1]])))
+            .collect(),
+        Dtype::Bf16 => words
+            .map(|w| f32::from_bits((u16::from_le_bytes([w[0], w[1]]) as u32) << 16))
+            .collect(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,396 @@
+use crate::field_sizes::{percentile, sample_plan};
+use crate::litdata::{
+    load_chunk_access, parse_index, parse_offsets, read_field_bytes, AppError, AppResult,
+    ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use crate::scan::par_map;
+use crate::tensor::TensorLayout;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+/// Shortest and longest sampled items listed per field.
+const OUTLIERS: usize = 10;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    Chars,
+    Tokens,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthStats {
+    min: u64,
+    p1: u64,
+    p50: u64,
+    p99: u64,
+    max: u64,
+    mean: f64,
+}
+
+/// Items with length in `from..to`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthBucket {
+    from: u64,
+    to: u64,
+    count: u64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthOutlier {
+    chunk: String,
+    item: u32,
+    length: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthDistribution {
+    field_index: usize,
+    format: Option<String>,
+    unit: LengthUnit,
+    items: u64,
+    empty_items: u64,
+    /// Text that is not UTF-8, or token payloads that are not a whole number of elements.
+    malformed_items: u64,
+    total: u64,
+    /// Absent when every item was malformed.
+    lengths: Option<LengthStats>,
+    /// Power-of-two buckets from the shortest item to the longest.
+    histogram: Vec<LengthBucket>,
+    /// Shortest non-empty items first.
+    shortest: Vec<LengthOutlier>,
+    /// Longest first.
+    longest: Vec<LengthOutlier>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextLengthReport {
+    job_id: String,
+    total_items: u64,
+    sampled_items: u64,
+    /// Entries in the loaded tokenizer's vocabulary.
+    tokenizer_vocab: Option<usize>,
+    unreadable_chunks: Vec<String>,
+    /// One entry per field and unit: text fields in characters, and in tokens too when
+    /// a tokenizer is given; token fields in tokens.
+    fields: Vec<LengthDistribution>,
+}
+
+/// Character and token length distributions of text and token fields. Reads every item
+/// unless `sample_items` is given. With `tokenizer_path` (a Hugging Face
+/// `tokenizer.json`, or a vocabulary with one token per line), text is also counted in
+/// tokens by greedy longest match against the vocabulary, which tracks but does not
+/// exactly reproduce the tokenizer's merges.
+#[tauri::command]
+pub async fn text_length_stats(
+    app: AppHandle,
+    index_path: String,
+    field_index: Option<usize>,
+    tokenizer_path: Option<String>,
+    sample_items: Option<u32>,
+    job_id: Option<String>,
+) -> AppResult<TextLengthReport> {
+    let progress = Progress::new(app, job_id, "text-stats");
+    spawn_blocking(move || {
+        let parsed = parse_index(Path::new(&index_path))?;
+        let tokenizer = tokenizer_path
+            .map(|path| Vocabulary::load(Path::new(&path)))
+            .transpose()?;
+        text_stats_of(
+            &parsed,
+            field_index,
+            tokenizer.as_ref(),
+            sample_items.unwrap_or(u32::MAX),
+            &progress,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+/// How a field's payload is measured.
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Text,
+    /// Packed token ids of this many bytes each.
+    Tokens(u8),
+}
+
+fn field_kind(format: &str) -> Option<FieldKind> {
+    let fmt = format.to_lowercase();
+    if matches!(fmt.as_str(), "str" | "string" | "text") {
+        return Some(FieldKind::Text);
+    }
+    match TensorLayout::of(&fmt)? {
+        TensorLayout::Bare(Some(dtype)) => Some(FieldKind::Tokens(dtype.width() as u8)),
+        _ => None,
+    }
+}
+
+/// Measurable fields; a requested field with an unmeasurable format is read as text.
+fn candidate_fields(
+    formats: &[String],
+    field_index: Option<usize>,
+) -> AppResult<Vec<(usize, FieldKind)>> {
+    if let Some(field) = field_index {
+        let format = formats
+            .get(field)
+            .ok_or_else(|| AppError::Invalid("field index out of range".into()))?;
+        return Ok(vec![(field, field_kind(format).unwrap_or(FieldKind::Text))]);
+    }
+    Ok(formats
+        .iter()
+        .enumerate()
+        .filter_map(|(field, fmt)| Some((field, field_kind(fmt)?)))
+        .collect())
+}
+
+/// Token vocabulary for approximate counting.
+pub(crate) struct Vocabulary {
+    tokens: HashSet<String>,
+    /// Longest token, in chars; bounds the match search.
+    longest: usize,
+}
+
+impl Vocabulary {
+    pub(crate) fn load(path: &Path) -> AppResult<Self> {
+        let raw = fs::read_to_string(path)
+            .map_err(|e| AppError::Open(format!("reading tokenizer {}: {e}", path.display())))?;
+        let tokens: HashSet<String> = match serde_json::from_str::<Value>(&raw) {
+            Ok(json) => {
+                // tokenizer.json keeps the vocabulary under `model.vocab`: a token → id map
+                // for BPE and WordPiece, `[token, score]` pairs for Unigram.
+                let vocab = json.pointer("/model/vocab").unwrap_or(&json);
+                match vocab {
+                    Value::Object(map) => map.keys().cloned().collect(),
+                    Value::Array(entries) => entries
+                        .iter()
+                        .filter_map(|e| e.get(0).unwrap_or(e).as_str().map(str::to_string))
+                        .collect(),
+                    _ => HashSet::new(),
+                }
+            }
+            Err(_) => raw.lines().map(str::to_string).collect(),
+        };
+        if tokens.is_empty() {
+            return Err(AppError::Invalid(format!(
+                "no vocabulary found in {}",
+                path.display()
+            )));
+        }
+        let longest = tokens.iter().map(|t| t.chars().count()).max().unwrap_or(1);
+        Ok(Self { tokens, longest })
+    }
+
+    fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whitespace-separated words, each matched greedily from the left. A word-initial
+    /// piece may carry the byte-level (`Ġ`) or SentencePiece (`▁`) space marker, a
+    /// continuation the WordPiece `##`; unknown chars count one token per UTF-8 byte.
+    fn count(&self, text: &str) -> u64 {
+        let mut count = 0u64;
+        let mut candidate = String::new();
+        for (w, word) in text.split_whitespace().enumerate() {
+            let chars: Vec<char> = word.chars().collect();
+            let mut pos = 0;
+            while pos < chars.len() {
+                let markers: &[&str] = match (pos, w) {
+                    (0, 0) => &["", "▁"],
+                    (0, _) => &["Ġ", "▁", ""],
+                    _ => &["", "##"],
+                };
+                let matched = (1..=self.longest.min(chars.len() - pos))
+                    .rev()
+                    .find(|&len| {
+                        markers.iter().any(|marker| {
+                            candidate.clear();
+                            candidate.push_str(marker);
+                            candidate.extend(&chars[pos..pos + len]);
+                            self.tokens.contains(&candidate)
+                        })
+                    });
+                match matched {
+                    Some(len) => {
+                        count += 1;
+                        pos += len;
+                    }
+                    None => {
+                        count += chars[pos].len_utf8() as u64;
+                        pos += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    /// `(length, chunk, item)` of every non-malformed item.
+    lengths: Vec<(u64, usize, u32)>,
+    malformed: u64,
+}
+
+fn distribution(
+    parsed: &ParsedIndex,
+    field_index: usize,
+    format: Option<String>,
+    unit: LengthUnit,
+    mut tally: Tally,
+) -> Option<LengthDistribution> {
+    if tally.lengths.is_empty() && tally.malformed == 0 {
+        return None;
+    }
+    tally.lengths.sort_unstable();
+    let lengths: Vec<u64> = tally.lengths.iter().map(|(len, _, _)| *len).collect();
+    let total: u64 = lengths.iter().sum();
+    let outlier = |&(length, chunk, item): &(u64, usize, u32)| LengthOutlier {
+        chunk: parsed.chunks[chunk].filename.clone(),
+        item,
+        length,
+    };
+
+    let mut histogram: Vec<LengthBucket> = Vec::new();
+    for &len in &lengths {
+        let from = if len == 0 { 0 } else { 1 << len.ilog2() };
+        // Empty buckets in between keep the histogram contiguous.
+        while histogram.last().is_none_or(|bucket| bucket.from < from) {
+            let next = histogram.last().map_or(from, |bucket| bucket.to);
+            histogram.push(LengthBucket {
+                from: next,
+                to: (next * 2).max(1),
+                count: 0,
+            });
+        }
+        if let Some(bucket) = histogram.last_mut() {
+            bucket.count += 1;
+        }
+    }
+    Some(LengthDistribution {
+        field_index,
+        format,
+        unit,
+        items: lengths.len() as u64,
+        empty_items: lengths.iter().take_while(|len| **len == 0).count() as u64,
+        malformed_items: tally.malformed,
+        total,
+        lengths: (!lengths.is_empty()).then(|| LengthStats {
+            min: lengths[0],
+            p1: percentile(&lengths, 1),
+            p50: percentile(&lengths, 50),
+            p99: percentile(&lengths, 99),
+            max: lengths[lengths.len() - 1],
+            mean: total as f64 / lengths.len() as f64,
+        }),
+        histogram,
+        shortest: tally
+            .lengths
+            .iter()
+            .filter(|(len, _, _)| *len > 0)
+            .take(OUTLIERS)
+            .map(outlier)
+            .collect(),
+        longest: tally
+            .lengths
+            .iter()
+            .rev()
+            .take(OUTLIERS)
+            .map(outlier)
+            .collect(),
+    })
+}
+
+pub(crate) fn text_stats_of(
+    parsed: &ParsedIndex,
+    field_index: Option<usize>,
+    tokenizer: Option<&Vocabulary>,
+    sample_items: u32,
+    progress: &Progress,
+) -> AppResult<TextLengthReport> {
+    let formats = parsed.config.data_format.clone().unwrap_or_default();
+    let fields = candidate_fields(&formats, field_index)?;
+    let plan = sample_plan(parsed, sample_items);
+    progress.report("measuring", 0, plan.len() as u64);
+    // Per item and field: `None` when malformed, else the length in each unit.
+    let measured = par_map(&plan, Some((progress, "measuring")), |(chunk, items)| {
+        let scratch = ChunkCache::default();
+        let access = load_chunk_access(parsed, &parsed.chunks[*chunk].filename, &scratch)?;
+        let table = parse_offsets(&access)?;
+        let mut lengths = Vec::with_capacity(items.len() * fields.len());
+        for item in items {
+            for &(field, kind) in &fields {
+                let (data, _) =
+                    read_field_bytes(&access, &table, *item, field, formats.len(), None)?;
+                let measured = match kind {
+                    FieldKind::Tokens(width) => (data.len() % width as usize == 0)
+                        .then(|| vec![(LengthUnit::Tokens, (data.len() / width as usize) as u64)]),
+                    FieldKind::Text => std::str::from_utf8(&data).ok().map(|text| {
+                        let mut units = vec![(LengthUnit::Chars, text.chars().count() as u64)];
+                        if let Some(vocab) = tokenizer {
+                            units.push((LengthUnit::Tokens, vocab.count(text)));
+                        }
+                        units
+                    }),
+                };
+                lengths.push((*item, field, kind, measured));
+            }
+        }
+        AppResult::Ok(lengths)
+    });
+
+    let mut tallies: BTreeMap<(usize, LengthUnit), Tally> = BTreeMap::new();
+    let mut sampled_items = 0u64;
+    let mut unreadable_chunks = Vec::new();
+    for ((chunk, items), result) in plan.iter().zip(measured) {
+        let Ok(lengths) = result else {
+            unreadable_chunks.push(parsed.chunks[*chunk].filename.clone());
+            continue;
+        };
+        sampled_items += items.len() as u64;
+        for (item, field, kind, measured) in lengths {
+            let Some(units) = measured else {
+                let unit = match kind {
+                    FieldKind::Text => LengthUnit::Chars,
+                    FieldKind::Tokens(_) => LengthUnit::Tokens,
+                };
+                tallies.entry((field, unit)).or_default().malformed += 1;
+                continue;
+            };
+            for (unit, length) in units {
+                let tally = tallies.entry((field, unit)).or_default();
+                tally.lengths.push((length, *chunk, item));
+            }
+        }
+    }
+    progress.report("done", plan.len() as u64, plan.len() as u64);
+
+    let fields = tallies
+        .into_iter()
+        .filter_map(|((field, unit), tally)| {
+            distribution(parsed, field, formats.get(field).cloned(), unit, tally)
+        })
+        .collect();
+    Ok(TextLengthReport {
+        job_id: progress.job_id().to_string(),
+        total_items: parsed.chunks.iter().map(|c| c.chunk_size as u64).sum(),
+        sampled_items,
+        tokenizer_vocab: tokenizer.map(Vocabulary::len),
+        unreadable_chunks,
+        fields,
+    })
+}
@@ -0,0 +1,256 @@
+use crate::litdata::AppResult;
+use crate::logging;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Write as _},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+/// Closed spans kept for `get_recent_spans`; older ones are dropped first.
+const MAX_RECORDED_SPANS: usize = 1024;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanTiming {
+    name: &'static str,
+    target: &'static str,
+    fields: String,
+    started_at_ms: u64,
+    duration_us: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanSummary {
+    name: &'static str,
+    count: u64,
+    total_us: u64,
+    max_us: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceDump {
+    spans: Vec<SpanTiming>,
+    summary: Vec<SpanSummary>,
+}
+
+struct LiveSpan {
+    name: &'static str,
+    target: &'static str,
+    fields: String,
+    started_at_ms: u64,
+    started: Instant,
+    refs: usize,
+}
+
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    live: Mutex<HashMap<u64, LiveSpan>>,
+    finished: Mutex<VecDeque<SpanTiming>>,
+}
+
+fn recorder() -> &'static Recorder {
+    static RECORDER: OnceLock<Recorder> = OnceLock::new();
+    RECORDER.get_or_init(Recorder::default)
+}
+
+/// Appends `name=value` pairs to a span's field string.
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = write!(self.0, "{}={:?}", field.name(), value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{value}"));
+    }
+}
+
+/// Splits an event's `message` from its other fields.
+#[derive(Default)]
+struct EventWriter {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventWriter {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            FieldWriter(&mut self.fields).record_debug(field, value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{value}"));
+    }
+}
+
+/// Keeps timings of this crate's spans in memory and hands its events at INFO and
+/// above to the log file.
+struct SpanRecorder;
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+            && (metadata.is_span() || *metadata.level() <= Level::INFO)
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let state = recorder();
+        let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut fields = String::new();
+        attrs.record(&mut FieldWriter(&mut fields));
+        let started_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        if let Ok(mut live) = state.live.lock() {
+            live.insert(
+                id,
+                LiveSpan {
+                    name: attrs.metadata().name(),
+                    target: attrs.metadata().target(),
+                    fields,
+                    started_at_ms,
+                    started: Instant::now(),
+                    refs: 1,
+                },
+            );
+        }
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        if let Ok(mut live) = recorder().live.lock() {
+            if let Some(entry) = live.get_mut(&span.into_u64()) {
+                values.record(&mut FieldWriter(&mut entry.fields));
+            }
+        }
+    }
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut writer = EventWriter::default();
+        event.record(&mut writer);
+        let metadata = event.metadata();
+        logging::write(
+            metadata.level().as_str(),
+            metadata.target(),
+            writer.message,
+            writer.fields,
+        );
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+
+    fn clone_span(&self, id: &span::Id) -> span::Id {
+        if let Ok(mut live) = recorder().live.lock() {
+            if let Some(entry) = live.get_mut(&id.into_u64()) {
+                entry.refs += 1;
+            }
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: span::Id) -> bool {
+        let state = recorder();
+        let closed = {
+            let Ok(mut live) = state.live.lock() else {
+                return false;
+            };
+            let key = id.into_u64();
+            match live.get_mut(&key) {
+                Some(entry) if entry.refs > 1 => {
+                    entry.refs -= 1;
+                    None
+                }
+                Some(_) => live.remove(&key),
+                None => None,
+            }
+        };
+        let Some(span) = closed else {
+            return false;
+        };
+        if let Ok(mut finished) = state.finished.lock() {
+            if finished.len() >= MAX_RECORDED_SPANS {
+                finished.pop_front();
+            }
+            finished.push_back(SpanTiming {
+                name: span.name,
+                target: span.target,
+                fields: span.fields,
+                started_at_ms: span.started_at_ms,
+                duration_us: span.started.elapsed().as_micros() as u64,
+            });
+        }
+        true
+    }
+}
+
+/// Installs the span recorder and log forwarder; called once at startup.
+pub fn install() {
+    let _ = tracing::subscriber::set_global_default(SpanRecorder);
+}
+
+/// Most recent closed spans (newest first), optionally filtered by span name, plus
+/// per-name totals over everything still recorded.
+#[tauri::command]
+pub async fn get_recent_spans(limit: Option<usize>, name: Option<String>) -> AppResult<TraceDump> {
+    let finished = recorder()
+        .finished
+        .lock()
+        .map(|f| f.iter().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut totals: HashMap<&'static str, SpanSummary> = HashMap::new();
+    for span in &finished {
+        let entry = totals.entry(span.name).or_insert(SpanSummary {
+            name: span.name,
+            count: 0,
+            total_us: 0,
+            max_us: 0,
+        });
+        entry.count += 1;
+        entry.total_us += span.duration_us;
+        entry.max_us = entry.max_us.max(span.duration_us);
+    }
+    let mut summary: Vec<SpanSummary> = totals.into_values().collect();
+    summary.sort_by_key(|s| std::cmp::Reverse(s.total_us));
+
+    let spans = finished
+        .into_iter()
+        .rev()
+        .filter(|s| name.as_deref().is_none_or(|n| s.name == n))
+        .take(limit.unwrap_or(MAX_RECORDED_SPANS))
+        .collect();
+    Ok(TraceDump { spans, summary })
+}
+
+/// Forgets every recorded span.
+#[tauri::command]
+pub async fn clear_recent_spans() -> AppResult<()> {
+    if let Ok(mut finished) = recorder().finished.lock() {
+        finished.clear();
+    }
+    Ok(())
+}
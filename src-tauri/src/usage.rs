@@ -0,0 +1,160 @@
+use crate::litdata::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::{async_runtime::spawn_blocking, AppHandle, Manager};
+
+const USAGE_FILE: &str = "usage-stats.json";
+
+/// Serializes read-modify-write cycles on the usage file.
+static USAGE_LOCK: Mutex<()> = Mutex::new(());
+/// Bytes read per index path since the last flush. Field reads are far too frequent to
+/// rewrite the file for each, so they accumulate here until the next open or query.
+static PENDING_BYTES: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetUsage {
+    index_path: String,
+    open_count: u64,
+    first_opened_at: u64,
+    last_opened_at: u64,
+    /// Field payload bytes handed to the viewer, exports and external programs.
+    bytes_read: u64,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn usage_path(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("app data dir unavailable: {e}")))?;
+    Ok(dir.join(USAGE_FILE))
+}
+
+/// A missing or unreadable file is treated as no history rather than failing the caller.
+fn read_usage(path: &Path) -> Vec<DatasetUsage> {
+    fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_usage(path: &Path, list: &[DatasetUsage]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(list)
+        .map_err(|e| AppError::Invalid(format!("serializing usage stats: {e}")))?;
+    let partial = path.with_extension("json.part");
+    fs::write(&partial, json)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn entry<'a>(list: &'a mut Vec<DatasetUsage>, index_path: &str) -> &'a mut DatasetUsage {
+    match list.iter().position(|u| u.index_path == index_path) {
+        Some(pos) => &mut list[pos],
+        None => {
+            let now = now_millis();
+            list.push(DatasetUsage {
+                index_path: index_path.to_string(),
+                open_count: 0,
+                first_opened_at: now,
+                last_opened_at: now,
+                bytes_read: 0,
+            });
+            list.last_mut().expect("just pushed")
+        }
+    }
+}
+
+/// Counts `bytes` of field data read from the dataset at `index_path`.
+pub(crate) fn record_bytes(index_path: &Path, bytes: usize) {
+    if bytes == 0 {
+        return;
+    }
+    if let Ok(mut pending) = PENDING_BYTES.lock() {
+        *pending
+            .get_or_insert_with(HashMap::new)
+            .entry(index_path.display().to_string())
+            .or_default() += bytes as u64;
+    }
+}
+
+/// Loads the stored stats, folds in pending byte counts and lets `update` adjust them
+/// before everything is written back.
+fn update_with(
+    app: &AppHandle,
+    update: impl FnOnce(&mut Vec<DatasetUsage>),
+) -> AppResult<Vec<DatasetUsage>> {
+    let path = usage_path(app)?;
+    let _guard = USAGE_LOCK
+        .lock()
+        .map_err(|_| AppError::Task("usage stats lock poisoned".into()))?;
+    let pending = PENDING_BYTES
+        .lock()
+        .map_err(|_| AppError::Task("usage stats lock poisoned".into()))?
+        .take()
+        .unwrap_or_default();
+    let mut list = read_usage(&path);
+    for (index_path, bytes) in &pending {
+        entry(&mut list, index_path).bytes_read += bytes;
+    }
+    update(&mut list);
+    if let Err(e) = write_usage(&path, &list) {
+        // Put the counts back so the next flush can retry them.
+        if let Ok(mut slot) = PENDING_BYTES.lock() {
+            let again = slot.get_or_insert_with(HashMap::new);
+            for (index_path, bytes) in pending {
+                *again.entry(index_path).or_default() += bytes;
+            }
+        }
+        return Err(e);
+    }
+    Ok(list)
+}
+
+pub(crate) fn record_open(app: &AppHandle, index_path: &str) -> AppResult<()> {
+    update_with(app, |list| {
+        let usage = entry(list, index_path);
+        usage.open_count += 1;
+        usage.last_opened_at = now_millis();
+    })
+    .map(|_| ())
+}
+
+/// Writes out byte counts accumulated since the last open; called on exit.
+pub(crate) fn flush(app: &AppHandle) -> AppResult<()> {
+    update_with(app, |_| {}).map(|_| ())
+}
+
+/// Per-dataset open counts and bytes read, most recently opened first.
+#[tauri::command]
+pub async fn get_usage_stats(app: AppHandle) -> AppResult<Vec<DatasetUsage>> {
+    spawn_blocking(move || {
+        let mut list = update_with(&app, |_| {})?;
+        list.sort_by_key(|u| std::cmp::Reverse(u.last_opened_at));
+        Ok(list)
+    })
+    .await
+    .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+#[tauri::command]
+pub async fn clear_usage_stats(app: AppHandle) -> AppResult<()> {
+    spawn_blocking(move || update_with(&app, |list| list.clear()).map(|_| ()))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
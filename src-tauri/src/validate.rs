@@ -0,0 +1,38 @@
+use crate::litdata::{
+    load_chunk_access, parse_index, AppError, AppResult, ChunkCache, ParsedIndex,
+};
+use crate::progress::Progress;
+use std::path::PathBuf;
+use tauri::{async_runtime::spawn_blocking, AppHandle};
+
+pub(crate) use litdata_core::validate::detect_field_count;
+pub use litdata_core::validate::ValidationReport;
+
+#[tauri::command]
+pub async fn validate_dataset(
+    app: AppHandle,
+    index_path: String,
+    job_id: Option<String>,
+) -> AppResult<ValidationReport> {
+    let path = PathBuf::from(index_path);
+    let progress = Progress::new(app, job_id, "validate");
+    spawn_blocking(move || validate_dataset_sync(path, &progress))
+        .await
+        .map_err(|e| AppError::Task(e.to_string()))?
+}
+
+fn validate_dataset_sync(index_path: PathBuf, progress: &Progress) -> AppResult<ValidationReport> {
+    validate_parsed(&parse_index(&index_path)?, progress)
+}
+
+pub(crate) fn validate_parsed(
+    parsed: &ParsedIndex,
+    progress: &Progress,
+) -> AppResult<ValidationReport> {
+    litdata_core::validate::validate_parsed(
+        parsed,
+        // A throwaway cache keeps a full-dataset scan from pinning every chunk in memory.
+        |chunk| load_chunk_access(parsed, chunk, &ChunkCache::default()),
+        progress,
+    )
+}
@@ -0,0 +1,84 @@
+//! Additional viewer windows. Every window shares the chunk cache, parsed-index cache,
+//! settings and workspace; each owns the dataset handles it opens, which close with it.
+
+use crate::deeplink::OpenRequest;
+use crate::litdata::{AppError, AppResult};
+use crate::workspace::Workspace;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Window, WindowEvent};
+
+/// Label of the window declared in tauri.conf.json.
+pub const MAIN_WINDOW: &str = "main";
+/// Extra windows are labelled `viewer-1`, `viewer-2`, …; the capability matches the prefix.
+const WINDOW_PREFIX: &str = "viewer-";
+
+static NEXT_WINDOW: AtomicU32 = AtomicU32::new(1);
+/// What each new window should open once its page asks, keyed by label.
+static INITIAL: Mutex<Option<HashMap<String, OpenRequest>>> = Mutex::new(None);
+
+fn initial() -> AppResult<std::sync::MutexGuard<'static, Option<HashMap<String, OpenRequest>>>> {
+    INITIAL
+        .lock()
+        .map_err(|_| AppError::Task("window request lock poisoned".into()))
+}
+
+/// Opens another viewer window, optionally pointed at a dataset; returns its label.
+#[tauri::command]
+pub async fn open_viewer_window(app: AppHandle, request: Option<OpenRequest>) -> AppResult<String> {
+    let label = format!(
+        "{WINDOW_PREFIX}{}",
+        NEXT_WINDOW.fetch_add(1, Ordering::Relaxed)
+    );
+    if let Some(request) = request {
+        initial()?
+            .get_or_insert_with(HashMap::new)
+            .insert(label.clone(), request);
+    }
+    let built = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("index.html".into()))
+        .title("LitData Viewer")
+        .inner_size(1440.0, 950.0)
+        .min_inner_size(1440.0, 820.0)
+        .build();
+    if let Err(e) = built {
+        if let Some(pending) = initial()?.as_mut() {
+            pending.remove(&label);
+        }
+        return Err(AppError::Open(format!("creating window {label}: {e}")));
+    }
+    tracing::info!(window = %label, "opened viewer window");
+    Ok(label)
+}
+
+/// The dataset the calling window was created for, if any; returned only once.
+#[tauri::command]
+pub async fn take_window_open_request(
+    window: tauri::WebviewWindow,
+) -> AppResult<Option<OpenRequest>> {
+    Ok(initial()?
+        .as_mut()
+        .and_then(|pending| pending.remove(window.label())))
+}
+
+/// Releases what a destroyed window held. Shared caches are left alone.
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    if !matches!(event, WindowEvent::Destroyed) {
+        return;
+    }
+    let label = window.label();
+    if let Ok(mut pending) = initial() {
+        if let Some(pending) = pending.as_mut() {
+            pending.remove(label);
+        }
+    }
+    match window.state::<Workspace>().close_window(label) {
+        Ok(0) => {}
+        Ok(closed) => tracing::info!(window = %label, closed, "closed window datasets"),
+        Err(e) => tracing::warn!(window = %label, "could not close window datasets: {e}"),
+    }
+}
@@ -0,0 +1,222 @@
+use crate::litdata::{AppError, AppResult, ParsedIndex};
+use crate::sidecar::Sidecar;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Opaque id the frontend passes to item and field commands in place of an index path.
+pub type DatasetHandle = u32;
+
+#[derive(Default)]
+struct WorkspaceState {
+    datasets: HashMap<DatasetHandle, Arc<ParsedIndex>>,
+    /// Label of the window that opened each handle, or the local API server's owner.
+    owners: HashMap<DatasetHandle, String>,
+    /// Label files joined onto each handle's item listings.
+    sidecars: HashMap<DatasetHandle, Arc<Sidecar>>,
+    next: DatasetHandle,
+}
+
+/// Managed-state set of open datasets. Each handle pins the parse it was opened with,
+/// so reads stay consistent even if index.json is rewritten underneath; reopening picks
+/// up the new version under a new handle. Rewrites the viewer makes itself refresh the
+/// handles in place. Handles are shared by every window, like the
+/// chunk and index caches; ownership only decides what a window lists and what closes
+/// with it.
+#[derive(Clone, Default)]
+pub struct Workspace {
+    inner: Arc<Mutex<WorkspaceState>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenDataset {
+    handle: DatasetHandle,
+    index_path: String,
+    root_dir: String,
+    chunk_count: usize,
+}
+
+impl Workspace {
+    pub(crate) fn open(&self, parsed: Arc<ParsedIndex>) -> AppResult<DatasetHandle> {
+        let mut guard = self.lock()?;
+        guard.next = guard.next.wrapping_add(1).max(1);
+        let handle = guard.next;
+        guard.datasets.insert(handle, parsed);
+        Ok(handle)
+    }
+
+    /// `open`, recording `window` as the owner so the handle closes with it.
+    pub(crate) fn open_in(
+        &self,
+        parsed: Arc<ParsedIndex>,
+        window: &str,
+    ) -> AppResult<DatasetHandle> {
+        let handle = self.open(parsed)?;
+        self.lock()?.owners.insert(handle, window.to_string());
+        Ok(handle)
+    }
+
+    pub(crate) fn get(&self, handle: DatasetHandle) -> AppResult<Arc<ParsedIndex>> {
+        self.lock()?
+            .datasets
+            .get(&handle)
+            .cloned()
+            .ok_or_else(|| AppError::Invalid(format!("dataset handle {handle} is not open")))
+    }
+
+    /// Points every handle opened on the index at `parsed.source` to `parsed`; returns
+    /// how many.
+    pub(crate) fn refresh(&self, parsed: Arc<ParsedIndex>) -> AppResult<usize> {
+        let mut guard = self.lock()?;
+        let mut refreshed = 0;
+        for open in guard.datasets.values_mut() {
+            if open.source == parsed.source {
+                *open = parsed.clone();
+                refreshed += 1;
+            }
+        }
+        Ok(refreshed)
+    }
+
+    /// Attaches `sidecar` to an open handle, replacing any earlier one.
+    pub(crate) fn attach_sidecar(&self, handle: DatasetHandle, sidecar: Sidecar) -> AppResult<()> {
+        let mut guard = self.lock()?;
+        if !guard.datasets.contains_key(&handle) {
+            return Err(AppError::Invalid(format!(
+                "dataset handle {handle} is not open"
+            )));
+        }
+        guard.sidecars.insert(handle, Arc::new(sidecar));
+        Ok(())
+    }
+
+    pub(crate) fn detach_sidecar(&self, handle: DatasetHandle) -> AppResult<bool> {
+        Ok(self.lock()?.sidecars.remove(&handle).is_some())
+    }
+
+    pub(crate) fn sidecar(&self, handle: DatasetHandle) -> AppResult<Option<Arc<Sidecar>>> {
+        Ok(self.lock()?.sidecars.get(&handle).cloned())
+    }
+
+    pub(crate) fn describe_all(&self) -> AppResult<Vec<OpenDataset>> {
+        Ok(self
+            .snapshot()?
+            .iter()
+            .map(|(handle, parsed)| describe(*handle, parsed))
+            .collect())
+    }
+
+    /// Open datasets owned by `window`.
+    pub(crate) fn describe_window(&self, window: &str) -> AppResult<Vec<OpenDataset>> {
+        let owned: Vec<DatasetHandle> = {
+            let guard = self.lock()?;
+            guard
+                .owners
+                .iter()
+                .filter(|(_, owner)| *owner == window)
+                .map(|(handle, _)| *handle)
+                .collect()
+        };
+        Ok(self
+            .snapshot()?
+            .iter()
+            .filter(|(handle, _)| owned.contains(handle))
+            .map(|(handle, parsed)| describe(*handle, parsed))
+            .collect())
+    }
+
+    pub(crate) fn close(&self, handle: DatasetHandle) -> AppResult<bool> {
+        let mut guard = self.lock()?;
+        guard.owners.remove(&handle);
+        guard.sidecars.remove(&handle);
+        Ok(guard.datasets.remove(&handle).is_some())
+    }
+
+    /// Releases every handle `window` (a window label or the API server's owner) opened;
+    /// returns how many.
+    pub(crate) fn close_window(&self, window: &str) -> AppResult<usize> {
+        let mut guard = self.lock()?;
+        let owned: Vec<DatasetHandle> = guard
+            .owners
+            .iter()
+            .filter(|(_, owner)| *owner == window)
+            .map(|(handle, _)| *handle)
+            .collect();
+        for handle in &owned {
+            guard.owners.remove(handle);
+            guard.sidecars.remove(handle);
+            guard.datasets.remove(handle);
+        }
+        Ok(owned.len())
+    }
+
+    /// Directories of every open dataset plus `also`, which read-only mode protects.
+    pub(crate) fn dataset_dirs(&self, also: &ParsedIndex) -> Vec<PathBuf> {
+        let mut dirs = also.dataset_dirs();
+        dirs.extend(self.open_dirs());
+        dirs
+    }
+
+    /// Directories of every open dataset.
+    pub(crate) fn open_dirs(&self) -> Vec<PathBuf> {
+        self.snapshot()
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|(_, parsed)| parsed.dataset_dirs())
+            .collect()
+    }
+
+    /// Every open dataset, by handle.
+    pub(crate) fn snapshot(&self) -> AppResult<Vec<(DatasetHandle, Arc<ParsedIndex>)>> {
+        let mut open: Vec<_> = self
+            .lock()?
+            .datasets
+            .iter()
+            .map(|(handle, parsed)| (*handle, parsed.clone()))
+            .collect();
+        open.sort_by_key(|(handle, _)| *handle);
+        Ok(open)
+    }
+
+    fn lock(&self) -> AppResult<std::sync::MutexGuard<'_, WorkspaceState>> {
+        self.inner
+            .lock()
+            .map_err(|_| AppError::Task("workspace lock poisoned".into()))
+    }
+}
+
+fn describe(handle: DatasetHandle, parsed: &ParsedIndex) -> OpenDataset {
+    OpenDataset {
+        handle,
+        index_path: parsed.source.display().to_string(),
+        root_dir: parsed.root_dir.display().to_string(),
+        chunk_count: parsed.chunks.len(),
+    }
+}
+
+/// Datasets the calling window opened, or every open dataset when `all` is set.
+#[tauri::command]
+pub async fn list_open_datasets(
+    window: tauri::WebviewWindow,
+    all: Option<bool>,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<Vec<OpenDataset>> {
+    if all.unwrap_or(false) {
+        workspace.describe_all()
+    } else {
+        workspace.describe_window(window.label())
+    }
+}
+
+/// Releases a handle. Chunks it loaded stay in the shared chunk cache until evicted.
+#[tauri::command]
+pub async fn close_dataset(
+    handle: DatasetHandle,
+    workspace: tauri::State<'_, Workspace>,
+) -> AppResult<bool> {
+    workspace.close(handle)
+}
@@ -15,6 +15,8 @@ type ViewerState = {
   setIndexPath: (path: string) => void;
   setChunkSelection: (paths: string[]) => void;
   triggerLoad: (mode: "index" | "chunks", payload?: string[]) => void;
+  /** Loads `indexPath` and preselects the given chunk, item and field once they load. */
+  openAt: (target: { indexPath: string; chunk?: string | null; item?: number | null; field?: number | null }) => void;
   selectChunk: (filename: string | null) => void;
   selectItem: (idx: number | null) => void;
   selectField: (idx: number | null) => void;
@@ -54,6 +56,15 @@ export const useViewerStore = create<ViewerState>((set, get) => ({
       selectedFieldIndex: null,
     });
   },
+  openAt: ({ indexPath, chunk, item, field }) =>
+    set({
+      indexPath,
+      chunkSelection: [],
+      mode: { kind: "index", indexPath, requestId: Date.now() },
+      selectedChunkName: chunk ?? null,
+      selectedItemIndex: item ?? null,
+      selectedFieldIndex: field ?? null,
+    }),
   selectChunk: (filename) => set({ selectedChunkName: filename, selectedItemIndex: null, selectedFieldIndex: null }),
   selectItem: (idx) => set({ selectedItemIndex: idx, selectedFieldIndex: null }),
   selectField: (idx) => set({ selectedFieldIndex: idx }),